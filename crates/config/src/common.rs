@@ -1,9 +1,9 @@
-use jsonschema::{Draft, JSONSchema};
-use log::{error, info};
+use jsonschema::{Draft, JSONSchema, ValidationError};
 use serde_json::Value;
-use shared_types::config::ConfigError;
+use shared_types::config::{ConfigError, ValidationIssue};
 use std::fs;
 use std::path::Path;
+use std::sync::atomic::{AtomicUsize, Ordering};
 
 /// Reads and parses JSON content from a file
 ///
@@ -18,14 +18,60 @@ use std::path::Path;
 /// # Errors
 ///
 /// - File read failure: `ConfigError::IoError`
-/// - JSON parse failure: `ConfigError::ParseError`
+/// - JSON parse failure: `ConfigError::ParseError`, formatted as `path:line:col: message`
 pub fn read_and_parse_json<P: AsRef<Path>>(path: P) -> Result<Value, ConfigError> {
+  let path = path.as_ref();
+  let content = fs::read_to_string(path)?;
+  let value: Value =
+    serde_json::from_str(&content).map_err(|e| ConfigError::ParseError(located(path, &e)))?;
+  Ok(value)
+}
+
+// Renders a JSON syntax error as `path:line:col: message`, dropping serde_json's own
+// "at line L column C" suffix so the position is not repeated.
+fn located(path: &Path, error: &serde_json::Error) -> String {
+  let message = error.to_string();
+  let suffix = format!(" at line {} column {}", error.line(), error.column());
+  let message = message.strip_suffix(&suffix).unwrap_or(&message);
+  format!(
+    "{}:{}:{}: {}",
+    path.display(),
+    error.line(),
+    error.column(),
+    message
+  )
+}
+
+/// Reads a TOML file and converts it into the equivalent JSON value
+///
+/// Tables become objects and arrays stay arrays, so the result can go through the same
+/// schema validation as JSON input.
+///
+/// # Errors
+///
+/// - File read failure: `ConfigError::IoError`
+/// - TOML parse failure: `ConfigError::ParseError`
+pub fn read_and_parse_toml<P: AsRef<Path>>(path: P) -> Result<Value, ConfigError> {
   let content = fs::read_to_string(path.as_ref())?;
   let value: Value =
-    serde_json::from_str(&content).map_err(|e| ConfigError::ParseError(e.to_string()))?;
+    toml::from_str(&content).map_err(|e| ConfigError::ParseError(e.to_string()))?;
   Ok(value)
 }
 
+/// Number of schema violations reported per validation unless changed with
+/// `set_max_reported_errors`
+pub const DEFAULT_MAX_REPORTED_ERRORS: usize = 20;
+
+static MAX_REPORTED_ERRORS: AtomicUsize = AtomicUsize::new(DEFAULT_MAX_REPORTED_ERRORS);
+
+/// Sets how many schema violations a validation error lists, for the whole process
+///
+/// Further violations are only counted and summarized as `...and N more`, so a huge
+/// invalid document cannot produce a message of the same size.
+pub fn set_max_reported_errors(max: usize) {
+  MAX_REPORTED_ERRORS.store(max, Ordering::Relaxed);
+}
+
 /// Validates JSON data using a precompiled schema
 ///
 /// # Arguments
@@ -39,14 +85,33 @@ pub fn read_and_parse_json<P: AsRef<Path>>(path: P) -> Result<Value, ConfigError
 ///
 /// # Errors
 ///
-/// Returns `ConfigError::ValidationError` on validation failure with detailed error messages
+/// Returns `ConfigError::SchemaViolations` on validation failure with one entry per
+/// violation, up to the `set_max_reported_errors` limit
 pub fn validate_json(schema: &JSONSchema, value: &Value) -> Result<(), ConfigError> {
+  validate_json_with(schema, value, |e| e.to_string())
+}
+
+/// Like `validate_json`, with each violation's message rendered by `describe`
+pub fn validate_json_with(
+  schema: &JSONSchema,
+  value: &Value,
+  describe: impl Fn(&ValidationError) -> String,
+) -> Result<(), ConfigError> {
   if let Err(errors) = schema.validate(value) {
-    let error_messages: Vec<String> = errors
-      .map(|e| format!("Path '{}': {}", e.instance_path, e))
-      .collect();
-    let combined_errors = error_messages.join("\n");
-    return Err(ConfigError::ValidationError(combined_errors));
+    let max = MAX_REPORTED_ERRORS.load(Ordering::Relaxed);
+    let mut issues = Vec::new();
+    let mut omitted = 0;
+    for error in errors {
+      if issues.len() < max {
+        issues.push(ValidationIssue {
+          path: error.instance_path.to_string(),
+          message: describe(&error),
+        });
+      } else {
+        omitted += 1;
+      }
+    }
+    return Err(ConfigError::SchemaViolations { issues, omitted });
   }
   Ok(())
 }
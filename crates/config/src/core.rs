@@ -1,13 +1,24 @@
 use jsonschema::JSONSchema;
+use log::warn;
 use once_cell::sync::Lazy;
+use serde_json::Value;
 use shared_types::config::{ConfigData, ConfigError};
 use std::env;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 
-use crate::common::{compile_schema, read_and_parse_json, validate_json};
+use crate::common::{compile_schema, read_and_parse_json, read_and_parse_toml, validate_json};
 
 const DEFAULT_CONFIG_FILE: &str = "bud.json";
 
+// Read when `bud.json` is absent.
+const TOML_CONFIG_FILE: &str = "bud.toml";
+
+/// Environment variable selecting the profile `load_config` applies.
+pub const PROFILE_ENV_VAR: &str = "BUD_PROFILE";
+
+/// Environment variable pointing the loaders at a config file other than `bud.json`.
+pub const CONFIG_ENV_VAR: &str = "BUD_CONFIG";
+
 static CONFIG_SCHEMA: &str = r#"{
     "type": "object",
     "properties": {
@@ -22,6 +33,16 @@ static CONFIG_SCHEMA: &str = r#"{
         "description": {
             "type": "string",
             "description": "A short description of the application."
+        },
+        "providers": {
+            "type": "object",
+            "description": "Provider-specific settings keyed by provider name; each provider validates its own.",
+            "additionalProperties": { "type": "object" }
+        },
+        "profiles": {
+            "type": "object",
+            "description": "Named overrides merged over the base fields, e.g. dev or prod.",
+            "additionalProperties": { "type": "object" }
         }
     },
     "required": ["name", "version", "description"]
@@ -29,19 +50,88 @@ static CONFIG_SCHEMA: &str = r#"{
 
 static COMPILED_SCHEMA: Lazy<JSONSchema> = Lazy::new(|| compile_schema(CONFIG_SCHEMA));
 
+/// Loads `bud.json` from the current directory
+///
+/// Without `bud.json`, `bud.toml` is read instead and validated the same way. When both
+/// exist `bud.json` wins and a warning is logged. When `BUD_CONFIG` is set to a non-empty
+/// path, that file is loaded instead, as TOML if its extension is `.toml`.
+///
+/// When `BUD_PROFILE` is set to a non-empty value, that profile is applied as if by
+/// `load_config_with_profile`; otherwise only the base fields are used.
+///
+/// # Errors
+///
+/// - Neither file exists, or `BUD_CONFIG` names a missing file: `ConfigError::FileNotFound`
+/// - `BUD_PROFILE` names a profile the file does not define: `ConfigError::UnknownProfile`
+/// - Invalid JSON or TOML, or schema violations: `ConfigError::ParseError` / `ConfigError::SchemaViolations`
 pub fn load_config() -> Result<ConfigData, ConfigError> {
-  let current_dir = env::current_dir()?;
-  let config_path = current_dir.join(DEFAULT_CONFIG_FILE);
+  let profile = env::var(PROFILE_ENV_VAR).ok().filter(|p| !p.is_empty());
+  parse_config(&default_config_path()?, profile.as_deref())
+}
 
-  if !config_path.exists() {
-    return Err(ConfigError::FileNotFound(config_path.display().to_string()));
+/// Loads the config file `load_config` would read, with the named profile applied
+///
+/// The profile's fields are merged over the base fields before validation: nested
+/// objects merge key by key and any other value replaces the base one.
+///
+/// # Errors
+///
+/// Returns `ConfigError::UnknownProfile` if the file defines no such profile, and
+/// otherwise the same errors as `load_config`.
+pub fn load_config_with_profile(profile: &str) -> Result<ConfigData, ConfigError> {
+  parse_config(&default_config_path()?, Some(profile))
+}
+
+fn default_config_path() -> Result<PathBuf, ConfigError> {
+  if let Some(path) = env::var_os(CONFIG_ENV_VAR).filter(|p| !p.is_empty()) {
+    let path = PathBuf::from(path);
+    if !path.exists() {
+      return Err(ConfigError::FileNotFound(path.display().to_string()));
+    }
+    return Ok(path);
   }
 
-  parse_config(&config_path)
+  let dir = env::current_dir()?;
+  let config_path = dir.join(DEFAULT_CONFIG_FILE);
+  let toml_path = dir.join(TOML_CONFIG_FILE);
+
+  match (config_path.exists(), toml_path.exists()) {
+    (true, true) => {
+      warn!(
+        "Both {} and {} found in {}; using {}",
+        DEFAULT_CONFIG_FILE,
+        TOML_CONFIG_FILE,
+        dir.display(),
+        DEFAULT_CONFIG_FILE
+      );
+      Ok(config_path)
+    }
+    (true, false) => Ok(config_path),
+    (false, true) => Ok(toml_path),
+    (false, false) => Err(ConfigError::FileNotFound(config_path.display().to_string())),
+  }
 }
 
-fn parse_config<P: AsRef<Path>>(path: P) -> Result<ConfigData, ConfigError> {
-  let value = read_and_parse_json(&path)?;
+fn parse_config<P: AsRef<Path>>(path: P, profile: Option<&str>) -> Result<ConfigData, ConfigError> {
+  let mut value = if path.as_ref().extension().is_some_and(|ext| ext == "toml") {
+    read_and_parse_toml(&path)?
+  } else {
+    read_and_parse_json(&path)?
+  };
+  // The profiles stay in place so the schema checks their shape too.
+  let profiles = value.get("profiles").cloned();
+
+  if let Some(profile) = profile {
+    let overrides = profiles
+      .as_ref()
+      .and_then(|profiles| profiles.get(profile))
+      .ok_or_else(|| ConfigError::UnknownProfile {
+        profile: profile.to_string(),
+        available: profile_names(profiles.as_ref()),
+      })?;
+    merge(&mut value, overrides);
+  }
+
   validate_json(&COMPILED_SCHEMA, &value)?;
 
   let config: ConfigData =
@@ -49,3 +139,34 @@ fn parse_config<P: AsRef<Path>>(path: P) -> Result<ConfigData, ConfigError> {
 
   Ok(config)
 }
+
+// Comma-separated profile names for error messages.
+fn profile_names(profiles: Option<&Value>) -> String {
+  let names: Vec<&str> = profiles
+    .and_then(Value::as_object)
+    .map(|profiles| profiles.keys().map(String::as_str).collect())
+    .unwrap_or_default();
+
+  if names.is_empty() {
+    "none".to_string()
+  } else {
+    names.join(", ")
+  }
+}
+
+// Merges `overrides` into `base`: objects recursively, everything else by replacement.
+fn merge(base: &mut Value, overrides: &Value) {
+  match (base, overrides) {
+    (Value::Object(base), Value::Object(overrides)) => {
+      for (key, value) in overrides {
+        match base.get_mut(key) {
+          Some(existing) => merge(existing, value),
+          None => {
+            base.insert(key.clone(), value.clone());
+          }
+        }
+      }
+    }
+    (base, overrides) => *base = overrides.clone(),
+  }
+}
@@ -2,11 +2,16 @@ mod common;
 mod core;
 mod plugin;
 
-pub use core::load_config;
+pub use common::{DEFAULT_MAX_REPORTED_ERRORS, set_max_reported_errors};
+pub use core::{CONFIG_ENV_VAR, PROFILE_ENV_VAR, load_config, load_config_with_profile};
 pub use plugin::{
   PLUGIN_CONFIG_FILE,
+  is_custom_permission,
   load_all_plugin_configs,
   load_plugin_config_validated,
   load_plugin_config,
+  register_permission_prefix,
+  set_strict_validation,
+  validate_function_args,
 };
 
@@ -1,18 +1,27 @@
-use jsonschema::JSONSchema;
+use jsonschema::error::ValidationErrorKind;
+use jsonschema::{Draft, JSONSchema};
 use log::{error, info, warn};
 use once_cell::sync::Lazy;
-use shared_types::config::{ConfigError, PluginConfigData};
-use std::collections::HashMap;
+use serde_json::Value;
+use shared_types::config::{ConfigError, PermissionGrant, PluginConfigData};
+use std::collections::{HashMap, HashSet};
 use std::fs::read_dir;
+use std::net::Ipv6Addr;
 use std::path::Path;
+use std::sync::RwLock;
+use std::sync::atomic::{AtomicBool, Ordering};
 
-use crate::common::{compile_schema, read_and_parse_json, validate_json};
+use crate::common::{compile_schema, read_and_parse_json, validate_json, validate_json_with};
 
 pub const PLUGIN_CONFIG_FILE: &str = "plugin.json";
 
 static PLUGIN_SCHEMA: &str = r#"{
   "type": "object",
   "properties": {
+    "id": {
+      "type": "string",
+      "pattern": "^[a-zA-Z0-9_-]+$"
+    },
     "name": {
       "type": "string",
       "pattern": "^[a-zA-Z0-9_-]+$"
@@ -29,6 +38,10 @@ static PLUGIN_SCHEMA: &str = r#"{
       "type": "string",
       "minLength": 1
     },
+    "main": {
+      "type": "string",
+      "pattern": "^[a-zA-Z0-9_-][a-zA-Z0-9_.-]*$"
+    },
     "permissions": {
       "type": "object",
       "properties": {
@@ -52,8 +65,8 @@ static PLUGIN_SCHEMA: &str = r#"{
             {
               "type": "object",
               "properties": {
-                "read":  { "type": "array", "items": { "type": "string" } },
-                "write": { "type": "array", "items": { "type": "string" } }
+                "read":  { "type": "array", "items": { "type": "string" }, "uniqueItems": true },
+                "write": { "type": "array", "items": { "type": "string" }, "uniqueItems": true }
               },
               "additionalProperties": false
             }
@@ -65,7 +78,7 @@ static PLUGIN_SCHEMA: &str = r#"{
             {
               "type": "object",
               "properties": {
-                "allowed_hosts": { "type": "array", "items": { "type": "string" } }
+                "allowed_hosts": { "type": "array", "items": { "type": "string" }, "uniqueItems": true }
               },
               "additionalProperties": false
             }
@@ -78,7 +91,7 @@ static PLUGIN_SCHEMA: &str = r#"{
               "type": "object",
               "properties": {
                 "inherit": { "type": "boolean" },
-                "keys":    { "type": "array", "items": { "type": "string" } }
+                "keys":    { "type": "array", "items": { "type": "string" }, "uniqueItems": true }
               },
               "additionalProperties": false
             }
@@ -97,7 +110,43 @@ static PLUGIN_SCHEMA: &str = r#"{
           ]
         }
       },
+      "patternProperties": {
+        "^[a-z][a-z0-9-]*:[a-zA-Z0-9_.-]+$": { "type": "boolean" }
+      },
       "additionalProperties": false
+    },
+    "timeoutMs": {
+      "type": "integer",
+      "minimum": 1
+    },
+    "categories": {
+      "type": "array",
+      "items": { "type": "string", "minLength": 1 }
+    },
+    "keywords": {
+      "type": "array",
+      "items": { "type": "string", "minLength": 1 }
+    },
+    "dependencies": {
+      "type": "array",
+      "items": { "type": "string", "pattern": "^[a-zA-Z0-9_-]+$" },
+      "uniqueItems": true
+    },
+    "targets": {
+      "type": "array",
+      "items": { "type": "string", "pattern": "^[a-z0-9_]+-[a-z0-9_]+$" },
+      "uniqueItems": true
+    },
+    "functions": {
+      "type": "object",
+      "additionalProperties": {
+        "type": "object",
+        "properties": {
+          "args": { "type": ["object", "boolean"] },
+          "cacheable": { "type": "boolean" }
+        },
+        "additionalProperties": false
+      }
     }
   },
   "required": ["name", "version", "description", "author"]
@@ -105,6 +154,278 @@ static PLUGIN_SCHEMA: &str = r#"{
 
 static COMPILED_PLUGIN_SCHEMA: Lazy<JSONSchema> = Lazy::new(|| compile_schema(PLUGIN_SCHEMA));
 
+static PERMISSION_PREFIXES: Lazy<RwLock<HashSet<String>>> = Lazy::new(Default::default);
+
+/// Registers a custom permission prefix for the whole process
+///
+/// Plugins may then declare permissions such as `"app:send-email": true` when `"app"`
+/// is registered. Keys with an unregistered prefix, and misspelled built-in dimensions,
+/// keep failing validation.
+///
+/// # Arguments
+///
+/// * `prefix` - Namespace without the trailing colon (e.g. `"app"`)
+pub fn register_permission_prefix(prefix: &str) {
+  PERMISSION_PREFIXES
+    .write()
+    .unwrap_or_else(|p| p.into_inner())
+    .insert(prefix.to_string());
+}
+
+/// Returns `true` if `key` is a well-formed custom permission with a registered prefix
+///
+/// Well-formed keys follow the `<prefix>:<name>` grammar enforced by the plugin schema.
+pub fn is_custom_permission(key: &str) -> bool {
+  let Some((prefix, name)) = key.split_once(':') else {
+    return false;
+  };
+
+  let prefix_ok = prefix.starts_with(|c: char| c.is_ascii_lowercase())
+    && prefix
+      .chars()
+      .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '-');
+  let name_ok = !name.is_empty()
+    && name
+      .chars()
+      .all(|c| c.is_ascii_alphanumeric() || matches!(c, '_' | '.' | '-'));
+
+  prefix_ok
+    && name_ok
+    && PERMISSION_PREFIXES
+      .read()
+      .unwrap_or_else(|p| p.into_inner())
+      .contains(prefix)
+}
+
+static STRICT_VALIDATION: AtomicBool = AtomicBool::new(false);
+
+// Permission lists the schema requires to hold unique entries, as JSON pointers.
+const UNIQUE_PERMISSION_LISTS: &[&str] = &[
+  "/permissions/filesystem/read",
+  "/permissions/filesystem/write",
+  "/permissions/network/allowed_hosts",
+  "/permissions/env/keys",
+];
+
+/// Sets whether plugin manifests are validated strictly, for the whole process
+///
+/// Strict validation rejects a manifest that repeats an entry in a permission list
+/// (such as `filesystem.read`). By default such duplicates are harmless mistakes: they
+/// are removed with a warning and the manifest loads.
+pub fn set_strict_validation(strict: bool) {
+  STRICT_VALIDATION.store(strict, Ordering::Relaxed);
+}
+
+// Handles repeated entries in permission lists before schema validation, whose
+// `anyOf` error would not point at them. Strict validation rejects the first one;
+// otherwise each is removed with a warning, keeping the first occurrence.
+fn check_permission_lists(value: &mut Value, path: &Path) -> Result<(), ConfigError> {
+  let strict = STRICT_VALIDATION.load(Ordering::Relaxed);
+  for pointer in UNIQUE_PERMISSION_LISTS {
+    let Some(Value::Array(items)) = value.pointer_mut(pointer) else {
+      continue;
+    };
+
+    let mut seen = Vec::with_capacity(items.len());
+    let mut duplicates = Vec::new();
+    items.retain(|item| {
+      if seen.contains(item) {
+        duplicates.push(item.clone());
+        return false;
+      }
+      seen.push(item.clone());
+      true
+    });
+
+    if let Some(duplicate) = duplicates.first()
+      && strict
+    {
+      return Err(ConfigError::ValidationError(format!(
+        "Path '{}': duplicate permission entry {}",
+        pointer, duplicate
+      )));
+    }
+    for duplicate in duplicates {
+      warn!(
+        "Ignoring duplicate permission entry {} at '{}' in {}",
+        duplicate,
+        pointer,
+        path.display()
+      );
+    }
+  }
+
+  Ok(())
+}
+
+// Example values for fields older manifests may lack, used in migration hints.
+const FIELD_EXAMPLES: &[(&str, &str)] = &[
+  ("author", r#""author": "<your name>""#),
+  ("permissions", r#""permissions": {}"#),
+];
+
+// Validates against the plugin schema. Missing required fields usually mean the
+// manifest predates the field, so they are reported as a migration step instead of
+// the raw schema message.
+fn validate_plugin_json(value: &Value) -> Result<(), ConfigError> {
+  validate_json_with(&COMPILED_PLUGIN_SCHEMA, value, |e| match &e.kind {
+    ValidationErrorKind::Required { property } => {
+      migration_hint(property.as_str().unwrap_or_default())
+    }
+    _ => e.to_string(),
+  })
+}
+
+fn migration_hint(field: &str) -> String {
+  let example = FIELD_EXAMPLES
+    .iter()
+    .find(|(name, _)| *name == field)
+    .map_or_else(
+      || format!(r#""{}": ..."#, field),
+      |(_, example)| example.to_string(),
+    );
+  format!(
+    "missing required field '{}', which the current plugin schema requires; add {} to {}",
+    field, example, PLUGIN_CONFIG_FILE
+  )
+}
+
+// Compiles a function's argument schema without panicking on malformed input.
+fn compile_args_schema(schema: &Value) -> Result<JSONSchema, ConfigError> {
+  JSONSchema::options()
+    .with_draft(Draft::Draft7)
+    .compile(schema)
+    .map_err(|e| ConfigError::ValidationError(format!("Invalid argument schema: {}", e)))
+}
+
+/// Validates a function's JSON arguments against the schema declared in plugin.json
+///
+/// # Arguments
+///
+/// * `schema` - The function's `args` schema
+/// * `args` - The argument array sent by the caller
+///
+/// # Errors
+///
+/// Returns `ConfigError::ValidationError` if the schema does not compile, or
+/// `ConfigError::SchemaViolations` listing each violation if `args` does not match it
+pub fn validate_function_args(schema: &Value, args: &Value) -> Result<(), ConfigError> {
+  validate_json(&compile_args_schema(schema)?, args)
+}
+
+// Rejects custom permissions whose prefix was never registered.
+fn check_custom_permissions(config: &PluginConfigData) -> Result<(), ConfigError> {
+  let Some(permissions) = &config.permissions else {
+    return Ok(());
+  };
+
+  let prefixes = PERMISSION_PREFIXES
+    .read()
+    .unwrap_or_else(|p| p.into_inner());
+  for key in permissions.custom.keys() {
+    let prefix = key
+      .split_once(':')
+      .map_or(key.as_str(), |(prefix, _)| prefix);
+    if !prefixes.contains(prefix) {
+      return Err(ConfigError::ValidationError(format!(
+        "Path '/permissions/{}': unknown permission prefix '{}'",
+        key, prefix
+      )));
+    }
+  }
+
+  Ok(())
+}
+
+// Explains why a grant's target is malformed, or `None` if it is well-formed. Paths may
+// be relative here; the provider decides which ones it can grant.
+fn malformed_grant(grant: &PermissionGrant) -> Option<&'static str> {
+  match grant {
+    PermissionGrant::FsRead(path) | PermissionGrant::FsWrite(path)
+      if path.is_empty() || path.contains('\0') =>
+    {
+      Some("paths must be non-empty and free of NUL bytes")
+    }
+    PermissionGrant::NetConnect(host) if !is_valid_host(host) => {
+      Some("hosts are written as `host`, `host:port`, `*.domain` or `*`")
+    }
+    PermissionGrant::EnvKey(key) if key.is_empty() || key.contains(['=', '\0']) => {
+      Some("variable names must be non-empty and free of `=` and NUL bytes")
+    }
+    _ => None,
+  }
+}
+
+fn is_valid_host(host: &str) -> bool {
+  if host == "*" {
+    return true;
+  }
+  // A port follows the last colon, except inside a bracketed IPv6 address.
+  let (name, port) = match host.rsplit_once(':') {
+    Some((name, port)) if !port.ends_with(']') => (name, Some(port)),
+    _ => (host, None),
+  };
+  let port_ok = port.is_none_or(|port| port.parse::<u16>().is_ok_and(|port| port > 0));
+  if let Some(ip) = name.strip_prefix('[').and_then(|n| n.strip_suffix(']')) {
+    return port_ok && ip.parse::<Ipv6Addr>().is_ok();
+  }
+
+  let name = name.strip_prefix("*.").unwrap_or(name);
+  let name_ok = !name.is_empty()
+    && name.split('.').all(|label| {
+      !label.is_empty()
+        && !label.starts_with('-')
+        && !label.ends_with('-')
+        && label.chars().all(|c| c.is_ascii_alphanumeric() || c == '-')
+    });
+
+  port_ok && name_ok
+}
+
+// Rejects permission entries outside the grant grammar (such as a URL where a host
+// belongs), listing every offending entry rather than just the first.
+fn check_permission_entries(config: &PluginConfigData) -> Result<(), ConfigError> {
+  let Some(permissions) = &config.permissions else {
+    return Ok(());
+  };
+
+  let problems: Vec<String> = permissions
+    .grants()
+    .iter()
+    .filter_map(|grant| {
+      malformed_grant(grant).map(|reason| {
+        format!(
+          "Path '/permissions': malformed permission '{}': {}",
+          grant, reason
+        )
+      })
+    })
+    .collect();
+
+  if problems.is_empty() {
+    Ok(())
+  } else {
+    Err(ConfigError::ValidationError(problems.join("\n")))
+  }
+}
+
+// Rejects argument schemas that would only fail once the function is called.
+fn check_function_schemas(config: &PluginConfigData) -> Result<(), ConfigError> {
+  let schemas = config
+    .functions
+    .iter()
+    .flatten()
+    .filter_map(|(name, spec)| spec.args.as_ref().map(|args| (name, args)));
+
+  for (name, schema) in schemas {
+    compile_args_schema(schema).map_err(|e| {
+      ConfigError::ValidationError(format!("Path '/functions/{}/args': {}", name, e))
+    })?;
+  }
+
+  Ok(())
+}
+
 /// Loads plugin configuration from the specified plugin directory
 ///
 /// # Arguments
@@ -119,7 +440,7 @@ static COMPILED_PLUGIN_SCHEMA: Lazy<JSONSchema> = Lazy::new(|| compile_schema(PL
 ///
 /// - plugin.json not found: `ConfigError::FileNotFound`
 /// - JSON parse failure: `ConfigError::ParseError`
-/// - Schema validation failure: `ConfigError::ValidationError`
+/// - Schema validation failure: `ConfigError::SchemaViolations`
 pub fn load_plugin_config<P: AsRef<Path>>(plugin_dir: P) -> Result<PluginConfigData, ConfigError> {
   let config_path = plugin_dir.as_ref().join(PLUGIN_CONFIG_FILE);
 
@@ -135,9 +456,11 @@ fn parse_plugin_config<P: AsRef<Path>>(path: P) -> Result<PluginConfigData, Conf
   let path_ref = path.as_ref();
   info!("Loading plugin config from: {}", path_ref.display());
 
-  let value = read_and_parse_json(&path)?;
+  let mut value = read_and_parse_json(&path)?;
 
-  if let Err(e) = validate_json(&COMPILED_PLUGIN_SCHEMA, &value) {
+  if let Err(e) =
+    check_permission_lists(&mut value, path_ref).and_then(|_| validate_plugin_json(&value))
+  {
     error!(
       "Plugin config validation failed for '{}': {}",
       path_ref.display(),
@@ -149,6 +472,18 @@ fn parse_plugin_config<P: AsRef<Path>>(path: P) -> Result<PluginConfigData, Conf
   let config: PluginConfigData =
     serde_json::from_value(value).map_err(|e| ConfigError::ParseError(e.to_string()))?;
 
+  if let Err(e) = check_custom_permissions(&config)
+    .and_then(|_| check_permission_entries(&config))
+    .and_then(|_| check_function_schemas(&config))
+  {
+    error!(
+      "Plugin config validation failed for '{}': {}",
+      path_ref.display(),
+      e
+    );
+    return Err(e);
+  }
+
   Ok(config)
 }
 
@@ -206,14 +541,18 @@ pub fn load_all_plugin_configs<P: AsRef<Path>>(
       continue;
     }
 
-    let plugin_name = path
-      .file_name()
-      .and_then(|n| n.to_str())
-      .unwrap_or("unknown");
+    // A lossy name could collide with a real plugin's, so such directories are skipped.
+    let Some(plugin_name) = path.file_name().and_then(|n| n.to_str()) else {
+      warn!(
+        "Skipping plugin directory with a non-UTF-8 name: {}",
+        path.display()
+      );
+      continue;
+    };
 
     match load_plugin_config_validated(&path, plugin_name) {
       Ok(config) => {
-        let config_name = config.name.clone();
+        let config_name = config.key().to_string();
 
         if plugins.insert(config_name.clone(), config).is_some() {
           error!("Duplicate plugin name detected: {}", config_name);
@@ -239,13 +578,14 @@ pub fn load_all_plugin_configs<P: AsRef<Path>>(
 /// Loads plugin configuration from the specified directory and validates name matching
 ///
 /// This function only loads and validates the plugin configuration file (plugin.json),
-/// not the actual plugin runtime files. It additionally validates that the plugin name
-/// in the configuration matches the expected name (typically the directory name).
+/// not the actual plugin runtime files. It additionally validates that the plugin's key
+/// (its `id`, or its `name` without one) matches the expected name (typically the
+/// directory name).
 ///
 /// # Arguments
 ///
 /// * `plugin_dir` - Path to the plugin directory
-/// * `expected_name` - Expected plugin name (typically the directory name)
+/// * `expected_name` - Expected plugin key (typically the directory name)
 ///
 /// # Returns
 ///
@@ -255,7 +595,7 @@ pub fn load_all_plugin_configs<P: AsRef<Path>>(
 ///
 /// - plugin.json not found: `ConfigError::FileNotFound`
 /// - JSON parse failure: `ConfigError::ParseError`
-/// - Schema validation failure: `ConfigError::ValidationError`
+/// - Schema validation failure: `ConfigError::SchemaViolations`
 /// - Name mismatch: `ConfigError::ValidationError`
 pub fn load_plugin_config_validated<P: AsRef<Path>>(
   plugin_dir: P,
@@ -263,10 +603,14 @@ pub fn load_plugin_config_validated<P: AsRef<Path>>(
 ) -> Result<PluginConfigData, ConfigError> {
   let config: PluginConfigData = load_plugin_config(&plugin_dir)?;
 
-  if config.name != expected_name {
+  if config.key() != expected_name {
+    let field = if config.id.is_some() { "id" } else { "name" };
     return Err(ConfigError::ValidationError(format!(
-      "Plugin directory name '{}' does not match {} name '{}'",
-      expected_name, PLUGIN_CONFIG_FILE, config.name
+      "Plugin directory name '{}' does not match {} {} '{}'",
+      expected_name,
+      PLUGIN_CONFIG_FILE,
+      field,
+      config.key()
     )));
   }
 
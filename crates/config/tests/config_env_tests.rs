@@ -0,0 +1,60 @@
+use config::{CONFIG_ENV_VAR, PROFILE_ENV_VAR, load_config};
+use shared_types::config::ConfigError;
+use std::env;
+use std::path::PathBuf;
+use std::sync::Mutex;
+use tempfile::TempDir;
+
+// The loaders read the process-wide current directory, BUD_CONFIG and BUD_PROFILE.
+static PROCESS_STATE: Mutex<()> = Mutex::new(());
+
+// Runs `f` from an empty directory with BUD_CONFIG set to `config_path`.
+fn with_config_env<T>(config_path: &PathBuf, f: impl FnOnce() -> T) -> T {
+  let _guard = PROCESS_STATE.lock().unwrap_or_else(|p| p.into_inner());
+  let empty_dir = TempDir::new().unwrap();
+  let original_dir = env::current_dir().unwrap();
+  env::set_current_dir(empty_dir.path()).unwrap();
+  // SAFETY: every test touching the environment holds PROCESS_STATE.
+  unsafe {
+    env::remove_var(PROFILE_ENV_VAR);
+    env::set_var(CONFIG_ENV_VAR, config_path);
+  }
+
+  let result = f();
+
+  unsafe { env::remove_var(CONFIG_ENV_VAR) };
+  env::set_current_dir(original_dir).unwrap();
+  result
+}
+
+fn fixture(relative: &str) -> PathBuf {
+  PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+    .join("tests")
+    .join("fixtures")
+    .join(relative)
+}
+
+#[test]
+fn test_override_loads_regardless_of_cwd() {
+  let config = with_config_env(&fixture("bud.json"), load_config).expect("override failed");
+
+  assert_eq!(config.name, "bud");
+  assert_eq!(config.description, "A test configuration");
+}
+
+#[test]
+fn test_override_may_point_at_toml() {
+  let config = with_config_env(&fixture("toml/bud.toml"), load_config).expect("override failed");
+
+  assert_eq!(config.description, "Base configuration");
+}
+
+#[test]
+fn test_override_to_missing_file_reports_its_path() {
+  let missing = fixture("does-not-exist.json");
+
+  match with_config_env(&missing, load_config) {
+    Err(ConfigError::FileNotFound(path)) => assert_eq!(path, missing.display().to_string()),
+    other => panic!("expected FileNotFound, got {:?}", other),
+  }
+}
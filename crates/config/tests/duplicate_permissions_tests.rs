@@ -0,0 +1,53 @@
+use config::{load_plugin_config, set_strict_validation};
+use shared_types::config::{ConfigError, FilesystemPermission, Permission};
+use std::fs;
+use tempfile::TempDir;
+
+fn write_manifest(dir: &TempDir) {
+  let plugin_json = r#"{
+    "name": "test-plugin",
+    "version": "1.0.0",
+    "description": "Test plugin",
+    "author": "tester",
+    "permissions": {
+      "filesystem": { "read": ["/data", "/tmp", "/data"] },
+      "env": { "keys": ["HOME", "HOME"] }
+    }
+  }"#;
+  fs::write(dir.path().join("plugin.json"), plugin_json).expect("failed to write plugin.json");
+}
+
+// One test, because the strictness flag is process-wide.
+#[test]
+fn test_duplicate_permission_entries() {
+  let dir = TempDir::new().expect("failed to create temp dir");
+  write_manifest(&dir);
+
+  // Lenient by default: duplicates are dropped, keeping the first occurrence.
+  let permissions = load_plugin_config(dir.path())
+    .expect("duplicates should be accepted")
+    .permissions
+    .unwrap();
+  assert_eq!(
+    permissions.filesystem,
+    Some(Permission::Config(FilesystemPermission {
+      read: Some(vec!["/data".to_string(), "/tmp".to_string()]),
+      write: None,
+    }))
+  );
+  match permissions.env {
+    Some(Permission::Config(env)) => assert_eq!(env.keys, Some(vec!["HOME".to_string()])),
+    other => panic!("unexpected env permission: {:?}", other),
+  }
+
+  set_strict_validation(true);
+  let result = load_plugin_config(dir.path());
+  set_strict_validation(false);
+  match result {
+    Err(ConfigError::ValidationError(msg)) => assert_eq!(
+      msg,
+      r#"Path '/permissions/filesystem/read': duplicate permission entry "/data""#
+    ),
+    other => panic!("expected ValidationError, got {:?}", other),
+  }
+}
@@ -0,0 +1,35 @@
+use config::{DEFAULT_MAX_REPORTED_ERRORS, set_max_reported_errors, validate_function_args};
+use serde_json::{Value, json};
+use shared_types::config::ConfigError;
+
+fn error_lines(args: &Value) -> Vec<String> {
+  let schema = json!({ "type": "array", "items": { "type": "integer" } });
+  match validate_function_args(&schema, args) {
+    // Skip the "Configuration validation failed:" header.
+    Err(e @ ConfigError::SchemaViolations { .. }) => {
+      e.to_string().lines().skip(1).map(str::to_string).collect()
+    }
+    other => panic!("expected SchemaViolations, got {:?}", other),
+  }
+}
+
+// One test, because the limit is process-wide.
+#[test]
+fn test_reported_errors_are_capped() {
+  let args = Value::Array(vec![json!("not an integer"); 100]);
+
+  let lines = error_lines(&args);
+  assert_eq!(lines.len(), DEFAULT_MAX_REPORTED_ERRORS + 1);
+  assert_eq!(lines.last().unwrap(), "...and 80 more");
+
+  set_max_reported_errors(3);
+  let lines = error_lines(&args);
+  assert_eq!(lines.len(), 4);
+  assert!(lines[0].starts_with("Path '/0'"), "{}", lines[0]);
+  assert_eq!(lines[3], "...and 97 more");
+
+  // A document under the limit is reported in full, without a summary.
+  let lines = error_lines(&json!(["a", "b"]));
+  assert_eq!(lines.len(), 2);
+  set_max_reported_errors(DEFAULT_MAX_REPORTED_ERRORS);
+}
@@ -0,0 +1,48 @@
+use config::load_plugin_config;
+use shared_types::config::ConfigError;
+use std::fs;
+use tempfile::TempDir;
+
+// A manifest from before `author` became required.
+const LEGACY_MANIFEST: &str = r#"{
+  "name": "legacy-plugin",
+  "version": "1.0.0",
+  "description": "Written against an older schema"
+}"#;
+
+#[test]
+fn test_missing_required_field_reports_migration_hint() {
+  let dir = TempDir::new().unwrap();
+  fs::write(dir.path().join("plugin.json"), LEGACY_MANIFEST).unwrap();
+
+  match load_plugin_config(dir.path()) {
+    Err(ConfigError::SchemaViolations { issues, .. }) => {
+      assert_eq!(issues.len(), 1);
+      assert_eq!(
+        issues[0].to_string(),
+        "Path '': missing required field 'author', which the current plugin schema \
+         requires; add \"author\": \"<your name>\" to plugin.json"
+      );
+    }
+    other => panic!("expected SchemaViolations, got {:?}", other),
+  }
+}
+
+#[test]
+fn test_other_violations_keep_schema_message() {
+  let dir = TempDir::new().unwrap();
+  fs::write(
+    dir.path().join("plugin.json"),
+    LEGACY_MANIFEST.replace("\"1.0.0\"", "\"one\""),
+  )
+  .unwrap();
+
+  match load_plugin_config(dir.path()) {
+    Err(e @ ConfigError::SchemaViolations { .. }) => {
+      let msg = e.to_string();
+      assert!(msg.contains("missing required field 'author'"), "{}", msg);
+      assert!(msg.contains("Path '/version'"), "{}", msg);
+    }
+    other => panic!("expected SchemaViolations, got {:?}", other),
+  }
+}
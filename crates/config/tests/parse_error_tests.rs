@@ -0,0 +1,44 @@
+use config::load_plugin_config;
+use shared_types::config::ConfigError;
+use std::fs;
+use tempfile::TempDir;
+
+fn parse_error(content: &str) -> (String, String) {
+  let dir = TempDir::new().expect("failed to create temp dir");
+  let path = dir.path().join("plugin.json");
+  fs::write(&path, content).expect("failed to write plugin.json");
+
+  match load_plugin_config(dir.path()) {
+    Err(ConfigError::ParseError(message)) => (path.display().to_string(), message),
+    other => panic!("expected a parse error, got {:?}", other),
+  }
+}
+
+#[test]
+fn test_missing_comma_reports_line_and_column() {
+  let (path, message) = parse_error(
+    "{\n  \"name\": \"broken\",\n  \"version\": \"1.0.0\"\n  \"description\": \"d\"\n}\n",
+  );
+
+  assert!(
+    message.starts_with(&format!("{}:4:3: ", path)),
+    "unexpected message: {}",
+    message
+  );
+}
+
+#[test]
+fn test_position_is_not_repeated() {
+  let (_, message) = parse_error("{\"name\": }");
+
+  assert!(
+    message.contains(":1:10: "),
+    "unexpected message: {}",
+    message
+  );
+  assert!(
+    !message.contains("at line"),
+    "unexpected message: {}",
+    message
+  );
+}
@@ -0,0 +1,137 @@
+use config::load_plugin_config_validated;
+use shared_types::config::{ConfigError, PermissionGrant, PluginConfigData};
+use std::fs;
+use tempfile::TempDir;
+
+// Helper: load a plugin named "grammar" declaring the given permissions JSON fragment.
+fn load(permissions_json: &str) -> Result<PluginConfigData, ConfigError> {
+  let dir = TempDir::new().expect("failed to create temp dir");
+  let plugin_json = format!(
+    r#"{{
+      "name": "grammar",
+      "version": "1.0.0",
+      "description": "Test plugin",
+      "author": "tester",
+      "permissions": {}
+    }}"#,
+    permissions_json
+  );
+  fs::write(dir.path().join("plugin.json"), plugin_json).expect("failed to write plugin.json");
+  load_plugin_config_validated(dir.path(), "grammar")
+}
+
+fn validation_message(permissions_json: &str) -> String {
+  match load(permissions_json) {
+    Err(ConfigError::ValidationError(message)) => message,
+    other => panic!("expected a validation error, got {:?}", other),
+  }
+}
+
+#[test]
+fn test_well_formed_entries_load() {
+  let config = load(
+    r#"{
+      "filesystem": {"read": ["/data", "./config"], "write": ["/tmp/out"]},
+      "network": {"allowed_hosts": ["example.com", "api.example.com:443", "*.example.org", "[::1]:8080", "*"]},
+      "env": {"keys": ["HOME", "APP_ENV"]}
+    }"#,
+  );
+
+  assert!(config.is_ok(), "unexpected error: {:?}", config.err());
+}
+
+#[test]
+fn test_malformed_host_is_rejected() {
+  let message = validation_message(r#"{"network": {"allowed_hosts": ["https://example.com"]}}"#);
+
+  assert!(
+    message.contains("'net:connect:https://example.com'"),
+    "unexpected message: {}",
+    message
+  );
+}
+
+#[test]
+fn test_bad_ports_are_rejected() {
+  for host in ["example.com:0", "example.com:99999", "example.com:http"] {
+    let message = validation_message(&format!(
+      r#"{{"network": {{"allowed_hosts": ["{}"]}}}}"#,
+      host
+    ));
+    assert!(message.contains(host), "unexpected message: {}", message);
+  }
+}
+
+#[test]
+fn test_every_offending_entry_is_listed() {
+  let message = validation_message(
+    r#"{
+      "filesystem": {"read": [""]},
+      "network": {"allowed_hosts": ["good.example.com", "bad host"]},
+      "env": {"keys": ["A=B", "HOME"]}
+    }"#,
+  );
+
+  let lines: Vec<&str> = message.lines().collect();
+  assert_eq!(lines.len(), 3, "unexpected message: {}", message);
+  assert!(lines[0].contains("'fs:read:'"));
+  assert!(lines[1].contains("'net:connect:bad host'"));
+  assert!(lines[2].contains("'env:A=B'"));
+}
+
+#[test]
+fn test_grants_expand_permissions() {
+  let config = load(
+    r#"{
+      "stdio": {"stdout": true, "stderr": false},
+      "filesystem": {"read": ["/data"], "write": ["/tmp/out"]},
+      "network": true,
+      "env": {"inherit": true, "keys": ["HOME"]},
+      "process": {"exit": false}
+    }"#,
+  )
+  .unwrap();
+
+  assert_eq!(
+    config.permissions.unwrap().grants(),
+    vec![
+      PermissionGrant::Stdout,
+      PermissionGrant::FsRead("/data".to_string()),
+      PermissionGrant::FsWrite("/tmp/out".to_string()),
+      PermissionGrant::NetConnect("*".to_string()),
+      PermissionGrant::EnvInherit,
+      PermissionGrant::EnvKey("HOME".to_string()),
+    ]
+  );
+}
+
+#[test]
+fn test_grant_display_follows_grammar() {
+  let rendered: Vec<String> = [
+    PermissionGrant::Stdin,
+    PermissionGrant::FsRead("/data".to_string()),
+    PermissionGrant::FsWrite("/tmp".to_string()),
+    PermissionGrant::NetConnect("example.com:443".to_string()),
+    PermissionGrant::EnvInherit,
+    PermissionGrant::EnvKey("HOME".to_string()),
+    PermissionGrant::ProcessExit,
+    PermissionGrant::Custom("app:send-email".to_string()),
+  ]
+  .iter()
+  .map(ToString::to_string)
+  .collect();
+
+  assert_eq!(
+    rendered,
+    [
+      "stdio:stdin",
+      "fs:read:/data",
+      "fs:write:/tmp",
+      "net:connect:example.com:443",
+      "env",
+      "env:HOME",
+      "process:exit",
+      "app:send-email",
+    ]
+  );
+}
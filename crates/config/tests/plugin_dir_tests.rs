@@ -0,0 +1,31 @@
+//! Non-UTF-8 directory names can only be created on Unix.
+#![cfg(unix)]
+
+use config::load_all_plugin_configs;
+use std::ffi::OsStr;
+use std::fs;
+use std::os::unix::ffi::OsStrExt;
+use std::path::Path;
+
+fn write_plugin(dir: &Path, name: &str) {
+  fs::create_dir_all(dir).unwrap();
+  fs::write(
+    dir.join("plugin.json"),
+    format!(
+      r#"{{"name": "{}", "version": "1.0.0", "description": "Fixture", "author": "tester"}}"#,
+      name
+    ),
+  )
+  .unwrap();
+}
+
+#[test_log::test]
+fn test_non_utf8_directory_is_skipped() {
+  let root = tempfile::tempdir().unwrap();
+  write_plugin(&root.path().join("good"), "good");
+  // Used to be read as a plugin directory named "unknown".
+  write_plugin(&root.path().join(OsStr::from_bytes(b"bad-\xff")), "unknown");
+
+  let plugins = load_all_plugin_configs(root.path()).expect("load failed");
+  assert_eq!(plugins.keys().collect::<Vec<_>>(), vec!["good"]);
+}
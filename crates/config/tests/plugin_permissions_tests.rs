@@ -151,3 +151,36 @@ fn test_invalid_stdio_type_rejected() {
     "expected validation error for invalid stdio type"
   );
 }
+
+// case 12: custom permissions validate only under a registered prefix
+#[test]
+fn test_custom_permission_prefix() {
+  config::register_permission_prefix("app");
+
+  let perms = load_permissions(r#"{"stdio": true, "app:send-email": true}"#).unwrap();
+  assert_eq!(perms.custom.get("app:send-email"), Some(&true));
+  assert_eq!(perms.stdio, Some(Permission::Bool(true)));
+
+  let rejected = |permissions_json: &str| {
+    let dir = TempDir::new().expect("failed to create temp dir");
+    let plugin_json = format!(
+      r#"{{"name": "test-plugin", "version": "1.0.0", "description": "Test plugin", "author": "tester", "permissions": {}}}"#,
+      permissions_json
+    );
+    fs::write(dir.path().join("plugin.json"), plugin_json).expect("failed to write plugin.json");
+    load_plugin_config(dir.path()).is_err()
+  };
+
+  assert!(
+    rejected(r#"{"billing:charge": true}"#),
+    "unregistered prefix accepted"
+  );
+  assert!(
+    rejected(r#"{"netwrok": true}"#),
+    "misspelled dimension accepted"
+  );
+  assert!(
+    rejected(r#"{"app:send-email": "yes"}"#),
+    "non-boolean value accepted"
+  );
+}
@@ -0,0 +1,94 @@
+use config::{PROFILE_ENV_VAR, load_config, load_config_with_profile};
+use shared_types::config::{ConfigData, ConfigError};
+use std::env;
+use std::path::PathBuf;
+use std::sync::Mutex;
+
+// The loaders read the process-wide current directory and BUD_PROFILE.
+static PROCESS_STATE: Mutex<()> = Mutex::new(());
+
+fn in_profiles_fixture<T>(f: impl FnOnce() -> T) -> T {
+  let _guard = PROCESS_STATE.lock().unwrap_or_else(|p| p.into_inner());
+  let fixture_dir = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+    .join("tests")
+    .join("fixtures")
+    .join("profiles");
+
+  let original_dir = env::current_dir().unwrap();
+  env::set_current_dir(&fixture_dir).unwrap();
+  let result = f();
+  env::set_current_dir(original_dir).unwrap();
+  result
+}
+
+#[test]
+fn test_base_only_ignores_profiles() {
+  let config = in_profiles_fixture(|| {
+    // SAFETY: every test touching BUD_PROFILE holds PROCESS_STATE.
+    unsafe { env::remove_var(PROFILE_ENV_VAR) };
+    load_config()
+  })
+  .expect("base config failed to load");
+
+  assert_eq!(config.version, "0.1.0");
+  assert_eq!(config.description, "Base configuration");
+}
+
+#[test]
+fn test_dev_profile_overrides_base_fields() {
+  let expected = ConfigData {
+    name: "bud".to_string(),
+    version: "0.1.0-dev".to_string(),
+    description: "Development configuration".to_string(),
+    providers: Default::default(),
+  };
+
+  let explicit = in_profiles_fixture(|| load_config_with_profile("dev")).unwrap();
+  assert_eq!(explicit, expected);
+
+  let from_env = in_profiles_fixture(|| {
+    // SAFETY: every test touching BUD_PROFILE holds PROCESS_STATE.
+    unsafe { env::set_var(PROFILE_ENV_VAR, "dev") };
+    let config = load_config();
+    unsafe { env::remove_var(PROFILE_ENV_VAR) };
+    config
+  })
+  .unwrap();
+  assert_eq!(from_env, expected);
+
+  // An empty profile keeps the base fields.
+  let prod = in_profiles_fixture(|| load_config_with_profile("prod")).unwrap();
+  assert_eq!(prod.version, "0.1.0");
+}
+
+#[test]
+fn test_unknown_profile_errors() {
+  match in_profiles_fixture(|| load_config_with_profile("staging")) {
+    Err(ConfigError::UnknownProfile { profile, available }) => {
+      assert_eq!(profile, "staging");
+      assert_eq!(available, "dev, prod");
+    }
+    other => panic!("expected UnknownProfile, got {:?}", other),
+  }
+}
+
+#[test]
+fn test_effective_json_shows_merged_profile() {
+  let config = in_profiles_fixture(|| {
+    // SAFETY: every test touching BUD_PROFILE holds PROCESS_STATE.
+    unsafe { env::set_var(PROFILE_ENV_VAR, "dev") };
+    let config = load_config();
+    unsafe { env::remove_var(PROFILE_ENV_VAR) };
+    config
+  })
+  .unwrap();
+
+  assert_eq!(
+    config.to_effective_json(),
+    serde_json::json!({
+      "name": "bud",
+      "version": "0.1.0-dev",
+      "description": "Development configuration"
+    })
+  );
+}
@@ -0,0 +1,43 @@
+use config::validate_function_args;
+use serde_json::json;
+use shared_types::config::{ConfigError, ValidationIssue};
+
+#[test]
+fn test_each_violation_is_a_separate_issue() {
+  let schema = json!({
+    "type": "object",
+    "properties": {
+      "name": { "type": "string" },
+      "count": { "type": "integer", "minimum": 1 }
+    },
+    "required": ["name"]
+  });
+
+  let err = validate_function_args(&schema, &json!({ "count": 0 })).unwrap_err();
+
+  let ConfigError::SchemaViolations { issues, omitted } = &err else {
+    panic!("expected SchemaViolations, got {:?}", err);
+  };
+  assert_eq!(*omitted, 0);
+  let mut paths: Vec<&str> = issues.iter().map(|issue| issue.path.as_str()).collect();
+  paths.sort();
+  assert_eq!(paths, ["", "/count"]);
+  assert!(
+    issues
+      .iter()
+      .any(|issue| issue.path.is_empty() && issue.message.contains("\"name\"")),
+    "{:?}",
+    issues
+  );
+
+  // Display still renders one `Path '...': message` line per issue.
+  let lines: Vec<String> = err
+    .to_string()
+    .lines()
+    .skip(1)
+    .map(str::to_string)
+    .collect();
+  let rendered: Vec<String> = issues.iter().map(ValidationIssue::to_string).collect();
+  assert_eq!(lines, rendered);
+  assert!(lines.iter().any(|line| line.starts_with("Path '/count': ")));
+}
@@ -0,0 +1,113 @@
+use config::{PROFILE_ENV_VAR, load_config, load_config_with_profile};
+use shared_types::config::{ConfigData, ConfigError};
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use tempfile::TempDir;
+
+// The loaders read the process-wide current directory and BUD_PROFILE.
+static PROCESS_STATE: Mutex<()> = Mutex::new(());
+
+fn fixture_dir(name: &str) -> PathBuf {
+  PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+    .join("tests")
+    .join("fixtures")
+    .join(name)
+}
+
+fn in_dir<T>(dir: &Path, f: impl FnOnce() -> T) -> T {
+  let _guard = PROCESS_STATE.lock().unwrap_or_else(|p| p.into_inner());
+  // SAFETY: every test touching BUD_PROFILE holds PROCESS_STATE.
+  unsafe { env::remove_var(PROFILE_ENV_VAR) };
+
+  let original_dir = env::current_dir().unwrap();
+  env::set_current_dir(dir).unwrap();
+  let result = f();
+  env::set_current_dir(original_dir).unwrap();
+  result
+}
+
+#[test]
+fn test_toml_matches_equivalent_json() {
+  let from_toml = in_dir(&fixture_dir("toml"), load_config).expect("bud.toml failed to load");
+  let from_json = in_dir(&fixture_dir("profiles"), load_config).expect("bud.json failed to load");
+
+  assert_eq!(from_toml, from_json);
+}
+
+#[test]
+fn test_toml_profiles_apply() {
+  let from_toml = in_dir(&fixture_dir("toml"), || load_config_with_profile("dev"))
+    .expect("bud.toml dev profile failed to load");
+  let from_json = in_dir(&fixture_dir("profiles"), || load_config_with_profile("dev"))
+    .expect("bud.json dev profile failed to load");
+
+  assert_eq!(from_toml.version, "0.1.0-dev");
+  assert_eq!(from_toml, from_json);
+}
+
+#[test]
+fn test_json_is_preferred_over_toml() {
+  let dir = TempDir::new().unwrap();
+  fs::write(
+    dir.path().join("bud.json"),
+    r#"{"name": "from-json", "version": "1.0.0", "description": "JSON"}"#,
+  )
+  .unwrap();
+  fs::write(
+    dir.path().join("bud.toml"),
+    "name = \"from-toml\"\nversion = \"1.0.0\"\ndescription = \"TOML\"\n",
+  )
+  .unwrap();
+
+  let config = in_dir(dir.path(), load_config).expect("config failed to load");
+
+  assert_eq!(
+    config,
+    ConfigData {
+      name: "from-json".to_string(),
+      version: "1.0.0".to_string(),
+      description: "JSON".to_string(),
+      providers: Default::default(),
+    }
+  );
+}
+
+#[test]
+fn test_toml_is_validated_against_the_schema() {
+  let dir = TempDir::new().unwrap();
+  fs::write(dir.path().join("bud.toml"), "name = \"bud\"\nversion = 1\n").unwrap();
+
+  let result = in_dir(dir.path(), load_config);
+
+  assert!(
+    matches!(result, Err(ConfigError::SchemaViolations { .. })),
+    "unexpected result: {:?}",
+    result
+  );
+}
+
+#[test]
+fn test_invalid_toml_is_a_parse_error() {
+  let dir = TempDir::new().unwrap();
+  fs::write(dir.path().join("bud.toml"), "name = \n").unwrap();
+
+  let result = in_dir(dir.path(), load_config);
+
+  assert!(
+    matches!(result, Err(ConfigError::ParseError(_))),
+    "unexpected result: {:?}",
+    result
+  );
+}
+
+#[test]
+fn test_missing_both_files_reports_bud_json() {
+  let dir = TempDir::new().unwrap();
+
+  match in_dir(dir.path(), load_config) {
+    Err(ConfigError::FileNotFound(path)) => assert!(path.ends_with("bud.json"), "{}", path),
+    other => panic!("expected FileNotFound, got {:?}", other),
+  }
+}
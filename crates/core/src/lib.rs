@@ -1,23 +1,81 @@
 pub mod plugin;
+mod preflight;
 
 use config::load_config;
 use log::{debug, info};
-use plugin::PluginManager;
+use plugin::{HealthReport, PluginManager};
 use shared_types::config::{ConfigData, ConfigError};
 use shared_types::plugin::PluginError;
 use shared_types::{Provider, ProviderError};
+use std::path::Path;
 use std::sync::{Arc, Once};
 
+pub use preflight::PreflightReport;
+
 static INIT: Once = Once::new();
 
+/// Timestamp style used for log records.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum TimestampFormat {
+  /// RFC 3339 truncated to whole seconds, e.g. `2024-05-01T12:00:00Z`.
+  #[default]
+  Seconds,
+  /// RFC 3339 with millisecond precision, e.g. `2024-05-01T12:00:00.123Z`.
+  Millis,
+  /// RFC 3339 with full (nanosecond) precision, for correlating with other services.
+  Rfc3339,
+  /// No timestamp; useful when the log collector adds its own.
+  None,
+}
+
+/// Returns the logger builder used by `init_logger_with`, without installing it.
+///
+/// Exposed so embedders can adjust the target or filters before calling `init`.
+pub fn logger_builder(format: TimestampFormat) -> env_logger::Builder {
+  let mut builder = env_logger::Builder::from_default_env();
+  builder
+    .filter_level(log::LevelFilter::Info)
+    .format_module_path(true)
+    .format_target(false);
+
+  match format {
+    TimestampFormat::Seconds => builder.format_timestamp_secs(),
+    TimestampFormat::Millis => builder.format_timestamp_millis(),
+    TimestampFormat::Rfc3339 => builder.format_timestamp_nanos(),
+    TimestampFormat::None => builder.format_timestamp(None),
+  };
+
+  builder
+}
+
+/// Initializes the global logger with `TimestampFormat::Seconds`.
 pub fn init_logger() {
+  init_logger_with(TimestampFormat::default());
+}
+
+/// Initializes the global logger with the given timestamp format.
+///
+/// Only the first call in a process takes effect.
+pub fn init_logger_with(format: TimestampFormat) {
+  install_logger(|| logger_builder(format));
+}
+
+/// Initializes the global logger at `level` instead of `Info`, without consulting
+/// `RUST_LOG` for the overall level.
+///
+/// Only the first call in a process takes effect, whichever `init_logger*` it is.
+pub fn init_logger_with_level(level: log::LevelFilter) {
+  install_logger(|| {
+    let mut builder = logger_builder(TimestampFormat::default());
+    builder.filter_level(level);
+    builder
+  });
+}
+
+// Installs the logger `builder` returns, once per process.
+fn install_logger(builder: impl FnOnce() -> env_logger::Builder) {
   INIT.call_once(|| {
-    env_logger::Builder::from_default_env()
-      .filter_level(log::LevelFilter::Info)
-      .format_timestamp_secs()
-      .format_module_path(true)
-      .format_target(false)
-      .init();
+    builder().init();
 
     info!("The initialization of the log system is complete");
     debug!("Debug level log is enabled");
@@ -54,8 +112,21 @@ pub enum BudCoreError {
 /// let provider = WasmProvider::new();
 /// let core = BudCore::builder(provider).build()?;
 /// ```
+///
+/// `build` borrows the builder, so one builder can build several cores. They share
+/// its provider.
 pub struct BudCoreBuilder<P: Provider> {
   provider: Arc<P>,
+  log_level: Option<log::LevelFilter>,
+}
+
+impl<P: Provider> Clone for BudCoreBuilder<P> {
+  fn clone(&self) -> Self {
+    BudCoreBuilder {
+      provider: Arc::clone(&self.provider),
+      log_level: self.log_level,
+    }
+  }
 }
 
 impl<P: Provider> BudCoreBuilder<P> {
@@ -74,30 +145,52 @@ impl<P: Provider> BudCoreBuilder<P> {
   pub fn new(provider: P) -> Self {
     BudCoreBuilder {
       provider: Arc::new(provider),
+      log_level: None,
     }
   }
 
+  /// Set the level `build` initializes the global logger at.
+  ///
+  /// Without it the logger starts at `Info`. Has no effect if the logger was already
+  /// initialized, e.g. by an earlier `build`.
+  #[must_use]
+  pub fn log_level(mut self, level: log::LevelFilter) -> Self {
+    self.log_level = Some(level);
+    self
+  }
+
   /// Build a BudCore instance.
   ///
   /// Steps performed:
-  /// 1. Initialize the logging system
+  /// 1. Initialize the logging system, at `log_level` if set
   /// 2. Load configuration file
-  /// 3. Initialize the Provider runtime instance
-  /// 4. Initialize the plugin manager
+  /// 3. Pass the provider its `providers.<NAME>` settings, if any
+  /// 4. Initialize the Provider runtime instance
+  /// 5. Initialize the plugin manager
   ///
   /// # Errors
   ///
   /// - `BudCoreError::Config` - Configuration loading failed
-  /// - `BudCoreError::ProviderInitFailed` - Provider initialization failed
+  /// - `BudCoreError::ProviderInitFailed` - Provider settings were rejected or initialization failed
   /// - `BudCoreError::Plugin` - Plugin manager initialization failed
-  pub fn build(self) -> Result<BudCore<P>, BudCoreError> {
-    init_logger();
+  pub fn build(&self) -> Result<BudCore<P>, BudCoreError> {
+    match self.log_level {
+      Some(level) => init_logger_with_level(level),
+      None => init_logger(),
+    }
     info!("BudCore Start Init");
 
     let config = Arc::new(load_config()?);
     info!("Config: {:?}", config);
 
-    let provider_instance = self
+    if let Some(settings) = config.providers.get(P::NAME) {
+      self
+        .provider
+        .configure(settings)
+        .map_err(BudCoreError::ProviderInitFailed)?;
+    }
+
+    self
       .provider
       .init()
       .map_err(BudCoreError::ProviderInitFailed)?;
@@ -156,4 +249,49 @@ impl<P: Provider> BudCore<P> {
   pub fn builder(provider: P) -> BudCoreBuilder<P> {
     BudCoreBuilder::new(provider)
   }
+
+  /// Report provider and plugin health, e.g. for a `/healthz` handler.
+  ///
+  /// See `PluginManager::health`.
+  pub fn health(&self) -> HealthReport {
+    self.plugin_manager.health()
+  }
+
+  /// Dump the effective configuration this instance was built with.
+  ///
+  /// Shows `bud.json` after the `BUD_PROFILE` profile was merged in, as JSON.
+  pub fn dump_config(&self) -> serde_json::Value {
+    self.config.to_effective_json()
+  }
+
+  /// Validate `bud.json` and every installed plugin without building an instance.
+  ///
+  /// Loads the config from the current directory like `build`, then checks each
+  /// plugin in the application's data directory for a valid manifest and the
+  /// provider's entry file (see `PluginManager::find_orphans`). Nothing is
+  /// initialized or loaded, and all problems are collected rather than stopping at
+  /// the first.
+  pub fn preflight() -> PreflightReport {
+    let data_path = load_config()
+      .map_err(BudCoreError::from)
+      .and_then(|config| Ok(PluginManager::<P>::get_project_data_path(&config.name)?));
+
+    match data_path {
+      Ok(data_path) => Self::preflight_with_data_path(&data_path),
+      Err(e) => PreflightReport {
+        config_error: Some(e.to_string()),
+        plugin_problems: Vec::new(),
+      },
+    }
+  }
+
+  /// Like `preflight`, but checks the plugins under `project_data_path`.
+  ///
+  /// Meant for tests and tools that keep plugins outside the default data directory.
+  pub fn preflight_with_data_path(project_data_path: &Path) -> PreflightReport {
+    PreflightReport {
+      config_error: load_config().err().map(|e| e.to_string()),
+      plugin_problems: PluginManager::<P>::find_orphans_in(project_data_path),
+    }
+  }
 }
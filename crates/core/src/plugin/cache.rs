@@ -0,0 +1,131 @@
+use config::PLUGIN_CONFIG_FILE;
+use log::warn;
+use serde::{Deserialize, Serialize};
+use shared_types::config::PluginConfigData;
+use std::collections::HashMap;
+use std::collections::hash_map::DefaultHasher;
+use std::fs;
+use std::hash::Hasher;
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+/// File name of the persisted manifest cache, stored in the project data directory.
+pub const CACHE_FILE: &str = "cache.json";
+
+/// Identifies one revision of a plugin's `plugin.json`.
+///
+/// The content hash uses `DefaultHasher`, which is only stable within a toolchain;
+/// a mismatch after an upgrade merely causes the manifest to be re-parsed.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+struct Fingerprint {
+  modified: SystemTime,
+  hash: u64,
+}
+
+impl Fingerprint {
+  fn of(plugin_dir: &Path) -> Option<Self> {
+    let manifest = plugin_dir.join(PLUGIN_CONFIG_FILE);
+    let modified = fs::metadata(&manifest).and_then(|m| m.modified()).ok()?;
+    let contents = fs::read(&manifest).ok()?;
+
+    let mut hasher = DefaultHasher::new();
+    hasher.write(&contents);
+
+    Some(Self {
+      modified,
+      hash: hasher.finish(),
+    })
+  }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct CacheEntry {
+  fingerprint: Fingerprint,
+  config: PluginConfigData,
+}
+
+/// Validated plugin manifests persisted across process starts.
+///
+/// Entries are keyed by plugin directory name and reused only while the manifest's
+/// mtime and content hash are unchanged. The cache is best effort: a missing or
+/// unreadable file starts empty and write failures are logged.
+pub(crate) struct ManifestCache {
+  path: PathBuf,
+  entries: HashMap<String, CacheEntry>,
+  dirty: bool,
+}
+
+impl ManifestCache {
+  /// Reads `cache.json` from `data_dir`.
+  pub(crate) fn open(data_dir: &Path) -> Self {
+    let path = data_dir.join(CACHE_FILE);
+
+    let entries = match fs::read(&path) {
+      Ok(bytes) => serde_json::from_slice(&bytes).unwrap_or_else(|e| {
+        warn!(
+          "Ignoring unreadable plugin cache '{}': {}",
+          path.display(),
+          e
+        );
+        HashMap::new()
+      }),
+      Err(_) => HashMap::new(),
+    };
+
+    Self {
+      path,
+      entries,
+      dirty: false,
+    }
+  }
+
+  /// Returns the cached config for `name` if its manifest is unchanged.
+  pub(crate) fn get_fresh(&self, name: &str, plugin_dir: &Path) -> Option<PluginConfigData> {
+    let entry = self.entries.get(name)?;
+    let current = Fingerprint::of(plugin_dir)?;
+    (entry.fingerprint == current).then(|| entry.config.clone())
+  }
+
+  /// Records a freshly validated config for `name`.
+  pub(crate) fn insert(&mut self, name: &str, plugin_dir: &Path, config: &PluginConfigData) {
+    let Some(fingerprint) = Fingerprint::of(plugin_dir) else {
+      return;
+    };
+
+    self.entries.insert(
+      name.to_string(),
+      CacheEntry {
+        fingerprint,
+        config: config.clone(),
+      },
+    );
+    self.dirty = true;
+  }
+
+  /// Drops entries for plugins that are no longer installed.
+  pub(crate) fn retain<F: FnMut(&str) -> bool>(&mut self, mut keep: F) {
+    let before = self.entries.len();
+    self.entries.retain(|name, _| keep(name));
+    self.dirty |= self.entries.len() != before;
+  }
+
+  /// Writes the cache back to disk if it changed since it was opened or last saved.
+  pub(crate) fn save(&mut self) {
+    if !self.dirty {
+      return;
+    }
+
+    let result = serde_json::to_vec_pretty(&self.entries)
+      .map_err(std::io::Error::other)
+      .and_then(|bytes| fs::write(&self.path, bytes));
+
+    match result {
+      Ok(()) => self.dirty = false,
+      Err(e) => warn!(
+        "Failed to write plugin cache '{}': {}",
+        self.path.display(),
+        e
+      ),
+    }
+  }
+}
@@ -0,0 +1,65 @@
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+use std::fs;
+use std::io;
+use std::path::Path;
+
+/// File name of the checksum manifest written into each installed plugin's directory.
+///
+/// One line per regular file, `<sha256 hex>  <path relative to the plugin directory>`,
+/// the same layout `sha256sum` prints, sorted by path.
+pub const CHECKSUMS_FILE: &str = ".bud-manifest";
+
+/// SHA-256 of the file at `path`, as lowercase hex.
+pub(crate) fn file_digest(path: &Path) -> io::Result<String> {
+  let digest = Sha256::digest(fs::read(path)?);
+  Ok(digest.iter().map(|byte| format!("{:02x}", byte)).collect())
+}
+
+/// Writes `CHECKSUMS_FILE` into `plugin_dir`, covering every regular file below it.
+///
+/// Symbolic links are not followed, so a link's target is neither hashed nor verified.
+pub(crate) fn write(plugin_dir: &Path) -> io::Result<()> {
+  let mut lines = Vec::new();
+  collect(plugin_dir, Path::new(""), &mut lines)?;
+  lines.sort();
+
+  let mut contents = String::new();
+  for (relative, digest) in lines {
+    contents.push_str(&format!("{}  {}\n", digest, relative));
+  }
+  fs::write(plugin_dir.join(CHECKSUMS_FILE), contents)
+}
+
+/// Reads `plugin_dir`'s manifest into digests by relative path, or `None` for a plugin
+/// installed without one.
+pub(crate) fn read(plugin_dir: &Path) -> io::Result<Option<HashMap<String, String>>> {
+  let contents = match fs::read_to_string(plugin_dir.join(CHECKSUMS_FILE)) {
+    Ok(contents) => contents,
+    Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(None),
+    Err(e) => return Err(e),
+  };
+  let digests = contents
+    .lines()
+    .filter_map(|line| line.split_once("  "))
+    .map(|(digest, path)| (path.to_string(), digest.to_string()))
+    .collect();
+  Ok(Some(digests))
+}
+
+// Pushes `(path, digest)` for each regular file under `dir`, with paths relative to the
+// plugin directory and `/`-separated on every platform.
+fn collect(dir: &Path, relative: &Path, lines: &mut Vec<(String, String)>) -> io::Result<()> {
+  for entry in fs::read_dir(dir)? {
+    let entry = entry?;
+    let file_type = entry.file_type()?;
+    let relative = relative.join(entry.file_name());
+    if file_type.is_dir() {
+      collect(&entry.path(), &relative, lines)?;
+    } else if file_type.is_file() && relative != Path::new(CHECKSUMS_FILE) {
+      let path = relative.to_string_lossy().replace('\\', "/");
+      lines.push((path, file_digest(&entry.path())?));
+    }
+  }
+  Ok(())
+}
@@ -0,0 +1,69 @@
+use std::path::PathBuf;
+use std::sync::Mutex;
+use std::sync::mpsc::{Receiver, Sender, channel};
+use std::time::Duration;
+
+/// Lifecycle event published by `PluginManager`
+#[derive(Debug, Clone, PartialEq)]
+pub enum PluginEvent {
+  /// A plugin was copied into the data path
+  PluginInstalled {
+    name: String,
+    version: String,
+    source: PathBuf,
+    destination: PathBuf,
+    /// Names of every installed plugin after the install, sorted
+    installed: Vec<String>,
+  },
+  /// A plugin was removed from the data path
+  PluginUninstalled { name: String },
+  /// A plugin was loaded into the provider
+  PluginLoaded { name: String },
+  /// A plugin was removed from the provider
+  PluginUnloaded { name: String },
+  /// A plugin was unloaded and loaded again by `PluginManager::reload`
+  PluginReloaded {
+    name: String,
+    /// Whether the reloaded main file differs from the one loaded before
+    changed: bool,
+  },
+  /// A plugin function returned successfully
+  PluginInvoked {
+    name: String,
+    function: String,
+    duration: Duration,
+  },
+  /// Loading or invoking a plugin failed
+  PluginFailed { name: String, error: String },
+}
+
+/// Fan-out of `PluginEvent`s to any number of subscribers
+///
+/// Each subscriber owns an unbounded channel. Subscribers whose receiver has been
+/// dropped are pruned on the next publish, so publishing never fails.
+#[derive(Default)]
+pub(crate) struct EventBus {
+  subscribers: Mutex<Vec<Sender<PluginEvent>>>,
+}
+
+impl EventBus {
+  /// Registers a new subscriber and returns its receiving end
+  pub(crate) fn subscribe(&self) -> Receiver<PluginEvent> {
+    let (sender, receiver) = channel();
+    self
+      .subscribers
+      .lock()
+      .unwrap_or_else(|p| p.into_inner())
+      .push(sender);
+    receiver
+  }
+
+  /// Sends `event` to every live subscriber
+  pub(crate) fn publish(&self, event: PluginEvent) {
+    self
+      .subscribers
+      .lock()
+      .unwrap_or_else(|p| p.into_inner())
+      .retain(|sender| sender.send(event.clone()).is_ok());
+  }
+}
@@ -1,25 +1,102 @@
-use config::{load_all_plugin_configs, load_plugin_config, load_plugin_config_validated};
+use super::cache::ManifestCache;
+use super::checksums;
+use super::events::{EventBus, PluginEvent};
+use super::results::{DEFAULT_RESULT_CACHE_CAPACITY, ResultCache, ResultKey};
+use super::state;
+use config::{
+  PLUGIN_CONFIG_FILE, load_all_plugin_configs, load_plugin_config, load_plugin_config_validated,
+  validate_function_args,
+};
 use directories::ProjectDirs;
-use log::error;
-use shared_types::config::{ConfigData, PluginConfigData};
+use log::{error, info, warn};
+use shared_types::{Provider, ProviderError};
+use shared_types::config::{ConfigData, PermissionKind, PluginConfigData, host_target};
 use shared_types::plugin::PluginError;
-use shared_types::Provider;
-use shared_types::ProviderValue;
+use shared_types::{FunctionSignature, ProviderValue};
+use std::collections::HashSet;
+use std::fmt;
+use std::collections::hash_map::DefaultHasher;
 use std::fs::create_dir_all;
+use std::hash::Hasher;
+use std::path::{Component, Path};
+use std::sync::mpsc::Receiver;
+use std::time::{Duration, Instant};
 use std::{collections::HashMap, path::PathBuf, sync::Arc};
-use utils::copy_dir_recursive;
+use utils::{SymlinkPolicy, copy_dir_atomic};
+use utils::provider_json::{json_to_provider_value, try_args_to_json};
 
 /// Manages plugin lifecycle and operations
 ///
 /// Uses `Arc<Config>` to share configuration efficiently across multiple components
 /// without cloning the potentially large configuration object.
+///
+/// Plugins are stored and cached under their key: the `id` from plugin.json, or the
+/// `name` for plugins without one. Methods taking a plugin name accept either the key
+/// or the display name of a cached plugin
 pub struct PluginManager<P: Provider> {
   config: Arc<ConfigData>,
   project_data_path: PathBuf,
   plugin_cache: HashMap<String, PluginConfigData>,
+  manifest_cache: ManifestCache,
+  allowed_permissions: Option<HashSet<PermissionKind>>,
+  system_path: Option<PathBuf>,
+  precompiled_dir: Option<PathBuf>,
+  value_bounds: Option<(usize, usize)>,
+  require_entry_file: bool,
+  results: ResultCache,
+  // In-memory config overlays by plugin key, merged over the manifest by `get`.
+  overlays: HashMap<String, serde_json::Value>,
+  // Hash of each loaded plugin's main file as it was loaded, compared by `reload`.
+  loaded_hashes: HashMap<String, u64>,
+  // Lifecycle of plugins loaded through the manager; see `status`.
+  lifecycle: HashMap<String, PluginLifecycle>,
+  events: EventBus,
   provider: Arc<P>,
 }
 
+/// Search directory a resolved plugin was found in
+///
+/// User-installed plugins (the project data path) shadow system plugins of the same name.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Origin {
+  /// The read-only system search path set with `PluginManager::with_system_path`
+  System,
+  /// The per-user project data path that `install` writes to
+  User,
+}
+
+/// Where a plugin is in its lifecycle, as reported by `PluginManager::status`
+///
+/// Plugins move `Installed` → `Loaded` on `load`, `Loaded` → `Failed` when a call
+/// traps, and `Loaded` or `Failed` → `Draining` on `drain`; `unload` returns any of
+/// the last three to `Installed`. Calls are only accepted while `Loaded`, and a
+/// `Draining` plugin cannot be loaded or reloaded until it is unloaded.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PluginLifecycle {
+  /// No plugin directory of that name in the user or system search path
+  NotInstalled,
+  /// On disk but not loaded into the provider
+  Installed,
+  /// Loaded and accepting calls
+  Loaded,
+  /// Loaded, but a trap left it unusable until it is reloaded
+  Failed,
+  /// Loaded, but refusing new calls until it is unloaded
+  Draining,
+}
+
+impl fmt::Display for PluginLifecycle {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    f.write_str(match self {
+      PluginLifecycle::NotInstalled => "not installed",
+      PluginLifecycle::Installed => "installed",
+      PluginLifecycle::Loaded => "loaded",
+      PluginLifecycle::Failed => "failed",
+      PluginLifecycle::Draining => "draining",
+    })
+  }
+}
+
 /// Contains plugin configuration and its filesystem path
 ///
 /// Returned by plugin query methods to provide both the configuration
@@ -29,6 +106,49 @@ pub struct PluginInfo {
   pub path: PathBuf,
 }
 
+/// Why a directory in the data path is not a loadable plugin
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum OrphanReason {
+  /// The directory has no `plugin.json`
+  MissingManifest,
+  /// `plugin.json` exists but fails parsing or validation
+  InvalidConfig(String),
+  /// The manifest is valid but the provider's entry file (e.g. `main.wasm`) is missing
+  MissingEntryFile,
+}
+
+/// A directory in the data path that `load` could not use, as found by `find_orphans`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OrphanReport {
+  pub path: PathBuf,
+  pub reason: OrphanReason,
+}
+
+/// Results of `PluginManager::invoke_batch`, one per attempted call
+#[derive(Debug)]
+pub struct BatchOutcome {
+  pub results: Vec<Result<ProviderValue, PluginError>>,
+  /// Index of the call that left the plugin unusable and ended the batch early
+  pub stopped_at: Option<usize>,
+}
+
+/// Snapshot of provider and plugin status, as returned by `PluginManager::health`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HealthReport {
+  pub provider_initialized: bool,
+  /// Names of the plugins loaded into the provider, sorted
+  pub loaded_plugins: Vec<String>,
+  /// Loaded plugins that trapped and must be reloaded, sorted
+  pub failed_plugins: Vec<String>,
+}
+
+impl HealthReport {
+  /// Returns `true` if the provider is initialized and no plugin has failed
+  pub fn is_healthy(&self) -> bool {
+    self.provider_initialized && self.failed_plugins.is_empty()
+  }
+}
+
 impl<P: Provider> PluginManager<P> {
   /// Gets the project data directory path
   ///
@@ -39,7 +159,7 @@ impl<P: Provider> PluginManager<P> {
   /// # Errors
   ///
   /// Returns `PluginError::ProjectDirsError` if the project directories cannot be determined
-  fn get_project_data_path(name: &str) -> Result<PathBuf, PluginError> {
+  pub(crate) fn get_project_data_path(name: &str) -> Result<PathBuf, PluginError> {
     let project_path =
       ProjectDirs::from("com", "bud", name).ok_or(PluginError::ProjectDirsError)?;
 
@@ -56,10 +176,170 @@ impl<P: Provider> PluginManager<P> {
     &self.config
   }
 
+  /// Restricts the permission dimensions any plugin may request
+  ///
+  /// This host-level policy sits above each plugin's own declarations: `install` and
+  /// `load` reject a plugin that requests a dimension outside `allowed`. Without a
+  /// policy every declared permission is accepted.
+  ///
+  /// # Examples
+  ///
+  /// ```
+  /// let manager = PluginManager::new(config, provider)?
+  ///   .with_allowed_permissions([PermissionKind::Stdio, PermissionKind::Env]);
+  /// ```
+  #[must_use]
+  pub fn with_allowed_permissions(
+    mut self,
+    allowed: impl IntoIterator<Item = PermissionKind>,
+  ) -> Self {
+    self.allowed_permissions = Some(allowed.into_iter().collect());
+    self
+  }
+
+  /// Adds a system-wide plugin search directory
+  ///
+  /// Plugins in this directory are never written to and are only used when the user
+  /// data path has no plugin of the same name; see `resolve`.
+  #[must_use]
+  pub fn with_system_path(mut self, system_path: PathBuf) -> Self {
+    self.system_path = Some(system_path);
+    self
+  }
+
+  /// Loads plugins exclusively from artifacts written by `precompile_all`
+  ///
+  /// `load` then never compiles: a plugin without an artifact matching its current
+  /// `main` file fails to load instead of falling back to the source. An artifact
+  /// that exists but cannot be loaded (corrupt, or written by an incompatible
+  /// runtime) is the exception: it is replaced by recompiling from the source.
+  #[must_use]
+  pub fn with_precompiled_dir(mut self, cache_dir: PathBuf) -> Self {
+    self.precompiled_dir = Some(cache_dir);
+    self
+  }
+
+  /// Bounds the size of each argument passed to `invoke_json`
+  ///
+  /// Arguments nested deeper than `max_depth` or holding more than `max_nodes` values
+  /// are rejected before they reach the plugin (see `ProviderValue::validate_bounds`).
+  /// Without this, `invoke_json` accepts arguments of any size.
+  #[must_use]
+  pub fn with_value_bounds(mut self, max_depth: usize, max_nodes: usize) -> Self {
+    self.value_bounds = Some((max_depth, max_nodes));
+    self
+  }
+
+  /// Makes `install` reject a source directory without the provider's entry file
+  ///
+  /// The entry file (the manifest's `main`, or `Provider::MAIN_FILE` such as
+  /// `main.wasm`) is checked before anything is copied, so a packaging mistake fails
+  /// at install time instead of at the first `load`. Without this, only the manifest
+  /// is checked on install.
+  #[must_use]
+  pub fn with_entry_file_check(mut self) -> Self {
+    self.require_entry_file = true;
+    self
+  }
+
+  /// Sets how many results `invoke_cached` and cacheable functions keep
+  ///
+  /// Defaults to `DEFAULT_RESULT_CACHE_CAPACITY`; the least recently used result is
+  /// evicted first, and `0` disables result caching.
+  #[must_use]
+  pub fn with_result_cache_capacity(mut self, capacity: usize) -> Self {
+    self.results.set_capacity(capacity);
+    self
+  }
+
+  /// Compiles every installed plugin ahead of time into `cache_dir`
+  ///
+  /// Meant for build time, so deployed hosts configured with `with_precompiled_dir`
+  /// never compile. Artifacts are named after the plugin and a hash of its main file,
+  /// so a changed plugin never picks up a stale artifact. The provider must already be
+  /// initialized.
+  ///
+  /// # Errors
+  ///
+  /// * `PluginError::LoadError` - If the provider fails to compile a plugin
+  /// * `PluginError::IoError` - If `cache_dir` cannot be created or a main file cannot be read
+  pub fn precompile_all(&self, cache_dir: &Path) -> Result<(), PluginError> {
+    create_dir_all(cache_dir)?;
+
+    for (name, config) in &self.plugin_cache {
+      let plugin_dir = self.project_data_path.join(name);
+      let artifact = precompiled_artifact_path::<P>(cache_dir, name, &plugin_dir, config)?;
+
+      self
+        .provider
+        .precompile(&plugin_dir, config, &artifact)
+        .map_err(|e| PluginError::LoadError(format!("Failed to precompile '{}': {}", name, e)))?;
+    }
+
+    Ok(())
+  }
+
+  /// Checks a plugin's requested permissions against the host policy
+  ///
+  /// # Errors
+  ///
+  /// Returns `PluginError::LoadError` naming the first requested permission that the
+  /// policy does not allow
+  fn check_permission_policy(&self, plugin_config: &PluginConfigData) -> Result<(), PluginError> {
+    let (Some(allowed), Some(permissions)) =
+      (&self.allowed_permissions, &plugin_config.permissions)
+    else {
+      return Ok(());
+    };
+
+    match permissions
+      .requested()
+      .into_iter()
+      .find(|kind| !allowed.contains(kind))
+    {
+      Some(kind) => {
+        error!(
+          "Plugin '{}' requests permission {} which the host policy forbids",
+          plugin_config.name, kind
+        );
+        Err(PluginError::LoadError(format!(
+          "permission {} not allowed by host policy",
+          kind
+        )))
+      }
+      None => Ok(()),
+    }
+  }
+
+  /// Checks that a plugin's declared `targets` include the running host
+  ///
+  /// # Errors
+  ///
+  /// Returns `PluginError::LoadError` naming the plugin's targets and the host's
+  fn check_target(plugin_config: &PluginConfigData) -> Result<(), PluginError> {
+    if plugin_config.supports_host() {
+      return Ok(());
+    }
+
+    let msg = format!(
+      "plugin '{}' is built for {} but this host is {}",
+      plugin_config.key(),
+      plugin_config.targets.join(", "),
+      host_target()
+    );
+    error!("{}", msg);
+    Err(PluginError::LoadError(msg))
+  }
+
   /// Installs a plugin from the given directory into the project data path
   ///
   /// Reads `plugin.json` from `dir_path` to determine the plugin name, then recursively
-  /// copies all files and subdirectories to `project_data_path/<plugin_name>/`.
+  /// copies its files and subdirectories to `project_data_path/<plugin_name>/`. Left
+  /// out are `.git` directories, a top-level `target` or `node_modules`, and editor
+  /// leftovers (`*~`, `*.swp`, `.DS_Store`). Symbolic links are recreated as links
+  /// rather than followed. The copy is only moved into place once complete, so a
+  /// failed install leaves no partial plugin behind. The SHA-256 of every copied file
+  /// is recorded in `CHECKSUMS_FILE`, which `load` checks the entry file against.
   ///
   /// # Arguments
   ///
@@ -71,45 +351,281 @@ impl<P: Provider> PluginManager<P> {
   ///
   /// # Errors
   ///
-  /// * `PluginError::InstallError` - If `dir_path` is not a directory, or `plugin.json` is missing/invalid
+  /// * `PluginError::InstallError` - If `dir_path` is not a directory, overlaps the data directory,
+  ///   `plugin.json` is missing/invalid, or the entry file is missing under
+  ///   `with_entry_file_check`
+  /// * `PluginError::LoadError` - If the plugin requests a permission the host policy forbids,
+  ///   or declares `targets` that exclude the host
   /// * `PluginError::IoError` - If directory creation or file copy operations fail
-  pub fn install(&mut self, dir_path: &PathBuf) -> Result<(), PluginError> {
-    if !dir_path.is_dir() {
-      let msg = format!("Path is not a directory: {}", dir_path.display());
+  pub fn install(&mut self, dir_path: &Path) -> Result<(), PluginError> {
+    self.install_checked(dir_path, None)
+  }
+
+  /// Installs a plugin only if its entry file has a known-good SHA-256 digest
+  ///
+  /// Behaves like `install`, but first hashes the entry file in `dir_path` and refuses
+  /// the plugin unless the digest equals `sha256` (hex, in either case). Nothing is
+  /// copied on a mismatch.
+  ///
+  /// # Errors
+  ///
+  /// * `PluginError::InstallError` - As `install`, or if the digest does not match
+  /// * `PluginError::LoadError` - As `install`
+  /// * `PluginError::IoError` - As `install`, or if the entry file cannot be read
+  pub fn install_pinned(&mut self, dir_path: &Path, sha256: &str) -> Result<(), PluginError> {
+    self.install_checked(dir_path, Some(sha256))
+  }
+
+  fn install_checked(&mut self, dir_path: &Path, sha256: Option<&str>) -> Result<(), PluginError> {
+    let plugin_config = self.check_install_source(dir_path)?;
+    let plugin_name = plugin_config.key().to_string();
+
+    // Check if plugin is already installed
+    if self.project_data_path.join(&plugin_name).is_dir() {
+      let msg = format!("plugin {} is already installed", plugin_name);
       error!("{}", msg);
       return Err(PluginError::InstallError(msg));
     }
 
-    let plugin_config = load_plugin_config(&dir_path)
-      .map_err(|e| PluginError::InstallError(format!("Failed to read plugin config: {}", e)))?;
+    if let Some(expected) = sha256 {
+      let entry_file = plugin_config.entry_file(P::MAIN_FILE);
+      let actual = checksums::file_digest(&dir_path.join(entry_file))?;
+      if !actual.eq_ignore_ascii_case(expected) {
+        let msg = format!(
+          "hash mismatch for {}: expected {}, found {}",
+          entry_file, expected, actual
+        );
+        error!("{} in {}", msg, dir_path.display());
+        return Err(PluginError::InstallError(msg));
+      }
+    }
 
-    let plugin_name = plugin_config.name.clone();
+    self.copy_in(dir_path, plugin_config)
+  }
 
+  /// Installs a plugin, replacing an installed copy with an older version
+  ///
+  /// Runs the same checks as `install`, then compares the incoming `version` with the
+  /// installed one as semantic versions: only a strictly greater version replaces it,
+  /// unless `force` allows reinstalling the same version or downgrading. A plugin that
+  /// is not installed yet is simply installed. A loaded plugin keeps running the old
+  /// version until it is loaded again (see `reload`).
+  ///
+  /// # Arguments
+  ///
+  /// * `dir_path` - Path to the source directory containing plugin files and `plugin.json`
+  /// * `force` - Skip the version comparison
+  ///
+  /// # Errors
+  ///
+  /// * `PluginError::InstallError` - As `install`, or if either version is not a valid
+  ///   semantic version or the incoming one is not newer without `force`
+  /// * `PluginError::LoadError` - As `install`
+  /// * `PluginError::IoError` - If removing the old copy or copying the new one fails
+  pub fn upgrade(&mut self, dir_path: &Path, force: bool) -> Result<(), PluginError> {
+    let plugin_config = self.check_install_source(dir_path)?;
+    let plugin_name = plugin_config.key().to_string();
     let dest_dir = self.project_data_path.join(&plugin_name);
+    if !dest_dir.is_dir() {
+      return self.copy_in(dir_path, plugin_config);
+    }
 
-    // Check if plugin is already installed
-    if dest_dir.is_dir() {
-      let msg = format!("plugin {} is already installed", plugin_name);
+    if !force {
+      let installed = load_plugin_config(&dest_dir).map_err(|e| {
+        PluginError::InstallError(format!("Failed to read installed plugin config: {}", e))
+      })?;
+      let parse = |version: &str| {
+        semver::Version::parse(version).map_err(|e| {
+          PluginError::InstallError(format!("invalid version '{}': {}", version, e))
+        })
+      };
+      if parse(&plugin_config.version)? <= parse(&installed.version)? {
+        let msg = format!(
+          "plugin {} {} is not newer than the installed {}",
+          plugin_name, plugin_config.version, installed.version
+        );
+        error!("{}", msg);
+        return Err(PluginError::InstallError(msg));
+      }
+    }
+
+    info!(
+      "Replacing installed plugin '{}' with version {}",
+      plugin_name, plugin_config.version
+    );
+    let enabled = state::is_enabled(&dest_dir);
+    std::fs::remove_dir_all(&dest_dir)?;
+    self.results.remove_plugin(&plugin_name);
+    self.copy_in(dir_path, plugin_config)?;
+    if !enabled {
+      state::set_enabled(&dest_dir, false)?;
+    }
+    Ok(())
+  }
+
+  // Checks run before anything is copied by `install` and `upgrade`, returning the
+  // incoming plugin's config.
+  fn check_install_source(&self, dir_path: &Path) -> Result<PluginConfigData, PluginError> {
+    if !dir_path.is_dir() {
+      let msg = format!("Path is not a directory: {}", dir_path.display());
       error!("{}", msg);
       return Err(PluginError::InstallError(msg));
     }
 
-    create_dir_all(&dest_dir)?;
+    // Copying between overlapping trees would recurse into its own output
+    let source = normalize_path(dir_path);
+    let data_dir = normalize_path(&self.project_data_path);
+    if source.starts_with(&data_dir) || data_dir.starts_with(&source) {
+      let msg = "cannot install from within the data directory".to_string();
+      error!("{}: {}", msg, dir_path.display());
+      return Err(PluginError::InstallError(msg));
+    }
+
+    let plugin_config = load_plugin_config(dir_path)
+      .map_err(|e| PluginError::InstallError(format!("Failed to read plugin config: {}", e)))?;
 
-    copy_dir_recursive(&dir_path, &dest_dir)?;
+    self.check_permission_policy(&plugin_config)?;
+    Self::check_target(&plugin_config)?;
 
-    self.plugin_cache.insert(plugin_name, plugin_config);
+    let entry_file = plugin_config.entry_file(P::MAIN_FILE);
+    if self.require_entry_file && !dir_path.join(entry_file).is_file() {
+      let msg = format!("missing entry file {}", entry_file);
+      error!("{} in {}", msg, dir_path.display());
+      return Err(PluginError::InstallError(msg));
+    }
+
+    Ok(plugin_config)
+  }
+
+  // Copies a checked plugin into the data path and registers it.
+  fn copy_in(&mut self, dir_path: &Path, plugin_config: PluginConfigData) -> Result<(), PluginError> {
+    let plugin_name = plugin_config.key().to_string();
+    let dest_dir = self.project_data_path.join(&plugin_name);
+
+    create_dir_all(&self.project_data_path)?;
+
+    copy_dir_atomic(dir_path, &dest_dir, SymlinkPolicy::default(), |path| {
+      !is_dev_noise(path)
+    })?;
+    if let Err(e) = checksums::write(&dest_dir) {
+      error!("Failed to record checksums for '{}': {}", plugin_name, e);
+      std::fs::remove_dir_all(&dest_dir)?;
+      return Err(e.into());
+    }
+
+    self
+      .manifest_cache
+      .insert(&plugin_name, &dest_dir, &plugin_config);
+    self.manifest_cache.save();
+    let version = plugin_config.version.clone();
+    self.plugin_cache.insert(plugin_name.clone(), plugin_config);
+
+    let mut installed: Vec<String> = self.plugin_cache.keys().cloned().collect();
+    installed.sort();
+    info!(
+      "Installed plugin '{}' {} from {} to {} (installed: {})",
+      plugin_name,
+      version,
+      dir_path.display(),
+      dest_dir.display(),
+      installed.join(", ")
+    );
+    self.events.publish(PluginEvent::PluginInstalled {
+      name: plugin_name,
+      version,
+      source: dir_path.to_path_buf(),
+      destination: dest_dir,
+      installed,
+    });
 
     Ok(())
   }
 
+  /// Lists the installed plugins by directory name, without reading their manifests
+  ///
+  /// A cheap alternative to `get_all` for menus and the like: every subdirectory of
+  /// the data path holding a `plugin.json` counts, valid or not, so callers should
+  /// expect `get` to fail for some names. Names are sorted; directories whose name is
+  /// not UTF-8 are skipped. A data path that does not exist yet lists nothing.
+  ///
+  /// # Errors
+  ///
+  /// Returns `PluginError::IoError` if the data path exists but cannot be read
+  pub fn list_installed(&self) -> Result<Vec<String>, PluginError> {
+    let entries = match std::fs::read_dir(&self.project_data_path) {
+      Ok(entries) => entries,
+      Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+      Err(e) => return Err(e.into()),
+    };
+
+    let mut names: Vec<String> = entries
+      .flatten()
+      .map(|entry| entry.path())
+      .filter(|path| path.join(PLUGIN_CONFIG_FILE).is_file())
+      .filter_map(|path| path.file_name()?.to_str().map(str::to_string))
+      .collect();
+
+    names.sort();
+    Ok(names)
+  }
+
+  /// Scans the data directory for plugin directories that cannot be loaded
+  ///
+  /// Reports directories without a manifest, with an invalid one, or without the
+  /// provider's entry file, typically left behind by failed installs or manual edits.
+  /// Plain files such as `cache.json` are metadata and never reported. Nothing is
+  /// removed; reports are sorted by path.
+  pub fn find_orphans(&self) -> Vec<OrphanReport> {
+    Self::find_orphans_in(&self.project_data_path)
+  }
+
+  // `find_orphans` for an arbitrary data directory, so it can run without a manager.
+  pub(crate) fn find_orphans_in(project_data_path: &Path) -> Vec<OrphanReport> {
+    let Ok(entries) = std::fs::read_dir(project_data_path) else {
+      return Vec::new();
+    };
+
+    let mut orphans: Vec<OrphanReport> = entries
+      .flatten()
+      .map(|entry| entry.path())
+      .filter(|path| path.is_dir())
+      .filter_map(|path| {
+        let reason = Self::orphan_reason(&path)?;
+        Some(OrphanReport { path, reason })
+      })
+      .collect();
+
+    orphans.sort_by(|a, b| a.path.cmp(&b.path));
+    orphans
+  }
+
+  // Returns why `plugin_dir` is not loadable, or `None` if it is a valid plugin.
+  fn orphan_reason(plugin_dir: &Path) -> Option<OrphanReason> {
+    if !plugin_dir.join(PLUGIN_CONFIG_FILE).is_file() {
+      return Some(OrphanReason::MissingManifest);
+    }
+
+    let name = plugin_dir.file_name()?.to_string_lossy();
+    let config = match load_plugin_config_validated(plugin_dir, &name) {
+      Ok(config) => config,
+      Err(e) => return Some(OrphanReason::InvalidConfig(e.to_string())),
+    };
+
+    if !plugin_dir.join(config.entry_file(P::MAIN_FILE)).is_file() {
+      return Some(OrphanReason::MissingEntryFile);
+    }
+
+    None
+  }
+
   /// Loads all plugin configurations and populates the cache
   ///
   /// This method only loads and validates plugin configuration files (plugin.json),
   /// not the actual plugin runtime files.
   ///
   /// On success, the cache contains all successfully loaded plugin configurations.
-  /// Individual plugin failures do not cause the entire method to fail.
+  /// Individual plugin failures do not cause the entire method to fail. Disabled
+  /// plugins are included; `PluginInfo::is_enabled` tells them apart.
   ///
   /// # Returns
   ///
@@ -126,6 +642,16 @@ impl<P: Provider> PluginManager<P> {
     // Update cache first, then build result from cache to avoid cloning the entire HashMap
     self.plugin_cache = plugins;
 
+    for (name, config) in &self.plugin_cache {
+      let plugin_dir = self.project_data_path.join(name);
+      self.manifest_cache.insert(name, &plugin_dir, config);
+    }
+    let installed = &self.plugin_cache;
+    self
+      .manifest_cache
+      .retain(|name| installed.contains_key(name));
+    self.manifest_cache.save();
+
     let plugin_infos: Vec<PluginInfo> = self
       .plugin_cache
       .iter()
@@ -141,16 +667,47 @@ impl<P: Provider> PluginManager<P> {
     Ok(plugin_infos)
   }
 
+  /// Gets the installed plugins listing `category` among their `categories`
+  ///
+  /// Categories match ignoring ASCII case, and results are sorted by plugin key. Like
+  /// `get_all` this refreshes the cache from disk; when nothing can be loaded the
+  /// result is empty.
+  ///
+  /// # Arguments
+  ///
+  /// * `category` - Category to filter by, e.g. `"formatting"`
+  pub fn get_by_category(&mut self, category: &str) -> Vec<PluginInfo> {
+    let mut plugins: Vec<PluginInfo> = match self.get_all() {
+      Ok(plugins) => plugins,
+      Err(e) => {
+        warn!("Failed to list plugins for category '{}': {}", category, e);
+        return Vec::new();
+      }
+    };
+
+    plugins.retain(|plugin| {
+      plugin
+        .config
+        .categories
+        .iter()
+        .any(|c| c.eq_ignore_ascii_case(category))
+    });
+    plugins.sort_by(|a, b| a.config.key().cmp(b.config.key()));
+    plugins
+  }
+
   /// Gets plugin configuration by name
   ///
   /// This method only loads and validates the plugin configuration file (plugin.json),
   /// not the actual plugin runtime files.
   ///
   /// Reads from cache first. If cache miss, attempts to load from disk and cache it.
+  /// Falls back to the system search path when the user data path lacks the plugin
+  /// (see `resolve`); system plugins are not cached.
   ///
   /// # Arguments
   ///
-  /// * `name` - Plugin name
+  /// * `name` - Plugin id or display name
   ///
   /// # Returns
   ///
@@ -160,36 +717,538 @@ impl<P: Provider> PluginManager<P> {
   ///
   /// - Plugin not found or load failed: `PluginError::LoadError`
   pub fn get(&mut self, name: &str) -> Result<PluginInfo, PluginError> {
-    let plugin_dir = self.project_data_path.join(name);
+    let name = &self.plugin_key(name);
+    let (plugin_info, origin) = self.resolve(name)?;
+
+    // Only user plugins are cached; the cache is keyed by the user data path
+    if origin == Origin::User && !self.plugin_cache.contains_key(name) {
+      self
+        .manifest_cache
+        .insert(name, &plugin_info.path, &plugin_info.config);
+      self.manifest_cache.save();
+      self
+        .plugin_cache
+        .insert(name.to_string(), plugin_info.config.clone());
+    }
+
+    // Overlays are applied after caching so they never reach `cache.json`.
+    self.apply_overlay(name, plugin_info)
+  }
+
+  /// Sets an in-memory overlay merged over the plugin's config whenever `get` returns it
+  ///
+  /// The overlay uses the plugin.json field names, e.g. `{"timeoutMs": 50}`. Nested
+  /// objects merge key by key and any other value replaces the manifest's. Identity
+  /// fields (`name` and `id`) cannot be overridden. Setting an overlay replaces the
+  /// previous one; it is never written to disk and takes effect on the next `load`.
+  ///
+  /// # Errors
+  ///
+  /// - Overlay is not an object or overrides `name` or `id`: `PluginError::InvalidOverlay`
+  pub fn set_overlay(&mut self, name: &str, overlay: serde_json::Value) -> Result<(), PluginError> {
+    let Some(fields) = overlay.as_object() else {
+      return Err(PluginError::InvalidOverlay(format!(
+        "overlay for '{}' must be an object",
+        name
+      )));
+    };
+    if let Some(field) = ["name", "id"].into_iter().find(|f| fields.contains_key(*f)) {
+      return Err(PluginError::InvalidOverlay(format!(
+        "overlay for '{}' cannot override '{}'",
+        name, field
+      )));
+    }
+
+    let name = self.plugin_key(name);
+    self.results.remove_plugin(&name);
+    self.overlays.insert(name, overlay);
+    Ok(())
+  }
+
+  /// Removes the overlay set by `set_overlay`, returning whether there was one
+  pub fn clear_overlay(&mut self, name: &str) -> bool {
+    let name = self.plugin_key(name);
+    self.results.remove_plugin(&name);
+    self.overlays.remove(&name).is_some()
+  }
+
+  // Merges the overlay for `name`, if any, over `plugin_info.config`.
+  fn apply_overlay(
+    &self,
+    name: &str,
+    mut plugin_info: PluginInfo,
+  ) -> Result<PluginInfo, PluginError> {
+    let Some(overlay) = self.overlays.get(name) else {
+      return Ok(plugin_info);
+    };
+
+    let invalid = |e: serde_json::Error| {
+      PluginError::InvalidOverlay(format!("overlay for '{}' does not apply: {}", name, e))
+    };
+    let mut config = serde_json::to_value(&plugin_info.config).map_err(invalid)?;
+    merge_json(&mut config, overlay);
+    plugin_info.config = serde_json::from_value(config).map_err(invalid)?;
+    Ok(plugin_info)
+  }
+
+  /// Resolves a plugin by name across the user and system search paths
+  ///
+  /// The user data path always wins: a plugin there shadows a system plugin with the
+  /// same name. The returned `Origin` tells which one was chosen, so a UI can show that
+  /// a user plugin is overriding a built-in one.
+  ///
+  /// # Arguments
+  ///
+  /// * `name` - Plugin id or display name
+  ///
+  /// # Errors
+  ///
+  /// - Plugin not found in either path or load failed: `PluginError::LoadError`
+  pub fn resolve(&self, name: &str) -> Result<(PluginInfo, Origin), PluginError> {
+    let name = &self.plugin_key(name);
+    let user_dir = self.project_data_path.join(name);
 
     // Return from cache if available
     if let Some(cached_config) = self.plugin_cache.get(name) {
-      return Ok(PluginInfo {
+      let plugin_info = PluginInfo {
         config: cached_config.clone(),
-        path: plugin_dir,
-      });
+        path: user_dir,
+      };
+      return Ok((plugin_info, Origin::User));
     }
 
-    // Load, cache, and return
+    let (plugin_dir, origin) = match &self.system_path {
+      Some(system_path) if !user_dir.is_dir() && system_path.join(name).is_dir() => {
+        (system_path.join(name), Origin::System)
+      }
+      _ => (user_dir, Origin::User),
+    };
+
     let config = load_plugin_config_validated(&plugin_dir, name)
       .map_err(|e| PluginError::LoadError(format!("Failed to load plugin '{}': {}", name, e)))?;
 
-    self.plugin_cache.insert(name.to_string(), config.clone());
-
-    Ok(PluginInfo {
+    let plugin_info = PluginInfo {
       config,
       path: plugin_dir,
-    })
+    };
+    Ok((plugin_info, origin))
   }
 
+  /// Subscribes to plugin lifecycle events
+  ///
+  /// Every call returns an independent receiver that sees all events published after
+  /// it was created. Dropping receivers is fine; the manager simply stops sending to them.
+  pub fn events(&self) -> Receiver<PluginEvent> {
+    self.events.subscribe()
+  }
+
+  /// Loads an installed plugin into the provider
+  ///
+  /// Plugins listed under `dependencies` in plugin.json are loaded first, each one
+  /// after its own dependencies; dependencies that are already loaded are left as
+  /// they are. Plugins whose `targets` exclude the host are refused before the
+  /// provider sees them, as are draining plugins (see `drain`). Publishes
+  /// `PluginEvent::PluginLoaded` or `PluginEvent::PluginFailed` for every plugin
+  /// it tries to load.
+  ///
+  /// # Errors
+  ///
+  /// - A dependency is not installed, or dependencies form a cycle:
+  ///   `PluginError::LoadError` naming it, or the cycle as `a -> b -> a`
+  /// - A dependency fails to load: its error
+  /// - Otherwise `PluginError::LoadError`, or `PluginError::InvalidState` while draining
   pub fn load(&mut self, name: &str) -> Result<(), PluginError> {
+    let name = &self.plugin_key(name);
+    let result = self
+      .check_transition(name, "load", &[PluginLifecycle::Draining])
+      .and_then(|()| self.load_dependencies(name))
+      .and_then(|()| self.load_plugin(name));
+    self.results.remove_plugin(name);
+    if result.is_ok() {
+      self.lifecycle.insert(name.to_string(), PluginLifecycle::Loaded);
+    }
+
+    self.events.publish(match &result {
+      Ok(()) => PluginEvent::PluginLoaded {
+        name: name.to_string(),
+      },
+      Err(e) => PluginEvent::PluginFailed {
+        name: name.to_string(),
+        error: e.to_string(),
+      },
+    });
+
+    result
+  }
+
+  /// Removes a loaded plugin from the provider, freeing its runtime state
+  ///
+  /// The plugin stays installed and can be loaded again. Publishes
+  /// `PluginEvent::PluginUnloaded` on success.
+  ///
+  /// # Errors
+  ///
+  /// - Plugin not loaded, or the provider cannot unload it: `PluginError::UnloadError`
+  pub fn unload(&mut self, name: &str) -> Result<(), PluginError> {
+    let name = &self.plugin_key(name);
+    self.provider.unload_plugin(name).map_err(|e| {
+      error!("Failed to unload plugin '{}': {}", name, e);
+      PluginError::UnloadError(e.to_string())
+    })?;
+    self.results.remove_plugin(name);
+    self.loaded_hashes.remove(name);
+    self.lifecycle.remove(name);
+
+    self.events.publish(PluginEvent::PluginUnloaded {
+      name: name.to_string(),
+    });
+    Ok(())
+  }
+
+  /// Reports where a plugin is in its lifecycle
+  ///
+  /// Plugins the manager did not load are `Installed` if their directory exists in
+  /// the user or system search path, without validating it, and `NotInstalled`
+  /// otherwise.
+  ///
+  /// # Arguments
+  ///
+  /// * `name` - Plugin id or display name
+  pub fn status(&self, name: &str) -> PluginLifecycle {
+    let name = &self.plugin_key(name);
+    match self.lifecycle.get(name) {
+      Some(PluginLifecycle::Loaded) if self.provider.failed_plugins().contains(name) => {
+        PluginLifecycle::Failed
+      }
+      Some(state) => *state,
+      None if self.plugin_cache.contains_key(name)
+        || self.project_data_path.join(name).is_dir()
+        || self
+          .system_path
+          .as_ref()
+          .is_some_and(|path| path.join(name).is_dir()) =>
+      {
+        PluginLifecycle::Installed
+      }
+      None => PluginLifecycle::NotInstalled,
+    }
+  }
+
+  /// Turns a plugin on or off without uninstalling it
+  ///
+  /// The flag is written to `STATE_FILE` in the plugin's directory, so it survives
+  /// restarts and upgrades. `load` refuses a disabled plugin, while `get_all` still
+  /// lists it; see `PluginInfo::is_enabled`. A loaded plugin keeps running until it
+  /// is unloaded.
+  ///
+  /// # Arguments
+  ///
+  /// * `name` - Plugin id or display name
+  /// * `enabled` - `false` to disable the plugin, `true` to enable it again
+  ///
+  /// # Errors
+  ///
+  /// - Plugin not installed: `PluginError::LoadError`
+  /// - The state file cannot be written: `PluginError::IoError`
+  pub fn set_enabled(&mut self, name: &str, enabled: bool) -> Result<(), PluginError> {
+    let name = &self.plugin_key(name);
+    let (plugin_info, _) = self.resolve(name)?;
+    state::set_enabled(&plugin_info.path, enabled)?;
+    info!(
+      "{} plugin '{}'",
+      if enabled { "Enabled" } else { "Disabled" },
+      name
+    );
+    Ok(())
+  }
+
+  /// Stops a loaded plugin from accepting new calls, ahead of unloading it
+  ///
+  /// Every later invocation fails with `PluginError::InvalidState` until `unload`
+  /// returns the plugin to `Installed`; `load` and `reload` are refused meanwhile.
+  ///
+  /// # Errors
+  ///
+  /// - Plugin not `Loaded` or `Failed`: `PluginError::InvalidState`
+  pub fn drain(&mut self, name: &str) -> Result<(), PluginError> {
+    let name = &self.plugin_key(name);
+    self.check_transition(
+      name,
+      "drain",
+      &[
+        PluginLifecycle::NotInstalled,
+        PluginLifecycle::Installed,
+        PluginLifecycle::Draining,
+      ],
+    )?;
+
+    info!("Draining plugin '{}'", name);
+    self
+      .lifecycle
+      .insert(name.to_string(), PluginLifecycle::Draining);
+    Ok(())
+  }
+
+  // Refuses `action` on `name` while the plugin is in one of the `forbidden` states.
+  fn check_transition(
+    &self,
+    name: &str,
+    action: &str,
+    forbidden: &[PluginLifecycle],
+  ) -> Result<(), PluginError> {
+    let state = self.status(name);
+    if !forbidden.contains(&state) {
+      return Ok(());
+    }
+
+    let msg = format!("cannot {} plugin '{}' while it is {}", action, name, state);
+    error!("{}", msg);
+    Err(PluginError::InvalidState(msg))
+  }
+
+  /// Reloads a loaded plugin from disk, picking up a rebuilt main file
+  ///
+  /// Unloads the plugin, drops its cached manifest so `plugin.json` is read again, and
+  /// loads it anew, instantiating the current main file. Publishes
+  /// `PluginEvent::PluginReloaded`, whose `changed` tells whether the main file
+  /// differs from the one loaded before, after the unload and load events. A main
+  /// file rebuilt in the data path fails the checksum `install` recorded; deleting the
+  /// plugin's `CHECKSUMS_FILE` opts a development copy out of verification.
+  ///
+  /// # Errors
+  ///
+  /// - Plugin not loaded: `PluginError::LoadError`, and nothing is unloaded
+  /// - Plugin draining: `PluginError::InvalidState`
+  /// - Otherwise as `unload` and `load`; a failed load leaves the plugin unloaded
+  pub fn reload(&mut self, name: &str) -> Result<(), PluginError> {
+    let name = &self.plugin_key(name);
+    self.ensure_loaded(name)?;
+
+    let previous = self.loaded_hashes.get(name).copied();
+    self.unload(name)?;
+    self.plugin_cache.remove(name);
+    self.load(name)?;
+
+    let changed = previous != self.loaded_hashes.get(name).copied();
+    info!("Reloaded plugin '{}' (changed: {})", name, changed);
+    self.events.publish(PluginEvent::PluginReloaded {
+      name: name.to_string(),
+      changed,
+    });
+    Ok(())
+  }
+
+  /// Removes an installed plugin from the data path
+  ///
+  /// Unloads the plugin first if it is loaded, then deletes `project_data_path/<name>`
+  /// recursively and forgets its cached manifest and results. Publishes
+  /// `PluginEvent::PluginUninstalled` on success.
+  ///
+  /// # Arguments
+  ///
+  /// * `name` - Plugin id or display name; must be a plain directory name
+  ///
+  /// # Errors
+  ///
+  /// - `name` is not a plain directory name (e.g. `../foo`), or the plugin is not
+  ///   installed: `PluginError::LoadError`
+  /// - The provider cannot unload it: `PluginError::UnloadError`
+  /// - The directory cannot be removed: `PluginError::IoError`
+  pub fn uninstall(&mut self, name: &str) -> Result<(), PluginError> {
+    let name = &self.plugin_key(name);
+
+    // Anything but a single normal component could point outside the data path
+    let mut components = Path::new(name).components();
+    if !matches!(
+      (components.next(), components.next()),
+      (Some(Component::Normal(_)), None)
+    ) {
+      let msg = format!("invalid plugin name '{}'", name);
+      error!("{}", msg);
+      return Err(PluginError::LoadError(msg));
+    }
+
+    let plugin_dir = self.project_data_path.join(name);
+    if !plugin_dir.is_dir() {
+      let msg = format!("plugin {} is not installed", name);
+      error!("{}", msg);
+      return Err(PluginError::LoadError(msg));
+    }
+
+    let loaded = self
+      .provider
+      .with_plugins(|plugins| plugins.contains_key(name))
+      .unwrap_or(false);
+    if loaded {
+      self.unload(name)?;
+    }
+
+    std::fs::remove_dir_all(&plugin_dir)?;
+
+    self.plugin_cache.remove(name);
+    self.manifest_cache.retain(|cached| cached != name);
+    self.manifest_cache.save();
+    self.results.remove_plugin(name);
+
+    info!("Uninstalled plugin '{}' from {}", name, plugin_dir.display());
+    self.events.publish(PluginEvent::PluginUninstalled {
+      name: name.to_string(),
+    });
+    Ok(())
+  }
+
+  // Loads the dependencies of `name` that are not loaded yet, in dependency order.
+  fn load_dependencies(&mut self, name: &str) -> Result<(), PluginError> {
+    let mut order = Vec::new();
+    self.dependency_order(name, &mut Vec::new(), &mut order)?;
+
+    // `order` ends with `name` itself.
+    order.pop();
+    for dependency in order {
+      if self.status(&dependency) != PluginLifecycle::Loaded {
+        self.load(&dependency)?;
+      }
+    }
+    Ok(())
+  }
+
+  // Appends `name` to `order` after its dependencies, depth first. `path` holds the
+  // plugins being resolved, to report a cycle.
+  fn dependency_order(
+    &mut self,
+    name: &str,
+    path: &mut Vec<String>,
+    order: &mut Vec<String>,
+  ) -> Result<(), PluginError> {
+    if order.iter().any(|done| done == name) {
+      return Ok(());
+    }
+    if let Some(start) = path.iter().position(|pending| pending == name) {
+      let mut cycle = path[start..].to_vec();
+      cycle.push(name.to_string());
+      let msg = format!("dependency cycle: {}", cycle.join(" -> "));
+      error!("{}", msg);
+      return Err(PluginError::LoadError(msg));
+    }
+
+    let dependencies = match self.get(name) {
+      Ok(plugin_info) => plugin_info.config.dependencies,
+      Err(_) if !path.is_empty() && self.status(name) == PluginLifecycle::NotInstalled => {
+        let msg = format!("dependency {} not installed", name);
+        error!("{} (required by {})", msg, path.join(" -> "));
+        return Err(PluginError::LoadError(msg));
+      }
+      Err(e) => return Err(e),
+    };
+
+    path.push(name.to_string());
+    for dependency in &dependencies {
+      let dependency = self.plugin_key(dependency);
+      self.dependency_order(&dependency, path, order)?;
+    }
+    path.pop();
+
+    order.push(name.to_string());
+    Ok(())
+  }
+
+  fn load_plugin(&mut self, name: &str) -> Result<(), PluginError> {
     let plugin_info = self.get(name)?;
 
+    if !plugin_info.is_enabled() {
+      let msg = format!("plugin '{}' is disabled", name);
+      error!("{}", msg);
+      return Err(PluginError::LoadError(msg));
+    }
+    self.check_permission_policy(&plugin_info.config)?;
+    Self::check_target(&plugin_info.config)?;
+    Self::verify_checksum(name, &plugin_info)?;
+
+    match &self.precompiled_dir {
+      Some(cache_dir) => self.load_from_cache(cache_dir, name, &plugin_info)?,
+      None => self
+        .provider
+        .load(&plugin_info.path, &plugin_info.config)
+        .map_err(|e| PluginError::LoadError(e.to_string()))?,
+    }
+
+    match main_file_hash::<P>(&plugin_info.path, &plugin_info.config) {
+      Ok(hash) => self.loaded_hashes.insert(name.to_string(), hash),
+      Err(_) => self.loaded_hashes.remove(name),
+    };
+    Ok(())
+  }
+
+  // Compares the entry file with the digest `install` recorded for it. Plugins without a
+  // checksum manifest, such as those on a search path, are not checked, and a missing
+  // entry file is left for the provider to report.
+  fn verify_checksum(name: &str, plugin_info: &PluginInfo) -> Result<(), PluginError> {
+    let Some(digests) = checksums::read(&plugin_info.path)? else {
+      return Ok(());
+    };
+    let entry_file = plugin_info.config.entry_file(P::MAIN_FILE);
+    let entry_path = plugin_info.path.join(entry_file);
+    if !entry_path.is_file() {
+      return Ok(());
+    }
+
+    let key = entry_file.replace('\\', "/");
+    let recorded = digests.get(key.trim_start_matches("./"));
+    if recorded != Some(&checksums::file_digest(&entry_path)?) {
+      let msg = format!("hash mismatch for {} of plugin '{}'", entry_file, name);
+      error!("{}", msg);
+      return Err(PluginError::LoadError(msg));
+    }
+    Ok(())
+  }
+
+  // Loads `name` from its precompiled artifact. An unusable artifact is deleted, the
+  // plugin is recompiled from source, and the artifact is rewritten on a best-effort
+  // basis so the next load is fast again.
+  fn load_from_cache(
+    &self,
+    cache_dir: &Path,
+    name: &str,
+    plugin_info: &PluginInfo,
+  ) -> Result<(), PluginError> {
+    let artifact =
+      precompiled_artifact_path::<P>(cache_dir, name, &plugin_info.path, &plugin_info.config)?;
+    if !artifact.is_file() {
+      return Err(PluginError::LoadError(format!(
+        "no precompiled artifact for '{}' in {}",
+        name,
+        cache_dir.display()
+      )));
+    }
+
+    let Err(e) = self
+      .provider
+      .load_precompiled(name, &artifact, &plugin_info.config)
+    else {
+      return Ok(());
+    };
+
+    warn!(
+      "Precompiled artifact {} for '{}' is unusable, recompiling: {}",
+      artifact.display(),
+      name,
+      e
+    );
+    if let Err(e) = std::fs::remove_file(&artifact) {
+      warn!("Failed to remove {}: {}", artifact.display(), e);
+    }
+
     self
       .provider
       .load(&plugin_info.path, &plugin_info.config)
       .map_err(|e| PluginError::LoadError(e.to_string()))?;
 
+    if let Err(e) = self
+      .provider
+      .precompile(&plugin_info.path, &plugin_info.config, &artifact)
+    {
+      warn!("Failed to rewrite {}: {}", artifact.display(), e);
+    }
     Ok(())
   }
 
@@ -202,15 +1261,225 @@ impl<P: Provider> PluginManager<P> {
     Ok(())
   }
 
+  /// Invokes a plugin function
+  ///
+  /// Functions declared `"cacheable": true` under `functions` in plugin.json are
+  /// answered from the result cache like `invoke_cached`.
   pub fn invoke(
     &mut self,
     name: &str,
     function: &str,
     args: Vec<ProviderValue>,
   ) -> Result<ProviderValue, PluginError> {
+    self.invoke_until(name, function, args, None, false)
+  }
+
+  /// Invokes a plugin function from async code
+  ///
+  /// Unlike `invoke` this borrows the manager immutably, so calls can be awaited
+  /// together; with a provider that runs calls asynchronously, such as `WasmProvider`
+  /// built with `async_support`, calls into different plugins overlap. The plugin must
+  /// have been loaded through this manager, and the result cache is neither read nor
+  /// written. Publishes the same events as `invoke`. Requires the `async` feature
+  ///
+  /// # Errors
+  ///
+  /// - Plugin not loaded through this manager: `PluginError::LoadError`
+  /// - Plugin draining: `PluginError::InvalidState`
+  /// - Provider invocation failure: `PluginError::InvokeError`
+  #[cfg(feature = "async")]
+  pub async fn invoke_async(
+    &self,
+    name: &str,
+    function: &str,
+    args: Vec<ProviderValue>,
+  ) -> Result<ProviderValue, PluginError> {
+    let name = &self.plugin_key(name);
+    self.check_transition(name, "call", &[PluginLifecycle::Draining])?;
+    if !self.lifecycle.contains_key(name) {
+      let msg = format!(
+        "Plugin '{}' not found, You must load the plugin first",
+        name
+      );
+      error!("{}", msg);
+      return Err(PluginError::LoadError(msg));
+    }
+
+    let started = Instant::now();
+    let result = self.provider.invoke_async(name, function, args).await;
+    self.publish_call(name, function, started, &result);
+    result.map_err(|e| PluginError::InvokeError(e.to_string()))
+  }
+
+  /// Invokes a plugin function, answering repeated identical calls from a result cache
+  ///
+  /// Results are keyed by plugin, function and arguments; a hit returns the stored
+  /// result without entering the plugin or publishing `PluginEvent::PluginInvoked`.
+  /// Only successful results are stored, and a plugin's results are dropped when it is
+  /// loaded again or unloaded. Only use this for functions without side effects whose
+  /// result depends on nothing but the arguments. Arguments that cannot be encoded as
+  /// JSON (e.g. NaN) bypass the cache.
+  ///
+  /// # Errors
+  ///
+  /// - As `invoke`
+  pub fn invoke_cached(
+    &mut self,
+    name: &str,
+    function: &str,
+    args: Vec<ProviderValue>,
+  ) -> Result<ProviderValue, PluginError> {
+    self.invoke_until(name, function, args, None, true)
+  }
+
+  /// Invokes a plugin function, interrupting it if it runs longer than `timeout`
+  ///
+  /// `timeout` replaces the `timeoutMs` declared in the plugin's plugin.json for this
+  /// call only; `invoke` applies the declared value.
+  ///
+  /// # Errors
+  ///
+  /// - The call ran out of time: `PluginError::InvokeError` wrapping
+  ///   `ProviderError::Timeout`
+  /// - Otherwise as `invoke`
+  pub fn invoke_with_timeout(
+    &mut self,
+    name: &str,
+    function: &str,
+    args: Vec<ProviderValue>,
+    timeout: Duration,
+  ) -> Result<ProviderValue, PluginError> {
+    self.invoke_until(name, function, args, Some(Instant::now() + timeout), false)
+  }
+
+  /// Invokes several functions of one plugin in order, collecting every result
+  ///
+  /// A failing call does not end the batch, except one that leaves the plugin unusable
+  /// (a trap, timeout or permission violation, see `ProviderError::is_fatal`). The
+  /// batch then stops, and `stopped_at` holds the index of that call; later calls are
+  /// not attempted and have no entry in `results`.
+  ///
+  /// # Errors
+  ///
+  /// - Plugin not installed or not loaded: as `invoke`, before any call is made
+  pub fn invoke_batch(
+    &mut self,
+    name: &str,
+    calls: Vec<(String, Vec<ProviderValue>)>,
+  ) -> Result<BatchOutcome, PluginError> {
+    let name = &self.plugin_key(name);
     let _plugin_info = self.get(name)?;
+    self.ensure_loaded(name)?;
+
+    let mut outcome = BatchOutcome {
+      results: Vec::with_capacity(calls.len()),
+      stopped_at: None,
+    };
+    for (index, (function, args)) in calls.into_iter().enumerate() {
+      let result = self.call(name, &function, args, None);
+      let fatal = result.as_ref().is_err_and(ProviderError::is_fatal);
+      outcome
+        .results
+        .push(result.map_err(|e| PluginError::InvokeError(e.to_string())));
+      if fatal {
+        outcome.stopped_at = Some(index);
+        break;
+      }
+    }
+
+    Ok(outcome)
+  }
+
+  /// Calls `steps` of `(plugin, function)` in order, passing each result to the next
+  ///
+  /// `initial` is the only argument of the first step, every later step receives the
+  /// previous step's result as its only argument, and the last result is returned. An
+  /// empty pipeline returns `initial` unchanged
+  ///
+  /// # Errors
+  ///
+  /// - A step failed: `PluginError::PipelineError` carrying the step's index and its
+  ///   error; later steps are not run
+  pub fn pipeline(
+    &mut self,
+    steps: &[(&str, &str)],
+    initial: ProviderValue,
+  ) -> Result<ProviderValue, PluginError> {
+    let mut value = initial;
+    for (step, (name, function)) in steps.iter().enumerate() {
+      value = self.invoke(name, function, vec![value]).map_err(|e| {
+        error!("Pipeline step {} ('{}::{}') failed: {}", step, name, function, e);
+        PluginError::PipelineError {
+          step,
+          source: Box::new(e),
+        }
+      })?;
+    }
+    Ok(value)
+  }
+
+  // Shared body of the `invoke` variants. `cached` forces the result cache, which
+  // functions declared cacheable use anyway.
+  fn invoke_until(
+    &mut self,
+    name: &str,
+    function: &str,
+    args: Vec<ProviderValue>,
+    deadline: Option<Instant>,
+    cached: bool,
+  ) -> Result<ProviderValue, PluginError> {
+    let name = &self.plugin_key(name);
+    let plugin_info = self.get(name)?;
+    self.ensure_loaded(name)?;
+
+    let cacheable = cached
+      || plugin_info
+        .config
+        .functions
+        .as_ref()
+        .and_then(|functions| functions.get(function))
+        .is_some_and(|spec| spec.cacheable);
+    let key = cacheable
+      .then(|| try_args_to_json(&args).ok())
+      .flatten()
+      .map(|args| ResultKey {
+        plugin: name.to_string(),
+        function: function.to_string(),
+        args: args.to_string(),
+      });
+    if let Some(value) = key.as_ref().and_then(|key| self.results.get(key)) {
+      return Ok(value);
+    }
+
+    let value = self
+      .call(name, function, args, deadline)
+      .map_err(|e| PluginError::InvokeError(e.to_string()))?;
+    if let Some(key) = key {
+      self.results.insert(key, value.clone());
+    }
+    Ok(value)
+  }
+
+  // Maps `name` to the key of the plugin it identifies, so methods accept both a
+  // plugin's `id` and its display name. A key always wins over a display name; names
+  // matching no cached plugin are returned unchanged.
+  fn plugin_key(&self, name: &str) -> String {
+    if self.plugin_cache.contains_key(name) {
+      return name.to_string();
+    }
+    self
+      .plugin_cache
+      .iter()
+      .find(|(_, config)| config.name == name)
+      .map_or_else(|| name.to_string(), |(key, _)| key.clone())
+  }
+
+  // Fails unless `name` is loaded and accepting calls, i.e. not draining.
+  fn ensure_loaded(&self, name: &str) -> Result<(), PluginError> {
+    if self.lifecycle.get(name) == Some(&PluginLifecycle::Draining) {
+      return self.check_transition(name, "call", &[PluginLifecycle::Draining]);
+    }
 
-    // Check if plugin is loaded
     self
       .provider
       .with_plugins(|plugins| {
@@ -224,12 +1493,164 @@ impl<P: Provider> PluginManager<P> {
         }
         Ok(())
       })
-      .map_err(|e| PluginError::InvokeError(e.to_string()))??;
+      .map_err(|e| PluginError::InvokeError(e.to_string()))?
+  }
+
+  // Invokes a function of a loaded plugin and publishes the outcome.
+  fn call(
+    &self,
+    name: &str,
+    function: &str,
+    args: Vec<ProviderValue>,
+    deadline: Option<Instant>,
+  ) -> Result<ProviderValue, ProviderError> {
+    let started = Instant::now();
+    let result = match deadline {
+      Some(deadline) => self
+        .provider
+        .invoke_with_deadline(name, function, args, deadline),
+      None => self.provider.invoke(name, function, args),
+    };
+
+    self.publish_call(name, function, started, &result);
+    result
+  }
+
+  // Publishes the outcome of a call that started at `started`.
+  fn publish_call(
+    &self,
+    name: &str,
+    function: &str,
+    started: Instant,
+    result: &Result<ProviderValue, ProviderError>,
+  ) {
+    self.events.publish(match result {
+      Ok(_) => PluginEvent::PluginInvoked {
+        name: name.to_string(),
+        function: function.to_string(),
+        duration: started.elapsed(),
+      },
+      Err(e) => PluginEvent::PluginFailed {
+        name: name.to_string(),
+        error: PluginError::InvokeError(e.to_string()).to_string(),
+      },
+    });
+  }
+
+  /// Invokes a plugin function with JSON arguments, validating them first
+  ///
+  /// If plugin.json declares an `args` schema for `function` under `functions`, `args`
+  /// must satisfy it before anything is converted or sent to the plugin. Functions
+  /// without a schema accept any argument array.
+  ///
+  /// # Arguments
+  ///
+  /// * `name` - Plugin id or display name
+  /// * `function` - Function name to invoke
+  /// * `args` - JSON array of arguments
+  ///
+  /// # Errors
+  ///
+  /// - Arguments rejected by the schema: `PluginError::InvalidArguments`
+  /// - `args` is not an array, or an argument exceeds the `with_value_bounds` limits:
+  ///   `PluginError::InvokeError`
+  /// - Otherwise as `invoke`
+  pub fn invoke_json(
+    &mut self,
+    name: &str,
+    function: &str,
+    args: &serde_json::Value,
+  ) -> Result<ProviderValue, PluginError> {
+    let name = &self.plugin_key(name);
+    let plugin_info = self.get(name)?;
+
+    let schema = plugin_info
+      .config
+      .functions
+      .as_ref()
+      .and_then(|functions| functions.get(function))
+      .and_then(|spec| spec.args.as_ref());
+    if let Some(schema) = schema {
+      validate_function_args(schema, args).map_err(|source| {
+        error!("Rejected arguments for '{}::{}': {}", name, function, source);
+        PluginError::InvalidArguments {
+          function: function.to_string(),
+          source,
+        }
+      })?;
+    }
+
+    let serde_json::Value::Array(values) = args else {
+      return Err(PluginError::InvokeError(
+        "JSON arguments must be an array".to_string(),
+      ));
+    };
+    let args: Vec<ProviderValue> = values.iter().map(json_to_provider_value).collect();
+    if let Some((max_depth, max_nodes)) = self.value_bounds {
+      for (index, arg) in args.iter().enumerate() {
+        arg.validate_bounds(max_depth, max_nodes).map_err(|e| {
+          error!("Rejected argument {} for '{}::{}': {}", index, name, function, e);
+          PluginError::InvokeError(format!("argument {}: {}", index, e))
+        })?;
+      }
+    }
+
+    self.invoke(name, function, args)
+  }
+
+  /// Reports whether the provider is initialized and which plugins are loaded or failed
+  ///
+  /// Meant as the backend of a readiness probe; see `HealthReport::is_healthy`.
+  pub fn health(&self) -> HealthReport {
+    let mut loaded_plugins = self
+      .provider
+      .with_plugins(|plugins| plugins.keys().cloned().collect::<Vec<_>>())
+      .unwrap_or_default();
+    loaded_plugins.sort();
+
+    HealthReport {
+      provider_initialized: self.provider.is_initialized(),
+      loaded_plugins,
+      failed_plugins: self.provider.failed_plugins(),
+    }
+  }
+
+  /// Lists every function a loaded plugin exposes, with its parameter and result kinds
+  ///
+  /// The signatures come from the provider's introspection and are provider-neutral,
+  /// which is enough for a client to render a form for calling any function.
+  ///
+  /// # Errors
+  ///
+  /// - Plugin not loaded: `PluginError::LoadError`
+  /// - Introspection failed: `PluginError::InvokeError`
+  pub fn function_catalog(&self, name: &str) -> Result<Vec<FunctionSignature>, PluginError> {
+    let name = &self.plugin_key(name);
+    self.ensure_loaded(name)?;
 
-    // Invoke the function
     self
       .provider
-      .invoke(name, function, args)
+      .describe(name)
+      .map_err(|e| PluginError::InvokeError(e.to_string()))
+  }
+
+  /// Lists the names of every function a loaded plugin exports
+  ///
+  /// Meant for debugging which symbols a plugin exposes: unlike `function_catalog` it
+  /// includes functions whose signatures cannot be described, such as a core module's
+  /// allocator.
+  ///
+  /// # Errors
+  ///
+  /// - Plugin not loaded: `PluginError::LoadError`
+  /// - Introspection failed: `PluginError::InvokeError`
+  pub fn list_functions(&self, name: &str) -> Result<Vec<String>, PluginError> {
+    let name = &self.plugin_key(name);
+    self.ensure_loaded(name)?;
+
+    self
+      .provider
+      .list_exports(name)
       .map_err(|e| PluginError::InvokeError(e.to_string()))
   }
 
@@ -256,11 +1677,145 @@ impl<P: Provider> PluginManager<P> {
   /// ```
   pub fn new(config: Arc<ConfigData>, provider: Arc<P>) -> Result<Self, PluginError> {
     let project_data_path = Self::get_project_data_path(&config.name)?;
-    Ok(Self {
+    Ok(Self::with_data_path(config, provider, project_data_path))
+  }
+
+  /// Creates a `PluginManager` that stores plugins under `project_data_path`
+  ///
+  /// Unlike `new`, the directory is not derived from the application name, which lets
+  /// callers (and tests) use an isolated location.
+  ///
+  /// The persisted manifest cache (`cache.json`) is loaded from the directory and
+  /// every installed plugin is registered: unchanged manifests are taken from the
+  /// cache, stale or new ones are re-parsed and validated.
+  pub fn with_data_path(
+    config: Arc<ConfigData>,
+    provider: Arc<P>,
+    project_data_path: PathBuf,
+  ) -> Self {
+    let manifest_cache = ManifestCache::open(&project_data_path);
+    let mut manager = Self {
       config,
       project_data_path,
       plugin_cache: HashMap::new(),
+      manifest_cache,
+      allowed_permissions: None,
+      system_path: None,
+      precompiled_dir: None,
+      value_bounds: None,
+      require_entry_file: false,
+      results: ResultCache::new(DEFAULT_RESULT_CACHE_CAPACITY),
+      overlays: HashMap::new(),
+      loaded_hashes: HashMap::new(),
+      lifecycle: HashMap::new(),
+      events: EventBus::default(),
       provider,
-    })
+    };
+    manager.warm_cache();
+    manager
   }
+
+  // Populates `plugin_cache` from the installed plugins, re-validating only manifests
+  // whose fingerprint no longer matches `cache.json`.
+  fn warm_cache(&mut self) {
+    let Ok(entries) = std::fs::read_dir(&self.project_data_path) else {
+      return;
+    };
+
+    for entry in entries.flatten() {
+      let plugin_dir = entry.path();
+      if !plugin_dir.is_dir() {
+        continue;
+      }
+      let Some(name) = plugin_dir.file_name().and_then(|n| n.to_str()) else {
+        warn!(
+          "Skipping plugin directory with a non-UTF-8 name: {}",
+          plugin_dir.display()
+        );
+        continue;
+      };
+
+      if let Some(config) = self.manifest_cache.get_fresh(name, &plugin_dir) {
+        self.plugin_cache.insert(name.to_string(), config);
+        continue;
+      }
+
+      match load_plugin_config_validated(&plugin_dir, name) {
+        Ok(config) => {
+          self.manifest_cache.insert(name, &plugin_dir, &config);
+          self.plugin_cache.insert(name.to_string(), config);
+        }
+        Err(e) => warn!("Skipping plugin '{}' while warming cache: {}", name, e),
+      }
+    }
+
+    let installed = &self.plugin_cache;
+    self
+      .manifest_cache
+      .retain(|name| installed.contains_key(name));
+    self.manifest_cache.save();
+  }
+}
+
+// Names a plugin's artifact in a precompiled cache directory after the plugin and a
+// hash of its main file. `DefaultHasher` is stable for a given build of the host, which
+// is what produces and consumes the cache.
+fn precompiled_artifact_path<P: Provider>(
+  cache_dir: &Path,
+  name: &str,
+  plugin_dir: &Path,
+  config: &PluginConfigData,
+) -> Result<PathBuf, PluginError> {
+  let hash = main_file_hash::<P>(plugin_dir, config)?;
+  Ok(cache_dir.join(format!("{}-{:016x}.compiled", name, hash)))
+}
+
+// Hashes the entry file in `plugin_dir`, to tell whether it changed.
+fn main_file_hash<P: Provider>(
+  plugin_dir: &Path,
+  config: &PluginConfigData,
+) -> Result<u64, PluginError> {
+  let contents = std::fs::read(plugin_dir.join(config.entry_file(P::MAIN_FILE)))?;
+  let mut hasher = DefaultHasher::new();
+  hasher.write(&contents);
+  Ok(hasher.finish())
+}
+
+// Whether `install` leaves out an entry of a plugin's source directory, given its path
+// relative to that directory.
+fn is_dev_noise(path: &Path) -> bool {
+  let Some(name) = path.file_name().and_then(|name| name.to_str()) else {
+    return false;
+  };
+  let top_level = path.parent() == Some(Path::new(""));
+  (top_level && matches!(name, "target" | "node_modules"))
+    || matches!(name, ".git" | ".DS_Store")
+    || name.ends_with('~')
+    || name.ends_with(".swp")
+}
+
+// Resolves symlinks and `..` so overlapping paths compare equal; paths that do not exist
+// yet fall back to their absolute form.
+// Merges `overlay` into `base`: objects recursively, everything else by replacement.
+fn merge_json(base: &mut serde_json::Value, overlay: &serde_json::Value) {
+  match (base, overlay) {
+    (serde_json::Value::Object(base), serde_json::Value::Object(overlay)) => {
+      for (key, value) in overlay {
+        match base.get_mut(key) {
+          Some(existing) => merge_json(existing, value),
+          None => {
+            base.insert(key.clone(), value.clone());
+          }
+        }
+      }
+    }
+    (base, overlay) => *base = overlay.clone(),
+  }
+}
+
+fn normalize_path(path: &Path) -> PathBuf {
+  path
+    .canonicalize()
+    .or_else(|_| std::path::absolute(path))
+    .unwrap_or_else(|_| path.to_path_buf())
 }
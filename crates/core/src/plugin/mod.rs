@@ -1,3 +1,18 @@
+mod cache;
+mod checksums;
+mod events;
 mod manager;
+mod permissions;
+mod results;
+mod state;
 
-pub use manager::{PluginInfo, PluginManager};
+pub use cache::CACHE_FILE;
+pub use checksums::CHECKSUMS_FILE;
+pub use events::PluginEvent;
+pub use manager::{
+  BatchOutcome, HealthReport, OrphanReason, OrphanReport, Origin, PluginInfo, PluginLifecycle,
+  PluginManager,
+};
+pub use permissions::PermissionCategory;
+pub use results::DEFAULT_RESULT_CACHE_CAPACITY;
+pub use state::STATE_FILE;
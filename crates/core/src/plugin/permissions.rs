@@ -0,0 +1,139 @@
+use super::manager::PluginInfo;
+use config::is_custom_permission;
+use shared_types::config::{
+  EnvPermission, FilesystemPermission, NetworkPermission, Permission, ProcessPermission,
+  StdioPermission,
+};
+use std::collections::HashMap;
+
+/// Group a declared permission is shown under in a permissions review
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum PermissionCategory {
+  Stdio,
+  Filesystem,
+  Network,
+  Environment,
+  Process,
+  /// Embedder-defined `<prefix>:<name>` permissions with a registered prefix
+  Custom,
+  /// Custom permissions that are malformed or use an unregistered prefix
+  Invalid,
+}
+
+impl PluginInfo {
+  /// Lists the plugin's granted permissions, grouped by category
+  ///
+  /// Each entry is one grant in a display-ready form: `stdout`, `read:/data`, a network
+  /// host or `*`, `inherit` or an env key, `exit`, or a custom permission key. Denied
+  /// and empty dimensions are omitted, so a plugin without permissions yields an empty map.
+  pub fn permissions_by_category(&self) -> HashMap<PermissionCategory, Vec<String>> {
+    let mut groups: HashMap<PermissionCategory, Vec<String>> = HashMap::new();
+    let Some(permissions) = &self.config.permissions else {
+      return groups;
+    };
+
+    let mut add = |category, entries: Vec<String>| {
+      if !entries.is_empty() {
+        groups.entry(category).or_default().extend(entries);
+      }
+    };
+
+    if let Some(stdio) = &permissions.stdio {
+      add(PermissionCategory::Stdio, stdio_entries(stdio));
+    }
+    if let Some(filesystem) = &permissions.filesystem {
+      add(
+        PermissionCategory::Filesystem,
+        filesystem_entries(filesystem),
+      );
+    }
+    if let Some(network) = &permissions.network {
+      add(PermissionCategory::Network, network_entries(network));
+    }
+    if let Some(env) = &permissions.env {
+      add(PermissionCategory::Environment, env_entries(env));
+    }
+    if let Some(process) = &permissions.process {
+      add(PermissionCategory::Process, process_entries(process));
+    }
+
+    for (key, granted) in &permissions.custom {
+      let category = if !is_custom_permission(key) {
+        PermissionCategory::Invalid
+      } else if *granted {
+        PermissionCategory::Custom
+      } else {
+        continue;
+      };
+      add(category, vec![key.clone()]);
+    }
+
+    groups
+  }
+}
+
+fn stdio_entries(permission: &Permission<StdioPermission>) -> Vec<String> {
+  let (stdin, stdout, stderr) = match permission {
+    Permission::Bool(granted) => (*granted, *granted, *granted),
+    Permission::Config(c) => (
+      c.stdin == Some(true),
+      c.stdout == Some(true),
+      c.stderr == Some(true),
+    ),
+  };
+  [("stdin", stdin), ("stdout", stdout), ("stderr", stderr)]
+    .into_iter()
+    .filter(|(_, granted)| *granted)
+    .map(|(stream, _)| stream.to_string())
+    .collect()
+}
+
+fn filesystem_entries(permission: &Permission<FilesystemPermission>) -> Vec<String> {
+  match permission {
+    Permission::Bool(true) => vec!["read:*".to_string(), "write:*".to_string()],
+    Permission::Bool(false) => Vec::new(),
+    Permission::Config(c) => {
+      let reads = c.read.iter().flatten().map(|path| format!("read:{}", path));
+      let writes = c
+        .write
+        .iter()
+        .flatten()
+        .map(|path| format!("write:{}", path));
+      reads.chain(writes).collect()
+    }
+  }
+}
+
+fn network_entries(permission: &Permission<NetworkPermission>) -> Vec<String> {
+  match permission {
+    Permission::Bool(true) => vec!["*".to_string()],
+    Permission::Bool(false) => Vec::new(),
+    Permission::Config(c) => c.allowed_hosts.clone().unwrap_or_default(),
+  }
+}
+
+fn env_entries(permission: &Permission<EnvPermission>) -> Vec<String> {
+  match permission {
+    Permission::Bool(true) => vec!["inherit".to_string()],
+    Permission::Bool(false) => Vec::new(),
+    Permission::Config(c) => {
+      let inherit = (c.inherit == Some(true)).then(|| "inherit".to_string());
+      inherit
+        .into_iter()
+        .chain(c.keys.iter().flatten().cloned())
+        .collect()
+    }
+  }
+}
+
+fn process_entries(permission: &Permission<ProcessPermission>) -> Vec<String> {
+  let exit = match permission {
+    Permission::Bool(granted) => *granted,
+    Permission::Config(c) => c.exit == Some(true),
+  };
+  if exit {
+    vec!["exit".to_string()]
+  } else {
+    Vec::new()
+  }
+}
@@ -0,0 +1,81 @@
+use shared_types::ProviderValue;
+use std::collections::{HashMap, VecDeque};
+
+/// Number of results `PluginManager` keeps unless `with_result_cache_capacity` says
+/// otherwise.
+pub const DEFAULT_RESULT_CACHE_CAPACITY: usize = 256;
+
+/// Identifies one call: the plugin key, the function and its arguments as JSON text.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub(crate) struct ResultKey {
+  pub(crate) plugin: String,
+  pub(crate) function: String,
+  pub(crate) args: String,
+}
+
+/// Least-recently-used cache of successful call results.
+pub(crate) struct ResultCache {
+  capacity: usize,
+  entries: HashMap<ResultKey, ProviderValue>,
+  // Keys from least to most recently used.
+  recency: VecDeque<ResultKey>,
+}
+
+impl ResultCache {
+  pub(crate) fn new(capacity: usize) -> Self {
+    Self {
+      capacity,
+      entries: HashMap::new(),
+      recency: VecDeque::new(),
+    }
+  }
+
+  /// Returns the cached result for `key`, marking it most recently used.
+  pub(crate) fn get(&mut self, key: &ResultKey) -> Option<ProviderValue> {
+    let value = self.entries.get(key)?.clone();
+    self.touch(key);
+    Some(value)
+  }
+
+  /// Stores `value` for `key`, evicting the least recently used result when full.
+  pub(crate) fn insert(&mut self, key: ResultKey, value: ProviderValue) {
+    if self.capacity == 0 {
+      return;
+    }
+    if self.entries.insert(key.clone(), value).is_some() {
+      self.touch(&key);
+      return;
+    }
+
+    self.recency.push_back(key);
+    self.evict();
+  }
+
+  /// Drops every result of `plugin`, e.g. after it was reloaded.
+  pub(crate) fn remove_plugin(&mut self, plugin: &str) {
+    self.entries.retain(|key, _| key.plugin != plugin);
+    self.recency.retain(|key| key.plugin != plugin);
+  }
+
+  pub(crate) fn set_capacity(&mut self, capacity: usize) {
+    self.capacity = capacity;
+    self.evict();
+  }
+
+  fn evict(&mut self) {
+    while self.entries.len() > self.capacity {
+      let Some(oldest) = self.recency.pop_front() else {
+        break;
+      };
+      self.entries.remove(&oldest);
+    }
+  }
+
+  fn touch(&mut self, key: &ResultKey) {
+    if let Some(index) = self.recency.iter().position(|k| k == key)
+      && let Some(key) = self.recency.remove(index)
+    {
+      self.recency.push_back(key);
+    }
+  }
+}
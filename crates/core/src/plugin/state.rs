@@ -0,0 +1,56 @@
+use super::manager::PluginInfo;
+use log::warn;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::io;
+use std::path::Path;
+
+/// File name of the per-plugin state kept in each installed plugin's directory.
+pub const STATE_FILE: &str = ".bud-state.json";
+
+/// User settings for one installed plugin that outlive the process.
+#[derive(Debug, Serialize, Deserialize)]
+struct PluginState {
+  #[serde(default = "enabled_by_default")]
+  enabled: bool,
+}
+
+fn enabled_by_default() -> bool {
+  true
+}
+
+impl PluginInfo {
+  /// Whether the plugin is enabled, i.e. has not been turned off with `set_enabled`
+  ///
+  /// Read from disk on every call, so it reflects changes made by other managers.
+  pub fn is_enabled(&self) -> bool {
+    is_enabled(&self.path)
+  }
+}
+
+/// Reads the enabled flag from `plugin_dir`. A missing state file means enabled; an
+/// unreadable one is reported and treated the same way.
+pub(crate) fn is_enabled(plugin_dir: &Path) -> bool {
+  let path = plugin_dir.join(STATE_FILE);
+  let contents = match fs::read_to_string(&path) {
+    Ok(contents) => contents,
+    Err(e) if e.kind() == io::ErrorKind::NotFound => return true,
+    Err(e) => {
+      warn!("Failed to read {}: {}", path.display(), e);
+      return true;
+    }
+  };
+  match serde_json::from_str::<PluginState>(&contents) {
+    Ok(state) => state.enabled,
+    Err(e) => {
+      warn!("Ignoring malformed {}: {}", path.display(), e);
+      true
+    }
+  }
+}
+
+/// Writes the enabled flag into `plugin_dir`.
+pub(crate) fn set_enabled(plugin_dir: &Path, enabled: bool) -> io::Result<()> {
+  let contents = serde_json::to_string_pretty(&PluginState { enabled })?;
+  fs::write(plugin_dir.join(STATE_FILE), contents)
+}
@@ -0,0 +1,19 @@
+use crate::plugin::OrphanReport;
+
+/// Problems found by `BudCore::preflight`, e.g. for a `bud check` command
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PreflightReport {
+  /// Why `bud.json` could not be loaded, or `None` if it is valid
+  pub config_error: Option<String>,
+  /// Installed plugins that `load` could not use, sorted by path
+  ///
+  /// Empty when `config_error` is set and the plugin directory was therefore unknown.
+  pub plugin_problems: Vec<OrphanReport>,
+}
+
+impl PreflightReport {
+  /// Returns `true` if the config and every installed plugin are valid
+  pub fn is_ok(&self) -> bool {
+    self.config_error.is_none() && self.plugin_problems.is_empty()
+  }
+}
@@ -0,0 +1,94 @@
+//! Integration tests for per-function argument schemas.
+
+mod common;
+
+use common::create_manager;
+use serde_json::json;
+use shared_types::ProviderValue;
+use shared_types::config::ConfigError;
+use shared_types::plugin::PluginError;
+use std::fs;
+
+const SUM_MANIFEST: &str = r#"{
+  "name": "sum-plugin",
+  "version": "1.0.0",
+  "description": "Sum plugin",
+  "author": "tester",
+  "functions": {
+    "Sum": {
+      "args": {
+        "type": "array",
+        "items": [{ "type": "integer" }, { "type": "integer" }],
+        "minItems": 2,
+        "additionalItems": false
+      }
+    }
+  }
+}"#;
+
+#[test]
+fn test_invoke_json_validates_against_function_schema() {
+  let source = tempfile::tempdir().unwrap();
+  let plugin_dir = source.path().join("sum-plugin");
+  fs::create_dir_all(&plugin_dir).unwrap();
+  fs::write(plugin_dir.join("plugin.json"), SUM_MANIFEST).unwrap();
+  fs::copy(
+    workspace_root::get_workspace_root().join("example/sum-plugin/main.wasm"),
+    plugin_dir.join("main.wasm"),
+  )
+  .unwrap();
+
+  let data_dir = tempfile::tempdir().unwrap();
+  let mut manager = create_manager(data_dir.path());
+  manager.init().expect("Failed to initialize provider");
+  manager
+    .install(&plugin_dir)
+    .expect("Failed to install plugin");
+  manager.load("sum-plugin").expect("Failed to load plugin");
+
+  assert_eq!(
+    manager
+      .invoke_json("sum-plugin", "Sum", &json!([1, 2]))
+      .unwrap(),
+    ProviderValue::Int(3)
+  );
+
+  match manager.invoke_json("sum-plugin", "Sum", &json!([{ "a": 1 }, 2])) {
+    Err(PluginError::InvalidArguments {
+      function,
+      source: ConfigError::SchemaViolations { issues, .. },
+    }) => {
+      assert_eq!(function, "Sum");
+      assert_eq!(issues.len(), 1);
+      assert_eq!(issues[0].path, "/0");
+    }
+    other => panic!("expected InvalidArguments, got {:?}", other),
+  }
+}
+
+#[test]
+fn test_invoke_json_enforces_value_bounds() {
+  let data_dir = tempfile::tempdir().unwrap();
+  let mut manager = create_manager(data_dir.path()).with_value_bounds(2, 3);
+  manager.init().expect("Failed to initialize provider");
+  manager
+    .install(&workspace_root::get_workspace_root().join("example/sum-plugin"))
+    .expect("Failed to install plugin");
+  manager.load("sum-plugin").expect("Failed to load plugin");
+
+  match manager.invoke_json("sum-plugin", "Sum", &json!([[[1]], 2])) {
+    Err(PluginError::InvokeError(msg)) => {
+      assert_eq!(
+        msg,
+        "argument 0: Function invocation failed: value is nested deeper than 2 levels"
+      )
+    }
+    other => panic!("expected InvokeError, got {:?}", other),
+  }
+  assert_eq!(
+    manager
+      .invoke_json("sum-plugin", "Sum", &json!([1, 2]))
+      .unwrap(),
+    ProviderValue::Int(3)
+  );
+}
@@ -0,0 +1,77 @@
+//! Integration tests for batch invocation.
+
+mod common;
+
+use common::create_manager;
+use shared_types::ProviderValue;
+use shared_types::plugin::PluginError;
+
+fn call(function: &str, args: Vec<ProviderValue>) -> (String, Vec<ProviderValue>) {
+  (function.to_string(), args)
+}
+
+#[test]
+fn test_trap_stops_batch_and_keeps_earlier_results() {
+  let data_dir = tempfile::tempdir().unwrap();
+  let mut manager = create_manager(data_dir.path());
+  manager.init().expect("Failed to initialize provider");
+  manager
+    .install(&workspace_root::get_workspace_root().join("example/test-plugin"))
+    .expect("Failed to install test-plugin");
+  manager.load("test-plugin").expect("Failed to load");
+
+  let name = || vec![ProviderValue::String("name".to_string())];
+  let outcome = manager
+    .invoke_batch(
+      "test-plugin",
+      vec![
+        call("Config", name()),
+        call("Missing", vec![]),
+        call("Panic", vec![]),
+        call("Config", name()),
+        call("Config", name()),
+      ],
+    )
+    .expect("batch should run");
+
+  assert_eq!(outcome.stopped_at, Some(2));
+  assert_eq!(outcome.results.len(), 3);
+  assert_eq!(
+    outcome.results[0].as_ref().unwrap(),
+    &ProviderValue::String("test-plugin".to_string())
+  );
+  // An ordinary failure does not end the batch.
+  assert!(matches!(
+    outcome.results[1],
+    Err(PluginError::InvokeError(_))
+  ));
+  match &outcome.results[2] {
+    Err(PluginError::InvokeError(msg)) => assert!(msg.contains("trapped"), "{}", msg),
+    other => panic!("expected InvokeError, got {:?}", other),
+  }
+}
+
+#[test]
+fn test_batch_without_fatal_errors_runs_every_call() {
+  let data_dir = tempfile::tempdir().unwrap();
+  let mut manager = create_manager(data_dir.path());
+  manager.init().expect("Failed to initialize provider");
+  manager
+    .install(&workspace_root::get_workspace_root().join("example/sum-plugin"))
+    .expect("Failed to install sum-plugin");
+  manager.load("sum-plugin").expect("Failed to load");
+
+  let outcome = manager
+    .invoke_batch(
+      "sum-plugin",
+      vec![
+        call("Sum", vec![ProviderValue::Int(1), ProviderValue::Int(2)]),
+        call("Sum", vec![ProviderValue::Int(3), ProviderValue::Int(4)]),
+      ],
+    )
+    .expect("batch should run");
+
+  assert_eq!(outcome.stopped_at, None);
+  let values: Vec<_> = outcome.results.into_iter().map(Result::unwrap).collect();
+  assert_eq!(values, vec![ProviderValue::Int(3), ProviderValue::Int(7)]);
+}
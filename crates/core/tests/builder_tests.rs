@@ -0,0 +1,33 @@
+//! Integration tests for `BudCoreBuilder`.
+
+use core::BudCore;
+use std::env;
+use std::path::PathBuf;
+use std::sync::Arc;
+use wasm_provider::WasmProvider;
+
+#[test]
+fn test_one_builder_builds_several_cores() {
+  let fixture_dir = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+    .join("tests")
+    .join("fixtures")
+    .join("app");
+  let original_dir = env::current_dir().unwrap();
+  env::set_current_dir(&fixture_dir).unwrap();
+
+  let builder = BudCore::builder(WasmProvider::new());
+  let first = builder.build();
+  let second = builder.build();
+  let from_clone = builder.clone().build();
+  env::set_current_dir(original_dir).unwrap();
+
+  let first = first.expect("first build failed");
+  let second = second.expect("second build failed");
+  let from_clone = from_clone.expect("build from clone failed");
+
+  for core in [&first, &second, &from_clone] {
+    assert_eq!(core.package_name, "bud-builder-test");
+  }
+  // Each core loads its own configuration.
+  assert!(!Arc::ptr_eq(&first.config, &second.config));
+}
@@ -0,0 +1,64 @@
+//! Integration tests for the persisted plugin manifest cache.
+
+mod common;
+
+use common::{create_manager, write_manifest};
+use core::plugin::CACHE_FILE;
+use std::fs;
+use std::path::Path;
+
+// Rewrites the cached description without touching the manifest; a manager that
+// reports the rewritten value must have used the cache instead of parsing plugin.json.
+fn tamper_cached_description(data_dir: &Path, name: &str, description: &str) {
+  let path = data_dir.join(CACHE_FILE);
+  let mut cache: serde_json::Value = serde_json::from_slice(&fs::read(&path).unwrap()).unwrap();
+  cache[name]["config"]["description"] = description.into();
+  fs::write(&path, serde_json::to_vec(&cache).unwrap()).unwrap();
+}
+
+#[test]
+fn test_second_manager_reuses_disk_cache() {
+  let data_dir = tempfile::tempdir().unwrap();
+  write_manifest(data_dir.path(), "alpha", "from manifest", "{}");
+
+  let mut first = create_manager(data_dir.path());
+  assert_eq!(
+    first.get("alpha").unwrap().config.description,
+    "from manifest"
+  );
+  assert!(data_dir.path().join(CACHE_FILE).is_file());
+
+  tamper_cached_description(data_dir.path(), "alpha", "from cache");
+
+  let mut second = create_manager(data_dir.path());
+  assert_eq!(
+    second.get("alpha").unwrap().config.description,
+    "from cache"
+  );
+}
+
+#[test]
+fn test_changed_manifest_is_revalidated() {
+  let data_dir = tempfile::tempdir().unwrap();
+  write_manifest(data_dir.path(), "alpha", "original", "{}");
+  create_manager(data_dir.path());
+
+  tamper_cached_description(data_dir.path(), "alpha", "from cache");
+  write_manifest(data_dir.path(), "alpha", "edited", "{}");
+
+  let mut manager = create_manager(data_dir.path());
+  assert_eq!(manager.get("alpha").unwrap().config.description, "edited");
+}
+
+#[test]
+fn test_corrupt_cache_file_is_ignored() {
+  let data_dir = tempfile::tempdir().unwrap();
+  write_manifest(data_dir.path(), "alpha", "from manifest", "{}");
+  fs::write(data_dir.path().join(CACHE_FILE), "not json").unwrap();
+
+  let mut manager = create_manager(data_dir.path());
+  assert_eq!(
+    manager.get("alpha").unwrap().config.description,
+    "from manifest"
+  );
+}
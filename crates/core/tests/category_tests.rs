@@ -0,0 +1,71 @@
+//! Integration tests for discovering plugins by category.
+
+mod common;
+
+use common::create_manager;
+use std::fs;
+use std::path::Path;
+
+// Writes `<root>/<name>/plugin.json` tagged with the given categories and keywords.
+fn write_tagged_manifest(root: &Path, name: &str, categories: &[&str], keywords: &[&str]) {
+  let dir = root.join(name);
+  fs::create_dir_all(&dir).unwrap();
+  let manifest = serde_json::json!({
+    "name": name,
+    "version": "1.0.0",
+    "description": "Tagged plugin",
+    "author": "tester",
+    "categories": categories,
+    "keywords": keywords,
+  });
+  fs::write(dir.join("plugin.json"), manifest.to_string()).unwrap();
+}
+
+#[test]
+fn test_get_by_category_filters_installed_plugins() {
+  let data_dir = tempfile::tempdir().unwrap();
+  write_tagged_manifest(data_dir.path(), "prettier", &["formatting"], &["js", "css"]);
+  write_tagged_manifest(data_dir.path(), "eslint", &["linting", "Formatting"], &[]);
+  write_tagged_manifest(data_dir.path(), "tsc", &["compilers"], &["typescript"]);
+  let mut manager = create_manager(data_dir.path());
+
+  let names = |manager: &mut core::plugin::PluginManager<_>, category: &str| -> Vec<String> {
+    manager
+      .get_by_category(category)
+      .into_iter()
+      .map(|info| info.config.name)
+      .collect()
+  };
+  assert_eq!(names(&mut manager, "formatting"), ["eslint", "prettier"]);
+  assert_eq!(names(&mut manager, "compilers"), ["tsc"]);
+  assert!(names(&mut manager, "testing").is_empty());
+
+  let tsc = manager.get("tsc").unwrap();
+  assert_eq!(tsc.config.keywords, ["typescript"]);
+  assert_eq!(tsc.config.categories, ["compilers"]);
+}
+
+#[test]
+fn test_categories_are_schema_validated() {
+  let data_dir = tempfile::tempdir().unwrap();
+  write_tagged_manifest(data_dir.path(), "untagged", &[], &[]);
+  write_tagged_manifest(data_dir.path(), "blank", &[""], &[]);
+  let mut manager = create_manager(data_dir.path());
+
+  // An empty list is the same as no categories.
+  assert!(
+    manager
+      .get("untagged")
+      .unwrap()
+      .config
+      .categories
+      .is_empty()
+  );
+  let err = manager
+    .get("blank")
+    .err()
+    .expect("an empty category should be rejected")
+    .to_string();
+  assert!(err.contains("/categories/0"), "{}", err);
+  assert!(manager.get_by_category("").is_empty());
+}
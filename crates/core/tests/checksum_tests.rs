@@ -0,0 +1,104 @@
+//! Integration tests for the checksums `install` records and `load` verifies.
+
+mod common;
+
+use common::{create_manager, write_manifest};
+use core::plugin::CHECKSUMS_FILE;
+use shared_types::plugin::PluginError;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+// Writes a plugin named `checked` with test-plugin's `main.wasm` into `root`.
+fn write_plugin(root: &Path) -> PathBuf {
+  let plugin_dir = write_manifest(root, "checked", "Checksum fixture", "{}");
+  fs::copy(
+    workspace_root::get_workspace_root().join("example/test-plugin/main.wasm"),
+    plugin_dir.join("main.wasm"),
+  )
+  .unwrap();
+  plugin_dir
+}
+
+fn main_wasm_digest(plugin_dir: &Path) -> String {
+  let manifest = fs::read_to_string(plugin_dir.join(CHECKSUMS_FILE)).unwrap();
+  let line = manifest
+    .lines()
+    .find(|line| line.ends_with("  main.wasm"))
+    .expect("main.wasm is not listed");
+  line.split_once("  ").unwrap().0.to_string()
+}
+
+#[test]
+fn test_install_records_checksums() {
+  let source = tempfile::tempdir().unwrap();
+  let plugin_dir = write_plugin(source.path());
+  let data_dir = tempfile::tempdir().unwrap();
+  let mut manager = create_manager(data_dir.path());
+
+  manager.install(&plugin_dir).expect("install failed");
+
+  let installed = data_dir.path().join("checked");
+  let manifest = fs::read_to_string(installed.join(CHECKSUMS_FILE)).unwrap();
+  let paths: Vec<_> = manifest
+    .lines()
+    .map(|line| line.split_once("  ").unwrap().1)
+    .collect();
+  assert_eq!(paths, ["main.wasm", "plugin.json"]);
+  assert_eq!(main_wasm_digest(&installed).len(), 64);
+}
+
+#[test]
+fn test_load_rejects_tampered_wasm() {
+  let source = tempfile::tempdir().unwrap();
+  let plugin_dir = write_plugin(source.path());
+  let data_dir = tempfile::tempdir().unwrap();
+  let mut manager = create_manager(data_dir.path());
+  manager.init().expect("Failed to initialize provider");
+  manager.install(&plugin_dir).expect("install failed");
+
+  let main_wasm = data_dir.path().join("checked/main.wasm");
+  let mut bytes = fs::read(&main_wasm).unwrap();
+  bytes.extend_from_slice(b"\0tampered");
+  fs::write(&main_wasm, bytes).unwrap();
+
+  match manager.load("checked") {
+    Err(PluginError::LoadError(msg)) => assert!(msg.contains("hash mismatch"), "{}", msg),
+    other => panic!("expected a hash mismatch, got {:?}", other),
+  }
+}
+
+#[test]
+fn test_untouched_plugin_loads() {
+  let source = tempfile::tempdir().unwrap();
+  let plugin_dir = write_plugin(source.path());
+  let data_dir = tempfile::tempdir().unwrap();
+  let mut manager = create_manager(data_dir.path());
+  manager.init().expect("Failed to initialize provider");
+  manager.install(&plugin_dir).expect("install failed");
+
+  manager.load("checked").expect("load failed");
+}
+
+#[test]
+fn test_install_pinned_checks_the_digest() {
+  let source = tempfile::tempdir().unwrap();
+  let plugin_dir = write_plugin(source.path());
+  let reference = tempfile::tempdir().unwrap();
+  create_manager(reference.path())
+    .install(&plugin_dir)
+    .expect("install failed");
+  let digest = main_wasm_digest(&reference.path().join("checked"));
+  let data_dir = tempfile::tempdir().unwrap();
+  let mut manager = create_manager(data_dir.path());
+
+  match manager.install_pinned(&plugin_dir, &"0".repeat(64)) {
+    Err(PluginError::InstallError(msg)) => assert!(msg.contains("hash mismatch"), "{}", msg),
+    other => panic!("expected a hash mismatch, got {:?}", other),
+  }
+  assert!(!data_dir.path().join("checked").exists());
+
+  manager
+    .install_pinned(&plugin_dir, &digest.to_uppercase())
+    .expect("install with the right digest failed");
+  assert!(data_dir.path().join("checked/main.wasm").is_file());
+}
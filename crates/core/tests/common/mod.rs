@@ -0,0 +1,36 @@
+//! Shared fixtures for the core integration tests.
+#![allow(dead_code)]
+
+use core::plugin::PluginManager;
+use shared_types::config::ConfigData;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use wasm_provider::WasmProvider;
+
+/// Creates a manager whose plugins live in `data_dir` instead of the real project directory.
+pub fn create_manager(data_dir: &Path) -> PluginManager<WasmProvider> {
+  let config = Arc::new(ConfigData {
+    name: "test-app".to_string(),
+    version: "0.1.0".to_string(),
+    description: "A test application".to_string(),
+    providers: Default::default(),
+  });
+  PluginManager::with_data_path(
+    config,
+    Arc::new(WasmProvider::new()),
+    data_dir.to_path_buf(),
+  )
+}
+
+/// Writes `<root>/<name>/plugin.json` with the given description and permissions JSON.
+pub fn write_manifest(root: &Path, name: &str, description: &str, permissions: &str) -> PathBuf {
+  let dir = root.join(name);
+  fs::create_dir_all(&dir).unwrap();
+  let manifest = format!(
+    r#"{{"name": "{}", "version": "1.0.0", "description": "{}", "author": "tester", "permissions": {}}}"#,
+    name, description, permissions
+  );
+  fs::write(dir.join("plugin.json"), manifest).unwrap();
+  dir
+}
@@ -0,0 +1,110 @@
+//! Integration tests for loading plugins after their dependencies.
+
+mod common;
+
+use common::create_manager;
+use core::plugin::{PluginEvent, PluginLifecycle, PluginManager};
+use shared_types::plugin::PluginError;
+use std::fs;
+use std::path::Path;
+use wasm_provider::WasmProvider;
+
+// Installs a copy of sum-plugin named `name` into `data_dir` with the given dependencies.
+fn write_plugin(data_dir: &Path, name: &str, dependencies: &[&str]) {
+  let dir = data_dir.join(name);
+  fs::create_dir_all(&dir).unwrap();
+  let manifest = serde_json::json!({
+    "name": name,
+    "version": "1.0.0",
+    "description": "Dependent plugin",
+    "author": "tester",
+    "dependencies": dependencies,
+  });
+  fs::write(dir.join("plugin.json"), manifest.to_string()).unwrap();
+  fs::copy(
+    workspace_root::get_workspace_root().join("example/sum-plugin/main.wasm"),
+    dir.join("main.wasm"),
+  )
+  .unwrap();
+}
+
+fn initialized_manager(data_dir: &Path) -> PluginManager<WasmProvider> {
+  let manager = create_manager(data_dir);
+  manager.init().expect("Failed to initialize provider");
+  manager
+}
+
+// Names of the plugins loaded since `events` was subscribed, in order.
+fn loaded(events: &std::sync::mpsc::Receiver<PluginEvent>) -> Vec<String> {
+  events
+    .try_iter()
+    .filter_map(|event| match event {
+      PluginEvent::PluginLoaded { name } => Some(name),
+      _ => None,
+    })
+    .collect()
+}
+
+#[test]
+fn test_load_resolves_linear_chain() {
+  let data_dir = tempfile::tempdir().unwrap();
+  write_plugin(data_dir.path(), "app", &["lib"]);
+  write_plugin(data_dir.path(), "lib", &["base"]);
+  write_plugin(data_dir.path(), "base", &[]);
+  let mut manager = initialized_manager(data_dir.path());
+  let events = manager.events();
+
+  manager.load("app").expect("Failed to load plugin");
+
+  assert_eq!(loaded(&events), ["base", "lib", "app"]);
+
+  // Loaded dependencies are not loaded again.
+  manager.load("app").expect("Failed to load plugin");
+  assert_eq!(loaded(&events), ["app"]);
+}
+
+#[test]
+fn test_load_resolves_diamond_once() {
+  let data_dir = tempfile::tempdir().unwrap();
+  write_plugin(data_dir.path(), "top", &["left", "right"]);
+  write_plugin(data_dir.path(), "left", &["bottom"]);
+  write_plugin(data_dir.path(), "right", &["bottom"]);
+  write_plugin(data_dir.path(), "bottom", &[]);
+  let mut manager = initialized_manager(data_dir.path());
+  let events = manager.events();
+
+  manager.load("top").expect("Failed to load plugin");
+
+  assert_eq!(loaded(&events), ["bottom", "left", "right", "top"]);
+}
+
+#[test]
+fn test_load_reports_cycle_path() {
+  let data_dir = tempfile::tempdir().unwrap();
+  write_plugin(data_dir.path(), "entry", &["first"]);
+  write_plugin(data_dir.path(), "first", &["second"]);
+  write_plugin(data_dir.path(), "second", &["first"]);
+  let mut manager = initialized_manager(data_dir.path());
+
+  match manager.load("entry") {
+    Err(PluginError::LoadError(msg)) => {
+      assert_eq!(msg, "dependency cycle: first -> second -> first")
+    }
+    other => panic!("expected LoadError, got {:?}", other),
+  }
+  for name in ["entry", "first", "second"] {
+    assert_eq!(manager.status(name), PluginLifecycle::Installed);
+  }
+}
+
+#[test]
+fn test_load_reports_missing_dependency() {
+  let data_dir = tempfile::tempdir().unwrap();
+  write_plugin(data_dir.path(), "needy", &["absent"]);
+  let mut manager = initialized_manager(data_dir.path());
+
+  match manager.load("needy") {
+    Err(PluginError::LoadError(msg)) => assert_eq!(msg, "dependency absent not installed"),
+    other => panic!("expected LoadError, got {:?}", other),
+  }
+}
@@ -0,0 +1,62 @@
+//! Integration tests for turning installed plugins off and on with `set_enabled`.
+
+mod common;
+
+use common::create_manager;
+use shared_types::plugin::PluginError;
+use std::path::Path;
+
+fn install_sum_plugin(data_dir: &Path) -> core::plugin::PluginManager<wasm_provider::WasmProvider> {
+  let mut manager = create_manager(data_dir);
+  manager.init().expect("Failed to initialize provider");
+  manager
+    .install(&workspace_root::get_workspace_root().join("example/sum-plugin"))
+    .expect("Failed to install sum-plugin");
+  manager
+}
+
+#[test]
+fn test_disabled_plugin_is_not_loaded() {
+  let data_dir = tempfile::tempdir().unwrap();
+  let mut manager = install_sum_plugin(data_dir.path());
+
+  manager.set_enabled("sum-plugin", false).unwrap();
+
+  match manager.load("sum-plugin") {
+    Err(PluginError::LoadError(msg)) => assert!(msg.contains("disabled"), "{}", msg),
+    other => panic!("expected LoadError, got {:?}", other),
+  }
+
+  manager.set_enabled("sum-plugin", true).unwrap();
+  manager
+    .load("sum-plugin")
+    .expect("Failed to load re-enabled plugin");
+}
+
+#[test]
+fn test_enabled_state_survives_a_new_manager() {
+  let data_dir = tempfile::tempdir().unwrap();
+  install_sum_plugin(data_dir.path())
+    .set_enabled("sum-plugin", false)
+    .unwrap();
+
+  let mut manager = create_manager(data_dir.path());
+  manager.init().expect("Failed to initialize provider");
+
+  // Still listed, but marked as disabled.
+  let plugins = manager.get_all().unwrap();
+  assert_eq!(plugins.len(), 1);
+  assert!(!plugins[0].is_enabled());
+  assert!(manager.load("sum-plugin").is_err());
+}
+
+#[test]
+fn test_set_enabled_requires_installed_plugin() {
+  let data_dir = tempfile::tempdir().unwrap();
+  let mut manager = create_manager(data_dir.path());
+
+  assert!(matches!(
+    manager.set_enabled("missing", false),
+    Err(PluginError::LoadError(_))
+  ));
+}
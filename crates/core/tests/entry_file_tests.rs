@@ -0,0 +1,74 @@
+//! Integration tests for plugins naming their entry file with `main`.
+
+mod common;
+
+use common::create_manager;
+use shared_types::ProviderValue;
+use shared_types::plugin::PluginError;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+// Writes a copy of sum-plugin whose wasm is stored as `main_file`.
+fn write_renamed_sum_plugin(root: &Path, main_file: &str, declared: &str) -> PathBuf {
+  let examples = workspace_root::get_workspace_root().join("example");
+  let dir = root.join("sum-plugin");
+  fs::create_dir_all(&dir).unwrap();
+  fs::write(
+    dir.join("plugin.json"),
+    format!(
+      r#"{{"name": "sum-plugin", "version": "1.0.0", "description": "Sum", "author": "tester", "main": "{}"}}"#,
+      declared
+    ),
+  )
+  .unwrap();
+  fs::copy(examples.join("sum-plugin/main.wasm"), dir.join(main_file)).unwrap();
+  dir
+}
+
+#[test]
+fn test_load_uses_declared_entry_file() {
+  let source = tempfile::tempdir().unwrap();
+  let data_dir = tempfile::tempdir().unwrap();
+  let plugin_dir = write_renamed_sum_plugin(source.path(), "sum-1.0.0.wasm", "sum-1.0.0.wasm");
+  let mut manager = create_manager(data_dir.path()).with_entry_file_check();
+  manager.init().expect("Failed to initialize provider");
+
+  manager
+    .install(&plugin_dir)
+    .expect("Failed to install plugin");
+  manager.load("sum-plugin").expect("Failed to load plugin");
+
+  let result = manager.invoke(
+    "sum-plugin",
+    "Sum",
+    vec![ProviderValue::Int(2), ProviderValue::Int(3)],
+  );
+  assert_eq!(result.unwrap(), ProviderValue::Int(5));
+}
+
+#[test]
+fn test_missing_declared_entry_file_fails_install() {
+  let source = tempfile::tempdir().unwrap();
+  let data_dir = tempfile::tempdir().unwrap();
+  // main.wasm exists, but the manifest points elsewhere.
+  let plugin_dir = write_renamed_sum_plugin(source.path(), "main.wasm", "plugin.wasm");
+  let mut manager = create_manager(data_dir.path()).with_entry_file_check();
+
+  match manager.install(&plugin_dir) {
+    Err(PluginError::InstallError(msg)) => assert!(msg.contains("plugin.wasm"), "{}", msg),
+    other => panic!("expected InstallError, got {:?}", other),
+  }
+}
+
+#[test]
+fn test_entry_file_outside_plugin_dir_is_rejected() {
+  let source = tempfile::tempdir().unwrap();
+  let data_dir = tempfile::tempdir().unwrap();
+  let plugin_dir = write_renamed_sum_plugin(source.path(), "main.wasm", "../main.wasm");
+  let mut manager = create_manager(data_dir.path());
+
+  assert!(matches!(
+    manager.install(&plugin_dir),
+    Err(PluginError::InstallError(_))
+  ));
+}
@@ -0,0 +1,107 @@
+//! Integration tests for the plugin lifecycle event stream.
+
+mod common;
+
+use common::create_manager;
+use core::plugin::PluginEvent;
+use shared_types::ProviderValue;
+
+#[test]
+fn test_events_report_load_and_invoke() {
+  let data_dir = tempfile::tempdir().unwrap();
+  let mut manager = create_manager(data_dir.path());
+  manager.init().expect("Failed to initialize provider");
+  manager
+    .install(&workspace_root::get_workspace_root().join("example/sum-plugin"))
+    .expect("Failed to install sum-plugin");
+
+  let events = manager.events();
+  drop(manager.events());
+
+  manager.load("sum-plugin").expect("Failed to load plugin");
+  assert_eq!(
+    events.try_recv(),
+    Ok(PluginEvent::PluginLoaded {
+      name: "sum-plugin".to_string()
+    })
+  );
+
+  manager
+    .invoke(
+      "sum-plugin",
+      "Sum",
+      vec![ProviderValue::Int(1), ProviderValue::Int(2)],
+    )
+    .expect("Failed to invoke plugin");
+  match events.try_recv() {
+    Ok(PluginEvent::PluginInvoked { name, function, .. }) => {
+      assert_eq!((name.as_str(), function.as_str()), ("sum-plugin", "Sum"))
+    }
+    other => panic!("expected PluginInvoked, got {:?}", other),
+  }
+
+  assert!(manager.invoke("sum-plugin", "Missing", vec![]).is_err());
+  assert!(matches!(
+    events.try_recv(),
+    Ok(PluginEvent::PluginFailed { .. })
+  ));
+}
+
+#[test]
+fn test_install_publishes_transition() {
+  let data_dir = tempfile::tempdir().unwrap();
+  let mut manager = create_manager(data_dir.path());
+  let events = manager.events();
+  let source = workspace_root::get_workspace_root().join("example/sum-plugin");
+
+  manager
+    .install(&source)
+    .expect("Failed to install sum-plugin");
+
+  match events.try_recv() {
+    Ok(PluginEvent::PluginInstalled {
+      name,
+      version,
+      source: from,
+      destination,
+      installed,
+    }) => {
+      assert_eq!(name, "sum-plugin");
+      assert_eq!(version, "1.0.0");
+      assert_eq!(from, source);
+      assert_eq!(destination, data_dir.path().join("sum-plugin"));
+      assert_eq!(installed, vec!["sum-plugin".to_string()]);
+    }
+    other => panic!("expected PluginInstalled, got {:?}", other),
+  }
+}
+
+#[test]
+fn test_unload_publishes_transition() {
+  let data_dir = tempfile::tempdir().unwrap();
+  let mut manager = create_manager(data_dir.path());
+  manager.init().expect("Failed to initialize provider");
+  manager
+    .install(&workspace_root::get_workspace_root().join("example/sum-plugin"))
+    .expect("Failed to install sum-plugin");
+  manager.load("sum-plugin").expect("Failed to load plugin");
+  let events = manager.events();
+
+  manager
+    .unload("sum-plugin")
+    .expect("Failed to unload plugin");
+  assert_eq!(
+    events.try_recv(),
+    Ok(PluginEvent::PluginUnloaded {
+      name: "sum-plugin".to_string()
+    })
+  );
+  assert!(manager.function_catalog("sum-plugin").is_err());
+
+  // A plugin that is not loaded cannot be unloaded, and nothing is published.
+  assert!(matches!(
+    manager.unload("sum-plugin"),
+    Err(shared_types::plugin::PluginError::UnloadError(_))
+  ));
+  assert!(events.try_recv().is_err());
+}
@@ -0,0 +1,61 @@
+//! Integration tests for the aggregated health report.
+
+mod common;
+
+use common::create_manager;
+use core::BudCore;
+use std::env;
+use std::path::PathBuf;
+use wasm_provider::WasmProvider;
+
+#[test]
+fn test_health_reflects_provider_and_loaded_plugin() {
+  let fixture_dir = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+    .join("tests")
+    .join("fixtures")
+    .join("app");
+  let original_dir = env::current_dir().unwrap();
+  env::set_current_dir(&fixture_dir).unwrap();
+  let core = BudCore::builder(WasmProvider::new()).build();
+  env::set_current_dir(original_dir).unwrap();
+  let mut core = core.expect("Failed to build BudCore");
+
+  let report = core.health();
+  assert!(report.provider_initialized);
+  assert!(report.loaded_plugins.is_empty());
+  assert!(report.is_healthy());
+
+  // Loaded from the system path so nothing is written to the user data directory.
+  core.plugin_manager = core
+    .plugin_manager
+    .with_system_path(workspace_root::get_workspace_root().join("example"));
+  core
+    .plugin_manager
+    .load("test-plugin")
+    .expect("Failed to load test-plugin");
+
+  let report = core.health();
+  assert_eq!(report.loaded_plugins, vec!["test-plugin".to_string()]);
+  assert!(report.failed_plugins.is_empty());
+  assert!(report.is_healthy());
+}
+
+#[test]
+fn test_health_reports_uninitialized_provider_and_failed_plugin() {
+  let data_dir = tempfile::tempdir().unwrap();
+  let mut manager = create_manager(data_dir.path());
+  assert!(!manager.health().provider_initialized);
+  assert!(!manager.health().is_healthy());
+
+  manager.init().expect("Failed to initialize provider");
+  manager
+    .install(&workspace_root::get_workspace_root().join("example/test-plugin"))
+    .expect("Failed to install test-plugin");
+  manager.load("test-plugin").expect("Failed to load");
+  assert!(manager.invoke("test-plugin", "Panic", vec![]).is_err());
+
+  let report = manager.health();
+  assert!(report.provider_initialized);
+  assert_eq!(report.failed_plugins, vec!["test-plugin".to_string()]);
+  assert!(!report.is_healthy());
+}
@@ -0,0 +1,33 @@
+//! Integration tests for installs failing part way through the copy.
+#![cfg(unix)]
+
+mod common;
+
+use common::{create_manager, write_manifest};
+use shared_types::plugin::PluginError;
+use std::fs;
+use std::os::unix::net::UnixListener;
+
+#[test]
+fn test_failed_copy_leaves_no_partial_plugin() {
+  let source = tempfile::tempdir().unwrap();
+  let plugin_dir = write_manifest(source.path(), "broken", "Fixture plugin", "{}");
+  fs::write(plugin_dir.join("main.wasm"), "").unwrap();
+  // A socket cannot be opened for reading, so copying it fails.
+  let _socket = UnixListener::bind(plugin_dir.join("socket")).unwrap();
+  let data_dir = tempfile::tempdir().unwrap();
+  let mut manager = create_manager(data_dir.path());
+
+  assert!(matches!(
+    manager.install(&plugin_dir),
+    Err(PluginError::IoError(_))
+  ));
+
+  let leftovers: Vec<_> = fs::read_dir(data_dir.path())
+    .unwrap()
+    .map(|entry| entry.unwrap().file_name())
+    .filter(|name| name.to_string_lossy().contains("broken"))
+    .collect();
+  assert!(leftovers.is_empty(), "left behind: {:?}", leftovers);
+  assert!(manager.list_installed().unwrap().is_empty());
+}
@@ -0,0 +1,77 @@
+//! Integration tests for checks that reject an install before anything is copied.
+
+mod common;
+
+use common::{create_manager, write_manifest};
+use shared_types::plugin::PluginError;
+use std::fs;
+use std::path::PathBuf;
+
+fn assert_rejected(result: Result<(), PluginError>) {
+  match result {
+    Err(PluginError::InstallError(msg)) => {
+      assert_eq!(msg, "cannot install from within the data directory")
+    }
+    other => panic!("expected InstallError, got {:?}", other.err()),
+  }
+}
+
+#[test]
+fn test_install_rejects_data_dir_as_source() {
+  let data_dir = tempfile::tempdir().unwrap();
+  let mut manager = create_manager(data_dir.path());
+
+  assert_rejected(manager.install(data_dir.path()));
+}
+
+#[test]
+fn test_install_rejects_child_of_data_dir() {
+  let data_dir = tempfile::tempdir().unwrap();
+  let plugin_dir = write_manifest(data_dir.path(), "nested", "Fixture plugin", "{}");
+  let mut manager = create_manager(data_dir.path());
+
+  assert_rejected(manager.install(&plugin_dir));
+
+  // The same directory reached through `..` is still detected.
+  let roundabout: PathBuf = plugin_dir.join("..").join("nested");
+  assert_rejected(manager.install(&roundabout));
+}
+
+#[test]
+fn test_install_rejects_parent_of_data_dir() {
+  let source = tempfile::tempdir().unwrap();
+  write_manifest(source.path(), "outer", "Fixture plugin", "{}");
+  let plugin_dir = source.path().join("outer");
+  let mut manager = create_manager(&plugin_dir.join("data"));
+
+  assert_rejected(manager.install(&plugin_dir));
+  assert!(!plugin_dir.join("data").exists());
+}
+
+#[test]
+fn test_install_requires_entry_file_when_checked() {
+  let source = tempfile::tempdir().unwrap();
+  let plugin_dir = write_manifest(source.path(), "unpackaged", "Fixture plugin", "{}");
+  let data_dir = tempfile::tempdir().unwrap();
+  let mut manager = create_manager(data_dir.path()).with_entry_file_check();
+
+  match manager.install(&plugin_dir) {
+    Err(PluginError::InstallError(msg)) => assert_eq!(msg, "missing entry file main.wasm"),
+    other => panic!("expected InstallError, got {:?}", other.err()),
+  }
+  assert!(!data_dir.path().join("unpackaged").exists());
+
+  fs::write(plugin_dir.join("main.wasm"), b"\0asm").unwrap();
+  manager.install(&plugin_dir).expect("install failed");
+  assert!(data_dir.path().join("unpackaged/main.wasm").is_file());
+}
+
+#[test]
+fn test_install_skips_entry_file_check_by_default() {
+  let source = tempfile::tempdir().unwrap();
+  let plugin_dir = write_manifest(source.path(), "unpackaged", "Fixture plugin", "{}");
+  let data_dir = tempfile::tempdir().unwrap();
+  let mut manager = create_manager(data_dir.path());
+
+  manager.install(&plugin_dir).expect("install failed");
+}
@@ -0,0 +1,52 @@
+//! Integration tests for the development files `install` does not copy.
+
+mod common;
+
+use common::{create_manager, write_manifest};
+use std::fs;
+
+#[test]
+fn test_install_skips_vcs_build_and_editor_files() {
+  let source = tempfile::tempdir().unwrap();
+  let plugin_dir = write_manifest(source.path(), "noisy", "Fixture plugin", "{}");
+  for dir in [
+    ".git/objects",
+    "target/debug",
+    "node_modules/dep",
+    "src/target",
+  ] {
+    fs::create_dir_all(plugin_dir.join(dir)).unwrap();
+  }
+  for file in [
+    ".git/HEAD",
+    "target/debug/main.wasm",
+    "node_modules/dep/index.js",
+    "src/target/keep.txt",
+    "src/lib.rs",
+    "src/lib.rs~",
+    "src/.lib.rs.swp",
+    ".DS_Store",
+  ] {
+    fs::write(plugin_dir.join(file), "").unwrap();
+  }
+  let data_dir = tempfile::tempdir().unwrap();
+  let mut manager = create_manager(data_dir.path());
+
+  manager.install(&plugin_dir).expect("install failed");
+
+  let installed = data_dir.path().join("noisy");
+  assert!(installed.join("plugin.json").is_file());
+  assert!(installed.join("src/lib.rs").is_file());
+  // Only a top-level `target` is build output.
+  assert!(installed.join("src/target/keep.txt").is_file());
+  for skipped in [
+    ".git",
+    "target",
+    "node_modules",
+    "src/lib.rs~",
+    "src/.lib.rs.swp",
+    ".DS_Store",
+  ] {
+    assert!(!installed.join(skipped).exists(), "{} was copied", skipped);
+  }
+}
@@ -0,0 +1,85 @@
+//! Integration tests for `PluginManager::invoke_async`.
+#![cfg(feature = "async")]
+
+mod common;
+
+use common::write_manifest;
+use core::plugin::PluginManager;
+use shared_types::ProviderValue;
+use shared_types::config::ConfigData;
+use shared_types::plugin::PluginError;
+use std::fs;
+use std::path::Path;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use wasm_provider::WasmProvider;
+
+// Manager over an async `WasmProvider` with copies of test-plugin installed and loaded
+// under each of `names`.
+fn async_manager(data_dir: &Path, source: &Path, names: &[&str]) -> PluginManager<WasmProvider> {
+  let config = Arc::new(ConfigData {
+    name: "test-app".to_string(),
+    version: "0.1.0".to_string(),
+    description: "A test application".to_string(),
+    providers: Default::default(),
+  });
+  let provider = WasmProvider::builder().async_support(true).build();
+  let mut manager =
+    PluginManager::with_data_path(config, Arc::new(provider), data_dir.to_path_buf());
+  manager.init().expect("Failed to initialize provider");
+
+  for name in names {
+    let plugin_dir = write_manifest(source, name, "Async fixture", "{}");
+    fs::copy(
+      workspace_root::get_workspace_root().join("example/test-plugin/main.wasm"),
+      plugin_dir.join("main.wasm"),
+    )
+    .unwrap();
+    manager.install(&plugin_dir).expect("Failed to install");
+    manager.load(name).expect("Failed to load");
+  }
+  manager
+}
+
+// `#[tokio::test]` expands to paths under `::core`, which this crate's name shadows.
+fn block_on<F: Future>(future: F) -> F::Output {
+  tokio::runtime::Builder::new_current_thread()
+    .enable_all()
+    .build()
+    .unwrap()
+    .block_on(future)
+}
+
+#[test]
+fn test_calls_into_two_plugins_overlap() {
+  let data_dir = tempfile::tempdir().unwrap();
+  let source = tempfile::tempdir().unwrap();
+  let manager = async_manager(data_dir.path(), source.path(), &["first", "second"]);
+  let sleep = || vec![ProviderValue::Int(400)];
+
+  let started = Instant::now();
+  let (first, second) = block_on(async {
+    tokio::join!(
+      manager.invoke_async("first", "Sleep", sleep()),
+      manager.invoke_async("second", "Sleep", sleep()),
+    )
+  });
+
+  assert_eq!(first.unwrap(), ProviderValue::Null);
+  assert_eq!(second.unwrap(), ProviderValue::Null);
+  let elapsed = started.elapsed();
+  assert!(elapsed < Duration::from_millis(700), "took {:?}", elapsed);
+}
+
+#[test]
+fn test_plugin_must_be_loaded() {
+  let data_dir = tempfile::tempdir().unwrap();
+  let source = tempfile::tempdir().unwrap();
+  let mut manager = async_manager(data_dir.path(), source.path(), &["first"]);
+  manager.unload("first").unwrap();
+
+  match block_on(manager.invoke_async("first", "Echo", vec![])) {
+    Err(PluginError::LoadError(msg)) => assert!(msg.contains("'first'"), "{}", msg),
+    other => panic!("expected LoadError, got {:?}", other),
+  }
+}
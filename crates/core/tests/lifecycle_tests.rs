@@ -0,0 +1,107 @@
+//! Integration tests for the plugin lifecycle state machine.
+
+mod common;
+
+use common::create_manager;
+use core::plugin::PluginLifecycle;
+use shared_types::ProviderValue;
+use shared_types::plugin::PluginError;
+
+fn sum(
+  manager: &mut core::plugin::PluginManager<wasm_provider::WasmProvider>,
+) -> Result<ProviderValue, PluginError> {
+  manager.invoke(
+    "sum-plugin",
+    "Sum",
+    vec![ProviderValue::Int(1), ProviderValue::Int(2)],
+  )
+}
+
+#[test]
+fn test_lifecycle_install_load_drain_unload() {
+  let data_dir = tempfile::tempdir().unwrap();
+  let mut manager = create_manager(data_dir.path());
+  manager.init().expect("Failed to initialize provider");
+  assert_eq!(manager.status("sum-plugin"), PluginLifecycle::NotInstalled);
+
+  manager
+    .install(&workspace_root::get_workspace_root().join("example/sum-plugin"))
+    .expect("Failed to install sum-plugin");
+  assert_eq!(manager.status("sum-plugin"), PluginLifecycle::Installed);
+  assert!(matches!(sum(&mut manager), Err(PluginError::LoadError(_))));
+
+  manager.load("sum-plugin").expect("Failed to load plugin");
+  assert_eq!(manager.status("sum-plugin"), PluginLifecycle::Loaded);
+  assert_eq!(sum(&mut manager).unwrap(), ProviderValue::Int(3));
+
+  manager.drain("sum-plugin").expect("Failed to drain plugin");
+  assert_eq!(manager.status("sum-plugin"), PluginLifecycle::Draining);
+  assert!(matches!(
+    sum(&mut manager),
+    Err(PluginError::InvalidState(_))
+  ));
+
+  manager
+    .unload("sum-plugin")
+    .expect("Failed to unload plugin");
+  assert_eq!(manager.status("sum-plugin"), PluginLifecycle::Installed);
+
+  // Unloading ends the drain, so the plugin can be loaded again.
+  manager.load("sum-plugin").expect("Failed to load plugin");
+  assert_eq!(sum(&mut manager).unwrap(), ProviderValue::Int(3));
+}
+
+#[test]
+fn test_lifecycle_rejects_invalid_transitions() {
+  let data_dir = tempfile::tempdir().unwrap();
+  let mut manager = create_manager(data_dir.path());
+  manager.init().expect("Failed to initialize provider");
+  manager
+    .install(&workspace_root::get_workspace_root().join("example/sum-plugin"))
+    .expect("Failed to install sum-plugin");
+
+  // Only loaded plugins can be drained.
+  for name in ["sum-plugin", "missing"] {
+    assert!(matches!(
+      manager.drain(name),
+      Err(PluginError::InvalidState(_))
+    ));
+  }
+
+  manager.load("sum-plugin").expect("Failed to load plugin");
+  manager.drain("sum-plugin").expect("Failed to drain plugin");
+
+  match manager.load("sum-plugin") {
+    Err(PluginError::InvalidState(msg)) => {
+      assert_eq!(msg, "cannot load plugin 'sum-plugin' while it is draining")
+    }
+    other => panic!("expected InvalidState, got {:?}", other),
+  }
+  assert!(matches!(
+    manager.reload("sum-plugin"),
+    Err(PluginError::InvalidState(_))
+  ));
+  assert!(matches!(
+    manager.drain("sum-plugin"),
+    Err(PluginError::InvalidState(_))
+  ));
+  assert_eq!(manager.status("sum-plugin"), PluginLifecycle::Draining);
+}
+
+#[test]
+fn test_lifecycle_reports_trapped_plugin_as_failed() {
+  let data_dir = tempfile::tempdir().unwrap();
+  let mut manager = create_manager(data_dir.path());
+  manager.init().expect("Failed to initialize provider");
+  manager
+    .install(&workspace_root::get_workspace_root().join("example/test-plugin"))
+    .expect("Failed to install test-plugin");
+  manager.load("test-plugin").expect("Failed to load plugin");
+
+  assert!(manager.invoke("test-plugin", "Panic", vec![]).is_err());
+  assert_eq!(manager.status("test-plugin"), PluginLifecycle::Failed);
+
+  // Loading again recovers the plugin.
+  manager.load("test-plugin").expect("Failed to load plugin");
+  assert_eq!(manager.status("test-plugin"), PluginLifecycle::Loaded);
+}
@@ -0,0 +1,35 @@
+//! Integration tests for listing installed plugins without validating them.
+
+mod common;
+
+use common::{create_manager, write_manifest};
+use std::fs;
+
+#[test]
+fn test_list_installed_names_plugin_directories() {
+  let data_dir = tempfile::tempdir().unwrap();
+  let root = data_dir.path();
+  let mut manager = create_manager(root);
+  manager
+    .install(&workspace_root::get_workspace_root().join("example/sum-plugin"))
+    .expect("Failed to install sum-plugin");
+
+  write_manifest(root, "alpha", "Fixture plugin", "{}");
+  // Listed by directory name even though the manifest is invalid.
+  fs::create_dir(root.join("broken")).unwrap();
+  fs::write(root.join("broken/plugin.json"), "{ not json").unwrap();
+  fs::create_dir(root.join("no-manifest")).unwrap();
+
+  assert_eq!(
+    manager.list_installed().unwrap(),
+    ["alpha", "broken", "sum-plugin"]
+  );
+}
+
+#[test]
+fn test_list_installed_is_empty_without_data_dir() {
+  let root = tempfile::tempdir().unwrap();
+  let manager = create_manager(&root.path().join("missing"));
+
+  assert!(manager.list_installed().unwrap().is_empty());
+}
@@ -0,0 +1,44 @@
+//! Integration tests for the logger level chosen through `BudCoreBuilder::log_level`.
+//!
+//! The global logger can only be installed once per process, so this file holds a
+//! single test.
+
+use config::CONFIG_ENV_VAR;
+use core::{BudCore, BudCoreError};
+use log::{Level, LevelFilter, Metadata};
+use shared_types::config::ConfigError;
+use wasm_provider::WasmProvider;
+
+fn enabled(level: Level) -> bool {
+  log::logger().enabled(
+    &Metadata::builder()
+      .level(level)
+      .target("log_level_tests")
+      .build(),
+  )
+}
+
+#[test]
+fn test_builder_log_level_takes_effect() {
+  let dir = tempfile::tempdir().unwrap();
+  // The logger is set up before the config is read, so a missing config still
+  // exercises it without touching the real project directory.
+  // SAFETY: this is the only test in the process.
+  unsafe { std::env::set_var(CONFIG_ENV_VAR, dir.path().join("missing.json")) };
+
+  let result = BudCore::builder(WasmProvider::new())
+    .log_level(LevelFilter::Warn)
+    .build();
+
+  assert!(matches!(
+    result,
+    Err(BudCoreError::Config(ConfigError::FileNotFound(_)))
+  ));
+  assert_eq!(log::max_level(), LevelFilter::Warn);
+  assert!(enabled(Level::Warn));
+  assert!(!enabled(Level::Info));
+
+  // Later initializations keep the first level.
+  core::init_logger_with_level(LevelFilter::Trace);
+  assert_eq!(log::max_level(), LevelFilter::Warn);
+}
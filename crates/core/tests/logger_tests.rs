@@ -0,0 +1,64 @@
+//! Integration tests for logger timestamp formatting.
+
+use core::{TimestampFormat, logger_builder};
+use env_logger::Target;
+use log::{Level, Log, Record};
+use std::io::{self, Write};
+use std::sync::{Arc, Mutex};
+
+#[derive(Clone, Default)]
+struct SharedBuffer(Arc<Mutex<Vec<u8>>>);
+
+impl Write for SharedBuffer {
+  fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+    self.0.lock().unwrap().extend_from_slice(buf);
+    Ok(buf.len())
+  }
+
+  fn flush(&mut self) -> io::Result<()> {
+    Ok(())
+  }
+}
+
+// Formats one record with the given timestamp format and returns the text between
+// the opening bracket and the level, i.e. the timestamp (empty when disabled).
+fn timestamp_of(format: TimestampFormat) -> String {
+  let buffer = SharedBuffer::default();
+  let logger = logger_builder(format)
+    .target(Target::Pipe(Box::new(buffer.clone())))
+    .write_style(env_logger::WriteStyle::Never)
+    .build();
+
+  logger.log(
+    &Record::builder()
+      .args(format_args!("hello"))
+      .level(Level::Info)
+      .target("logger_tests")
+      .build(),
+  );
+
+  let line = String::from_utf8(buffer.0.lock().unwrap().clone()).unwrap();
+  assert!(line.ends_with("hello\n"), "unexpected record: {:?}", line);
+  let header = line.trim_start_matches('[');
+  header[..header.find("INFO").expect("level missing")]
+    .trim()
+    .to_string()
+}
+
+// Length of the fractional-seconds part of an RFC 3339 timestamp ending in `Z`.
+fn fraction_digits(timestamp: &str) -> usize {
+  assert!(timestamp.ends_with('Z'), "not UTC RFC 3339: {}", timestamp);
+  timestamp
+    .split_once('.')
+    .map_or(0, |(_, frac)| frac.len() - 1)
+}
+
+#[test]
+fn test_timestamp_formats_are_applied() {
+  assert_eq!(TimestampFormat::default(), TimestampFormat::Seconds);
+
+  assert_eq!(fraction_digits(&timestamp_of(TimestampFormat::Seconds)), 0);
+  assert_eq!(fraction_digits(&timestamp_of(TimestampFormat::Millis)), 3);
+  assert_eq!(fraction_digits(&timestamp_of(TimestampFormat::Rfc3339)), 9);
+  assert_eq!(timestamp_of(TimestampFormat::None), "");
+}
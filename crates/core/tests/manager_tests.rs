@@ -4,9 +4,9 @@
 //! They can only access public APIs of the core crate.
 
 use core::plugin::PluginManager;
-use shared_types::ProviderValue;
 use shared_types::config::ConfigData;
 use shared_types::plugin::PluginError;
+use shared_types::{ProviderValue, ValueKind};
 use std::sync::{Arc, Once};
 use wasm_provider::WasmProvider;
 
@@ -17,6 +17,7 @@ fn create_manager() -> PluginManager<WasmProvider> {
     name: "test-app".to_string(),
     version: "0.1.0".to_string(),
     description: "A test application".to_string(),
+    providers: Default::default(),
   });
   let provider = Arc::new(WasmProvider::new());
   PluginManager::new(config, provider).unwrap()
@@ -77,7 +78,6 @@ fn test_plugin_manager_load() {
 }
 
 #[test]
-#[ignore]
 fn test_plugin_manager_invoke() {
   let mut manager = create_manager();
   manager.init().expect("Failed to initialize provider");
@@ -98,3 +98,46 @@ fn test_plugin_manager_invoke() {
   let value = result.unwrap();
   assert_eq!(value, ProviderValue::Int(3), "Sum(1, 2) should return 3");
 }
+
+#[test]
+fn test_plugin_manager_function_catalog() {
+  let mut manager = create_manager();
+  manager.init().expect("Failed to initialize provider");
+  setup_test_plugin_once();
+
+  assert!(matches!(
+    manager.function_catalog("sum-plugin"),
+    Err(PluginError::LoadError(_))
+  ));
+
+  manager.load("sum-plugin").expect("Failed to load plugin");
+  let catalog = manager
+    .function_catalog("sum-plugin")
+    .expect("Failed to describe plugin");
+
+  let sum = catalog
+    .iter()
+    .find(|sig| sig.name == "Sum")
+    .expect("Sum missing from catalog");
+  assert_eq!(sum.params, vec![ValueKind::I32, ValueKind::I32]);
+  assert_eq!(sum.results, vec![ValueKind::I32]);
+  assert_eq!(sum.to_string(), "Sum(i32, i32) -> i32");
+}
+
+#[test]
+fn test_plugin_manager_list_functions() {
+  let mut manager = create_manager();
+  manager.init().expect("Failed to initialize provider");
+  setup_test_plugin_once();
+
+  match manager.list_functions("sum-plugin") {
+    Err(PluginError::LoadError(msg)) => assert!(msg.contains("not found"), "{}", msg),
+    other => panic!("expected LoadError, got {:?}", other),
+  }
+
+  manager.load("sum-plugin").expect("Failed to load plugin");
+  let functions = manager
+    .list_functions("sum-plugin")
+    .expect("Failed to list functions");
+  assert!(functions.contains(&"Sum".to_string()), "{:?}", functions);
+}
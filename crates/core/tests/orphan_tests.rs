@@ -0,0 +1,38 @@
+//! Integration tests for detecting unusable directories in the data path.
+
+mod common;
+
+use common::{create_manager, write_manifest};
+use core::plugin::{CACHE_FILE, OrphanReason, OrphanReport};
+use std::fs;
+
+#[test]
+fn test_find_orphans_reports_unloadable_directories() {
+  let data_dir = tempfile::tempdir().unwrap();
+  let root = data_dir.path();
+
+  write_manifest(root, "no-main", "Fixture plugin", "{}");
+  fs::create_dir(root.join("no-manifest")).unwrap();
+  fs::create_dir(root.join("broken")).unwrap();
+  fs::write(root.join("broken/plugin.json"), "{ not json").unwrap();
+
+  let mut manager = create_manager(root);
+  manager
+    .install(&workspace_root::get_workspace_root().join("example/sum-plugin"))
+    .expect("Failed to install sum-plugin");
+  assert!(root.join(CACHE_FILE).is_file());
+
+  let orphans = manager.find_orphans();
+  let reasons: Vec<(&str, &OrphanReason)> = orphans
+    .iter()
+    .map(|OrphanReport { path, reason }| (path.file_name().unwrap().to_str().unwrap(), reason))
+    .collect();
+
+  assert_eq!(reasons.len(), 3, "{:?}", orphans);
+  assert!(matches!(
+    reasons[0],
+    ("broken", OrphanReason::InvalidConfig(_))
+  ));
+  assert_eq!(reasons[1], ("no-main", &OrphanReason::MissingEntryFile));
+  assert_eq!(reasons[2], ("no-manifest", &OrphanReason::MissingManifest));
+}
@@ -0,0 +1,45 @@
+//! Integration tests for in-memory config overlays.
+
+mod common;
+
+use common::{create_manager, write_manifest};
+use serde_json::json;
+use shared_types::plugin::PluginError;
+use tempfile::TempDir;
+
+#[test]
+fn test_overlay_overrides_timeout() {
+  let data_dir = TempDir::new().unwrap();
+  let source = TempDir::new().unwrap();
+  let plugin_dir = write_manifest(source.path(), "overlaid", "Overlaid plugin", "{}");
+
+  let mut manager = create_manager(data_dir.path());
+  manager.install(&plugin_dir).expect("install failed");
+  assert_eq!(manager.get("overlaid").unwrap().config.timeout_ms, None);
+
+  manager
+    .set_overlay("overlaid", json!({"timeoutMs": 50}))
+    .expect("set_overlay failed");
+  let config = manager.get("overlaid").unwrap().config;
+  assert_eq!(config.timeout_ms, Some(50));
+  assert_eq!(config.name, "overlaid");
+  assert_eq!(config.description, "Overlaid plugin");
+
+  // The overlay is not persisted in the manifest cache.
+  assert!(manager.clear_overlay("overlaid"));
+  assert_eq!(manager.get("overlaid").unwrap().config.timeout_ms, None);
+}
+
+#[test]
+fn test_overlay_cannot_override_identity() {
+  let data_dir = TempDir::new().unwrap();
+  let mut manager = create_manager(data_dir.path());
+
+  for overlay in [json!({"name": "other"}), json!({"id": "other"}), json!(50)] {
+    assert!(matches!(
+      manager.set_overlay("overlaid", overlay),
+      Err(PluginError::InvalidOverlay(_))
+    ));
+  }
+  assert!(!manager.clear_overlay("overlaid"));
+}
@@ -0,0 +1,54 @@
+//! Integration tests for grouping declared permissions for review.
+
+mod common;
+
+use common::{create_manager, write_manifest};
+use core::plugin::PermissionCategory;
+
+#[test]
+fn test_permissions_grouped_by_category() {
+  config::register_permission_prefix("app");
+
+  let data_dir = tempfile::tempdir().unwrap();
+  write_manifest(
+    data_dir.path(),
+    "reviewed",
+    "Fixture plugin",
+    r#"{
+      "stdio": {"stdout": true, "stderr": false},
+      "filesystem": {"read": ["/data"], "write": ["/tmp/out"]},
+      "network": {"allowed_hosts": ["api.example.com"]},
+      "env": {"keys": ["HOME"]},
+      "process": false,
+      "app:send-email": true,
+      "app:delete-account": false
+    }"#,
+  );
+
+  let mut manager = create_manager(data_dir.path());
+  let mut info = manager.get("reviewed").expect("plugin not found");
+
+  // Validation keeps malformed keys out of plugin.json, but configs built in code can
+  // still carry them.
+  info
+    .config
+    .permissions
+    .as_mut()
+    .unwrap()
+    .custom
+    .insert("no-prefix".to_string(), true);
+
+  let groups = info.permissions_by_category();
+  let entries = |category| groups.get(&category).cloned().unwrap_or_default();
+
+  assert_eq!(entries(PermissionCategory::Stdio), ["stdout"]);
+  assert_eq!(
+    entries(PermissionCategory::Filesystem),
+    ["read:/data", "write:/tmp/out"]
+  );
+  assert_eq!(entries(PermissionCategory::Network), ["api.example.com"]);
+  assert_eq!(entries(PermissionCategory::Environment), ["HOME"]);
+  assert_eq!(entries(PermissionCategory::Custom), ["app:send-email"]);
+  assert_eq!(entries(PermissionCategory::Invalid), ["no-prefix"]);
+  assert!(!groups.contains_key(&PermissionCategory::Process));
+}
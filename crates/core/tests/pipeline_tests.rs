@@ -0,0 +1,102 @@
+//! Integration tests for chaining plugin calls into a pipeline.
+
+mod common;
+
+use common::create_manager;
+use core::plugin::PluginManager;
+use shared_types::ProviderValue;
+use shared_types::plugin::PluginError;
+use std::fs;
+use std::path::Path;
+use utils::copy_dir_recursive;
+use wasm_provider::WasmProvider;
+
+// Installs the test plugin twice, as "test-plugin" and "doubler", and loads both.
+fn manager_with_two_plugins(sources: &Path, data_dir: &Path) -> PluginManager<WasmProvider> {
+  let test_plugin = workspace_root::get_workspace_root().join("example/test-plugin");
+  let doubler = sources.join("doubler");
+  copy_dir_recursive(&test_plugin, &doubler).unwrap();
+  let manifest = fs::read_to_string(doubler.join("plugin.json")).unwrap();
+  fs::write(
+    doubler.join("plugin.json"),
+    manifest.replace("\"test-plugin\"", "\"doubler\""),
+  )
+  .unwrap();
+
+  let mut manager = create_manager(data_dir);
+  manager.init().expect("Failed to initialize provider");
+  for source in [&test_plugin, &doubler] {
+    manager.install(source).expect("Failed to install plugin");
+  }
+  manager
+    .load("test-plugin")
+    .expect("Failed to load test-plugin");
+  manager.load("doubler").expect("Failed to load doubler");
+  manager
+}
+
+#[test]
+fn test_pipeline_threads_results_across_plugins() {
+  let sources = tempfile::tempdir().unwrap();
+  let data_dir = tempfile::tempdir().unwrap();
+  let mut manager = manager_with_two_plugins(sources.path(), data_dir.path());
+
+  let result = manager
+    .pipeline(
+      &[("test-plugin", "Increment"), ("doubler", "Double")],
+      ProviderValue::Int(4),
+    )
+    .expect("pipeline failed");
+  assert_eq!(result, ProviderValue::Int(10));
+
+  // Order matters: doubling first gives a different result.
+  let result = manager
+    .pipeline(
+      &[("doubler", "Double"), ("test-plugin", "Increment")],
+      ProviderValue::Int(4),
+    )
+    .expect("pipeline failed");
+  assert_eq!(result, ProviderValue::Int(9));
+
+  assert_eq!(
+    manager.pipeline(&[], ProviderValue::Int(4)).unwrap(),
+    ProviderValue::Int(4)
+  );
+}
+
+#[test]
+fn test_pipeline_reports_failing_step() {
+  let sources = tempfile::tempdir().unwrap();
+  let data_dir = tempfile::tempdir().unwrap();
+  let mut manager = manager_with_two_plugins(sources.path(), data_dir.path());
+
+  let err = manager
+    .pipeline(
+      &[
+        ("test-plugin", "Increment"),
+        ("doubler", "Missing"),
+        ("doubler", "Panic"),
+      ],
+      ProviderValue::Int(1),
+    )
+    .expect_err("pipeline should fail");
+  match err {
+    PluginError::PipelineError { step, source } => {
+      assert_eq!(step, 1);
+      assert!(
+        matches!(*source, PluginError::InvokeError(_)),
+        "{:?}",
+        source
+      );
+    }
+    other => panic!("expected PipelineError, got {:?}", other),
+  }
+
+  // The step after the failure never ran, so the doubler did not trap.
+  assert_eq!(
+    manager
+      .invoke("doubler", "Double", vec![ProviderValue::Int(3)])
+      .unwrap(),
+    ProviderValue::Int(6)
+  );
+}
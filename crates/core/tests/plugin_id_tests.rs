@@ -0,0 +1,113 @@
+//! Integration tests for plugins identified by a stable `id` rather than their name.
+
+mod common;
+
+use common::create_manager;
+use core::plugin::PluginManager;
+use shared_types::ProviderValue;
+use std::fs;
+use std::path::{Path, PathBuf};
+use utils::copy_dir_recursive;
+use wasm_provider::WasmProvider;
+
+// Rewrites the `name` and `id` fields of `<dir>/plugin.json`.
+fn set_identity(dir: &Path, name: &str, id: &str) {
+  let path = dir.join("plugin.json");
+  let mut manifest: serde_json::Value =
+    serde_json::from_str(&fs::read_to_string(&path).unwrap()).unwrap();
+  manifest["name"] = name.into();
+  manifest["id"] = id.into();
+  fs::write(&path, manifest.to_string()).unwrap();
+}
+
+// Copies example/sum-plugin into `root` with the given id.
+fn sum_plugin_with_id(root: &Path, id: &str) -> PathBuf {
+  let source = root.join("sum-plugin");
+  copy_dir_recursive(
+    &workspace_root::get_workspace_root().join("example/sum-plugin"),
+    &source,
+  )
+  .unwrap();
+  set_identity(&source, "sum-plugin", id);
+  source
+}
+
+fn sum(manager: &mut PluginManager<WasmProvider>, name: &str) -> i64 {
+  match manager.invoke(
+    name,
+    "Sum",
+    vec![ProviderValue::Int(2), ProviderValue::Int(3)],
+  ) {
+    Ok(ProviderValue::Int(n)) => n,
+    other => panic!("unexpected result: {:?}", other),
+  }
+}
+
+#[test]
+fn test_install_stores_plugin_under_id() {
+  let sources = tempfile::tempdir().unwrap();
+  let data_dir = tempfile::tempdir().unwrap();
+  let mut manager = create_manager(data_dir.path());
+
+  manager
+    .install(&sum_plugin_with_id(sources.path(), "sum-7f3a"))
+    .expect("install failed");
+  assert!(data_dir.path().join("sum-7f3a").is_dir());
+  assert!(!data_dir.path().join("sum-plugin").exists());
+
+  // Both the id and the display name identify the plugin.
+  manager.init().expect("init failed");
+  manager.load("sum-plugin").expect("load by name failed");
+  assert_eq!(sum(&mut manager, "sum-7f3a"), 5);
+  assert_eq!(sum(&mut manager, "sum-plugin"), 5);
+}
+
+#[test]
+fn test_resolve_by_id_after_rename() {
+  let sources = tempfile::tempdir().unwrap();
+  let data_dir = tempfile::tempdir().unwrap();
+  let mut manager = create_manager(data_dir.path());
+  manager
+    .install(&sum_plugin_with_id(sources.path(), "sum-7f3a"))
+    .expect("install failed");
+  drop(manager);
+
+  // A later version renames the plugin for display but keeps its id.
+  set_identity(&data_dir.path().join("sum-7f3a"), "adder", "sum-7f3a");
+
+  let mut manager = create_manager(data_dir.path());
+  let info = manager.get("sum-7f3a").expect("lookup by id failed");
+  assert_eq!(info.config.name, "adder");
+  assert_eq!(info.path, data_dir.path().join("sum-7f3a"));
+
+  assert_eq!(
+    manager
+      .get("adder")
+      .expect("lookup by new name failed")
+      .path,
+    info.path
+  );
+  assert!(manager.get("sum-plugin").is_err());
+
+  manager.init().expect("init failed");
+  manager.load("sum-7f3a").expect("load by id failed");
+  assert_eq!(sum(&mut manager, "adder"), 5);
+}
+
+#[test]
+fn test_directory_must_match_id() {
+  let data_dir = tempfile::tempdir().unwrap();
+  sum_plugin_with_id(data_dir.path(), "sum-7f3a");
+
+  let mut manager = create_manager(data_dir.path());
+  let err = manager
+    .get("sum-plugin")
+    .err()
+    .expect("directory named after the display name should be rejected")
+    .to_string();
+  assert!(
+    err.contains("does not match plugin.json id 'sum-7f3a'"),
+    "{}",
+    err
+  );
+}
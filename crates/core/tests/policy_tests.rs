@@ -0,0 +1,63 @@
+//! Integration tests for the host-level permission policy.
+
+mod common;
+
+use common::{create_manager, write_manifest};
+use shared_types::config::PermissionKind;
+use shared_types::plugin::PluginError;
+
+#[test]
+fn test_policy_accepts_compliant_plugin() {
+  let source = tempfile::tempdir().unwrap();
+  let data_dir = tempfile::tempdir().unwrap();
+  let plugin_dir = write_manifest(
+    source.path(),
+    "quiet",
+    "Fixture plugin",
+    r#"{"stdio": true, "network": false}"#,
+  );
+
+  let mut manager =
+    create_manager(data_dir.path()).with_allowed_permissions([PermissionKind::Stdio]);
+  manager
+    .install(&plugin_dir)
+    .expect("compliant plugin rejected");
+  assert!(data_dir.path().join("quiet").is_dir());
+}
+
+#[test]
+fn test_policy_rejects_forbidden_permission() {
+  let source = tempfile::tempdir().unwrap();
+  let data_dir = tempfile::tempdir().unwrap();
+  let plugin_dir = write_manifest(
+    source.path(),
+    "chatty",
+    "Fixture plugin",
+    r#"{"stdio": true, "network": {"allowed_hosts": ["example.com"]}}"#,
+  );
+
+  let mut manager =
+    create_manager(data_dir.path()).with_allowed_permissions([PermissionKind::Stdio]);
+  match manager.install(&plugin_dir) {
+    Err(PluginError::LoadError(msg)) => {
+      assert_eq!(msg, "permission network not allowed by host policy")
+    }
+    other => panic!("expected LoadError, got {:?}", other.err()),
+  }
+  assert!(!data_dir.path().join("chatty").exists());
+
+  // A plugin already on disk is rejected at load time, before the provider is touched.
+  write_manifest(
+    data_dir.path(),
+    "chatty",
+    "Fixture plugin",
+    r#"{"network": true}"#,
+  );
+  let mut manager = create_manager(data_dir.path()).with_allowed_permissions([]);
+  match manager.load("chatty") {
+    Err(PluginError::LoadError(msg)) => {
+      assert_eq!(msg, "permission network not allowed by host policy")
+    }
+    other => panic!("expected LoadError, got {:?}", other.err()),
+  }
+}
@@ -0,0 +1,92 @@
+//! Integration tests for build-time precompilation into a shared cache directory.
+
+mod common;
+
+use common::create_manager;
+use core::plugin::CHECKSUMS_FILE;
+use shared_types::ProviderValue;
+use shared_types::plugin::PluginError;
+use std::fs;
+
+#[test]
+fn test_load_exclusively_from_precompiled_cache() {
+  let data_dir = tempfile::tempdir().unwrap();
+  let cache_dir = tempfile::tempdir().unwrap();
+
+  // Build time: install and precompile.
+  let mut builder = create_manager(data_dir.path());
+  builder.init().expect("Failed to initialize provider");
+  builder
+    .install(&workspace_root::get_workspace_root().join("example/sum-plugin"))
+    .expect("Failed to install sum-plugin");
+  builder
+    .precompile_all(cache_dir.path())
+    .expect("Failed to precompile");
+  assert_eq!(fs::read_dir(cache_dir.path()).unwrap().count(), 1);
+
+  // Run time: a fresh manager and engine that only reads the cache.
+  let mut runtime =
+    create_manager(data_dir.path()).with_precompiled_dir(cache_dir.path().to_path_buf());
+  runtime.init().expect("Failed to initialize provider");
+  runtime
+    .load("sum-plugin")
+    .expect("Failed to load from cache");
+  let result = runtime
+    .invoke(
+      "sum-plugin",
+      "Sum",
+      vec![ProviderValue::Int(1), ProviderValue::Int(2)],
+    )
+    .expect("Failed to invoke plugin");
+  assert_eq!(result, ProviderValue::Int(3));
+
+  // A changed main file has no artifact, and the source is never compiled instead.
+  // Dropping the checksum manifest keeps the edit from failing verification first.
+  fs::remove_file(data_dir.path().join("sum-plugin").join(CHECKSUMS_FILE)).unwrap();
+  fs::write(data_dir.path().join("sum-plugin/main.wasm"), b"changed").unwrap();
+  match runtime.load("sum-plugin") {
+    Err(PluginError::LoadError(msg)) => {
+      assert!(msg.contains("no precompiled artifact"), "{}", msg)
+    }
+    other => panic!("expected LoadError, got {:?}", other.err()),
+  }
+}
+
+#[test]
+fn test_corrupt_artifact_falls_back_to_recompiling() {
+  let data_dir = tempfile::tempdir().unwrap();
+  let cache_dir = tempfile::tempdir().unwrap();
+
+  let mut manager =
+    create_manager(data_dir.path()).with_precompiled_dir(cache_dir.path().to_path_buf());
+  manager.init().expect("Failed to initialize provider");
+  manager
+    .install(&workspace_root::get_workspace_root().join("example/sum-plugin"))
+    .expect("Failed to install sum-plugin");
+  manager
+    .precompile_all(cache_dir.path())
+    .expect("Failed to precompile");
+
+  let artifact = fs::read_dir(cache_dir.path())
+    .unwrap()
+    .next()
+    .unwrap()
+    .unwrap()
+    .path();
+  fs::write(&artifact, b"not a compiled component").unwrap();
+
+  manager
+    .load("sum-plugin")
+    .expect("corrupt artifact should not fail the load");
+  let result = manager
+    .invoke(
+      "sum-plugin",
+      "Sum",
+      vec![ProviderValue::Int(2), ProviderValue::Int(3)],
+    )
+    .expect("Failed to invoke plugin");
+  assert_eq!(result, ProviderValue::Int(5));
+
+  // The bad artifact was replaced by a freshly compiled one.
+  assert_ne!(fs::read(&artifact).unwrap(), b"not a compiled component");
+}
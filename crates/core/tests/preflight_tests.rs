@@ -0,0 +1,45 @@
+//! Integration tests for the pre-flight check.
+
+mod common;
+
+use common::write_manifest;
+use core::BudCore;
+use core::plugin::OrphanReason;
+use std::env;
+use std::fs;
+use std::path::PathBuf;
+use tempfile::TempDir;
+use wasm_provider::WasmProvider;
+
+#[test]
+fn test_preflight_flags_only_the_broken_plugin() {
+  let data_dir = TempDir::new().unwrap();
+  let good = write_manifest(data_dir.path(), "good", "A valid plugin", "{}");
+  fs::write(good.join("main.wasm"), b"").unwrap();
+  let broken = write_manifest(data_dir.path(), "broken", "Missing its module", "{}");
+
+  let fixture_dir = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+    .join("tests")
+    .join("fixtures")
+    .join("app");
+  let original_dir = env::current_dir().unwrap();
+  env::set_current_dir(&fixture_dir).unwrap();
+  let report = BudCore::<WasmProvider>::preflight_with_data_path(data_dir.path());
+  env::set_current_dir(&original_dir).unwrap();
+
+  assert_eq!(report.config_error, None);
+  assert_eq!(report.plugin_problems.len(), 1, "{:?}", report);
+  assert_eq!(report.plugin_problems[0].path, broken);
+  assert_eq!(
+    report.plugin_problems[0].reason,
+    OrphanReason::MissingEntryFile
+  );
+  assert!(!report.is_ok());
+
+  // Without a bud.json the config is reported, and the plugins are still checked.
+  env::set_current_dir(data_dir.path()).unwrap();
+  let report = BudCore::<WasmProvider>::preflight_with_data_path(data_dir.path());
+  env::set_current_dir(original_dir).unwrap();
+  assert!(report.config_error.is_some());
+  assert_eq!(report.plugin_problems.len(), 1);
+}
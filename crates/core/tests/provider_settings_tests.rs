@@ -0,0 +1,51 @@
+//! Integration tests for provider settings read from `bud.json`.
+
+use core::BudCore;
+use shared_types::ProviderValue;
+use std::env;
+use std::path::PathBuf;
+use wasm_provider::WasmProvider;
+
+#[test]
+fn test_fuel_limit_from_config_is_applied() {
+  let fixture_dir = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+    .join("tests")
+    .join("fixtures")
+    .join("provider_settings");
+  let original_dir = env::current_dir().unwrap();
+  env::set_current_dir(&fixture_dir).unwrap();
+  let core = BudCore::builder(WasmProvider::new()).build();
+  env::set_current_dir(original_dir).unwrap();
+  let core = core.expect("Failed to build BudCore");
+
+  // Settings for other providers are kept but not interpreted.
+  assert_eq!(
+    core.config.providers["bun"]["binary"],
+    serde_json::json!("/usr/local/bin/bun")
+  );
+
+  // Loaded from the system path so nothing is written to the user data directory.
+  let mut manager = core
+    .plugin_manager
+    .with_system_path(workspace_root::get_workspace_root().join("example"));
+  manager
+    .load("test-plugin")
+    .expect("Failed to load test-plugin");
+
+  // Ordinary calls fit in the budget, and it is refilled for every call.
+  for _ in 0..3 {
+    assert_eq!(
+      manager
+        .invoke("test-plugin", "Increment", vec![ProviderValue::Int(1)])
+        .unwrap(),
+      ProviderValue::Int(2)
+    );
+  }
+
+  // Without fuel metering this call would never return.
+  let err = manager
+    .invoke("test-plugin", "Spin", vec![])
+    .expect_err("Spin should run out of fuel")
+    .to_string();
+  assert!(err.contains("fuel"), "unexpected error: {}", err);
+}
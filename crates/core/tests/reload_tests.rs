@@ -0,0 +1,83 @@
+//! Integration tests for reloading a plugin after its main file was rebuilt.
+
+mod common;
+
+use common::create_manager;
+use core::plugin::{CHECKSUMS_FILE, PluginEvent};
+use shared_types::ProviderValue;
+use shared_types::plugin::PluginError;
+use std::fs;
+
+#[test]
+fn test_reload_picks_up_rebuilt_main_file() {
+  let data_dir = tempfile::tempdir().unwrap();
+  let examples = workspace_root::get_workspace_root().join("example");
+  let mut manager = create_manager(data_dir.path());
+  manager.init().expect("Failed to initialize provider");
+  manager
+    .install(&examples.join("sum-plugin"))
+    .expect("Failed to install sum-plugin");
+  manager.load("sum-plugin").expect("Failed to load plugin");
+
+  let sum = |manager: &mut core::plugin::PluginManager<_>| {
+    manager.invoke(
+      "sum-plugin",
+      "Sum",
+      vec![ProviderValue::Int(2), ProviderValue::Int(3)],
+    )
+  };
+  assert_eq!(sum(&mut manager).unwrap(), ProviderValue::Int(5));
+
+  // "Rebuild" the plugin as one without a `Sum` function, as a development plugin
+  // without install-time checksums.
+  fs::remove_file(data_dir.path().join("sum-plugin").join(CHECKSUMS_FILE)).unwrap();
+  fs::copy(
+    examples.join("test-plugin/main.wasm"),
+    data_dir.path().join("sum-plugin/main.wasm"),
+  )
+  .unwrap();
+  let events = manager.events();
+  manager
+    .reload("sum-plugin")
+    .expect("Failed to reload plugin");
+
+  assert!(sum(&mut manager).is_err());
+  let reloaded: Vec<PluginEvent> = events
+    .try_iter()
+    .filter(|event| matches!(event, PluginEvent::PluginReloaded { .. }))
+    .collect();
+  assert_eq!(
+    reloaded,
+    [PluginEvent::PluginReloaded {
+      name: "sum-plugin".to_string(),
+      changed: true
+    }]
+  );
+
+  // Reloading an unchanged file still works, and says so.
+  manager
+    .reload("sum-plugin")
+    .expect("Failed to reload plugin");
+  assert!(events.try_iter().any(|event| event
+    == PluginEvent::PluginReloaded {
+      name: "sum-plugin".to_string(),
+      changed: false
+    }));
+}
+
+#[test]
+fn test_reload_requires_loaded_plugin() {
+  let data_dir = tempfile::tempdir().unwrap();
+  let mut manager = create_manager(data_dir.path());
+  manager.init().expect("Failed to initialize provider");
+  manager
+    .install(&workspace_root::get_workspace_root().join("example/sum-plugin"))
+    .expect("Failed to install sum-plugin");
+  let events = manager.events();
+
+  assert!(matches!(
+    manager.reload("sum-plugin"),
+    Err(PluginError::LoadError(_))
+  ));
+  assert!(events.try_recv().is_err());
+}
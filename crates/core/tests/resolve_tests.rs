@@ -0,0 +1,33 @@
+//! Integration tests for resolving plugins across the user and system search paths.
+
+mod common;
+
+use common::{create_manager, write_manifest};
+use core::plugin::Origin;
+
+#[test]
+fn test_user_plugin_shadows_system_plugin() {
+  let system_dir = tempfile::tempdir().unwrap();
+  let user_dir = tempfile::tempdir().unwrap();
+  write_manifest(system_dir.path(), "shared", "built-in", "{}");
+  write_manifest(user_dir.path(), "shared", "override", "{}");
+  write_manifest(system_dir.path(), "system-only", "built-in", "{}");
+
+  let mut manager =
+    create_manager(user_dir.path()).with_system_path(system_dir.path().to_path_buf());
+
+  let (info, origin) = manager.resolve("shared").expect("resolve failed");
+  assert_eq!(origin, Origin::User);
+  assert_eq!(info.config.description, "override");
+  assert_eq!(info.path, user_dir.path().join("shared"));
+
+  let (info, origin) = manager.resolve("system-only").expect("resolve failed");
+  assert_eq!(origin, Origin::System);
+  assert_eq!(info.path, system_dir.path().join("system-only"));
+  assert_eq!(
+    manager.get("system-only").unwrap().path,
+    system_dir.path().join("system-only")
+  );
+
+  assert!(manager.resolve("missing").is_err());
+}
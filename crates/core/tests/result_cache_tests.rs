@@ -0,0 +1,128 @@
+//! Integration tests for caching invoke results.
+
+use core::plugin::PluginManager;
+use shared_types::config::ConfigData;
+use shared_types::provider::HostFunction;
+use shared_types::{Provider, ProviderValue};
+use std::fs;
+use std::path::Path;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use wasm_provider::{WasiVersion, WasmProvider};
+
+// Squares its argument after reporting the call through `env.count`.
+const SQUARE_WAT: &str = r#"
+(module
+  (import "env" "count" (func $count (param i32) (result i32)))
+  (func (export "Square") (param i32) (result i32)
+    (local $x i32)
+    (local.set $x (call $count (local.get 0)))
+    (i32.mul (local.get $x) (local.get $x)))
+)
+"#;
+
+// Returns a manager with a `square` plugin loaded and the number of times the plugin
+// has run so far. `functions` is the plugin.json `functions` object.
+fn square_manager(
+  data_dir: &Path,
+  functions: &str,
+) -> (PluginManager<WasmProvider>, Arc<AtomicUsize>) {
+  let calls = Arc::new(AtomicUsize::new(0));
+  let count: Arc<HostFunction> = {
+    let calls = Arc::clone(&calls);
+    Arc::new(move |args| {
+      calls.fetch_add(1, Ordering::SeqCst);
+      Ok(args.into_iter().next().unwrap_or(ProviderValue::Int(0)))
+    })
+  };
+
+  // The manager's `init` would reset the injected function, so the provider is
+  // initialized here instead.
+  let provider = WasmProvider::builder()
+    .wasi_version(WasiVersion::Preview1)
+    .host_module("env")
+    .build();
+  let mut instance = provider.init().expect("init failed");
+  provider
+    .inject(&mut instance, &[("count", count)])
+    .expect("inject failed");
+
+  let source = tempfile::tempdir().unwrap();
+  let plugin_dir = source.path().join("square");
+  fs::create_dir(&plugin_dir).unwrap();
+  let manifest = format!(
+    r#"{{"name": "square", "version": "1.0.0", "description": "Squares numbers", "author": "tester", "functions": {}}}"#,
+    functions
+  );
+  fs::write(plugin_dir.join("plugin.json"), manifest).unwrap();
+  fs::write(plugin_dir.join("main.wasm"), SQUARE_WAT).unwrap();
+
+  let config = Arc::new(ConfigData {
+    name: "test-app".to_string(),
+    version: "0.1.0".to_string(),
+    description: "A test application".to_string(),
+    providers: Default::default(),
+  });
+  let mut manager =
+    PluginManager::with_data_path(config, Arc::new(provider), data_dir.to_path_buf());
+  manager.install(&plugin_dir).expect("install failed");
+  manager.load("square").expect("load failed");
+  (manager, calls)
+}
+
+#[test]
+fn test_identical_cached_call_does_not_reenter_plugin() {
+  let data_dir = tempfile::tempdir().unwrap();
+  let (mut manager, calls) = square_manager(data_dir.path(), "{}");
+
+  let square = |manager: &mut PluginManager<WasmProvider>, x| {
+    manager
+      .invoke_cached("square", "Square", vec![ProviderValue::Int(x)])
+      .unwrap()
+  };
+  assert_eq!(square(&mut manager, 3), ProviderValue::Int(9));
+  assert_eq!(square(&mut manager, 3), ProviderValue::Int(9));
+  assert_eq!(calls.load(Ordering::SeqCst), 1);
+
+  // Different arguments and uncached calls reach the plugin.
+  assert_eq!(square(&mut manager, 4), ProviderValue::Int(16));
+  manager
+    .invoke("square", "Square", vec![ProviderValue::Int(3)])
+    .unwrap();
+  assert_eq!(calls.load(Ordering::SeqCst), 3);
+
+  // Loading the plugin again drops its results.
+  manager.load("square").expect("reload failed");
+  assert_eq!(square(&mut manager, 3), ProviderValue::Int(9));
+  assert_eq!(calls.load(Ordering::SeqCst), 4);
+}
+
+#[test]
+fn test_function_declared_cacheable_is_cached_by_invoke() {
+  let data_dir = tempfile::tempdir().unwrap();
+  let (mut manager, calls) = square_manager(data_dir.path(), r#"{"Square": {"cacheable": true}}"#);
+
+  for _ in 0..3 {
+    assert_eq!(
+      manager
+        .invoke("square", "Square", vec![ProviderValue::Int(5)])
+        .unwrap(),
+      ProviderValue::Int(25)
+    );
+  }
+  assert_eq!(calls.load(Ordering::SeqCst), 1);
+}
+
+#[test]
+fn test_zero_capacity_disables_result_cache() {
+  let data_dir = tempfile::tempdir().unwrap();
+  let (manager, calls) = square_manager(data_dir.path(), "{}");
+  let mut manager = manager.with_result_cache_capacity(0);
+
+  for _ in 0..2 {
+    manager
+      .invoke_cached("square", "Square", vec![ProviderValue::Int(2)])
+      .unwrap();
+  }
+  assert_eq!(calls.load(Ordering::SeqCst), 2);
+}
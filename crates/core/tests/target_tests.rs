@@ -0,0 +1,64 @@
+//! Integration tests for plugins restricted to particular host targets.
+
+mod common;
+
+use common::create_manager;
+use shared_types::config::host_target;
+use shared_types::plugin::PluginError;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+// Writes `<root>/<name>/plugin.json` declaring the given targets.
+fn write_targeted_manifest(root: &Path, name: &str, targets: &[&str]) -> PathBuf {
+  let dir = root.join(name);
+  fs::create_dir_all(&dir).unwrap();
+  let manifest = serde_json::json!({
+    "name": name,
+    "version": "1.0.0",
+    "description": "Native plugin",
+    "author": "tester",
+    "targets": targets,
+  });
+  fs::write(dir.join("plugin.json"), manifest.to_string()).unwrap();
+  dir
+}
+
+#[test]
+fn test_load_refuses_plugin_built_for_another_host() {
+  let data_dir = tempfile::tempdir().unwrap();
+  write_targeted_manifest(data_dir.path(), "elsewhere", &["sparc64-solaris"]);
+  let mut manager = create_manager(data_dir.path());
+
+  match manager.load("elsewhere") {
+    Err(PluginError::LoadError(msg)) => assert_eq!(
+      msg,
+      format!(
+        "plugin 'elsewhere' is built for sparc64-solaris but this host is {}",
+        host_target()
+      )
+    ),
+    other => panic!("expected LoadError, got {:?}", other),
+  }
+}
+
+#[test]
+fn test_install_checks_targets_against_host() {
+  let source = tempfile::tempdir().unwrap();
+  let data_dir = tempfile::tempdir().unwrap();
+  let mut manager = create_manager(data_dir.path());
+
+  let foreign = write_targeted_manifest(source.path(), "foreign", &["sparc64-solaris"]);
+  assert!(matches!(
+    manager.install(&foreign),
+    Err(PluginError::LoadError(_))
+  ));
+  assert!(!data_dir.path().join("foreign").exists());
+
+  let host = host_target();
+  let native = write_targeted_manifest(source.path(), "native", &["sparc64-solaris", &host]);
+  manager.install(&native).expect("install failed");
+
+  let config = manager.get("native").unwrap().config;
+  assert!(config.supports_host());
+  assert_eq!(config.targets, ["sparc64-solaris", host.as_str()]);
+}
@@ -0,0 +1,68 @@
+//! Integration tests for the `timeoutMs` plugin.json field.
+
+mod common;
+
+use common::create_manager;
+use shared_types::ProviderValue;
+use shared_types::plugin::PluginError;
+use std::fs;
+use std::time::Duration;
+
+const TIMED_MANIFEST: &str = r#"{
+  "name": "test-plugin",
+  "version": "1.0.0",
+  "description": "Fixture plugin with a short declared timeout",
+  "author": "tester",
+  "permissions": {},
+  "timeoutMs": 50
+}"#;
+
+#[test]
+fn test_declared_timeout_applies_and_can_be_overridden() {
+  let source = tempfile::tempdir().unwrap();
+  let plugin_dir = source.path().join("test-plugin");
+  fs::create_dir_all(&plugin_dir).unwrap();
+  fs::write(plugin_dir.join("plugin.json"), TIMED_MANIFEST).unwrap();
+  fs::copy(
+    workspace_root::get_workspace_root().join("example/test-plugin/main.wasm"),
+    plugin_dir.join("main.wasm"),
+  )
+  .unwrap();
+
+  let data_dir = tempfile::tempdir().unwrap();
+  let mut manager = create_manager(data_dir.path());
+  manager.init().expect("Failed to initialize provider");
+  manager.install(&plugin_dir).expect("Failed to install");
+  manager.load("test-plugin").expect("Failed to load");
+
+  // A per-call timeout replaces the declared 50ms.
+  manager
+    .invoke_with_timeout(
+      "test-plugin",
+      "Sleep",
+      vec![ProviderValue::Int(100)],
+      Duration::from_secs(10),
+    )
+    .expect("longer timeout should let the call finish");
+
+  match manager.invoke("test-plugin", "Spin", vec![]) {
+    Err(PluginError::InvokeError(msg)) => assert!(msg.contains("timed out"), "{}", msg),
+    other => panic!("expected InvokeError, got {:?}", other),
+  }
+}
+
+#[test]
+fn test_non_positive_timeout_is_rejected() {
+  let source = tempfile::tempdir().unwrap();
+  let plugin_dir = source.path().join("test-plugin");
+  fs::create_dir_all(&plugin_dir).unwrap();
+  fs::write(
+    plugin_dir.join("plugin.json"),
+    TIMED_MANIFEST.replace("\"timeoutMs\": 50", "\"timeoutMs\": 0"),
+  )
+  .unwrap();
+
+  let data_dir = tempfile::tempdir().unwrap();
+  let mut manager = create_manager(data_dir.path());
+  assert!(manager.install(&plugin_dir).is_err());
+}
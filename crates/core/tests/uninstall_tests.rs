@@ -0,0 +1,62 @@
+//! Integration tests for removing installed plugins.
+
+mod common;
+
+use common::{create_manager, write_manifest};
+use core::plugin::PluginEvent;
+use shared_types::plugin::PluginError;
+
+#[test]
+fn test_uninstall_removes_plugin_directory() {
+  let data_dir = tempfile::tempdir().unwrap();
+  let mut manager = create_manager(data_dir.path());
+  manager.init().expect("Failed to initialize provider");
+  manager
+    .install(&workspace_root::get_workspace_root().join("example/test-plugin"))
+    .expect("Failed to install test-plugin");
+  manager.load("test-plugin").expect("Failed to load plugin");
+  let events = manager.events();
+
+  manager
+    .uninstall("test-plugin")
+    .expect("Failed to uninstall plugin");
+
+  assert!(!data_dir.path().join("test-plugin").exists());
+  assert!(manager.health().loaded_plugins.is_empty());
+  assert!(manager.get("test-plugin").is_err());
+  assert_eq!(
+    events.try_iter().collect::<Vec<_>>(),
+    [
+      PluginEvent::PluginUnloaded {
+        name: "test-plugin".to_string()
+      },
+      PluginEvent::PluginUninstalled {
+        name: "test-plugin".to_string()
+      },
+    ]
+  );
+
+  // Uninstalling again finds nothing to remove.
+  assert!(matches!(
+    manager.uninstall("test-plugin"),
+    Err(PluginError::LoadError(_))
+  ));
+}
+
+#[test]
+fn test_uninstall_rejects_path_traversal() {
+  let root = tempfile::tempdir().unwrap();
+  let data_dir = root.path().join("data");
+  let victim = write_manifest(root.path(), "victim", "Fixture plugin", "{}");
+  let mut manager = create_manager(&data_dir);
+
+  for name in ["../victim", "..", "/tmp", "a/b", ""] {
+    match manager.uninstall(name) {
+      Err(PluginError::LoadError(msg)) => {
+        assert_eq!(msg, format!("invalid plugin name '{}'", name))
+      }
+      other => panic!("expected LoadError for '{}', got {:?}", name, other),
+    }
+  }
+  assert!(victim.join("plugin.json").is_file());
+}
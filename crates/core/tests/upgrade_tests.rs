@@ -0,0 +1,87 @@
+//! Integration tests for replacing installed plugins by version.
+
+mod common;
+
+use common::create_manager;
+use shared_types::plugin::PluginError;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+// Writes `<root>/<version>/plugin.json` for a plugin named "versioned".
+fn write_release(root: &Path, version: &str) -> PathBuf {
+  let dir = root.join(version);
+  fs::create_dir_all(&dir).unwrap();
+  let manifest = serde_json::json!({
+    "name": "versioned",
+    "version": version,
+    "description": "Versioned plugin",
+    "author": "tester",
+  });
+  fs::write(dir.join("plugin.json"), manifest.to_string()).unwrap();
+  fs::write(dir.join("release.txt"), version).unwrap();
+  dir
+}
+
+fn installed_version(data_dir: &Path) -> String {
+  fs::read_to_string(data_dir.join("versioned/release.txt")).unwrap()
+}
+
+#[test]
+fn test_upgrade_replaces_older_version() {
+  let source = tempfile::tempdir().unwrap();
+  let data_dir = tempfile::tempdir().unwrap();
+  let mut manager = create_manager(data_dir.path());
+
+  // Upgrading a plugin that is not installed installs it.
+  manager
+    .upgrade(&write_release(source.path(), "1.2.0"), false)
+    .expect("install failed");
+  manager
+    .upgrade(&write_release(source.path(), "1.10.0"), false)
+    .expect("upgrade failed");
+
+  assert_eq!(installed_version(data_dir.path()), "1.10.0");
+  assert_eq!(manager.get("versioned").unwrap().config.version, "1.10.0");
+}
+
+#[test]
+fn test_upgrade_rejects_downgrade_and_same_version() {
+  let source = tempfile::tempdir().unwrap();
+  let data_dir = tempfile::tempdir().unwrap();
+  let mut manager = create_manager(data_dir.path());
+  manager
+    .install(&write_release(source.path(), "2.0.0"))
+    .expect("install failed");
+
+  // A pre-release sorts before its release.
+  for version in ["1.9.9", "2.0.0", "2.0.0-rc.1"] {
+    match manager.upgrade(&write_release(source.path(), version), false) {
+      Err(PluginError::InstallError(msg)) => assert_eq!(
+        msg,
+        format!(
+          "plugin versioned {} is not newer than the installed 2.0.0",
+          version
+        )
+      ),
+      other => panic!("expected InstallError for {}, got {:?}", version, other),
+    }
+  }
+  assert_eq!(installed_version(data_dir.path()), "2.0.0");
+}
+
+#[test]
+fn test_forced_upgrade_allows_downgrade() {
+  let source = tempfile::tempdir().unwrap();
+  let data_dir = tempfile::tempdir().unwrap();
+  let mut manager = create_manager(data_dir.path());
+  manager
+    .install(&write_release(source.path(), "2.0.0"))
+    .expect("install failed");
+
+  manager
+    .upgrade(&write_release(source.path(), "1.0.0"), true)
+    .expect("forced downgrade failed");
+
+  assert_eq!(installed_version(data_dir.path()), "1.0.0");
+  assert_eq!(manager.get("versioned").unwrap().config.version, "1.0.0");
+}
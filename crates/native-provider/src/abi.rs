@@ -0,0 +1,185 @@
+//! The C ABI between `NativeProvider` and a native plugin.
+//!
+//! A plugin is a shared library exporting two functions:
+//!
+//! ```c
+//! int32_t bud_invoke(const char *function, size_t argc, const BudValue *argv, BudValue *out);
+//! void bud_free(BudValue *value);
+//! ```
+//!
+//! `bud_invoke` runs `function` (a NUL-terminated UTF-8 name) with `argc` arguments
+//! and writes its result to `out`, returning `BUD_OK`. On failure it returns any other
+//! value and writes a `BUD_STRING` message to `out`. The host copies `out` and then
+//! passes it to `bud_free`, so the plugin may point it at memory it allocated. The
+//! arguments are owned by the host and only valid during the call.
+
+use shared_types::ProviderValue;
+use std::ffi::c_char;
+
+/// Status `bud_invoke` returns on success.
+pub const BUD_OK: i32 = 0;
+
+/// Name of the invoke entry point.
+pub const INVOKE_SYMBOL: &[u8] = b"bud_invoke";
+
+/// Name of the function releasing a result written by `bud_invoke`.
+pub const FREE_SYMBOL: &[u8] = b"bud_free";
+
+/// `BudValue::tag` of `ProviderValue::Null`.
+pub const BUD_NULL: u32 = 0;
+/// `BudValue::tag` of a boolean stored in `int` as 0 or 1.
+pub const BUD_BOOL: u32 = 1;
+/// `BudValue::tag` of a signed integer stored in `int`.
+pub const BUD_INT: u32 = 2;
+/// `BudValue::tag` of an unsigned integer whose bits are stored in `int`.
+pub const BUD_UINT: u32 = 3;
+/// `BudValue::tag` of a float stored in `float`.
+pub const BUD_FLOAT: u32 = 4;
+/// `BudValue::tag` of a UTF-8 string in `ptr` and `len`.
+pub const BUD_STRING: u32 = 5;
+/// `BudValue::tag` of an array, tuple or object encoded as UTF-8 JSON in `ptr` and
+/// `len`. A plugin's JSON arrays come back as `ProviderValue::Array`.
+pub const BUD_JSON: u32 = 6;
+/// `BudValue::tag` of raw bytes in `ptr` and `len`, not required to be UTF-8.
+pub const BUD_BYTES: u32 = 7;
+
+/// One value crossing the C ABI, laid out as a C struct.
+///
+/// Scalars live inline; strings, bytes and nested values point at bytes that are not
+/// NUL-terminated. Fields a tag does not use are zero.
+#[repr(C)]
+#[derive(Debug, Clone, Copy)]
+pub struct BudValue {
+  pub tag: u32,
+  pub int: i64,
+  pub float: f64,
+  pub ptr: *const u8,
+  pub len: usize,
+}
+
+/// `bud_invoke` as a Rust function pointer type.
+pub type InvokeFn = unsafe extern "C" fn(
+  function: *const c_char,
+  argc: usize,
+  argv: *const BudValue,
+  out: *mut BudValue,
+) -> i32;
+
+/// `bud_free` as a Rust function pointer type.
+pub type FreeFn = unsafe extern "C" fn(value: *mut BudValue);
+
+impl BudValue {
+  /// The `BUD_NULL` value, also used to initialize `out`.
+  pub const NULL: BudValue = BudValue {
+    tag: BUD_NULL,
+    int: 0,
+    float: 0.0,
+    ptr: std::ptr::null(),
+    len: 0,
+  };
+
+  fn bytes(tag: u32, bytes: &[u8]) -> BudValue {
+    BudValue {
+      tag,
+      ptr: bytes.as_ptr(),
+      len: bytes.len(),
+      ..BudValue::NULL
+    }
+  }
+
+  // Reads the bytes `ptr` and `len` point at.
+  //
+  // SAFETY: `ptr` must point at `len` readable bytes, or be null with `len` zero.
+  unsafe fn as_bytes(&self) -> &[u8] {
+    if self.len == 0 {
+      return &[];
+    }
+    unsafe { std::slice::from_raw_parts(self.ptr, self.len) }
+  }
+}
+
+// Owns the buffers the `BudValue`s of one call's arguments point into.
+pub(crate) struct Args {
+  values: Vec<BudValue>,
+  _buffers: Vec<Vec<u8>>,
+}
+
+impl Args {
+  pub(crate) fn new(args: &[ProviderValue]) -> Result<Args, String> {
+    let mut buffers = Vec::with_capacity(args.len());
+    let mut values = Vec::with_capacity(args.len());
+
+    for arg in args {
+      let value = match arg {
+        ProviderValue::Null => BudValue::NULL,
+        ProviderValue::Bool(b) => scalar(BUD_BOOL, *b as i64),
+        ProviderValue::Int(i) => scalar(BUD_INT, *i),
+        ProviderValue::UInt(u) => scalar(BUD_UINT, *u as i64),
+        ProviderValue::Float(f) => BudValue {
+          tag: BUD_FLOAT,
+          float: *f,
+          ..BudValue::NULL
+        },
+        ProviderValue::String(s) => {
+          buffers.push(s.clone().into_bytes());
+          BudValue::bytes(BUD_STRING, buffers.last().unwrap())
+        }
+        ProviderValue::Bytes(bytes) => {
+          buffers.push(bytes.clone());
+          BudValue::bytes(BUD_BYTES, buffers.last().unwrap())
+        }
+        ProviderValue::Array(_) | ProviderValue::Tuple(_) | ProviderValue::Object(_) => {
+          buffers.push(serde_json::to_vec(arg).map_err(|e| e.to_string())?);
+          BudValue::bytes(BUD_JSON, buffers.last().unwrap())
+        }
+      };
+      values.push(value);
+    }
+
+    // Moving a `Vec<u8>` keeps its heap buffer in place, so the pointers stay valid.
+    Ok(Args {
+      values,
+      _buffers: buffers,
+    })
+  }
+
+  pub(crate) fn as_slice(&self) -> &[BudValue] {
+    &self.values
+  }
+}
+
+fn scalar(tag: u32, int: i64) -> BudValue {
+  BudValue {
+    tag,
+    int,
+    ..BudValue::NULL
+  }
+}
+
+// Copies a value written by `bud_invoke` into a `ProviderValue`.
+//
+// SAFETY: `value` must be as `bud_invoke` left it, before `bud_free`.
+pub(crate) unsafe fn to_provider_value(value: &BudValue) -> Result<ProviderValue, String> {
+  let text = || {
+    // SAFETY: guaranteed by the caller for tags carrying bytes.
+    let bytes = unsafe { value.as_bytes() };
+    std::str::from_utf8(bytes).map_err(|e| format!("invalid UTF-8 in result: {}", e))
+  };
+
+  match value.tag {
+    BUD_NULL => Ok(ProviderValue::Null),
+    BUD_BOOL => Ok(ProviderValue::Bool(value.int != 0)),
+    BUD_INT => Ok(ProviderValue::Int(value.int)),
+    BUD_UINT => {
+      // Same rule as the other providers: only values above `i64::MAX` stay unsigned.
+      let u = value.int as u64;
+      Ok(i64::try_from(u).map_or(ProviderValue::UInt(u), ProviderValue::Int))
+    }
+    BUD_FLOAT => Ok(ProviderValue::Float(value.float)),
+    BUD_STRING => Ok(ProviderValue::String(text()?.to_string())),
+    BUD_JSON => serde_json::from_str(text()?).map_err(|e| format!("invalid JSON in result: {}", e)),
+    // SAFETY: guaranteed by the caller for tags carrying bytes.
+    BUD_BYTES => Ok(ProviderValue::Bytes(unsafe { value.as_bytes() }.to_vec())),
+    tag => Err(format!("unknown value tag {}", tag)),
+  }
+}
@@ -0,0 +1,254 @@
+//! Runs trusted plugins as native shared libraries, without a sandbox.
+//!
+//! See `abi` for the functions a plugin library must export.
+
+pub mod abi;
+
+use abi::{Args, BUD_OK, BudValue, FREE_SYMBOL, FreeFn, INVOKE_SYMBOL, InvokeFn};
+use libloading::Library;
+use log::{error, info, warn};
+use shared_types::provider::HostFunction;
+use shared_types::{FunctionSignature, PluginConfigData, Provider, ProviderError, ProviderValue};
+use std::collections::HashMap;
+use std::ffi::CString;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+
+/// A loaded native plugin.
+pub struct NativePlugin {
+  path: PathBuf,
+  invoke: InvokeFn,
+  free: FreeFn,
+  // Keeps `invoke` and `free` valid; closed when the last handle is dropped.
+  _library: Library,
+}
+
+impl NativePlugin {
+  /// The shared library the plugin was loaded from.
+  pub fn path(&self) -> &Path {
+    &self.path
+  }
+}
+
+/// Provider running plugins as shared libraries loaded into the host process.
+///
+/// Plugins run with the host's full privileges: manifest permissions are not enforced,
+/// a crash or undefined behavior in a plugin takes the host down with it, and calls
+/// cannot be interrupted. Only load first-party code you would link in directly.
+///
+/// Plugins are addressed by key like the other providers, and calls into different
+/// plugins may run in parallel. A plugin must therefore make `bud_invoke` safe to call
+/// from several threads at once, and must not let a panic or exception unwind into the
+/// host.
+#[derive(Default)]
+pub struct NativeProvider {
+  initialized: AtomicBool,
+  plugins: Mutex<HashMap<String, Arc<NativePlugin>>>,
+}
+
+impl NativeProvider {
+  #[must_use]
+  pub fn new() -> Self {
+    Self::default()
+  }
+
+  fn plugin(&self, plugin_name: &str) -> Result<Arc<NativePlugin>, ProviderError> {
+    self
+      .plugins
+      .lock()
+      .unwrap_or_else(|p| p.into_inner())
+      .get(plugin_name)
+      .cloned()
+      .ok_or_else(|| ProviderError::InvocationFailed(format!("Plugin '{}' not found", plugin_name)))
+  }
+}
+
+// Resolves `symbol` in `library` as a function pointer of type `T`.
+//
+// SAFETY: `T` must be the function's real signature.
+unsafe fn entry_point<T: Copy>(
+  library: &Library,
+  symbol: &[u8],
+  path: &Path,
+) -> Result<T, ProviderError> {
+  unsafe { library.get::<T>(symbol) }
+    .map(|f| *f)
+    .map_err(|e| {
+      ProviderError::LoadFailed(format!(
+        "{} does not export {}: {}",
+        path.display(),
+        String::from_utf8_lossy(symbol),
+        e
+      ))
+    })
+}
+
+impl Provider for NativeProvider {
+  type Instance = ();
+  type PluginInstance = Arc<NativePlugin>;
+  #[cfg(target_os = "windows")]
+  const MAIN_FILE: &'static str = "main.dll";
+  #[cfg(target_os = "macos")]
+  const MAIN_FILE: &'static str = "main.dylib";
+  #[cfg(not(any(target_os = "windows", target_os = "macos")))]
+  const MAIN_FILE: &'static str = "main.so";
+  const NAME: &'static str = "native";
+
+  fn with_plugins<F, R>(&self, f: F) -> Result<R, ProviderError>
+  where
+    F: FnOnce(&HashMap<String, Self::PluginInstance>) -> R,
+  {
+    let plugins = self.plugins.lock().unwrap_or_else(|p| p.into_inner());
+    Ok(f(&plugins))
+  }
+
+  fn init(&self) -> Result<Self::Instance, ProviderError> {
+    info!("Initializing NativeProvider");
+    self.initialized.store(true, Ordering::Relaxed);
+    Ok(())
+  }
+
+  fn is_initialized(&self) -> bool {
+    self.initialized.load(Ordering::Relaxed)
+  }
+
+  /// Opens the plugin's shared library and resolves `bud_invoke` and `bud_free`.
+  ///
+  /// Opening a library runs its initializers in the host process, so this is where an
+  /// untrusted library would already do harm. A plugin loaded again under the same key
+  /// replaces the previous library.
+  fn load<P: AsRef<Path>>(&self, path: P, config: &PluginConfigData) -> Result<(), ProviderError> {
+    if !self.is_initialized() {
+      return Err(ProviderError::LoadFailed(
+        "Provider not initialized. Call init() first.".to_string(),
+      ));
+    }
+
+    let main_file = config.entry_file(Self::MAIN_FILE);
+    let library_path = path.as_ref().join(main_file);
+    if !library_path.is_file() {
+      let msg = format!("{} not found: {}", main_file, library_path.display());
+      error!("{}", msg);
+      return Err(ProviderError::LoadFailed(msg));
+    }
+
+    // SAFETY: loading native code is inherently trusted; see the type's docs.
+    let library = unsafe { Library::new(&library_path) }.map_err(|e| {
+      let msg = format!("Failed to open {}: {}", library_path.display(), e);
+      error!("{}", msg);
+      ProviderError::LoadFailed(msg)
+    })?;
+    // SAFETY: the ABI in `abi` fixes both signatures.
+    let invoke = unsafe { entry_point::<InvokeFn>(&library, INVOKE_SYMBOL, &library_path)? };
+    let free = unsafe { entry_point::<FreeFn>(&library, FREE_SYMBOL, &library_path)? };
+
+    let plugin = NativePlugin {
+      path: library_path,
+      invoke,
+      free,
+      _library: library,
+    };
+    self
+      .plugins
+      .lock()
+      .unwrap_or_else(|p| p.into_inner())
+      .insert(config.key().to_string(), Arc::new(plugin));
+
+    info!("Native plugin '{}' loaded successfully", config.key());
+    Ok(())
+  }
+
+  /// Native plugins link against whatever they need directly, so host functions are
+  /// not offered to them; this only warns when some are passed.
+  fn inject(
+    &self,
+    _instance: &mut Self::Instance,
+    functions: &[(&str, Arc<HostFunction>)],
+  ) -> Result<(), ProviderError> {
+    if !functions.is_empty() {
+      warn!(
+        "NativeProvider ignores {} injected host function(s)",
+        functions.len()
+      );
+    }
+    Ok(())
+  }
+
+  fn invoke(
+    &self,
+    plugin_name: &str,
+    function: &str,
+    args: Vec<ProviderValue>,
+  ) -> Result<ProviderValue, ProviderError> {
+    let plugin = self.plugin(plugin_name)?;
+    let function_name = CString::new(function).map_err(|_| {
+      ProviderError::InvocationFailed(format!("function name '{}' contains NUL", function))
+    })?;
+    let args = Args::new(&args).map_err(ProviderError::InvocationFailed)?;
+    let argv = args.as_slice();
+
+    let mut out = BudValue::NULL;
+    // SAFETY: the arguments and their buffers outlive the call, and `out` is freed by
+    // the plugin's own `bud_free` after it has been copied.
+    let (status, result) = unsafe {
+      let status = (plugin.invoke)(function_name.as_ptr(), argv.len(), argv.as_ptr(), &mut out);
+      let result = abi::to_provider_value(&out);
+      (plugin.free)(&mut out);
+      (status, result)
+    };
+
+    let value = result.map_err(|e| {
+      ProviderError::InvocationFailed(format!("{}::{}: {}", plugin_name, function, e))
+    })?;
+    if status != BUD_OK {
+      let message = match value {
+        ProviderValue::String(message) => message,
+        other => format!("status {} ({:?})", status, other),
+      };
+      return Err(ProviderError::InvocationFailed(format!(
+        "{}::{}: {}",
+        plugin_name, function, message
+      )));
+    }
+
+    Ok(value)
+  }
+
+  /// The C ABI carries no signatures, so native plugins describe no functions.
+  fn describe(&self, plugin_name: &str) -> Result<Vec<FunctionSignature>, ProviderError> {
+    if !self.with_plugins(|plugins| plugins.contains_key(plugin_name))? {
+      return Err(ProviderError::LoadFailed(format!(
+        "Plugin '{}' not found",
+        plugin_name
+      )));
+    }
+    Ok(Vec::new())
+  }
+
+  /// Drops the provider's handle on the library; it is closed once calls already in
+  /// flight have returned.
+  fn unload_plugin(&self, plugin_name: &str) -> Result<(), ProviderError> {
+    self
+      .plugins
+      .lock()
+      .unwrap_or_else(|p| p.into_inner())
+      .remove(plugin_name)
+      .ok_or_else(|| ProviderError::UnloadFailed(format!("Plugin '{}' not found", plugin_name)))?;
+
+    info!("Unloaded native plugin '{}'", plugin_name);
+    Ok(())
+  }
+
+  fn unload(&self, _instance: Self::Instance) -> Result<(), ProviderError> {
+    self
+      .plugins
+      .lock()
+      .unwrap_or_else(|p| p.into_inner())
+      .clear();
+    self.initialized.store(false, Ordering::Relaxed);
+
+    info!("NativeProvider unloaded");
+    Ok(())
+  }
+}
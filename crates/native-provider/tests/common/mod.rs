@@ -0,0 +1,62 @@
+//! Shared fixtures for the native-provider integration tests.
+#![allow(dead_code)]
+
+use native_provider::NativeProvider;
+use shared_types::{PluginConfigData, Provider};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::sync::OnceLock;
+
+/// Builds `tests/fixtures/echo-plugin` once per test binary and returns the library.
+pub fn echo_library() -> &'static Path {
+  static LIBRARY: OnceLock<PathBuf> = OnceLock::new();
+  LIBRARY.get_or_init(|| {
+    let fixture = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/echo-plugin");
+    let target_dir = Path::new(env!("CARGO_TARGET_TMPDIR")).join("echo-plugin");
+    let cargo = std::env::var("CARGO").unwrap_or_else(|_| "cargo".to_string());
+    let status = Command::new(cargo)
+      .arg("build")
+      .arg("--quiet")
+      .arg("--manifest-path")
+      .arg(fixture.join("Cargo.toml"))
+      .arg("--target-dir")
+      .arg(&target_dir)
+      .status()
+      .expect("failed to run cargo");
+    assert!(status.success(), "building echo-plugin failed");
+
+    let file_name = format!(
+      "{}echo_plugin{}",
+      std::env::consts::DLL_PREFIX,
+      std::env::consts::DLL_SUFFIX
+    );
+    target_dir.join("debug").join(file_name)
+  })
+}
+
+/// Writes `<root>/<name>` with a plugin.json and the echo library as its entry file.
+pub fn write_echo_plugin(root: &Path, name: &str) -> PathBuf {
+  let dir = root.join(name);
+  fs::create_dir_all(&dir).unwrap();
+  fs::write(
+    dir.join("plugin.json"),
+    format!(
+      r#"{{"name": "{}", "version": "1.0.0", "description": "Echo", "author": "tester"}}"#,
+      name
+    ),
+  )
+  .unwrap();
+  fs::copy(echo_library(), dir.join(NativeProvider::MAIN_FILE)).unwrap();
+  dir
+}
+
+pub fn plugin_config(name: &str) -> PluginConfigData {
+  serde_json::from_value(serde_json::json!({
+    "name": name,
+    "version": "1.0.0",
+    "description": "Echo",
+    "author": "tester"
+  }))
+  .unwrap()
+}
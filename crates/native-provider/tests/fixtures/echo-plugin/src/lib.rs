@@ -0,0 +1,107 @@
+//! Test plugin implementing the native-provider C ABI by hand.
+
+use std::ffi::{CStr, c_char};
+
+const BUD_NULL: u32 = 0;
+const BUD_INT: u32 = 2;
+const BUD_STRING: u32 = 5;
+const BUD_JSON: u32 = 6;
+const BUD_BYTES: u32 = 7;
+
+#[repr(C)]
+#[derive(Clone, Copy)]
+pub struct BudValue {
+  tag: u32,
+  int: i64,
+  float: f64,
+  ptr: *const u8,
+  len: usize,
+}
+
+const NULL: BudValue = BudValue {
+  tag: BUD_NULL,
+  int: 0,
+  float: 0.0,
+  ptr: std::ptr::null(),
+  len: 0,
+};
+
+// A value owning a copy of `bytes`, released by `bud_free`.
+fn owned(tag: u32, bytes: &[u8]) -> BudValue {
+  let bytes: Box<[u8]> = bytes.into();
+  let len = bytes.len();
+  BudValue {
+    tag,
+    ptr: Box::into_raw(bytes) as *const u8,
+    len,
+    ..NULL
+  }
+}
+
+unsafe fn bytes_of(value: &BudValue) -> &[u8] {
+  if value.len == 0 {
+    return &[];
+  }
+  unsafe { std::slice::from_raw_parts(value.ptr, value.len) }
+}
+
+/// # Safety
+///
+/// Called by the host as described in native-provider's `abi` module.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn bud_invoke(
+  function: *const c_char,
+  argc: usize,
+  argv: *const BudValue,
+  out: *mut BudValue,
+) -> i32 {
+  let function = unsafe { CStr::from_ptr(function) }.to_bytes();
+  let args = if argc == 0 {
+    &[][..]
+  } else {
+    unsafe { std::slice::from_raw_parts(argv, argc) }
+  };
+
+  let (status, result) = match function {
+    b"Echo" => match args.first() {
+      Some(arg) if matches!(arg.tag, BUD_STRING | BUD_JSON | BUD_BYTES) => {
+        (0, owned(arg.tag, unsafe { bytes_of(arg) }))
+      }
+      Some(arg) => (0, *arg),
+      None => (0, NULL),
+    },
+    b"Add" => {
+      let sum = args
+        .iter()
+        .filter(|a| a.tag == BUD_INT)
+        .map(|a| a.int)
+        .sum();
+      (
+        0,
+        BudValue {
+          tag: BUD_INT,
+          int: sum,
+          ..NULL
+        },
+      )
+    }
+    b"Pair" => (0, owned(BUD_JSON, br#"[1, "two"]"#)),
+    _ => (1, owned(BUD_STRING, b"unknown function")),
+  };
+
+  unsafe { *out = result };
+  status
+}
+
+/// # Safety
+///
+/// `value` must have been written by `bud_invoke` and not freed yet.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn bud_free(value: *mut BudValue) {
+  let value = unsafe { &mut *value };
+  if matches!(value.tag, BUD_STRING | BUD_JSON | BUD_BYTES) && value.len > 0 {
+    let slice = std::ptr::slice_from_raw_parts_mut(value.ptr as *mut u8, value.len);
+    drop(unsafe { Box::from_raw(slice) });
+  }
+  *value = NULL;
+}
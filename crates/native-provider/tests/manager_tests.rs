@@ -0,0 +1,44 @@
+//! Integration tests for running native plugins through `PluginManager`.
+
+mod common;
+
+use common::write_echo_plugin;
+use core::plugin::PluginManager;
+use native_provider::NativeProvider;
+use shared_types::ProviderValue;
+use shared_types::config::ConfigData;
+use std::sync::Arc;
+
+#[test]
+fn test_manager_installs_loads_and_invokes_native_plugins() {
+  let source = tempfile::tempdir().unwrap();
+  let data_dir = tempfile::tempdir().unwrap();
+  let plugin_dir = write_echo_plugin(source.path(), "echo");
+  let config = Arc::new(ConfigData {
+    name: "test-app".to_string(),
+    version: "0.1.0".to_string(),
+    description: "A test application".to_string(),
+    providers: Default::default(),
+  });
+  let mut manager = PluginManager::with_data_path(
+    config,
+    Arc::new(NativeProvider::new()),
+    data_dir.path().to_path_buf(),
+  )
+  .with_entry_file_check();
+
+  manager.init().expect("Failed to initialize provider");
+  manager
+    .install(&plugin_dir)
+    .expect("Failed to install plugin");
+  manager.load("echo").expect("Failed to load plugin");
+
+  let result = manager.invoke(
+    "echo",
+    "Add",
+    vec![ProviderValue::Int(20), ProviderValue::Int(22)],
+  );
+  assert_eq!(result.unwrap(), ProviderValue::Int(42));
+
+  manager.unload("echo").expect("Failed to unload plugin");
+}
@@ -0,0 +1,109 @@
+//! Integration tests for calling into native plugins through `NativeProvider`.
+
+mod common;
+
+use common::{plugin_config, write_echo_plugin};
+use native_provider::NativeProvider;
+use shared_types::{Provider, ProviderError, ProviderValue};
+
+fn loaded_provider(root: &std::path::Path) -> NativeProvider {
+  let provider = NativeProvider::new();
+  provider.init().unwrap();
+  let dir = write_echo_plugin(root, "echo");
+  provider.load(&dir, &plugin_config("echo")).unwrap();
+  provider
+}
+
+#[test]
+fn test_values_round_trip_through_the_c_abi() {
+  let root = tempfile::tempdir().unwrap();
+  let provider = loaded_provider(root.path());
+  let echo = |value: ProviderValue| provider.invoke("echo", "Echo", vec![value]).unwrap();
+
+  for value in [
+    ProviderValue::Null,
+    ProviderValue::Bool(true),
+    ProviderValue::Int(-7),
+    ProviderValue::UInt(u64::MAX),
+    ProviderValue::Float(1.5),
+    ProviderValue::String("héllo".to_string()),
+    ProviderValue::Bytes(vec![0, 0xff, b'a']),
+    ProviderValue::Array(vec![ProviderValue::Int(1), ProviderValue::Null]),
+    ProviderValue::Object(vec![("k".to_string(), ProviderValue::Bool(false))]),
+  ] {
+    assert_eq!(echo(value.clone()), value);
+  }
+}
+
+#[test]
+fn test_plugin_results_are_converted() {
+  let root = tempfile::tempdir().unwrap();
+  let provider = loaded_provider(root.path());
+
+  let sum = provider
+    .invoke(
+      "echo",
+      "Add",
+      vec![ProviderValue::Int(2), ProviderValue::Int(3)],
+    )
+    .unwrap();
+  assert_eq!(sum, ProviderValue::Int(5));
+
+  let pair = provider.invoke("echo", "Pair", Vec::new()).unwrap();
+  assert_eq!(
+    pair,
+    ProviderValue::Array(vec![
+      ProviderValue::Int(1),
+      ProviderValue::String("two".to_string())
+    ])
+  );
+}
+
+#[test]
+fn test_plugin_errors_carry_its_message() {
+  let root = tempfile::tempdir().unwrap();
+  let provider = loaded_provider(root.path());
+
+  match provider.invoke("echo", "Missing", Vec::new()) {
+    Err(ProviderError::InvocationFailed(msg)) => {
+      assert!(msg.contains("unknown function"), "{}", msg)
+    }
+    other => panic!("expected InvocationFailed, got {:?}", other),
+  }
+}
+
+#[test]
+fn test_unloaded_plugin_cannot_be_called() {
+  let root = tempfile::tempdir().unwrap();
+  let provider = loaded_provider(root.path());
+
+  provider.unload_plugin("echo").unwrap();
+
+  assert!(matches!(
+    provider.invoke("echo", "Echo", Vec::new()),
+    Err(ProviderError::InvocationFailed(_))
+  ));
+  assert!(matches!(
+    provider.unload_plugin("echo"),
+    Err(ProviderError::UnloadFailed(_))
+  ));
+}
+
+#[test]
+fn test_load_requires_init_and_entry_file() {
+  let root = tempfile::tempdir().unwrap();
+  let dir = write_echo_plugin(root.path(), "echo");
+  let provider = NativeProvider::new();
+
+  assert!(matches!(
+    provider.load(&dir, &plugin_config("echo")),
+    Err(ProviderError::LoadFailed(_))
+  ));
+
+  provider.init().unwrap();
+  std::fs::remove_file(dir.join(NativeProvider::MAIN_FILE)).unwrap();
+  assert!(matches!(
+    provider.load(&dir, &plugin_config("echo")),
+    Err(ProviderError::LoadFailed(_))
+  ));
+}
@@ -1,11 +1,24 @@
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fmt;
 use thiserror::Error;
 
-#[derive(Debug, Deserialize, PartialEq)]
+#[derive(Debug, Serialize, Deserialize, PartialEq)]
 pub struct ConfigData {
   pub name: String,
   pub version: String,
   pub description: String,
+  /// Provider-specific settings keyed by `Provider::NAME`, e.g. `{"wasm": {...}}`.
+  #[serde(default, skip_serializing_if = "serde_json::Map::is_empty")]
+  pub providers: serde_json::Map<String, serde_json::Value>,
+}
+
+impl ConfigData {
+  /// Returns the configuration as it was resolved, with the selected profile already
+  /// merged in, as JSON for inspection and debugging.
+  pub fn to_effective_json(&self) -> serde_json::Value {
+    serde_json::to_value(self).expect("ConfigData always serializes to JSON")
+  }
 }
 
 // Permission<T> is a generic enum representing a permission dimension that can be either:
@@ -14,15 +27,113 @@ pub struct ConfigData {
 //
 // IMPORTANT: In serde's untagged mode, variants are tried in declaration order.
 // `Bool` must come before `Config`, otherwise JSON `true` would be attempted as an object and fail.
-#[derive(Debug, Clone, Deserialize, PartialEq)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 #[serde(untagged)]
 pub enum Permission<T> {
   Bool(bool),
   Config(T),
 }
 
+impl<T> Permission<T> {
+  /// Returns `true` unless the permission is the `false` shorthand.
+  pub fn is_granted(&self) -> bool {
+    !matches!(self, Permission::Bool(false))
+  }
+}
+
+/// Identifies one permission dimension of `Permissions`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum PermissionKind {
+  Stdio,
+  Filesystem,
+  Network,
+  Env,
+  Process,
+}
+
+impl PermissionKind {
+  /// Every permission dimension, in declaration order.
+  pub const ALL: [PermissionKind; 5] = [
+    PermissionKind::Stdio,
+    PermissionKind::Filesystem,
+    PermissionKind::Network,
+    PermissionKind::Env,
+    PermissionKind::Process,
+  ];
+
+  /// The key used for this dimension in plugin.json.
+  pub fn as_str(&self) -> &'static str {
+    match self {
+      PermissionKind::Stdio => "stdio",
+      PermissionKind::Filesystem => "filesystem",
+      PermissionKind::Network => "network",
+      PermissionKind::Env => "env",
+      PermissionKind::Process => "process",
+    }
+  }
+}
+
+impl fmt::Display for PermissionKind {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    f.write_str(self.as_str())
+  }
+}
+
+/// One access right granted by a plugin's `permissions`.
+///
+/// Its string form follows the `<dimension>:<action>[:<target>]` grammar used in
+/// validation messages, e.g. `fs:read:/data`, `net:connect:example.com` or `env:HOME`.
+/// A `*` target stands for any path or host.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum PermissionGrant {
+  Stdin,
+  Stdout,
+  Stderr,
+  FsRead(String),
+  FsWrite(String),
+  NetConnect(String),
+  EnvInherit,
+  EnvKey(String),
+  ProcessExit,
+  /// An embedder-defined `<prefix>:<name>` permission.
+  Custom(String),
+}
+
+impl PermissionGrant {
+  /// The dimension this grant belongs to, or `None` for custom permissions.
+  pub fn kind(&self) -> Option<PermissionKind> {
+    match self {
+      PermissionGrant::Stdin | PermissionGrant::Stdout | PermissionGrant::Stderr => {
+        Some(PermissionKind::Stdio)
+      }
+      PermissionGrant::FsRead(_) | PermissionGrant::FsWrite(_) => Some(PermissionKind::Filesystem),
+      PermissionGrant::NetConnect(_) => Some(PermissionKind::Network),
+      PermissionGrant::EnvInherit | PermissionGrant::EnvKey(_) => Some(PermissionKind::Env),
+      PermissionGrant::ProcessExit => Some(PermissionKind::Process),
+      PermissionGrant::Custom(_) => None,
+    }
+  }
+}
+
+impl fmt::Display for PermissionGrant {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    match self {
+      PermissionGrant::Stdin => f.write_str("stdio:stdin"),
+      PermissionGrant::Stdout => f.write_str("stdio:stdout"),
+      PermissionGrant::Stderr => f.write_str("stdio:stderr"),
+      PermissionGrant::FsRead(path) => write!(f, "fs:read:{}", path),
+      PermissionGrant::FsWrite(path) => write!(f, "fs:write:{}", path),
+      PermissionGrant::NetConnect(host) => write!(f, "net:connect:{}", host),
+      PermissionGrant::EnvInherit => f.write_str("env"),
+      PermissionGrant::EnvKey(key) => write!(f, "env:{}", key),
+      PermissionGrant::ProcessExit => f.write_str("process:exit"),
+      PermissionGrant::Custom(key) => f.write_str(key),
+    }
+  }
+}
+
 /// Detailed configuration for stdio permissions, controlling access to stdin/stdout/stderr.
-#[derive(Debug, Clone, Deserialize, PartialEq, Default)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Default)]
 pub struct StdioPermission {
   pub stdin: Option<bool>,
   pub stdout: Option<bool>,
@@ -30,49 +141,335 @@ pub struct StdioPermission {
 }
 
 /// Detailed configuration for filesystem permissions, specifying allowed read and write paths.
-#[derive(Debug, Clone, Deserialize, PartialEq, Default)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Default)]
 pub struct FilesystemPermission {
   pub read: Option<Vec<String>>,
   pub write: Option<Vec<String>>,
 }
 
 /// Detailed configuration for network permissions, specifying the list of allowed hosts.
-#[derive(Debug, Clone, Deserialize, PartialEq, Default)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Default)]
 pub struct NetworkPermission {
   pub allowed_hosts: Option<Vec<String>>,
 }
 
 /// Detailed configuration for environment variable permissions, controlling inheritance and allowed keys.
-#[derive(Debug, Clone, Deserialize, PartialEq, Default)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Default)]
 pub struct EnvPermission {
   pub inherit: Option<bool>,
   pub keys: Option<Vec<String>>,
 }
 
 /// Detailed configuration for process-level permissions (e.g. whether the plugin may call exit).
-#[derive(Debug, Clone, Deserialize, PartialEq, Default)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Default)]
 pub struct ProcessPermission {
   pub exit: Option<bool>,
 }
 
 /// Aggregated plugin permissions. Each field represents one permission dimension.
 /// A field may be Bool (quick toggle), a Config object (fine-grained), or absent (defaults to deny).
-#[derive(Debug, Clone, Deserialize, PartialEq, Default)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Default)]
 pub struct Permissions {
   pub stdio: Option<Permission<StdioPermission>>,
   pub filesystem: Option<Permission<FilesystemPermission>>,
   pub network: Option<Permission<NetworkPermission>>,
   pub env: Option<Permission<EnvPermission>>,
   pub process: Option<Permission<ProcessPermission>>,
+  /// Embedder-defined permissions keyed as `<prefix>:<name>` (e.g. `app:send-email`).
+  ///
+  /// Only prefixes registered with `config::register_permission_prefix` pass validation.
+  #[serde(flatten)]
+  pub custom: BTreeMap<String, bool>,
 }
 
-#[derive(Debug, Clone, Deserialize, PartialEq)]
+impl Permissions {
+  /// Returns the dimensions this plugin requests.
+  ///
+  /// Absent dimensions and the `false` shorthand count as not requested.
+  pub fn requested(&self) -> Vec<PermissionKind> {
+    PermissionKind::ALL
+      .into_iter()
+      .filter(|kind| self.is_requested(*kind))
+      .collect()
+  }
+
+  /// Returns `true` if the given dimension is present and not set to `false`.
+  pub fn is_requested(&self, kind: PermissionKind) -> bool {
+    match kind {
+      PermissionKind::Stdio => self.stdio.as_ref().is_some_and(Permission::is_granted),
+      PermissionKind::Filesystem => self.filesystem.as_ref().is_some_and(Permission::is_granted),
+      PermissionKind::Network => self.network.as_ref().is_some_and(Permission::is_granted),
+      PermissionKind::Env => self.env.as_ref().is_some_and(Permission::is_granted),
+      PermissionKind::Process => self.process.as_ref().is_some_and(Permission::is_granted),
+    }
+  }
+
+  /// Expands the permissions into the individual grants they make.
+  ///
+  /// The `true` shorthand grants a whole dimension: every stdio stream, `*` for
+  /// filesystem reads and writes and network hosts, and environment inheritance.
+  /// Denied dimensions and custom permissions set to `false` contribute nothing.
+  pub fn grants(&self) -> Vec<PermissionGrant> {
+    let mut grants = Vec::new();
+
+    match &self.stdio {
+      Some(Permission::Bool(true)) => grants.extend([
+        PermissionGrant::Stdin,
+        PermissionGrant::Stdout,
+        PermissionGrant::Stderr,
+      ]),
+      Some(Permission::Config(c)) => {
+        let streams = [
+          (c.stdin, PermissionGrant::Stdin),
+          (c.stdout, PermissionGrant::Stdout),
+          (c.stderr, PermissionGrant::Stderr),
+        ];
+        grants.extend(
+          streams
+            .into_iter()
+            .filter(|(granted, _)| *granted == Some(true))
+            .map(|(_, grant)| grant),
+        );
+      }
+      _ => {}
+    }
+
+    match &self.filesystem {
+      Some(Permission::Bool(true)) => grants.extend([
+        PermissionGrant::FsRead("*".to_string()),
+        PermissionGrant::FsWrite("*".to_string()),
+      ]),
+      Some(Permission::Config(c)) => {
+        grants.extend(
+          c.read
+            .iter()
+            .flatten()
+            .cloned()
+            .map(PermissionGrant::FsRead),
+        );
+        grants.extend(
+          c.write
+            .iter()
+            .flatten()
+            .cloned()
+            .map(PermissionGrant::FsWrite),
+        );
+      }
+      _ => {}
+    }
+
+    match &self.network {
+      Some(Permission::Bool(true)) => grants.push(PermissionGrant::NetConnect("*".to_string())),
+      Some(Permission::Config(c)) => grants.extend(
+        c.allowed_hosts
+          .iter()
+          .flatten()
+          .cloned()
+          .map(PermissionGrant::NetConnect),
+      ),
+      _ => {}
+    }
+
+    match &self.env {
+      Some(Permission::Bool(true)) => grants.push(PermissionGrant::EnvInherit),
+      Some(Permission::Config(c)) => {
+        if c.inherit == Some(true) {
+          grants.push(PermissionGrant::EnvInherit);
+        }
+        grants.extend(
+          c.keys
+            .iter()
+            .flatten()
+            .cloned()
+            .map(PermissionGrant::EnvKey),
+        );
+      }
+      _ => {}
+    }
+
+    let exit = match &self.process {
+      Some(Permission::Bool(granted)) => *granted,
+      Some(Permission::Config(c)) => c.exit == Some(true),
+      None => false,
+    };
+    if exit {
+      grants.push(PermissionGrant::ProcessExit);
+    }
+
+    grants.extend(
+      self
+        .custom
+        .iter()
+        .filter(|(_, granted)| **granted)
+        .map(|(key, _)| PermissionGrant::Custom(key.clone())),
+    );
+
+    grants
+  }
+
+  // Compares one dimension between two permission sets.
+  fn dimension_eq(&self, other: &Permissions, kind: PermissionKind) -> bool {
+    match kind {
+      PermissionKind::Stdio => self.stdio == other.stdio,
+      PermissionKind::Filesystem => self.filesystem == other.filesystem,
+      PermissionKind::Network => self.network == other.network,
+      PermissionKind::Env => self.env == other.env,
+      PermissionKind::Process => self.process == other.process,
+    }
+  }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub struct PluginConfigData {
+  /// Immutable identifier (a slug or UUID). When set it replaces `name` as the key the
+  /// plugin is stored and cached under, leaving `name` free to change between versions.
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub id: Option<String>,
+  /// Display name; also the storage key of plugins without an `id`.
   pub name: String,
   pub version: String,
   pub description: String,
   pub author: String,
+  /// Entry file inside the plugin directory, such as `plugin.wasm`. Without it the
+  /// provider's `Provider::MAIN_FILE` is used.
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub main: Option<String>,
   pub permissions: Option<Permissions>,
+  /// Per-function call contracts, keyed by function name.
+  pub functions: Option<BTreeMap<String, FunctionSpec>>,
+  /// Expected maximum runtime of one call; longer calls are interrupted.
+  #[serde(rename = "timeoutMs", default, skip_serializing_if = "Option::is_none")]
+  pub timeout_ms: Option<u64>,
+  /// Catalog categories the plugin belongs to, e.g. `"formatting"`.
+  #[serde(default, skip_serializing_if = "Vec::is_empty")]
+  pub categories: Vec<String>,
+  /// Free-form search terms describing the plugin.
+  #[serde(default, skip_serializing_if = "Vec::is_empty")]
+  pub keywords: Vec<String>,
+  /// Keys of plugins that must be loaded before this one.
+  #[serde(default, skip_serializing_if = "Vec::is_empty")]
+  pub dependencies: Vec<String>,
+  /// Hosts (`<arch>-<os>`, e.g. `x86_64-linux`) the plugin's native code was built
+  /// for. Empty for plugins that run anywhere, such as WASM plugins.
+  #[serde(default, skip_serializing_if = "Vec::is_empty")]
+  pub targets: Vec<String>,
+  /// Top-level plugin.json fields the host does not interpret (e.g. custom settings),
+  /// kept so they can be handed to the plugin at runtime.
+  #[serde(flatten)]
+  pub extra: serde_json::Map<String, serde_json::Value>,
+}
+
+/// Declared contract for one plugin function.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Default)]
+pub struct FunctionSpec {
+  /// JSON Schema (Draft 7) the function's argument array must satisfy.
+  pub args: Option<serde_json::Value>,
+  /// Marks the function deterministic and free of side effects, so the host may answer
+  /// repeated calls with identical arguments from its result cache.
+  #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+  pub cacheable: bool,
+}
+
+/// The running host as a plugin `targets` entry, e.g. `x86_64-linux` or `aarch64-macos`.
+pub fn host_target() -> String {
+  format!("{}-{}", std::env::consts::ARCH, std::env::consts::OS)
+}
+
+/// Differences between two versions of a plugin configuration.
+///
+/// Produced by `PluginConfigData::diff` to drive upgrade prompts such as
+/// "approve new permissions?".
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct ConfigDiff {
+  /// `(from, to)` when the version string changed.
+  pub version: Option<(String, String)>,
+  /// Dimensions requested by the new config but not the old one.
+  pub added_permissions: Vec<PermissionKind>,
+  /// Dimensions requested by the old config but not the new one.
+  pub removed_permissions: Vec<PermissionKind>,
+  /// Dimensions requested by both whose detailed settings differ.
+  pub changed_permissions: Vec<PermissionKind>,
+}
+
+impl ConfigDiff {
+  /// Returns `true` if nothing relevant to an upgrade changed.
+  pub fn is_empty(&self) -> bool {
+    self.version.is_none()
+      && self.added_permissions.is_empty()
+      && self.removed_permissions.is_empty()
+      && self.changed_permissions.is_empty()
+  }
+
+  /// Returns `true` if the new config asks for more authority than the old one.
+  pub fn requires_approval(&self) -> bool {
+    !self.added_permissions.is_empty() || !self.changed_permissions.is_empty()
+  }
+}
+
+impl PluginConfigData {
+  /// Key the plugin is stored and cached under: its `id`, or its `name` without one.
+  pub fn key(&self) -> &str {
+    self.id.as_deref().unwrap_or(&self.name)
+  }
+
+  /// Entry file name: `main` if set, otherwise `default` (the provider's `MAIN_FILE`).
+  pub fn entry_file<'a>(&'a self, default: &'a str) -> &'a str {
+    self.main.as_deref().unwrap_or(default)
+  }
+
+  /// Returns `true` if the plugin declares no `targets` or lists `host_target()`.
+  pub fn supports_host(&self) -> bool {
+    self.targets.is_empty() || self.targets.iter().any(|t| *t == host_target())
+  }
+
+  /// Compares this (installed) config against `other` (the incoming one).
+  pub fn diff(&self, other: &PluginConfigData) -> ConfigDiff {
+    let version =
+      (self.version != other.version).then(|| (self.version.clone(), other.version.clone()));
+
+    let empty = Permissions::default();
+    let old = self.permissions.as_ref().unwrap_or(&empty);
+    let new = other.permissions.as_ref().unwrap_or(&empty);
+
+    let mut diff = ConfigDiff {
+      version,
+      ..ConfigDiff::default()
+    };
+
+    for kind in PermissionKind::ALL {
+      match (old.is_requested(kind), new.is_requested(kind)) {
+        (false, true) => diff.added_permissions.push(kind),
+        (true, false) => diff.removed_permissions.push(kind),
+        (true, true) if !old.dimension_eq(new, kind) => diff.changed_permissions.push(kind),
+        _ => {}
+      }
+    }
+
+    diff
+  }
+}
+
+/// One schema violation in a configuration document.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ValidationIssue {
+  /// JSON pointer to the offending value, empty for the document root.
+  pub path: String,
+  pub message: String,
+}
+
+impl fmt::Display for ValidationIssue {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    write!(f, "Path '{}': {}", self.path, self.message)
+  }
+}
+
+// One issue per line, followed by a count of the ones left out.
+fn issue_lines(issues: &[ValidationIssue], omitted: &usize) -> String {
+  let mut lines: Vec<String> = issues.iter().map(ValidationIssue::to_string).collect();
+  if *omitted > 0 {
+    lines.push(format!("...and {} more", omitted));
+  }
+  lines.join("\n")
 }
 
 #[derive(Error, Debug)]
@@ -88,6 +485,17 @@ pub enum ConfigError {
   #[error("Configuration validation failed:\n{0}")]
   ValidationError(String),
 
+  /// A document does not match its JSON Schema. `issues` holds each violation, up to
+  /// the reporting limit; `omitted` counts the ones beyond it.
+  #[error("Configuration validation failed:\n{}", issue_lines(.issues, .omitted))]
+  SchemaViolations {
+    issues: Vec<ValidationIssue>,
+    omitted: usize,
+  },
+
+  #[error("Unknown configuration profile '{profile}' (available: {available})")]
+  UnknownProfile { profile: String, available: String },
+
   #[error("File reading error: {0}")]
   IoError(#[from] std::io::Error),
 }
@@ -2,6 +2,8 @@ pub mod config;
 pub mod plugin;
 pub mod provider;
 
-pub use config::{ConfigData, ConfigError, PluginConfigData};
+pub use config::{
+  ConfigData, ConfigDiff, ConfigError, PermissionGrant, PermissionKind, PluginConfigData,
+};
 pub use plugin::Plugin;
-pub use provider::{Provider, ProviderError, ProviderValue};
+pub use provider::{FunctionSignature, Provider, ProviderError, ProviderValue, ValueKind};
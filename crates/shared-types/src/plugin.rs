@@ -2,6 +2,8 @@ use std::path::PathBuf;
 
 use thiserror::Error;
 
+use crate::config::ConfigError;
+
 #[derive(Error, Debug)]
 pub enum PluginError {
   #[error("Failed to initialize plugin manager: {0}")]
@@ -19,6 +21,29 @@ pub enum PluginError {
   #[error("Failed to invoke plugin: {0}")]
   InvokeError(String),
 
+  #[error("Failed to unload plugin: {0}")]
+  UnloadError(String),
+
+  #[error("Invalid arguments for '{function}': {source}")]
+  InvalidArguments {
+    function: String,
+    #[source]
+    source: ConfigError,
+  },
+
+  #[error("Pipeline step {step} failed: {source}")]
+  PipelineError {
+    step: usize,
+    #[source]
+    source: Box<PluginError>,
+  },
+
+  #[error("Invalid config overlay: {0}")]
+  InvalidOverlay(String),
+
+  #[error("Invalid plugin state: {0}")]
+  InvalidState(String),
+
   #[error("IO error: {0}")]
   IoError(#[from] std::io::Error),
 }
@@ -1,6 +1,14 @@
 use crate::config::PluginConfigData;
+use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
+use std::fmt;
 use std::path::Path;
+use std::sync::Arc;
+use std::time::Instant;
+
+/// Host function signature exposed to guest runtimes through `Provider::inject`.
+pub type HostFunction =
+  dyn Fn(Vec<ProviderValue>) -> Result<ProviderValue, ProviderError> + Send + Sync;
 
 /// Provider runtime error types.
 ///
@@ -19,6 +27,12 @@ pub enum ProviderError {
   /// Runtime function invocation failed.
   #[error("Function invocation failed: {0}")]
   InvocationFailed(String),
+  /// The plugin trapped during a call (including Rust panics, which abort via `unreachable`).
+  #[error("Plugin trapped: {0}")]
+  Trap(String),
+  /// The call did not finish before its deadline.
+  #[error("Plugin timed out: {0}")]
+  Timeout(String),
   /// Runtime instance unload failed.
   #[error("Instance unload failed: {0}")]
   UnloadFailed(String),
@@ -27,9 +41,36 @@ pub enum ProviderError {
   PermissionDenied(String),
 }
 
+impl ProviderError {
+  /// Returns `true` for errors after which the plugin instance cannot be trusted and
+  /// must be reloaded before it is called again.
+  pub fn is_fatal(&self) -> bool {
+    matches!(
+      self,
+      ProviderError::Trap(_) | ProviderError::Timeout(_) | ProviderError::PermissionDenied(_)
+    )
+  }
+}
+
 /// Unified value type across different runtime environments.
 ///
 /// Supports primitive and composite types for WASM, Bun, Node, etc.
+///
+/// Equality is structural, and floats compare with IEEE semantics rather than
+/// bitwise: `Float(f64::NAN)` never equals itself, so a value holding a NaN is not
+/// equal to its own clone, while `Float(0.0)` equals `Float(-0.0)`. Values of
+/// different variants are never equal, even `Int(1)` and `UInt(1)`.
+///
+/// `Display` prints scalars plainly, strings without quotes, bytes as a Rust byte
+/// string literal (`b"\x00ab"`), and arrays and objects in a JSON-like form with quoted
+/// strings, e.g. `{"a": [1, 2.5, "x"], "b": null}`.
+///
+/// It serializes untagged: `Object` as a map in field order, `Array` as a sequence,
+/// `Bytes` as serde bytes and the numeric variants as plain numbers. Deserializing is
+/// best-effort by the number's form, so integers become `Int` (or `UInt` above
+/// `i64::MAX`) and numbers with a fraction or exponent become `Float`. Whole floats
+/// keep their fraction in `serde_json` output, so every variant round-trips through
+/// JSON except `Tuple`, `Bytes` and non-finite floats, which JSON cannot represent.
 #[derive(Debug, Clone, PartialEq)]
 pub enum ProviderValue {
   /// Null value.
@@ -38,16 +79,294 @@ pub enum ProviderValue {
   Bool(bool),
   /// Integer value (i64).
   Int(i64),
+  /// Unsigned integer value (u64), for lengths, hashes and other values that use the
+  /// unsigned range. Providers produce it only for integers above `i64::MAX`; smaller
+  /// ones come back as `Int`.
+  UInt(u64),
   /// Float value (f64).
   Float(f64),
   /// String value.
   String(String),
+  /// Binary data, passed without UTF-8 validation.
+  ///
+  /// Formats without a bytes type, like JSON, write it as an array of numbers, which
+  /// deserializes back as `Array`.
+  Bytes(Vec<u8>),
   /// Array of values.
   Array(Vec<ProviderValue>),
+  /// The results of a function returning several values, in order.
+  ///
+  /// Providers produce it only for multi-value returns, so a function that returns one
+  /// array still yields `Array`. It serializes as an array, which deserializes back as
+  /// `Array`.
+  Tuple(Vec<ProviderValue>),
   /// Object as key-value pairs.
   Object(Vec<(String, ProviderValue)>),
 }
 
+impl ProviderValue {
+  /// Checks that the value nests at most `max_depth` levels and holds at most
+  /// `max_nodes` values in total, counting itself and every array element and object
+  /// field.
+  ///
+  /// A scalar has depth 1 and `[[1]]` has depth 3. The walk is iterative, so it is
+  /// safe on values too deep to traverse recursively.
+  ///
+  /// # Errors
+  ///
+  /// Returns `ProviderError::InvocationFailed` naming the limit that was exceeded.
+  pub fn validate_bounds(&self, max_depth: usize, max_nodes: usize) -> Result<(), ProviderError> {
+    let mut pending = vec![(self, 1)];
+    let mut nodes = 0;
+
+    while let Some((value, depth)) = pending.pop() {
+      nodes += 1;
+      if nodes > max_nodes {
+        return Err(ProviderError::InvocationFailed(format!(
+          "value has more than {} nodes",
+          max_nodes
+        )));
+      }
+      if depth > max_depth {
+        return Err(ProviderError::InvocationFailed(format!(
+          "value is nested deeper than {} levels",
+          max_depth
+        )));
+      }
+
+      match value {
+        ProviderValue::Array(items) | ProviderValue::Tuple(items) => {
+          pending.extend(items.iter().map(|v| (v, depth + 1)))
+        }
+        ProviderValue::Object(fields) => pending.extend(fields.iter().map(|(_, v)| (v, depth + 1))),
+        _ => {}
+      }
+    }
+
+    Ok(())
+  }
+}
+
+impl fmt::Display for ProviderValue {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    match self {
+      ProviderValue::String(s) => f.write_str(s),
+      other => write_nested(other, f),
+    }
+  }
+}
+
+// Writes `value` as it appears inside an array or object, with strings quoted.
+fn write_nested(value: &ProviderValue, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+  match value {
+    ProviderValue::Null => f.write_str("null"),
+    ProviderValue::Bool(b) => write!(f, "{}", b),
+    ProviderValue::Int(i) => write!(f, "{}", i),
+    ProviderValue::UInt(u) => write!(f, "{}", u),
+    // Debug keeps the fraction of whole floats, so `2.0` is not mistaken for `Int(2)`.
+    ProviderValue::Float(x) => write!(f, "{:?}", x),
+    ProviderValue::String(s) => write!(f, "{:?}", s),
+    ProviderValue::Bytes(bytes) => write!(f, "b\"{}\"", bytes.escape_ascii()),
+    ProviderValue::Array(items) => write_items(items, "[", "]", f),
+    ProviderValue::Tuple(items) => write_items(items, "(", ")", f),
+    ProviderValue::Object(fields) => {
+      f.write_str("{")?;
+      for (i, (key, field)) in fields.iter().enumerate() {
+        if i > 0 {
+          f.write_str(", ")?;
+        }
+        write!(f, "{:?}: ", key)?;
+        write_nested(field, f)?;
+      }
+      f.write_str("}")
+    }
+  }
+}
+
+fn write_items(
+  items: &[ProviderValue],
+  open: &str,
+  close: &str,
+  f: &mut fmt::Formatter<'_>,
+) -> fmt::Result {
+  f.write_str(open)?;
+  for (i, item) in items.iter().enumerate() {
+    if i > 0 {
+      f.write_str(", ")?;
+    }
+    write_nested(item, f)?;
+  }
+  f.write_str(close)
+}
+
+impl Serialize for ProviderValue {
+  fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+    use serde::ser::{SerializeMap, SerializeSeq};
+
+    match self {
+      ProviderValue::Null => serializer.serialize_unit(),
+      ProviderValue::Bool(b) => serializer.serialize_bool(*b),
+      ProviderValue::Int(i) => serializer.serialize_i64(*i),
+      ProviderValue::UInt(u) => serializer.serialize_u64(*u),
+      ProviderValue::Float(x) => serializer.serialize_f64(*x),
+      ProviderValue::String(s) => serializer.serialize_str(s),
+      ProviderValue::Bytes(bytes) => serializer.serialize_bytes(bytes),
+      ProviderValue::Array(items) | ProviderValue::Tuple(items) => {
+        let mut seq = serializer.serialize_seq(Some(items.len()))?;
+        for item in items {
+          seq.serialize_element(item)?;
+        }
+        seq.end()
+      }
+      ProviderValue::Object(fields) => {
+        let mut map = serializer.serialize_map(Some(fields.len()))?;
+        for (key, value) in fields {
+          map.serialize_entry(key, value)?;
+        }
+        map.end()
+      }
+    }
+  }
+}
+
+impl<'de> Deserialize<'de> for ProviderValue {
+  fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+    deserializer.deserialize_any(ProviderValueVisitor)
+  }
+}
+
+struct ProviderValueVisitor;
+
+impl<'de> serde::de::Visitor<'de> for ProviderValueVisitor {
+  type Value = ProviderValue;
+
+  fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    f.write_str("a provider value")
+  }
+
+  fn visit_unit<E>(self) -> Result<ProviderValue, E> {
+    Ok(ProviderValue::Null)
+  }
+
+  fn visit_none<E>(self) -> Result<ProviderValue, E> {
+    Ok(ProviderValue::Null)
+  }
+
+  fn visit_some<D: serde::Deserializer<'de>>(self, d: D) -> Result<ProviderValue, D::Error> {
+    ProviderValue::deserialize(d)
+  }
+
+  fn visit_bool<E>(self, b: bool) -> Result<ProviderValue, E> {
+    Ok(ProviderValue::Bool(b))
+  }
+
+  fn visit_i64<E>(self, i: i64) -> Result<ProviderValue, E> {
+    Ok(ProviderValue::Int(i))
+  }
+
+  // Formats report small non-negative integers as `u64`; only the ones `Int` cannot
+  // hold become `UInt`, matching the providers.
+  fn visit_u64<E>(self, u: u64) -> Result<ProviderValue, E> {
+    Ok(i64::try_from(u).map_or(ProviderValue::UInt(u), ProviderValue::Int))
+  }
+
+  fn visit_f64<E>(self, x: f64) -> Result<ProviderValue, E> {
+    Ok(ProviderValue::Float(x))
+  }
+
+  fn visit_str<E>(self, s: &str) -> Result<ProviderValue, E> {
+    Ok(ProviderValue::String(s.to_string()))
+  }
+
+  fn visit_string<E>(self, s: String) -> Result<ProviderValue, E> {
+    Ok(ProviderValue::String(s))
+  }
+
+  fn visit_bytes<E>(self, bytes: &[u8]) -> Result<ProviderValue, E> {
+    Ok(ProviderValue::Bytes(bytes.to_vec()))
+  }
+
+  fn visit_byte_buf<E>(self, bytes: Vec<u8>) -> Result<ProviderValue, E> {
+    Ok(ProviderValue::Bytes(bytes))
+  }
+
+  fn visit_seq<A: serde::de::SeqAccess<'de>>(self, mut seq: A) -> Result<ProviderValue, A::Error> {
+    let mut items = Vec::with_capacity(seq.size_hint().unwrap_or_default());
+    while let Some(item) = seq.next_element()? {
+      items.push(item);
+    }
+    Ok(ProviderValue::Array(items))
+  }
+
+  fn visit_map<A: serde::de::MapAccess<'de>>(self, mut map: A) -> Result<ProviderValue, A::Error> {
+    let mut fields = Vec::with_capacity(map.size_hint().unwrap_or_default());
+    while let Some(field) = map.next_entry()? {
+      fields.push(field);
+    }
+    Ok(ProviderValue::Object(fields))
+  }
+}
+
+/// Provider-neutral kind of a function parameter or result.
+///
+/// Serializes as its display name, e.g. `"i32"`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ValueKind {
+  Bool,
+  I32,
+  I64,
+  F32,
+  F64,
+  String,
+  Array,
+  Object,
+}
+
+impl fmt::Display for ValueKind {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    f.write_str(match self {
+      ValueKind::Bool => "bool",
+      ValueKind::I32 => "i32",
+      ValueKind::I64 => "i64",
+      ValueKind::F32 => "f32",
+      ValueKind::F64 => "f64",
+      ValueKind::String => "string",
+      ValueKind::Array => "array",
+      ValueKind::Object => "object",
+    })
+  }
+}
+
+/// Signature of one function a plugin exposes to the host.
+///
+/// Displays as `name(params) -> result`, e.g. `Sum(i32, i32) -> i32`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FunctionSignature {
+  pub name: String,
+  pub params: Vec<ValueKind>,
+  pub results: Vec<ValueKind>,
+}
+
+impl fmt::Display for FunctionSignature {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    let join = |kinds: &[ValueKind]| {
+      kinds
+        .iter()
+        .map(ValueKind::to_string)
+        .collect::<Vec<_>>()
+        .join(", ")
+    };
+
+    write!(f, "{}({})", self.name, join(&self.params))?;
+    match self.results.as_slice() {
+      [] => Ok(()),
+      [single] => write!(f, " -> {}", single),
+      many => write!(f, " -> ({})", join(many)),
+    }
+  }
+}
+
 /// Cross-runtime provider abstraction.
 ///
 /// Defines a unified interface for different runtime environments
@@ -58,34 +377,45 @@ pub enum ProviderValue {
 /// - Must implement `Send + Sync` for thread safety
 /// - Associated type `Instance` represents the provider's runtime instance
 /// - Associated constant `MAIN_FILE` defines the main entry file
+/// - Associated constant `NAME` selects the provider's settings in `bud.json`
 /// - All methods must return `Result` for error propagation
 ///
 /// # Examples
 ///
 /// ```
-/// use shared_types::{Provider, ProviderError, ProviderValue};
+/// use shared_types::provider::HostFunction;
+/// use shared_types::{FunctionSignature, PluginConfigData, Provider, ProviderError, ProviderValue};
+/// use std::collections::HashMap;
+/// use std::path::Path;
+/// use std::sync::Arc;
 ///
 /// struct MyProvider;
 ///
 /// impl Provider for MyProvider {
 ///   type Instance = ();
+///   type PluginInstance = ();
 ///   const MAIN_FILE: &'static str = "main.js";
+///   const NAME: &'static str = "my";
+///
+///   fn with_plugins<F, R>(&self, f: F) -> Result<R, ProviderError>
+///   where
+///     F: FnOnce(&HashMap<String, Self::PluginInstance>) -> R,
+///   {
+///     Ok(f(&HashMap::new()))
+///   }
 ///
 ///   fn init(&self) -> Result<Self::Instance, ProviderError> {
 ///     Ok(())
 ///   }
 ///
-///   fn load<P: AsRef<Path>>(&self, path: P) -> Result<(), ProviderError> {
+///   fn load<P: AsRef<Path>>(&self, _path: P, _config: &PluginConfigData) -> Result<(), ProviderError> {
 ///     Ok(())
 ///   }
 ///
 ///   fn inject(
 ///     &self,
 ///     _instance: &mut Self::Instance,
-///     _functions: &[(
-///       &str,
-///       &dyn Fn(Vec<ProviderValue>) -> Result<ProviderValue, ProviderError>,
-///     )],
+///     _functions: &[(&str, Arc<HostFunction>)],
 ///   ) -> Result<(), ProviderError> {
 ///     Ok(())
 ///   }
@@ -99,6 +429,10 @@ pub enum ProviderValue {
 ///     Ok(ProviderValue::Null)
 ///   }
 ///
+///   fn describe(&self, _plugin_name: &str) -> Result<Vec<FunctionSignature>, ProviderError> {
+///     Ok(Vec::new())
+///   }
+///
 ///   fn unload(&self, _instance: Self::Instance) -> Result<(), ProviderError> {
 ///     Ok(())
 ///   }
@@ -123,16 +457,40 @@ pub trait Provider: Send + Sync {
   /// - `BunProvider::MAIN_FILE = "main.js"`
   /// - `NodeProvider::MAIN_FILE = "index.js"`
   ///
+  /// It is only the default: a plugin may name another file with `main` in its
+  /// plugin.json, resolved by `PluginConfigData::entry_file`.
+  ///
   /// Benefits of using associated constants over methods:
   /// - Zero runtime overhead (inlined at compile time)
   /// - Can be used in generic constraints
   /// - Clearer semantics (explicitly constant)
   const MAIN_FILE: &'static str;
 
+  /// Key of the provider's settings under `providers` in `bud.json`.
+  ///
+  /// Examples:
+  /// - `WasmProvider::NAME = "wasm"`
+  /// - `BunProvider::NAME = "bun"`
+  const NAME: &'static str;
+
   fn with_plugins<F, R>(&self, f: F) -> Result<R, ProviderError>
   where
     F: FnOnce(&HashMap<String, Self::PluginInstance>) -> R;
 
+  /// Apply provider-specific settings.
+  ///
+  /// `BudCoreBuilder::build` passes the `providers.<NAME>` object from `bud.json`, if
+  /// present, before calling `init`. Providers without settings keep the default,
+  /// which ignores them.
+  ///
+  /// # Errors
+  ///
+  /// Returns `ProviderError::InitFailed` if the settings are invalid.
+  fn configure(&self, settings: &serde_json::Value) -> Result<(), ProviderError> {
+    let _ = settings;
+    Ok(())
+  }
+
   /// Initialize the provider instance.
   ///
   /// Creates and returns the provider's internal runtime instance.
@@ -142,9 +500,25 @@ pub trait Provider: Send + Sync {
   /// Returns `ProviderError::InitFailed` if initialization fails.
   fn init(&self) -> Result<Self::Instance, ProviderError>;
 
+  /// Whether `init` has created the runtime instance.
+  ///
+  /// Providers without runtime state keep the default, which reports `true`.
+  fn is_initialized(&self) -> bool {
+    true
+  }
+
+  /// Names of loaded plugins that must be reloaded before they can be called again.
+  ///
+  /// Providers whose plugins cannot end up in a failed state keep the default, which
+  /// reports none.
+  fn failed_plugins(&self) -> Vec<String> {
+    Vec::new()
+  }
+
   /// Load plugin using the provider.
   ///
-  /// Loads the plugin and returns an error if it fails.
+  /// Loads the plugin's entry file, `config.entry_file(Self::MAIN_FILE)`, from the
+  /// plugin directory `path` and returns an error if it fails.
   ///
   /// # Errors
   ///
@@ -166,15 +540,14 @@ pub trait Provider: Send + Sync {
   fn inject(
     &self,
     instance: &mut Self::Instance,
-    functions: &[(
-      &str,
-      &dyn Fn(Vec<ProviderValue>) -> Result<ProviderValue, ProviderError>,
-    )],
+    functions: &[(&str, Arc<HostFunction>)],
   ) -> Result<(), ProviderError>;
 
   /// Invoke a function in the runtime.
   ///
-  /// Executes a named function in the runtime environment.
+  /// Executes a named function in the runtime environment. Plugins are addressed by
+  /// the name they were loaded under rather than by `Self::Instance`, which stays
+  /// private to the provider.
   ///
   /// # Arguments
   ///
@@ -192,6 +565,152 @@ pub trait Provider: Send + Sync {
     args: Vec<ProviderValue>,
   ) -> Result<ProviderValue, ProviderError>;
 
+  /// Invoke a function that must finish by `deadline`.
+  ///
+  /// Providers that can interrupt a running call should override this. The default
+  /// only rejects a deadline that has already passed and otherwise calls `invoke`.
+  ///
+  /// # Errors
+  ///
+  /// Returns `ProviderError::Timeout` if the deadline passes, and otherwise the same
+  /// errors as `invoke`.
+  fn invoke_with_deadline(
+    &self,
+    plugin_name: &str,
+    function: &str,
+    args: Vec<ProviderValue>,
+    deadline: Instant,
+  ) -> Result<ProviderValue, ProviderError> {
+    if Instant::now() >= deadline {
+      return Err(ProviderError::Timeout(
+        "deadline passed before the call started".to_string(),
+      ));
+    }
+    self.invoke(plugin_name, function, args)
+  }
+
+  /// Invoke a function from an async context.
+  ///
+  /// Only available with the `async` feature. Providers that can suspend a running
+  /// call should override this; the default runs `invoke` when the future is first
+  /// polled, blocking the polling thread for the whole call.
+  ///
+  /// # Errors
+  ///
+  /// Returns the same errors as `invoke`.
+  #[cfg(feature = "async")]
+  fn invoke_async(
+    &self,
+    plugin_name: &str,
+    function: &str,
+    args: Vec<ProviderValue>,
+  ) -> impl std::future::Future<Output = Result<ProviderValue, ProviderError>> + Send {
+    async move { self.invoke(plugin_name, function, args) }
+  }
+
+  /// Describe the functions a loaded plugin exposes.
+  ///
+  /// # Arguments
+  ///
+  /// * `plugin_name` - Name of the loaded plugin
+  ///
+  /// # Errors
+  ///
+  /// Returns `ProviderError::LoadFailed` if the plugin is not loaded, or
+  /// `ProviderError::InvocationFailed` if introspection fails.
+  fn describe(&self, plugin_name: &str) -> Result<Vec<FunctionSignature>, ProviderError>;
+
+  /// List the names of the functions a loaded plugin exports.
+  ///
+  /// Unlike `describe`, this includes functions whose signatures cannot be described.
+  /// The default lists the names from `describe`.
+  ///
+  /// # Arguments
+  ///
+  /// * `plugin_name` - Name of the loaded plugin
+  ///
+  /// # Errors
+  ///
+  /// Same as `describe`.
+  fn list_exports(&self, plugin_name: &str) -> Result<Vec<String>, ProviderError> {
+    Ok(
+      self
+        .describe(plugin_name)?
+        .into_iter()
+        .map(|sig| sig.name)
+        .collect(),
+    )
+  }
+
+  /// Compile a plugin ahead of time into a loadable artifact.
+  ///
+  /// Providers without a compile step keep the default, which always fails.
+  ///
+  /// # Arguments
+  ///
+  /// * `path` - Plugin directory containing the entry file
+  /// * `config` - Plugin configuration, which names the entry file
+  /// * `output` - File the compiled artifact is written to
+  ///
+  /// # Errors
+  ///
+  /// Returns `ProviderError::LoadFailed` if compiling or writing the artifact fails.
+  fn precompile<P: AsRef<Path>>(
+    &self,
+    path: P,
+    config: &PluginConfigData,
+    output: &Path,
+  ) -> Result<(), ProviderError> {
+    let _ = (path, config, output);
+    Err(ProviderError::LoadFailed(
+      "precompilation is not supported by this provider".to_string(),
+    ))
+  }
+
+  /// Load a plugin from an artifact written by `precompile`, skipping compilation.
+  ///
+  /// Artifacts are trusted: only load files produced by this provider's `precompile`.
+  ///
+  /// # Arguments
+  ///
+  /// * `name` - Name the plugin is registered under
+  /// * `artifact` - File written by `precompile`
+  /// * `config` - Plugin configuration
+  ///
+  /// # Errors
+  ///
+  /// Returns `ProviderError::LoadFailed` if the artifact is incompatible or loading fails.
+  fn load_precompiled(
+    &self,
+    name: &str,
+    artifact: &Path,
+    config: &PluginConfigData,
+  ) -> Result<(), ProviderError> {
+    let _ = (name, artifact, config);
+    Err(ProviderError::LoadFailed(
+      "precompilation is not supported by this provider".to_string(),
+    ))
+  }
+
+  /// Unload one plugin, releasing everything it holds.
+  ///
+  /// Providers that cannot unload single plugins keep the default, which always fails.
+  ///
+  /// # Arguments
+  ///
+  /// * `plugin_name` - Name of the loaded plugin
+  ///
+  /// # Errors
+  ///
+  /// Returns `ProviderError::UnloadFailed` if the plugin is not loaded or cannot be
+  /// unloaded.
+  fn unload_plugin(&self, plugin_name: &str) -> Result<(), ProviderError> {
+    let _ = plugin_name;
+    Err(ProviderError::UnloadFailed(
+      "unloading single plugins is not supported by this provider".to_string(),
+    ))
+  }
+
   /// Unload the runtime instance.
   ///
   /// Releases all resources held by the provider instance.
@@ -0,0 +1,93 @@
+use shared_types::config::{FilesystemPermission, Permission, Permissions};
+use shared_types::{PermissionKind, PluginConfigData};
+
+fn plugin_config(version: &str, permissions: Option<Permissions>) -> PluginConfigData {
+  PluginConfigData {
+    id: None,
+    name: "test-plugin".to_string(),
+    version: version.to_string(),
+    description: "Test plugin".to_string(),
+    author: "tester".to_string(),
+    main: None,
+    permissions,
+    functions: None,
+    timeout_ms: None,
+    categories: Vec::new(),
+    keywords: Vec::new(),
+    dependencies: Vec::new(),
+    targets: Vec::new(),
+    extra: Default::default(),
+  }
+}
+
+#[test]
+fn test_diff_version_only_change() {
+  let old = plugin_config("1.0.0", Some(Permissions::default()));
+  let new = plugin_config("1.1.0", Some(Permissions::default()));
+
+  let diff = old.diff(&new);
+  assert_eq!(
+    diff.version,
+    Some(("1.0.0".to_string(), "1.1.0".to_string()))
+  );
+  assert!(diff.added_permissions.is_empty());
+  assert!(diff.removed_permissions.is_empty());
+  assert!(!diff.requires_approval());
+}
+
+#[test]
+fn test_diff_added_permission_requires_approval() {
+  let old = plugin_config(
+    "1.0.0",
+    Some(Permissions {
+      stdio: Some(Permission::Bool(true)),
+      ..Permissions::default()
+    }),
+  );
+  let new = plugin_config(
+    "2.0.0",
+    Some(Permissions {
+      stdio: Some(Permission::Bool(true)),
+      network: Some(Permission::Bool(true)),
+      ..Permissions::default()
+    }),
+  );
+
+  let diff = old.diff(&new);
+  assert_eq!(diff.added_permissions, vec![PermissionKind::Network]);
+  assert!(diff.removed_permissions.is_empty());
+  assert!(diff.requires_approval());
+}
+
+#[test]
+fn test_diff_removed_and_changed_permissions() {
+  let old = plugin_config(
+    "1.0.0",
+    Some(Permissions {
+      env: Some(Permission::Bool(true)),
+      filesystem: Some(Permission::Config(FilesystemPermission {
+        read: Some(vec!["./config".to_string()]),
+        write: None,
+      })),
+      ..Permissions::default()
+    }),
+  );
+  let new = plugin_config(
+    "1.0.0",
+    Some(Permissions {
+      // `false` is equivalent to not requesting the dimension at all.
+      env: Some(Permission::Bool(false)),
+      filesystem: Some(Permission::Config(FilesystemPermission {
+        read: Some(vec!["./config".to_string()]),
+        write: Some(vec!["./output".to_string()]),
+      })),
+      ..Permissions::default()
+    }),
+  );
+
+  let diff = old.diff(&new);
+  assert_eq!(diff.version, None);
+  assert_eq!(diff.removed_permissions, vec![PermissionKind::Env]);
+  assert_eq!(diff.changed_permissions, vec![PermissionKind::Filesystem]);
+  assert!(diff.requires_approval());
+}
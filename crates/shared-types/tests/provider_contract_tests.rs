@@ -0,0 +1,128 @@
+//! A second, in-memory `Provider` that locks in the trait's name-based contract.
+
+use shared_types::provider::HostFunction;
+use shared_types::{FunctionSignature, PluginConfigData, Provider, ProviderError, ProviderValue};
+use std::collections::HashMap;
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+// Loads every plugin with a single `Echo` function returning its arguments.
+#[derive(Default)]
+struct EchoProvider {
+  plugins: Mutex<HashMap<String, ()>>,
+}
+
+impl Provider for EchoProvider {
+  type Instance = ();
+  type PluginInstance = ();
+  const MAIN_FILE: &'static str = "main.echo";
+  const NAME: &'static str = "echo";
+
+  fn with_plugins<F, R>(&self, f: F) -> Result<R, ProviderError>
+  where
+    F: FnOnce(&HashMap<String, Self::PluginInstance>) -> R,
+  {
+    Ok(f(&self.plugins.lock().unwrap()))
+  }
+
+  fn init(&self) -> Result<Self::Instance, ProviderError> {
+    Ok(())
+  }
+
+  fn load<P: AsRef<Path>>(&self, _path: P, config: &PluginConfigData) -> Result<(), ProviderError> {
+    self
+      .plugins
+      .lock()
+      .unwrap()
+      .insert(config.key().to_string(), ());
+    Ok(())
+  }
+
+  fn inject(
+    &self,
+    _instance: &mut Self::Instance,
+    _functions: &[(&str, Arc<HostFunction>)],
+  ) -> Result<(), ProviderError> {
+    Ok(())
+  }
+
+  fn invoke(
+    &self,
+    plugin_name: &str,
+    function: &str,
+    args: Vec<ProviderValue>,
+  ) -> Result<ProviderValue, ProviderError> {
+    if !self.plugins.lock().unwrap().contains_key(plugin_name) {
+      return Err(ProviderError::InvocationFailed(format!(
+        "plugin '{}' is not loaded",
+        plugin_name
+      )));
+    }
+    match function {
+      "Echo" => Ok(ProviderValue::Array(args)),
+      _ => Err(ProviderError::InvocationFailed(format!(
+        "unknown function '{}'",
+        function
+      ))),
+    }
+  }
+
+  fn describe(&self, _plugin_name: &str) -> Result<Vec<FunctionSignature>, ProviderError> {
+    Ok(Vec::new())
+  }
+
+  fn unload(&self, _instance: Self::Instance) -> Result<(), ProviderError> {
+    Ok(())
+  }
+}
+
+fn config(name: &str) -> PluginConfigData {
+  serde_json::from_value(serde_json::json!({
+    "name": name,
+    "version": "1.0.0",
+    "description": "Echo plugin",
+    "author": "tester"
+  }))
+  .unwrap()
+}
+
+// Written against the trait alone, as host code generic over providers is.
+fn echo<P: Provider>(provider: &P, plugin_name: &str) -> Result<ProviderValue, ProviderError> {
+  provider.invoke(plugin_name, "Echo", vec![ProviderValue::Int(7)])
+}
+
+#[test]
+fn test_invoke_addresses_plugins_by_name() {
+  let provider = EchoProvider::default();
+  provider.load("unused", &config("first")).unwrap();
+
+  assert_eq!(
+    echo(&provider, "first").unwrap(),
+    ProviderValue::Array(vec![ProviderValue::Int(7)])
+  );
+  assert!(matches!(
+    echo(&provider, "second"),
+    Err(ProviderError::InvocationFailed(_))
+  ));
+}
+
+#[test]
+fn test_default_deadline_invoke_delegates_to_invoke() {
+  let provider = EchoProvider::default();
+  provider.load("unused", &config("first")).unwrap();
+
+  let later = Instant::now() + Duration::from_secs(60);
+  assert_eq!(
+    provider
+      .invoke_with_deadline("first", "Echo", Vec::new(), later)
+      .unwrap(),
+    ProviderValue::Array(Vec::new())
+  );
+
+  let passed = Instant::now() - Duration::from_millis(1);
+  assert!(matches!(
+    provider.invoke_with_deadline("first", "Echo", Vec::new(), passed),
+    Err(ProviderError::Timeout(_))
+  ));
+}
@@ -0,0 +1,147 @@
+use shared_types::ProviderValue;
+
+fn sample() -> ProviderValue {
+  ProviderValue::Object(vec![
+    (
+      "items".to_string(),
+      ProviderValue::Array(vec![
+        ProviderValue::Int(1),
+        ProviderValue::Float(2.0),
+        ProviderValue::String("x \"y\"".to_string()),
+      ]),
+    ),
+    ("big".to_string(), ProviderValue::UInt(u64::MAX)),
+    ("none".to_string(), ProviderValue::Null),
+    ("ok".to_string(), ProviderValue::Bool(true)),
+  ])
+}
+
+#[test]
+fn test_nested_values_compare_structurally() {
+  assert_eq!(sample(), sample());
+
+  let mut changed = sample();
+  if let ProviderValue::Object(fields) = &mut changed {
+    fields[0].1 = ProviderValue::Array(vec![ProviderValue::Int(1)]);
+  }
+  assert_ne!(sample(), changed);
+
+  // Field order is significant, and variants never compare equal to each other.
+  assert_ne!(
+    ProviderValue::Object(vec![
+      ("a".to_string(), ProviderValue::Null),
+      ("b".to_string(), ProviderValue::Null),
+    ]),
+    ProviderValue::Object(vec![
+      ("b".to_string(), ProviderValue::Null),
+      ("a".to_string(), ProviderValue::Null),
+    ])
+  );
+  assert_ne!(ProviderValue::Int(1), ProviderValue::UInt(1));
+}
+
+#[test]
+fn test_floats_compare_with_ieee_semantics() {
+  let nan = ProviderValue::Array(vec![ProviderValue::Float(f64::NAN)]);
+  assert_ne!(nan, nan.clone());
+  assert_eq!(ProviderValue::Float(0.0), ProviderValue::Float(-0.0));
+}
+
+#[test]
+fn test_display_output() {
+  assert_eq!(ProviderValue::Null.to_string(), "null");
+  assert_eq!(ProviderValue::Int(-3).to_string(), "-3");
+  assert_eq!(ProviderValue::Float(2.5).to_string(), "2.5");
+  assert_eq!(ProviderValue::String("plain".into()).to_string(), "plain");
+  assert_eq!(
+    sample().to_string(),
+    r#"{"items": [1, 2.0, "x \"y\""], "big": 18446744073709551615, "none": null, "ok": true}"#
+  );
+  assert_eq!(ProviderValue::Array(vec![]).to_string(), "[]");
+  assert_eq!(ProviderValue::Object(vec![]).to_string(), "{}");
+}
+
+#[test]
+fn test_tuple_is_distinct_from_array() {
+  let items = vec![ProviderValue::Int(1), ProviderValue::String("a".into())];
+  let tuple = ProviderValue::Tuple(items.clone());
+
+  assert_ne!(tuple, ProviderValue::Array(items.clone()));
+  assert_eq!(tuple.to_string(), r#"(1, "a")"#);
+  // JSON has no tuples, so one comes back as an array.
+  assert_eq!(round_trip(&tuple), ProviderValue::Array(items));
+}
+
+#[test]
+fn test_bytes_are_distinct_from_strings() {
+  let bytes = ProviderValue::Bytes(vec![0, b'a', 0xff]);
+
+  assert_ne!(bytes, ProviderValue::String("\0a".to_string()));
+  assert_eq!(bytes.to_string(), r#"b"\x00a\xff""#);
+  // JSON has no bytes type, so they come back as an array of numbers.
+  assert_eq!(
+    round_trip(&bytes),
+    ProviderValue::Array(vec![
+      ProviderValue::Int(0),
+      ProviderValue::Int(97),
+      ProviderValue::Int(255),
+    ])
+  );
+}
+
+fn round_trip(value: &ProviderValue) -> ProviderValue {
+  let json = serde_json::to_string(value).expect("serialize failed");
+  serde_json::from_str(&json).expect("deserialize failed")
+}
+
+#[test]
+fn test_every_variant_round_trips_through_json() {
+  let values = [
+    ProviderValue::Null,
+    ProviderValue::Bool(false),
+    ProviderValue::Int(-42),
+    ProviderValue::Int(i64::MAX),
+    ProviderValue::UInt(u64::MAX),
+    ProviderValue::Float(2.0),
+    ProviderValue::Float(-1.5e-7),
+    ProviderValue::String("héllo \"world\"".to_string()),
+    ProviderValue::Array(vec![]),
+    ProviderValue::Object(vec![]),
+    sample(),
+    ProviderValue::Array(vec![sample(), ProviderValue::Array(vec![sample()])]),
+  ];
+  for value in values {
+    assert_eq!(round_trip(&value), value);
+  }
+}
+
+#[test]
+fn test_json_shape_and_number_inference() {
+  assert_eq!(
+    serde_json::to_value(sample()).unwrap(),
+    serde_json::json!({
+      "items": [1, 2.0, "x \"y\""],
+      "big": u64::MAX,
+      "none": null,
+      "ok": true,
+    })
+  );
+
+  let value: ProviderValue = serde_json::from_str(r#"[3, 3.0, 1e2, 9223372036854775808]"#).unwrap();
+  assert_eq!(
+    value,
+    ProviderValue::Array(vec![
+      ProviderValue::Int(3),
+      ProviderValue::Float(3.0),
+      ProviderValue::Float(100.0),
+      ProviderValue::UInt(1 << 63),
+    ])
+  );
+
+  // Field order follows the input.
+  let value: ProviderValue = serde_json::from_str(r#"{"z": 1, "a": 2}"#).unwrap();
+  match value {
+    ProviderValue::Object(fields) => assert_eq!(fields[0].0, "z"),
+    other => panic!("expected an object, got {:?}", other),
+  }
+}
@@ -0,0 +1,38 @@
+use shared_types::{ProviderError, ProviderValue};
+
+fn nested(depth: usize) -> ProviderValue {
+  (1..depth).fold(ProviderValue::Int(0), |inner, _| {
+    ProviderValue::Array(vec![inner])
+  })
+}
+
+#[test]
+fn test_value_within_bounds_is_accepted() {
+  let value = ProviderValue::Object(vec![
+    ("a".to_string(), ProviderValue::Int(1)),
+    ("b".to_string(), nested(3)),
+  ]);
+  // Five values, with b's integer at depth 4.
+  value.validate_bounds(4, 5).expect("value should fit");
+}
+
+#[test]
+fn test_too_deep_value_is_rejected() {
+  match nested(1000).validate_bounds(64, usize::MAX) {
+    Err(ProviderError::InvocationFailed(msg)) => {
+      assert_eq!(msg, "value is nested deeper than 64 levels")
+    }
+    other => panic!("expected InvocationFailed, got {:?}", other),
+  }
+}
+
+#[test]
+fn test_value_with_too_many_nodes_is_rejected() {
+  let wide = ProviderValue::Array(vec![ProviderValue::Null; 1000]);
+  match wide.validate_bounds(usize::MAX, 1000) {
+    Err(ProviderError::InvocationFailed(msg)) => {
+      assert_eq!(msg, "value has more than 1000 nodes")
+    }
+    other => panic!("expected InvocationFailed, got {:?}", other),
+  }
+}
@@ -11,7 +11,9 @@ fn main() {
         core.config
       );
       let mut manager = core.plugin_manager;
-      manager.load("test-plugin");
+      if let Err(e) = manager.load("test-plugin") {
+        println!("Failed to load test-plugin: {}", e);
+      }
     }
     Err(e) => println!("BudCore with WASM Provider initialization failed: {}", e),
   }
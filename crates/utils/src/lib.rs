@@ -1,12 +1,27 @@
 pub mod provider_json;
 
+use std::ffi::OsString;
 use std::io;
-use std::path::Path;
+use std::path::{Path, PathBuf};
+
+/// What the directory copies do with symbolic links found in the source.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum SymlinkPolicy {
+    /// Creates a link with the same target in the copy, without reading through it.
+    #[default]
+    Recreate,
+    /// Leaves links out of the copy.
+    Skip,
+    /// Copies what a link points to in its place. A link to a directory the copy is
+    /// already inside fails the copy rather than recursing forever.
+    Follow,
+}
 
 /// Recursively copies all files and subdirectories from `src` to `dst`.
 ///
 /// Creates `dst` directory and all necessary parent directories.
-/// Overwrites existing files with the same name in `dst`.
+/// Overwrites existing files with the same name in `dst`. Symbolic links are
+/// recreated rather than followed (`SymlinkPolicy::Recreate`).
 ///
 /// # Arguments
 ///
@@ -17,16 +32,159 @@ use std::path::Path;
 ///
 /// Returns `io::Error` if directory creation or file copy operations fail.
 pub fn copy_dir_recursive(src: &Path, dst: &Path) -> io::Result<()> {
-    std::fs::create_dir_all(dst)?;
-    for entry in std::fs::read_dir(src)? {
-        let entry = entry?;
-        let src_path = entry.path();
-        let dst_path = dst.join(entry.file_name());
-        if src_path.is_dir() {
-            copy_dir_recursive(&src_path, &dst_path)?;
-        } else {
-            std::fs::copy(&src_path, &dst_path)?;
+    copy_dir_with_filter(src, dst, SymlinkPolicy::default(), |_| true)
+}
+
+/// Like `copy_dir_recursive`, but copies only the entries `filter` accepts and treats
+/// symbolic links as `symlinks` says.
+///
+/// `filter` receives each entry's path relative to `src`, e.g. `target` or
+/// `assets/icon.png~`. A rejected directory is skipped along with everything in it.
+///
+/// # Errors
+///
+/// Returns `io::Error` if directory creation, file copy or link operations fail, or if
+/// `SymlinkPolicy::Follow` meets a link back into a directory being copied.
+pub fn copy_dir_with_filter(
+    src: &Path,
+    dst: &Path,
+    symlinks: SymlinkPolicy,
+    filter: impl Fn(&Path) -> bool,
+) -> io::Result<()> {
+    let copy = FilteredCopy {
+        src,
+        dst,
+        symlinks,
+        filter: &filter,
+    };
+    copy.dir(Path::new(""), &mut Vec::new())
+}
+
+/// Like `copy_dir_with_filter`, but `dst` only appears once the copy is complete.
+///
+/// The copy is made in a hidden sibling of `dst` and renamed into place, so `dst`
+/// either holds everything or does not exist. On any error the partial copy is
+/// removed. `dst` must not exist yet, while its parent must. Should the rename fail
+/// because the two are on different devices, the copy is moved by copying it again
+/// and removing it instead, which is not atomic.
+///
+/// # Errors
+///
+/// Returns `io::Error` if `dst` has no file name or a copy or rename fails.
+pub fn copy_dir_atomic(
+    src: &Path,
+    dst: &Path,
+    symlinks: SymlinkPolicy,
+    filter: impl Fn(&Path) -> bool,
+) -> io::Result<()> {
+    let name = dst.file_name().ok_or_else(|| {
+        io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!("{} has no file name", dst.display()),
+        )
+    })?;
+    let mut partial_name = OsString::from(".");
+    partial_name.push(name);
+    partial_name.push(".partial");
+    let partial = dst.with_file_name(partial_name);
+
+    // Left over if a previous copy was interrupted.
+    if partial.exists() {
+        std::fs::remove_dir_all(&partial)?;
+    }
+    let result = copy_dir_with_filter(src, &partial, symlinks, filter).and_then(|()| {
+        match std::fs::rename(&partial, dst) {
+            Err(e) if e.kind() == io::ErrorKind::CrossesDevices => {
+                copy_dir_recursive(&partial, dst)
+                    .inspect_err(|_| {
+                        let _ = std::fs::remove_dir_all(dst);
+                    })
+                    .and_then(|()| std::fs::remove_dir_all(&partial))
+            }
+            result => result,
         }
+    });
+    if result.is_err() && partial.exists() {
+        let _ = std::fs::remove_dir_all(&partial);
+    }
+    result
+}
+
+// One `copy_dir_with_filter` call.
+struct FilteredCopy<'a> {
+    src: &'a Path,
+    dst: &'a Path,
+    symlinks: SymlinkPolicy,
+    filter: &'a dyn Fn(&Path) -> bool,
+}
+
+impl FilteredCopy<'_> {
+    // Copies the directory `relative` under `src` to the same place under `dst`.
+    // `ancestors` holds the canonical paths of the directories being copied, to catch
+    // followed links that loop.
+    fn dir(&self, relative: &Path, ancestors: &mut Vec<PathBuf>) -> io::Result<()> {
+        let from = self.src.join(relative);
+        if self.symlinks == SymlinkPolicy::Follow {
+            let canonical = from.canonicalize()?;
+            if ancestors.contains(&canonical) {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidInput,
+                    format!("symlink loop at {}", from.display()),
+                ));
+            }
+            ancestors.push(canonical);
+        }
+
+        std::fs::create_dir_all(self.dst.join(relative))?;
+        for entry in std::fs::read_dir(&from)? {
+            let entry = entry?;
+            let entry_path = relative.join(entry.file_name());
+            if !(self.filter)(&entry_path) {
+                continue;
+            }
+            let to = self.dst.join(&entry_path);
+            // Unlike `Path::is_dir`, the entry's own type does not look through links.
+            if entry.file_type()?.is_symlink() {
+                match self.symlinks {
+                    SymlinkPolicy::Skip => continue,
+                    SymlinkPolicy::Recreate => {
+                        // Replaced like a file would be overwritten.
+                        if to.symlink_metadata().is_ok_and(|meta| !meta.is_dir()) {
+                            std::fs::remove_file(&to)?;
+                        }
+                        symlink(&std::fs::read_link(entry.path())?, &to, &entry.path())?;
+                        continue;
+                    }
+                    SymlinkPolicy::Follow => {}
+                }
+            }
+            if entry.path().is_dir() {
+                self.dir(&entry_path, ancestors)?;
+            } else {
+                std::fs::copy(entry.path(), &to)?;
+            }
+        }
+
+        if self.symlinks == SymlinkPolicy::Follow {
+            ancestors.pop();
+        }
+        Ok(())
+    }
+}
+
+// Creates a link at `link` pointing at `target`, as `original` does.
+#[cfg(unix)]
+fn symlink(target: &Path, link: &Path, _original: &Path) -> io::Result<()> {
+    std::os::unix::fs::symlink(target, link)
+}
+
+// Windows tells file and directory links apart, so the kind follows what `original`
+// points at; a dangling link becomes a file link.
+#[cfg(windows)]
+fn symlink(target: &Path, link: &Path, original: &Path) -> io::Result<()> {
+    if original.is_dir() {
+        std::os::windows::fs::symlink_dir(target, link)
+    } else {
+        std::os::windows::fs::symlink_file(target, link)
     }
-    Ok(())
 }
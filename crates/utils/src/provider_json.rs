@@ -6,9 +6,13 @@ pub fn provider_value_to_json(val: &ProviderValue) -> Value {
     ProviderValue::Null => Value::Null,
     ProviderValue::Bool(b) => Value::Bool(*b),
     ProviderValue::Int(i) => Value::Number((*i).into()),
+    ProviderValue::UInt(u) => Value::Number((*u).into()),
     ProviderValue::Float(f) => serde_json::Number::from_f64(*f).map_or(Value::Null, Value::Number),
     ProviderValue::String(s) => Value::String(s.clone()),
-    ProviderValue::Array(arr) => Value::Array(arr.iter().map(provider_value_to_json).collect()),
+    ProviderValue::Bytes(bytes) => Value::Array(bytes.iter().map(|b| Value::from(*b)).collect()),
+    ProviderValue::Array(arr) | ProviderValue::Tuple(arr) => {
+      Value::Array(arr.iter().map(provider_value_to_json).collect())
+    }
     ProviderValue::Object(obj) => Value::Object(
       obj
         .iter()
@@ -25,6 +29,8 @@ pub fn json_to_provider_value(val: &Value) -> ProviderValue {
     Value::Number(n) => {
       if let Some(i) = n.as_i64() {
         ProviderValue::Int(i)
+      } else if let Some(u) = n.as_u64() {
+        ProviderValue::UInt(u)
       } else if let Some(f) = n.as_f64() {
         ProviderValue::Float(f)
       } else {
@@ -45,3 +51,79 @@ pub fn json_to_provider_value(val: &Value) -> ProviderValue {
 pub fn args_to_json(args: &[ProviderValue]) -> Value {
   Value::Array(args.iter().map(provider_value_to_json).collect())
 }
+
+/// One step into a nested `ProviderValue`, used to locate conversion errors.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PathSegment {
+  /// Top-level argument by position.
+  Arg(usize),
+  /// The value returned to the guest.
+  Result,
+  /// Array element by index.
+  Index(usize),
+  /// Object field by name.
+  Field(String),
+}
+
+/// Renders a path as `arg 0 -> [2] -> field 'x'`.
+pub fn format_path(path: &[PathSegment]) -> String {
+  path
+    .iter()
+    .map(|segment| match segment {
+      PathSegment::Arg(i) => format!("arg {}", i),
+      PathSegment::Result => "result".to_string(),
+      PathSegment::Index(i) => format!("[{}]", i),
+      PathSegment::Field(name) => format!("field '{}'", name),
+    })
+    .collect::<Vec<_>>()
+    .join(" -> ")
+}
+
+/// Like `args_to_json`, but fails on values JSON cannot represent instead of sending `null`.
+///
+/// The error names the offending value by its location, e.g.
+/// `arg 0 -> [2] -> field 'x': NaN is not representable in JSON`.
+pub fn try_args_to_json(args: &[ProviderValue]) -> Result<Value, String> {
+  let mut path = Vec::new();
+  args
+    .iter()
+    .enumerate()
+    .map(|(i, arg)| {
+      path.push(PathSegment::Arg(i));
+      let value = try_to_json(arg, &mut path)?;
+      path.pop();
+      Ok(value)
+    })
+    .collect::<Result<_, _>>()
+    .map(Value::Array)
+}
+
+fn try_to_json(val: &ProviderValue, path: &mut Vec<PathSegment>) -> Result<Value, String> {
+  match val {
+    ProviderValue::Float(f) => serde_json::Number::from_f64(*f)
+      .map(Value::Number)
+      .ok_or_else(|| format!("{}: {} is not representable in JSON", format_path(path), f)),
+    ProviderValue::Array(arr) | ProviderValue::Tuple(arr) => arr
+      .iter()
+      .enumerate()
+      .map(|(i, item)| {
+        path.push(PathSegment::Index(i));
+        let value = try_to_json(item, path)?;
+        path.pop();
+        Ok(value)
+      })
+      .collect::<Result<_, _>>()
+      .map(Value::Array),
+    ProviderValue::Object(obj) => obj
+      .iter()
+      .map(|(k, v)| {
+        path.push(PathSegment::Field(k.clone()));
+        let value = try_to_json(v, path)?;
+        path.pop();
+        Ok((k.clone(), value))
+      })
+      .collect::<Result<_, _>>()
+      .map(Value::Object),
+    other => Ok(provider_value_to_json(other)),
+  }
+}
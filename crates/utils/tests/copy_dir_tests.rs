@@ -0,0 +1,179 @@
+//! Integration tests for `copy_dir_recursive`, `copy_dir_with_filter` and
+//! `copy_dir_atomic`, including their handling of symbolic links.
+
+use std::fs;
+use std::path::Path;
+use utils::{SymlinkPolicy, copy_dir_atomic, copy_dir_recursive, copy_dir_with_filter};
+
+// Source tree with a plugin's files next to build output and an editor backup.
+fn write_source(root: &Path) {
+  fs::create_dir_all(root.join("assets")).unwrap();
+  fs::create_dir_all(root.join("target/debug")).unwrap();
+  fs::write(root.join("plugin.json"), "{}").unwrap();
+  fs::write(root.join("assets/icon.png"), "icon").unwrap();
+  fs::write(root.join("assets/icon.png~"), "backup").unwrap();
+  fs::write(root.join("target/debug/main.wasm"), "build").unwrap();
+}
+
+#[test]
+fn test_copy_dir_recursive_copies_everything() {
+  let src = tempfile::tempdir().unwrap();
+  let dst = tempfile::tempdir().unwrap();
+  write_source(src.path());
+
+  copy_dir_recursive(src.path(), dst.path()).unwrap();
+
+  assert!(dst.path().join("plugin.json").is_file());
+  assert!(dst.path().join("assets/icon.png~").is_file());
+  assert!(dst.path().join("target/debug/main.wasm").is_file());
+}
+
+#[test]
+fn test_filter_skips_rejected_files_and_directories() {
+  let src = tempfile::tempdir().unwrap();
+  let dst = tempfile::tempdir().unwrap();
+  write_source(src.path());
+
+  copy_dir_with_filter(src.path(), dst.path(), SymlinkPolicy::Recreate, |path| {
+    path != Path::new("target") && !path.to_string_lossy().ends_with('~')
+  })
+  .unwrap();
+
+  assert_eq!(
+    fs::read_to_string(dst.path().join("assets/icon.png")).unwrap(),
+    "icon"
+  );
+  assert!(dst.path().join("plugin.json").is_file());
+  assert!(!dst.path().join("assets/icon.png~").exists());
+  assert!(!dst.path().join("target").exists());
+}
+
+#[test]
+fn test_filter_sees_paths_relative_to_the_source() {
+  let src = tempfile::tempdir().unwrap();
+  let dst = tempfile::tempdir().unwrap();
+  write_source(src.path());
+
+  let seen = std::cell::RefCell::new(Vec::new());
+  copy_dir_with_filter(src.path(), dst.path(), SymlinkPolicy::Recreate, |path| {
+    seen.borrow_mut().push(path.to_path_buf());
+    true
+  })
+  .unwrap();
+
+  let mut seen = seen.into_inner();
+  seen.sort();
+  assert_eq!(
+    seen,
+    [
+      "assets",
+      "assets/icon.png",
+      "assets/icon.png~",
+      "plugin.json",
+      "target",
+      "target/debug",
+      "target/debug/main.wasm",
+    ]
+    .map(Path::new)
+  );
+}
+
+#[test]
+fn test_atomic_copy_moves_complete_copy_into_place() {
+  let src = tempfile::tempdir().unwrap();
+  let parent = tempfile::tempdir().unwrap();
+  write_source(src.path());
+  let dst = parent.path().join("plugin");
+
+  copy_dir_atomic(src.path(), &dst, SymlinkPolicy::Recreate, |_| true).unwrap();
+
+  assert!(dst.join("target/debug/main.wasm").is_file());
+  let names: Vec<_> = fs::read_dir(parent.path())
+    .unwrap()
+    .map(|entry| entry.unwrap().file_name())
+    .collect();
+  assert_eq!(names, ["plugin"]);
+}
+
+// A socket cannot be opened for reading, so copying one fails part way through.
+#[cfg(unix)]
+#[test]
+fn test_failed_atomic_copy_leaves_nothing_behind() {
+  let src = tempfile::tempdir().unwrap();
+  let parent = tempfile::tempdir().unwrap();
+  write_source(src.path());
+  let _socket = std::os::unix::net::UnixListener::bind(src.path().join("assets/socket")).unwrap();
+
+  assert!(
+    copy_dir_atomic(
+      src.path(),
+      &parent.path().join("plugin"),
+      SymlinkPolicy::Recreate,
+      |_| true
+    )
+    .is_err()
+  );
+
+  assert_eq!(fs::read_dir(parent.path()).unwrap().count(), 0);
+}
+
+// Source tree whose `assets/loop` links back to the root, next to a link to a file.
+#[cfg(unix)]
+fn write_looping_source(root: &Path) {
+  use std::os::unix::fs::symlink;
+
+  write_source(root);
+  symlink("..", root.join("assets/loop")).unwrap();
+  symlink("icon.png", root.join("assets/alias.png")).unwrap();
+}
+
+#[cfg(unix)]
+#[test]
+fn test_symlink_cycle_is_recreated_not_followed() {
+  let src = tempfile::tempdir().unwrap();
+  let dst = tempfile::tempdir().unwrap();
+  write_looping_source(src.path());
+
+  copy_dir_recursive(src.path(), dst.path()).unwrap();
+
+  let link = dst.path().join("assets/loop");
+  assert!(link.symlink_metadata().unwrap().is_symlink());
+  assert_eq!(fs::read_link(&link).unwrap(), Path::new(".."));
+  assert_eq!(
+    fs::read_link(dst.path().join("assets/alias.png")).unwrap(),
+    Path::new("icon.png")
+  );
+}
+
+#[cfg(unix)]
+#[test]
+fn test_skip_policy_leaves_links_out() {
+  let src = tempfile::tempdir().unwrap();
+  let dst = tempfile::tempdir().unwrap();
+  write_looping_source(src.path());
+
+  copy_dir_with_filter(src.path(), dst.path(), SymlinkPolicy::Skip, |_| true).unwrap();
+
+  assert!(dst.path().join("assets/icon.png").is_file());
+  assert!(!dst.path().join("assets/loop").exists());
+  assert!(!dst.path().join("assets/alias.png").exists());
+}
+
+#[cfg(unix)]
+#[test]
+fn test_follow_policy_copies_targets_and_stops_at_a_cycle() {
+  let src = tempfile::tempdir().unwrap();
+  let dst = tempfile::tempdir().unwrap();
+  write_looping_source(src.path());
+
+  let without_loop = |path: &Path| path != Path::new("assets/loop");
+  copy_dir_with_filter(src.path(), dst.path(), SymlinkPolicy::Follow, without_loop).unwrap();
+  let alias = dst.path().join("assets/alias.png");
+  assert!(!alias.symlink_metadata().unwrap().is_symlink());
+  assert_eq!(fs::read_to_string(alias).unwrap(), "icon");
+
+  let looped = tempfile::tempdir().unwrap();
+  let err = copy_dir_with_filter(src.path(), looped.path(), SymlinkPolicy::Follow, |_| true)
+    .expect_err("a cycle must fail the copy");
+  assert!(err.to_string().contains("symlink loop"), "{}", err);
+}
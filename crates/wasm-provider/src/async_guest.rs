@@ -0,0 +1,167 @@
+use crate::PluginInstance;
+use log::warn;
+use shared_types::{FunctionSignature, ProviderError, ValueKind};
+use std::collections::HashMap;
+use std::future::Future;
+use std::pin::pin;
+use std::sync::{Arc, Mutex, RwLock};
+use std::task::{Context, Poll, Wake, Waker};
+use std::thread::{self, Thread};
+
+// Bindings for components in an async store: exports are called through `call_async`,
+// while the host interface stays the synchronous one from the crate root.
+wasmtime::component::bindgen!({
+    world: "bud-plugin",
+    path: "../../wit/bud.wit",
+    exports: { default: async },
+    with: { "bud:sdk/host": crate::bud::sdk::host },
+});
+
+// Wakes the thread parked in `block_on`.
+struct Unpark(Thread);
+
+impl Wake for Unpark {
+  fn wake(self: Arc<Self>) {
+    self.0.unpark();
+  }
+}
+
+/// Drives `future` to completion on the calling thread, for the synchronous entry
+/// points of an async provider.
+///
+/// WASI host calls made meanwhile find a Tokio runtime: the caller's, or else the one
+/// `wasmtime_wasi` keeps for synchronous embedders.
+pub(crate) fn block_on<F: Future>(future: F) -> F::Output {
+  wasmtime_wasi::runtime::with_ambient_tokio_runtime(|| {
+    let waker = Waker::from(Arc::new(Unpark(thread::current())));
+    let mut cx = Context::from_waker(&waker);
+    let mut future = pin!(future);
+    loop {
+      if let Poll::Ready(output) = future.as_mut().poll(&mut cx) {
+        return output;
+      }
+      thread::park();
+    }
+  })
+}
+
+/// Converts the signatures returned by an async component's `describe`.
+pub(crate) fn signatures(
+  signatures: Vec<exports::bud::sdk::plugin::FunctionSignature>,
+) -> Vec<FunctionSignature> {
+  signatures
+    .into_iter()
+    .map(|sig| FunctionSignature {
+      name: sig.name,
+      params: sig.params.into_iter().map(value_kind).collect(),
+      results: sig.results.into_iter().map(value_kind).collect(),
+    })
+    .collect()
+}
+
+fn value_kind(kind: exports::bud::sdk::plugin::ValueKind) -> ValueKind {
+  use exports::bud::sdk::plugin::ValueKind as Wit;
+  match kind {
+    Wit::Boolean => ValueKind::Bool,
+    Wit::Int32 => ValueKind::I32,
+    Wit::Int64 => ValueKind::I64,
+    Wit::Float32 => ValueKind::F32,
+    Wit::Float64 => ValueKind::F64,
+    Wit::Text => ValueKind::String,
+    Wit::Array => ValueKind::Array,
+    Wit::Object => ValueKind::Object,
+  }
+}
+
+/// One lock per plugin, held by the async call that has it checked out.
+#[derive(Default)]
+pub(crate) struct Turns(Mutex<HashMap<String, Arc<tokio::sync::Mutex<()>>>>);
+
+impl Turns {
+  pub(crate) fn get(&self, plugin_name: &str) -> Arc<tokio::sync::Mutex<()>> {
+    let mut turns = self.0.lock().unwrap_or_else(|p| p.into_inner());
+    Arc::clone(turns.entry(plugin_name.to_string()).or_default())
+  }
+
+  pub(crate) fn remove(&self, plugin_name: &str) {
+    self
+      .0
+      .lock()
+      .unwrap_or_else(|p| p.into_inner())
+      .remove(plugin_name);
+  }
+
+  pub(crate) fn clear(&self) {
+    self.0.lock().unwrap_or_else(|p| p.into_inner()).clear();
+  }
+}
+
+/// A plugin taken out of the provider's table for one async call, so no lock is held
+/// across `.await`.
+///
+/// Dropping it puts the plugin back, unless another plugin was loaded under the same
+/// key meanwhile. A call dropped before `finish` leaves the plugin failed, since the
+/// guest was stopped part way through.
+pub(crate) struct Checkout<'a> {
+  plugins: &'a RwLock<HashMap<String, Arc<Mutex<PluginInstance>>>>,
+  name: String,
+  plugin: Option<PluginInstance>,
+  finished: bool,
+}
+
+impl<'a> Checkout<'a> {
+  pub(crate) fn take(
+    plugins: &'a RwLock<HashMap<String, Arc<Mutex<PluginInstance>>>>,
+    plugin_name: &str,
+  ) -> Result<Self, ProviderError> {
+    let mut shared = plugins
+      .write()
+      .unwrap_or_else(|p| p.into_inner())
+      .remove(plugin_name)
+      .ok_or_else(|| ProviderError::LoadFailed(format!("Plugin '{}' not found", plugin_name)))?;
+    // Synchronous callers may have looked the plugin up just before; wait until they
+    // are done with it.
+    let plugin = loop {
+      drop(shared.lock().unwrap_or_else(|p| p.into_inner()));
+      match Arc::try_unwrap(shared) {
+        Ok(plugin) => break plugin.into_inner().unwrap_or_else(|p| p.into_inner()),
+        Err(still_shared) => shared = still_shared,
+      }
+    };
+    Ok(Self {
+      plugins,
+      name: plugin_name.to_string(),
+      plugin: Some(plugin),
+      finished: false,
+    })
+  }
+
+  pub(crate) fn plugin(&mut self) -> &mut PluginInstance {
+    self.plugin.as_mut().expect("plugin already returned")
+  }
+
+  pub(crate) fn finish(mut self) {
+    self.finished = true;
+  }
+}
+
+impl Drop for Checkout<'_> {
+  fn drop(&mut self) {
+    let Some(mut plugin) = self.plugin.take() else {
+      return;
+    };
+    if !self.finished {
+      warn!(
+        "Call into plugin '{}' was cancelled; it is now marked failed",
+        self.name
+      );
+      plugin.failed = true;
+    }
+    self
+      .plugins
+      .write()
+      .unwrap_or_else(|p| p.into_inner())
+      .entry(std::mem::take(&mut self.name))
+      .or_insert_with(|| Arc::new(Mutex::new(plugin)));
+  }
+}
@@ -0,0 +1,6 @@
+//! Worker process for `WasmProviderBuilder::process_isolation`: runs one plugin and
+//! serves the host's requests over stdin/stdout.
+
+fn main() -> std::io::Result<()> {
+  wasm_provider::run_worker(std::io::stdin().lock(), std::io::stdout().lock())
+}
@@ -0,0 +1,91 @@
+use crate::{Code, WasiVersion};
+use log::{debug, warn};
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+use std::path::{Path, PathBuf};
+use wasmtime::component::Component;
+use wasmtime::{Engine, Module};
+
+/// On-disk cache of compiled plugin code, enabled with
+/// `WasmProviderBuilder::compile_cache`.
+///
+/// Entries are keyed by the wasm bytes, the WASI version they are compiled as and the
+/// engine's compatibility hash (which covers the wasmtime version and the engine
+/// settings that affect code generation), so a changed `main.wasm` or a wasmtime
+/// upgrade misses instead of loading stale code.
+#[derive(Debug, Clone)]
+pub(crate) struct CompileCache {
+  dir: PathBuf,
+}
+
+impl CompileCache {
+  pub(crate) fn new(dir: PathBuf) -> Self {
+    Self { dir }
+  }
+
+  pub(crate) fn dir(&self) -> &Path {
+    &self.dir
+  }
+
+  /// Returns the cached code for `bytes`, or compiles it and stores the result.
+  ///
+  /// The flag is `true` when the code came from the cache. Entries that fail to
+  /// deserialize are recompiled and overwritten; failing to write an entry only logs
+  /// a warning, since the compiled code is still usable.
+  pub(crate) fn compile(
+    &self,
+    engine: &Engine,
+    version: WasiVersion,
+    bytes: &[u8],
+  ) -> wasmtime::Result<(Code, bool)> {
+    let entry = self.entry_path(engine, version, bytes);
+    if entry.is_file() {
+      match deserialize(engine, version, &entry) {
+        Ok(code) => {
+          debug!("Loaded compiled code from {}", entry.display());
+          return Ok((code, true));
+        }
+        Err(e) => warn!("Ignoring unusable cache entry {}: {}", entry.display(), e),
+      }
+    }
+
+    let code = Code::compile(engine, version, bytes)?;
+    if let Err(e) = self.store(&entry, &code) {
+      warn!("Failed to write cache entry {}: {}", entry.display(), e);
+    }
+    Ok((code, false))
+  }
+
+  fn entry_path(&self, engine: &Engine, version: WasiVersion, bytes: &[u8]) -> PathBuf {
+    let mut hasher = DefaultHasher::new();
+    engine.precompile_compatibility_hash().hash(&mut hasher);
+    version.hash(&mut hasher);
+    bytes.hash(&mut hasher);
+    self.dir.join(format!("{:016x}.cwasm", hasher.finish()))
+  }
+
+  // Writes through a temporary file so concurrent loads never read a partial entry.
+  fn store(&self, entry: &Path, code: &Code) -> wasmtime::Result<()> {
+    let serialized = match code {
+      Code::Component(component) => component.serialize()?,
+      Code::Module(module) => module.serialize()?,
+    };
+    std::fs::create_dir_all(&self.dir)?;
+    let partial = entry.with_extension(format!("cwasm.{}.tmp", std::process::id()));
+    std::fs::write(&partial, serialized)?;
+    std::fs::rename(&partial, entry)?;
+    Ok(())
+  }
+}
+
+fn deserialize(engine: &Engine, version: WasiVersion, entry: &Path) -> wasmtime::Result<Code> {
+  // SAFETY: entries are only written by `CompileCache::store` from code this engine
+  // compiled, and the key includes the engine's compatibility hash. Callers of
+  // `compile_cache` are told the directory must not be writable by untrusted users.
+  Ok(match version {
+    WasiVersion::Preview1 => Code::Module(unsafe { Module::deserialize_file(engine, entry) }?),
+    WasiVersion::Preview2 => {
+      Code::Component(unsafe { Component::deserialize_file(engine, entry) }?)
+    }
+  })
+}
@@ -0,0 +1,103 @@
+use std::sync::mpsc::{self, RecvTimeoutError, Sender};
+use std::thread::{self, JoinHandle};
+use std::time::Duration;
+#[cfg(feature = "async")]
+use std::time::Instant;
+#[cfg(feature = "async")]
+use wasmtime::{Store, UpdateDeadline};
+use wasmtime::Engine;
+
+/// Epoch delta used for calls without a deadline.
+///
+/// Epoch interruption is enabled engine-wide, so every store needs a deadline; this one
+/// is far enough out never to be reached. Wasmtime adds the delta to the current epoch
+/// without saturating, hence not `u64::MAX`.
+pub(crate) const UNBOUNDED: u64 = u64::MAX / 2;
+
+/// Bumps the engine epoch once `remaining` elapses, interrupting the call in flight.
+///
+/// Dropping the timer cancels it and waits for the thread, so a timer can never fire
+/// into a later call.
+pub(crate) struct DeadlineTimer {
+  cancel: Option<Sender<()>>,
+  thread: Option<JoinHandle<()>>,
+}
+
+impl DeadlineTimer {
+  pub(crate) fn start(engine: &Engine, remaining: Duration) -> Self {
+    let (cancel, cancelled) = mpsc::channel::<()>();
+    let engine = engine.clone();
+    let thread = thread::spawn(move || {
+      if let Err(RecvTimeoutError::Timeout) = cancelled.recv_timeout(remaining) {
+        engine.increment_epoch();
+      }
+    });
+    Self {
+      cancel: Some(cancel),
+      thread: Some(thread),
+    }
+  }
+}
+
+impl Drop for DeadlineTimer {
+  fn drop(&mut self) {
+    drop(self.cancel.take());
+    if let Some(thread) = self.thread.take() {
+      let _ = thread.join();
+    }
+  }
+}
+
+/// How often `EpochTicker` bumps the engine epoch.
+#[cfg(feature = "async")]
+pub(crate) const TICK: Duration = Duration::from_millis(10);
+
+/// Bumps the epoch of an async engine every `TICK` until dropped.
+///
+/// Each bump makes calls armed by `yield_until` yield to the executor, and is when
+/// their deadline is checked.
+#[cfg(feature = "async")]
+pub(crate) struct EpochTicker {
+  stop: Option<Sender<()>>,
+  thread: Option<JoinHandle<()>>,
+}
+
+#[cfg(feature = "async")]
+impl EpochTicker {
+  pub(crate) fn start(engine: &Engine) -> Self {
+    let (stop, stopped) = mpsc::channel::<()>();
+    let engine = engine.clone();
+    let thread = thread::spawn(move || {
+      while let Err(RecvTimeoutError::Timeout) = stopped.recv_timeout(TICK) {
+        engine.increment_epoch();
+      }
+    });
+    Self {
+      stop: Some(stop),
+      thread: Some(thread),
+    }
+  }
+}
+
+#[cfg(feature = "async")]
+impl Drop for EpochTicker {
+  fn drop(&mut self) {
+    drop(self.stop.take());
+    if let Some(thread) = self.thread.take() {
+      let _ = thread.join();
+    }
+  }
+}
+
+/// Arms an async store to yield on every epoch tick, and to trap with
+/// `Trap::Interrupt` on the first tick past `deadline`.
+#[cfg(feature = "async")]
+pub(crate) fn yield_until<T>(store: &mut Store<T>, deadline: Option<Instant>) {
+  store.epoch_deadline_callback(move |_| {
+    Ok(match deadline {
+      Some(deadline) if Instant::now() >= deadline => UpdateDeadline::Interrupt,
+      _ => UpdateDeadline::Yield(1),
+    })
+  });
+  store.set_epoch_deadline(1);
+}
@@ -0,0 +1,51 @@
+use shared_types::FunctionSignature;
+
+/// Differences between the functions two plugins export, from
+/// `WasmProvider::compare_exports`.
+///
+/// Every list is sorted by function name.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ExportDiff {
+  /// Functions only the first plugin exports.
+  pub only_in_a: Vec<FunctionSignature>,
+  /// Functions only the second plugin exports.
+  pub only_in_b: Vec<FunctionSignature>,
+  /// Functions both export with different signatures, as `(a, b)` pairs.
+  pub changed: Vec<(FunctionSignature, FunctionSignature)>,
+}
+
+impl ExportDiff {
+  pub(crate) fn new(mut a: Vec<FunctionSignature>, b: Vec<FunctionSignature>) -> Self {
+    let mut diff = Self::default();
+    for sig_b in b {
+      match a.iter().position(|sig_a| sig_a.name == sig_b.name) {
+        Some(index) => {
+          let sig_a = a.swap_remove(index);
+          if sig_a != sig_b {
+            diff.changed.push((sig_a, sig_b));
+          }
+        }
+        None => diff.only_in_b.push(sig_b),
+      }
+    }
+    diff.only_in_a = a;
+
+    diff.only_in_a.sort_by(|x, y| x.name.cmp(&y.name));
+    diff.only_in_b.sort_by(|x, y| x.name.cmp(&y.name));
+    diff.changed.sort_by(|x, y| x.0.name.cmp(&y.0.name));
+    diff
+  }
+
+  /// Whether both plugins export the same functions with the same signatures.
+  #[must_use]
+  pub fn is_empty(&self) -> bool {
+    self.only_in_a.is_empty() && self.only_in_b.is_empty() && self.changed.is_empty()
+  }
+
+  /// Whether the second plugin can replace the first: it exports every function of
+  /// the first with the same signature, and possibly more.
+  #[must_use]
+  pub fn is_drop_in(&self) -> bool {
+    self.only_in_a.is_empty() && self.changed.is_empty()
+  }
+}
@@ -0,0 +1,175 @@
+use crate::numeric::kind_name;
+use shared_types::ProviderValue;
+use shared_types::provider::HostFunction;
+use std::sync::Arc;
+use utils::provider_json::{PathSegment, format_path};
+use wasmtime::StoreContextMut;
+use wasmtime::component::types::ComponentFunc;
+use wasmtime::component::{Type, Val};
+
+/// Default import name under which injected host functions are registered.
+pub const DEFAULT_HOST_MODULE: &str = "host";
+
+/// Adapts a `HostFunction` to a dynamically typed component function.
+///
+/// Arguments are converted to `ProviderValue`s, and the returned value is converted
+/// back to whatever result type the importing component declared. An `Err` from the
+/// host function traps the calling guest.
+pub(crate) fn adapt<T>(
+  function: Arc<HostFunction>,
+) -> impl Fn(StoreContextMut<'_, T>, ComponentFunc, &[Val], &mut [Val]) -> wasmtime::Result<()>
++ Send
++ Sync
++ 'static {
+  move |_store, ty, params, results| {
+    let args = params.iter().map(to_provider_value).collect();
+    let value = function(args).map_err(|e| wasmtime::Error::msg(e.to_string()))?;
+
+    if let (Some(slot), Some(result_ty)) = (results.first_mut(), ty.results().next()) {
+      *slot =
+        to_val(value, &result_ty, &mut vec![PathSegment::Result]).map_err(wasmtime::Error::msg)?;
+    }
+    Ok(())
+  }
+}
+
+fn to_provider_value(val: &Val) -> ProviderValue {
+  match val {
+    Val::Bool(b) => ProviderValue::Bool(*b),
+    Val::S8(n) => ProviderValue::Int((*n).into()),
+    Val::U8(n) => ProviderValue::Int((*n).into()),
+    Val::S16(n) => ProviderValue::Int((*n).into()),
+    Val::U16(n) => ProviderValue::Int((*n).into()),
+    Val::S32(n) => ProviderValue::Int((*n).into()),
+    Val::U32(n) => ProviderValue::Int((*n).into()),
+    Val::S64(n) => ProviderValue::Int(*n),
+    // Values above i64::MAX have no ProviderValue::Int form.
+    Val::U64(n) => i64::try_from(*n)
+      .map(ProviderValue::Int)
+      .unwrap_or(ProviderValue::UInt(*n)),
+    Val::Float32(f) => ProviderValue::Float((*f).into()),
+    Val::Float64(f) => ProviderValue::Float(*f),
+    Val::Char(c) => ProviderValue::String(c.to_string()),
+    Val::String(s) => ProviderValue::String(s.clone()),
+    Val::List(items) | Val::Tuple(items) => {
+      ProviderValue::Array(items.iter().map(to_provider_value).collect())
+    }
+    Val::Record(fields) => ProviderValue::Object(
+      fields
+        .iter()
+        .map(|(name, value)| (name.clone(), to_provider_value(value)))
+        .collect(),
+    ),
+    Val::Enum(case) => ProviderValue::String(case.clone()),
+    Val::Option(value) => value
+      .as_deref()
+      .map(to_provider_value)
+      .unwrap_or(ProviderValue::Null),
+    _ => ProviderValue::Null,
+  }
+}
+
+// Converts `value` to the component type `ty`. `path` locates `value` within the
+// host function's result and prefixes any error.
+fn to_val(value: ProviderValue, ty: &Type, path: &mut Vec<PathSegment>) -> Result<Val, String> {
+  Ok(match (ty, value) {
+    (Type::Bool, ProviderValue::Bool(b)) => Val::Bool(b),
+    (Type::S8, ProviderValue::Int(n)) => Val::S8(int(n, ty, path)?),
+    (Type::U8, ProviderValue::Int(n)) => Val::U8(int(n, ty, path)?),
+    (Type::S16, ProviderValue::Int(n)) => Val::S16(int(n, ty, path)?),
+    (Type::U16, ProviderValue::Int(n)) => Val::U16(int(n, ty, path)?),
+    (Type::S32, ProviderValue::Int(n)) => Val::S32(int(n, ty, path)?),
+    (Type::U32, ProviderValue::Int(n)) => Val::U32(int(n, ty, path)?),
+    (Type::S64, ProviderValue::Int(n)) => Val::S64(n),
+    (Type::U64, ProviderValue::Int(n)) => Val::U64(int(n, ty, path)?),
+    (Type::S8, ProviderValue::UInt(n)) => Val::S8(int(n, ty, path)?),
+    (Type::U8, ProviderValue::UInt(n)) => Val::U8(int(n, ty, path)?),
+    (Type::S16, ProviderValue::UInt(n)) => Val::S16(int(n, ty, path)?),
+    (Type::U16, ProviderValue::UInt(n)) => Val::U16(int(n, ty, path)?),
+    (Type::S32, ProviderValue::UInt(n)) => Val::S32(int(n, ty, path)?),
+    (Type::U32, ProviderValue::UInt(n)) => Val::U32(int(n, ty, path)?),
+    (Type::S64, ProviderValue::UInt(n)) => Val::S64(int(n, ty, path)?),
+    (Type::U64, ProviderValue::UInt(n)) => Val::U64(n),
+    (Type::Float32, ProviderValue::UInt(n)) => Val::Float32(n as f32),
+    (Type::Float64, ProviderValue::UInt(n)) => Val::Float64(n as f64),
+    (Type::Float32, ProviderValue::Int(n)) => Val::Float32(n as f32),
+    (Type::Float32, ProviderValue::Float(f)) => Val::Float32(f as f32),
+    (Type::Float64, ProviderValue::Int(n)) => Val::Float64(n as f64),
+    (Type::Float64, ProviderValue::Float(f)) => Val::Float64(f),
+    (Type::String, ProviderValue::String(s)) => Val::String(s),
+    (Type::List(list), ProviderValue::Array(items)) => Val::List(
+      items
+        .into_iter()
+        .enumerate()
+        .map(|(i, item)| {
+          path.push(PathSegment::Index(i));
+          let val = to_val(item, &list.ty(), path)?;
+          path.pop();
+          Ok(val)
+        })
+        .collect::<Result<_, String>>()?,
+    ),
+    (Type::Record(record), ProviderValue::Object(mut fields)) => Val::Record(
+      record
+        .fields()
+        .map(|field| {
+          let index = fields.iter().position(|(name, _)| name == field.name);
+          let value = index.map_or(ProviderValue::Null, |i| fields.swap_remove(i).1);
+          path.push(PathSegment::Field(field.name.to_string()));
+          let val = to_val(value, &field.ty, path)?;
+          path.pop();
+          Ok((field.name.to_string(), val))
+        })
+        .collect::<Result<_, String>>()?,
+    ),
+    (Type::Option(_), ProviderValue::Null) => Val::Option(None),
+    (Type::Option(option), value) => {
+      Val::Option(Some(Box::new(to_val(value, &option.ty(), path)?)))
+    }
+    (ty, value) => {
+      return Err(format!(
+        "{}: expected {}, found {}",
+        format_path(path),
+        type_name(ty),
+        kind_name(&value)
+      ));
+    }
+  })
+}
+
+fn int<N: Copy + std::fmt::Display, T: TryFrom<N>>(
+  n: N,
+  ty: &Type,
+  path: &[PathSegment],
+) -> Result<T, String> {
+  T::try_from(n).map_err(|_| {
+    format!(
+      "{}: {} does not fit in {}",
+      format_path(path),
+      n,
+      type_name(ty)
+    )
+  })
+}
+
+fn type_name(ty: &Type) -> &'static str {
+  match ty {
+    Type::Bool => "bool",
+    Type::S8 => "s8",
+    Type::U8 => "u8",
+    Type::S16 => "s16",
+    Type::U16 => "u16",
+    Type::S32 => "s32",
+    Type::U32 => "u32",
+    Type::S64 => "s64",
+    Type::U64 => "u64",
+    Type::Float32 => "f32",
+    Type::Float64 => "f64",
+    Type::Char => "char",
+    Type::String => "string",
+    Type::List(_) => "list",
+    Type::Record(_) => "record",
+    Type::Option(_) => "option",
+    _ => "an unsupported type",
+  }
+}
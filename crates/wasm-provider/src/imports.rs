@@ -0,0 +1,65 @@
+use std::collections::{HashMap, HashSet};
+use wasmtime::Engine;
+use wasmtime::component::Component;
+use wasmtime::component::types::ComponentItem;
+
+/// Interface implemented by `PluginState`, as declared in `wit/bud.wit`.
+pub(crate) const HOST_INTERFACE: &str = "bud:sdk/host@0.1.0";
+
+/// Functions of `HOST_INTERFACE`; keep in sync with `interface host` in `wit/bud.wit`.
+pub(crate) const HOST_FUNCTIONS: &[&str] =
+  &["emit", "log", "get-config", "read-shared", "shared-size"];
+
+/// Host functions registered in the linker, grouped by import interface.
+pub type HostImports = HashMap<String, HashSet<String>>;
+
+/// Returns the host imports registered by the provider out of the box.
+pub(crate) fn default_host_imports() -> HostImports {
+  HashMap::from([(
+    HOST_INTERFACE.to_string(),
+    HOST_FUNCTIONS.iter().map(|f| f.to_string()).collect(),
+  )])
+}
+
+// WASI interfaces are supplied wholesale by wasmtime-wasi and checked by the linker itself.
+fn is_wasi_import(name: &str) -> bool {
+  name.starts_with("wasi:")
+}
+
+/// Lists the component's non-WASI function imports that the host does not provide.
+///
+/// Entries are formatted as `interface#function`, or just `function` for root-level imports.
+pub(crate) fn missing_host_imports(
+  engine: &Engine,
+  component: &Component,
+  provided: &HostImports,
+) -> Vec<String> {
+  let mut missing = Vec::new();
+
+  for (name, item) in component.component_type().imports(engine) {
+    if is_wasi_import(name) {
+      continue;
+    }
+
+    match item {
+      ComponentItem::ComponentInstance(instance) => {
+        let functions = provided.get(name);
+        for (export, export_item) in instance.exports(engine) {
+          let is_provided = functions.is_some_and(|f| f.contains(export));
+          if matches!(export_item, ComponentItem::ComponentFunc(_)) && !is_provided {
+            missing.push(format!("{}#{}", name, export));
+          }
+        }
+      }
+      ComponentItem::ComponentFunc(_) => {
+        let is_provided = provided.get("").is_some_and(|f| f.contains(name));
+        if !is_provided {
+          missing.push(name.to_string());
+        }
+      }
+      _ => {}
+    }
+  }
+
+  missing
+}
@@ -1,11 +1,38 @@
-use log::{error, info};
-use shared_types::{PluginConfigData, Provider, ProviderError, ProviderValue};
+#[cfg(feature = "async")]
+mod async_guest;
+mod compile_cache;
+mod deadline;
+mod export_diff;
+mod host_funcs;
+mod imports;
+mod limits;
+mod numeric;
+mod permissions;
+mod preview1;
+mod process;
+mod shared_data;
+mod stdio;
+mod store_count;
+mod store_limiter;
+mod trap;
+mod wasi_usage;
+
+use log::{error, info, warn};
+use shared_types::provider::HostFunction;
+use shared_types::{
+  FunctionSignature, PluginConfigData, Provider, ProviderError, ProviderValue, ValueKind,
+};
 use std::collections::HashMap;
-use std::path::Path;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::mpsc::{Receiver, channel};
 use std::sync::{Arc, Mutex, RwLock};
-use utils::provider_json::{args_to_json, json_to_provider_value};
+use std::thread;
+use std::time::{Duration, Instant};
+use utils::provider_json::{json_to_provider_value, try_args_to_json};
 use wasmtime::component::{Component, HasSelf, Linker, ResourceTable};
-use wasmtime::{Config, Engine, Store};
+use wasmtime::{Config, Engine, Module, Store, Val};
+use wasmtime_wasi::p1::WasiP1Ctx;
 use wasmtime_wasi::{WasiCtx, WasiCtxBuilder, WasiCtxView, WasiView};
 
 wasmtime::component::bindgen!({
@@ -15,9 +42,27 @@ wasmtime::component::bindgen!({
 
 use bud::sdk::host::{Host, LogLevel};
 
+pub use export_diff::ExportDiff;
+pub use host_funcs::DEFAULT_HOST_MODULE;
+pub use imports::HostImports;
+pub use limits::CapacityPolicy;
+pub use preview1::{ALLOC_EXPORT, DEALLOC_EXPORT, WasiVersion};
+pub use process::run_worker;
+pub use stdio::{CapturedOutput, CapturedStream, STDIO_ENV_VAR, StdioConfig, StdioMode};
+pub use wasi_usage::WasiUsage;
+
 pub struct PluginState {
   wasi: WasiCtx,
   table: ResourceTable,
+  // WASI context of a Preview 1 module; `wasi` is left empty for those.
+  preview1: Option<WasiP1Ctx>,
+  output: CapturedOutput,
+  // The plugin's own config, served to it through `get-config`.
+  config: serde_json::Value,
+  limiter: store_limiter::StoreLimiter,
+  shared: shared_data::SharedData,
+  // Keeps the store counted by `WasmProvider::active_store_count` until it is dropped.
+  _counted: store_count::StoreGuard,
 }
 
 impl WasiView for PluginState {
@@ -45,96 +90,540 @@ impl Host for PluginState {
     println!("data: {}", data);
   }
 
-  fn get_config(&mut self, _key: String) -> Option<String> {
-    None
+  fn get_config(&mut self, key: String) -> Option<String> {
+    let value = if key.is_empty() {
+      &self.config
+    } else {
+      self.config.get(&key)?
+    };
+    Some(value.to_string())
+  }
+
+  fn read_shared(&mut self, name: String, offset: u64, len: u32) -> Option<Vec<u8>> {
+    self.shared.read(&name, offset, len)
+  }
+
+  fn shared_size(&mut self, name: String) -> Option<u64> {
+    self.shared.size(&name)
+  }
+}
+
+// Maps the WIT `value-kind` enum onto the provider-neutral kind.
+fn value_kind(kind: exports::bud::sdk::plugin::ValueKind) -> ValueKind {
+  use exports::bud::sdk::plugin::ValueKind as Wit;
+  match kind {
+    Wit::Boolean => ValueKind::Bool,
+    Wit::Int32 => ValueKind::I32,
+    Wit::Int64 => ValueKind::I64,
+    Wit::Float32 => ValueKind::F32,
+    Wit::Float64 => ValueKind::F64,
+    Wit::Text => ValueKind::String,
+    Wit::Array => ValueKind::Array,
+    Wit::Object => ValueKind::Object,
   }
 }
 
-// Holds the wasmtime Store and the generated bindings for one loaded plugin component.
+/// Wall-clock time spent in each phase of `WasmProvider::load_timed`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LoadTiming {
+  /// Compiling `main.wasm` into a component.
+  pub compile: Duration,
+  /// Instantiating the compiled component against the linker.
+  pub instantiate: Duration,
+  /// Whether the compiled code came from the compile cache, in which case `compile`
+  /// covers reading and deserializing it.
+  pub cached: bool,
+}
+
+// Holds the wasmtime Store and the instantiated guest for one loaded plugin.
 pub struct PluginInstance {
   pub store: Store<PluginState>,
-  pub bindings: BudPlugin,
+  guest: Guest,
   pub module_name: String,
+  // Compiled code, kept so `invoke_isolated` can instantiate it again.
+  code: Code,
+  // Where the plugin was loaded from, so `reload` can rebuild it.
+  source: PluginSource,
+  config: PluginConfigData,
+  // Set after a trap; the component instance must not be entered again.
+  failed: bool,
+  // Fuel each call starts with, when fuel metering is enabled.
+  fuel_limit: Option<u64>,
+  // Limit for calls without a deadline or declared timeout.
+  default_timeout: Option<Duration>,
+  // Unique per instantiation, so `FuncHandle`s resolved before a reload are detected.
+  generation: u64,
+}
+
+// Source of `PluginInstance::generation`.
+static NEXT_GENERATION: AtomicU64 = AtomicU64::new(0);
+
+/// A plugin function resolved once by `WasmProvider::resolve_function`.
+///
+/// `WasmProvider::invoke_handle` calls it without looking the export up by name again.
+/// The handle belongs to the instance it was resolved against: once the plugin is
+/// reloaded, calls through it fail and it must be resolved again.
+#[derive(Debug, Clone)]
+pub struct FuncHandle {
+  plugin_name: String,
+  function: String,
+  generation: u64,
+  // The export itself for Preview 1 modules; components dispatch by name through
+  // `on-invoke`, so there is nothing to resolve.
+  func: Option<wasmtime::Func>,
+}
+
+impl FuncHandle {
+  /// Name of the plugin the function belongs to.
+  pub fn plugin_name(&self) -> &str {
+    &self.plugin_name
+  }
+
+  /// Name of the resolved function.
+  pub fn function(&self) -> &str {
+    &self.function
+  }
+}
+
+impl PluginInstance {
+  /// Returns `true` if a trap left this plugin unusable until it is reloaded.
+  pub fn is_failed(&self) -> bool {
+    self.failed
+  }
+
+  /// Copies `s` into the memory of a Preview 1 module through its `ALLOC_EXPORT`.
+  ///
+  /// Returns the pointer and length, as passed for string arguments.
+  ///
+  /// # Errors
+  ///
+  /// Returns `ProviderError::InvocationFailed` for components, for modules without
+  /// `memory` or `ALLOC_EXPORT`, and if `alloc` returns an out-of-bounds pointer.
+  pub fn write_string(&mut self, s: &str) -> Result<(i32, i32), ProviderError> {
+    match &self.guest {
+      Guest::Module(instance) => preview1::write_string(&mut self.store, instance, s),
+      Guest::Component(_) => Err(strings_unsupported()),
+      #[cfg(feature = "async")]
+      Guest::AsyncComponent(_) => Err(strings_unsupported()),
+    }
+  }
+
+  /// Reads the UTF-8 string of `len` bytes at `ptr` from a Preview 1 module's memory.
+  ///
+  /// # Errors
+  ///
+  /// Returns `ProviderError::InvocationFailed` for components, for modules without
+  /// `memory`, and if the range is out of bounds or not valid UTF-8.
+  pub fn read_string(&mut self, ptr: i32, len: i32) -> Result<String, ProviderError> {
+    match &self.guest {
+      Guest::Module(instance) => preview1::read_string(&mut self.store, instance, ptr, len),
+      Guest::Component(_) => Err(strings_unsupported()),
+      #[cfg(feature = "async")]
+      Guest::AsyncComponent(_) => Err(strings_unsupported()),
+    }
+  }
+}
+
+// Origin of a loaded plugin: a plugin directory on disk, or code handed over in memory.
+#[derive(Clone)]
+enum PluginSource {
+  Path(PathBuf),
+  Memory(Code),
 }
 
+// Compiled plugin code: a component, or a WASI Preview 1 core module.
+#[derive(Clone)]
+enum Code {
+  Component(Component),
+  Module(Module),
+}
+
+impl Code {
+  // Picks the kind `version` plugins are compiled as; accepts binaries and WAT.
+  fn compile(engine: &Engine, version: WasiVersion, bytes: &[u8]) -> wasmtime::Result<Self> {
+    Ok(match version {
+      WasiVersion::Preview1 => Code::Module(Module::new(engine, bytes)?),
+      WasiVersion::Preview2 => Code::Component(Component::new(engine, bytes)?),
+    })
+  }
+
+  fn engine(&self) -> &Engine {
+    match self {
+      Code::Component(component) => component.engine(),
+      Code::Module(module) => module.engine(),
+    }
+  }
+}
+
+// An instantiated plugin.
+enum Guest {
+  Component(BudPlugin),
+  Module(wasmtime::Instance),
+  // A component in an async store, called through `call_async`.
+  #[cfg(feature = "async")]
+  AsyncComponent(async_guest::BudPlugin),
+}
+
+/// Core module export the provider calls once after instantiation, if present.
+///
+/// Plugins put expensive lazy initialization here so the first `invoke` is not
+/// slowed down by it. A trap fails the load.
+pub const WARMUP_EXPORT: &str = "__bud_warmup";
+
+/// Component equivalent of `WARMUP_EXPORT`, a root export `func()`; component export
+/// names must be kebab-case.
+pub const COMPONENT_WARMUP_EXPORT: &str = "bud-warmup";
+
+/// Message returned by calls into a plugin that previously trapped.
+pub const FAILED_STATE_MESSAGE: &str = "plugin in failed state; reload required";
+
+/// Descriptor `WasmProvider::invoke_with_file` passes for the file: standard input.
+pub const FILE_FD: i32 = 0;
+
+/// Default for `WasmProviderBuilder::default_timeout`.
+pub const DEFAULT_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Default for `WasmProviderBuilder::max_memory`: 64 MiB.
+pub const DEFAULT_MAX_MEMORY: usize = 64 * 1024 * 1024;
+
 // Shared engine and linker; cloned cheaply via Arc for each plugin load.
 #[derive(Clone)]
 pub struct WasmInstance {
   pub engine: Arc<Engine>,
   pub linker: Arc<Linker<PluginState>>,
+  /// Effective stdio modes, resolved at init from the builder option or `BUD_PLUGIN_STDIO`.
+  pub stdio: StdioConfig,
+  /// Fuel each plugin call may consume, from the `fuelLimit` setting; `None` disables
+  /// fuel metering.
+  pub fuel_limit: Option<u64>,
+  /// Time limit for calls without a deadline or declared `timeoutMs`; `None` lets
+  /// them run unbounded.
+  pub default_timeout: Option<Duration>,
+  /// Maximum size in bytes of each plugin linear memory; `None` for no limit.
+  pub max_memory: Option<usize>,
+  /// Non-WASI host functions registered in `linker`, used to vet plugin imports on load.
+  pub host_imports: Arc<HostImports>,
+  // Everything passed to `inject` so far, replayed whenever the linker is rebuilt.
+  injected: Arc<HashMap<String, Arc<HostFunction>>>,
+  // Import name the injected functions are registered under.
+  host_module: String,
+  // Core module linker, present when the provider was built for WASI Preview 1.
+  preview1_linker: Option<Arc<wasmtime::Linker<PluginState>>>,
+  // Datasets registered with `WasmProvider::share_data`.
+  shared: shared_data::SharedData,
+  stores: store_count::StoreCounter,
+  // Drives yields and deadlines of an async engine; `None` for a synchronous one.
+  #[cfg(feature = "async")]
+  _ticker: Option<Arc<deadline::EpochTicker>>,
 }
 
-pub struct WasmProvider {
-  instance: Arc<RwLock<Option<WasmInstance>>>,
-  pub plugins: Arc<Mutex<HashMap<String, PluginInstance>>>,
+/// Builder for `WasmProvider`.
+///
+/// Options set here take precedence over environment defaults.
+///
+/// # Examples
+///
+/// ```
+/// use wasm_provider::{StdioMode, WasmProvider};
+///
+/// let provider = WasmProvider::builder().stdio(StdioMode::Capture).build();
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct WasmProviderBuilder {
+  stdio: Option<StdioConfig>,
+  host_module: Option<String>,
+  wasi_version: WasiVersion,
+  worker: Option<PathBuf>,
+  default_timeout: Option<Option<Duration>>,
+  max_memory: Option<Option<usize>>,
+  compile_cache: Option<PathBuf>,
+  store_warning_threshold: Option<usize>,
+  #[cfg(feature = "async")]
+  async_support: bool,
 }
 
-impl WasmProvider {
+impl WasmProviderBuilder {
+  /// Sets the stdio mode for every plugin, overriding `BUD_PLUGIN_STDIO`.
   #[must_use]
-  pub fn new() -> Self {
-    Self {
+  pub fn stdio(mut self, mode: StdioMode) -> Self {
+    self.stdio = Some(mode.into());
+    self
+  }
+
+  /// Sets the mode of each stdio stream separately, overriding `BUD_PLUGIN_STDIO`.
+  ///
+  /// For example, stderr can be inherited for crash diagnostics while stdout is
+  /// captured for data.
+  #[must_use]
+  pub fn stdio_streams(mut self, config: StdioConfig) -> Self {
+    self.stdio = Some(config);
+    self
+  }
+
+  /// Sets the import name injected host functions are grouped under.
+  ///
+  /// Defaults to `DEFAULT_HOST_MODULE` (`"host"`). Plugins import the functions from an
+  /// instance of this name, e.g. `"env"` for toolchains that expect one.
+  #[must_use]
+  pub fn host_module(mut self, name: impl Into<String>) -> Self {
+    self.host_module = Some(name.into());
+    self
+  }
+
+  /// Selects the WASI version `load` and `load_bytes` expect plugins to target.
+  ///
+  /// Defaults to `WasiVersion::Preview2` components. `load_component` always takes
+  /// a component, whichever version is selected.
+  #[must_use]
+  pub fn wasi_version(mut self, version: WasiVersion) -> Self {
+    self.wasi_version = version;
+    self
+  }
+
+  /// Sets the wall-clock limit for calls that have neither a deadline nor a declared
+  /// `timeoutMs`, or `None` to let them run unbounded.
+  ///
+  /// Defaults to `DEFAULT_TIMEOUT`. Calls past the limit are interrupted through epoch
+  /// interruption and fail with `ProviderError::Timeout`, leaving the plugin failed
+  /// until `reload`. Time spent in host calls (e.g. sleeping) is only noticed once the
+  /// guest runs again.
+  #[must_use]
+  pub fn default_timeout(mut self, timeout: Option<Duration>) -> Self {
+    self.default_timeout = Some(timeout);
+    self
+  }
+
+  /// Caps each linear memory of every plugin at `max_bytes`, or `None` for no limit
+  /// beyond the module's declared maximum.
+  ///
+  /// Defaults to `DEFAULT_MAX_MEMORY`. A `memory.grow` past the limit traps, and the
+  /// call fails with `ProviderError::InvocationFailed` naming the limit; the plugin is
+  /// left failed until `reload`. A plugin whose initial memory exceeds the limit fails
+  /// to load.
+  #[must_use]
+  pub fn max_memory(mut self, max_bytes: Option<usize>) -> Self {
+    self.max_memory = Some(max_bytes);
+    self
+  }
+
+  /// Caches compiled plugin code in `dir`, or `None` to compile on every load.
+  ///
+  /// Off by default; embedders typically pass a directory under the project's data
+  /// directory. `load` and `load_timed` look up `main.wasm` by a hash of its bytes, the
+  /// WASI version and the engine's compatibility hash, which covers the wasmtime
+  /// version and settings. A hit deserializes the stored code instead of compiling, and
+  /// `LoadTiming::cached` reports it; a miss compiles and stores the result. Editing
+  /// `main.wasm` or upgrading wasmtime therefore misses, and stale entries are left for
+  /// the embedder to clear. The in-memory loaders never use the cache.
+  ///
+  /// Entries are native code that is loaded without validation, so `dir` must not be
+  /// writable by anyone less trusted than the host.
+  #[must_use]
+  pub fn compile_cache(mut self, dir: Option<PathBuf>) -> Self {
+    self.compile_cache = dir;
+    self
+  }
+
+  /// Logs a warning whenever the number of live stores rises above `threshold`.
+  ///
+  /// Every loaded plugin owns a store, and `invoke_isolated` and `compare_exports`
+  /// create short-lived ones; a count that keeps growing points at stores that are
+  /// never dropped. See `WasmProvider::active_store_count`. Off by default.
+  #[must_use]
+  pub fn store_warning_threshold(mut self, threshold: usize) -> Self {
+    self.store_warning_threshold = Some(threshold);
+    self
+  }
+
+  /// Runs each plugin loaded from a directory in its own worker process.
+  ///
+  /// `worker` is the executable to spawn, normally the `bud-wasm-worker` binary built
+  /// from this crate (or any program calling `run_worker`). The worker compiles and
+  /// runs the plugin with this provider's WASI version, host module, fuel limit and
+  /// stderr mode; calls and results travel over a pipe as JSON, so a crash in the
+  /// runtime takes down only the worker. The plugin is then reported failed, and
+  /// `reload` starts a new worker.
+  ///
+  /// `load`, `invoke`, `invoke_readonly`, `invoke_with_deadline`, `take_stdout`,
+  /// `reload` and unloading reach worker plugins. Plugin stdout is relayed after each
+  /// call according to the stdout mode. Injected host functions cannot cross the
+  /// process boundary, and the in-memory loaders and the other invocation methods keep
+  /// running in-process.
+  #[must_use]
+  pub fn process_isolation(mut self, worker: impl Into<PathBuf>) -> Self {
+    self.worker = Some(worker.into());
+    self
+  }
+
+  /// Runs plugins through wasmtime's async API, so `Provider::invoke_async` suspends
+  /// instead of blocking the thread polling it. Off by default.
+  ///
+  /// A call yields back to the executor every few milliseconds of guest execution,
+  /// which is also when its timeout is checked. Calls into one plugin still run one at
+  /// a time; while one is in flight, the plugin is absent from `with_plugins`. The
+  /// synchronous entry points keep working by blocking on the same calls, so avoid
+  /// them inside a current-thread Tokio runtime. Only components are supported: `init`
+  /// fails for `WasiVersion::Preview1`, and `invoke_numeric` is unavailable.
+  #[cfg(feature = "async")]
+  #[must_use]
+  pub fn async_support(mut self, enabled: bool) -> Self {
+    self.async_support = enabled;
+    self
+  }
+
+  /// Builds the provider. The runtime itself is created later by `init`.
+  #[must_use]
+  pub fn build(self) -> WasmProvider {
+    WasmProvider {
       instance: Arc::new(RwLock::new(None)),
-      plugins: Arc::new(Mutex::new(HashMap::new())),
+      plugins: Arc::new(RwLock::new(HashMap::new())),
+      stdio: self.stdio,
+      host_module: self
+        .host_module
+        .unwrap_or_else(|| DEFAULT_HOST_MODULE.to_string()),
+      wasi_version: self.wasi_version,
+      invocations: Arc::default(),
+      fuel_limit: RwLock::new(None),
+      worker: self.worker,
+      workers: Mutex::default(),
+      shared: shared_data::SharedData::default(),
+      stores: store_count::StoreCounter::new(self.store_warning_threshold),
+      default_timeout: self.default_timeout.unwrap_or(Some(DEFAULT_TIMEOUT)),
+      max_memory: self.max_memory.unwrap_or(Some(DEFAULT_MAX_MEMORY)),
+      compile_cache: self.compile_cache.map(compile_cache::CompileCache::new),
+      #[cfg(feature = "async")]
+      async_support: self.async_support,
+      #[cfg(feature = "async")]
+      turns: async_guest::Turns::default(),
     }
   }
 }
 
-impl Provider for WasmProvider {
-  type Instance = WasmInstance;
-  type PluginInstance = PluginInstance;
-  const MAIN_FILE: &'static str = "main.wasm";
+pub struct WasmProvider {
+  instance: Arc<RwLock<Option<WasmInstance>>>,
+  /// In-process plugins by key, each behind its own lock.
+  ///
+  /// The table itself is only locked to look a plugin up, so calls into different
+  /// plugins run in parallel. Calls into one plugin still take turns: its `Store` is not
+  /// `Sync`, and a component instance cannot be entered again mid-call.
+  pub plugins: Arc<RwLock<HashMap<String, Arc<Mutex<PluginInstance>>>>>,
+  stdio: Option<StdioConfig>,
+  host_module: String,
+  wasi_version: WasiVersion,
+  invocations: Arc<limits::InvocationLimiter>,
+  // Set by `configure`, read by `init`.
+  fuel_limit: RwLock<Option<u64>>,
+  // Worker executable and per-plugin workers under process isolation.
+  worker: Option<PathBuf>,
+  workers: Mutex<HashMap<String, Arc<Mutex<process::Worker>>>>,
+  shared: shared_data::SharedData,
+  stores: store_count::StoreCounter,
+  default_timeout: Option<Duration>,
+  max_memory: Option<usize>,
+  compile_cache: Option<compile_cache::CompileCache>,
+  #[cfg(feature = "async")]
+  async_support: bool,
+  // Serializes async calls into each plugin while it is checked out of `plugins`.
+  #[cfg(feature = "async")]
+  turns: async_guest::Turns,
+}
 
-  fn with_plugins<F, R>(&self, f: F) -> Result<R, ProviderError>
-  where
-    F: FnOnce(&HashMap<String, PluginInstance>) -> R,
-  {
-    let plugins = self.plugins.lock().unwrap_or_else(|p| p.into_inner());
-    Ok(f(&plugins))
+impl Default for WasmProvider {
+  fn default() -> Self {
+    Self::new()
   }
+}
 
-  fn init(&self) -> Result<Self::Instance, ProviderError> {
-    info!("Initializing WasmProvider (Component Model)");
-
-    let mut config = Config::default();
-    config.wasm_component_model(true);
-
-    let engine = Engine::new(&config).map_err(|e| {
-      error!("Failed to create Engine: {}", e);
-      ProviderError::InitFailed
-    })?;
+impl WasmProvider {
+  #[must_use]
+  pub fn new() -> Self {
+    Self::builder().build()
+  }
 
-    let mut linker: Linker<PluginState> = Linker::new(&engine);
+  /// Creates a builder for configuring the provider before use.
+  #[must_use]
+  pub fn builder() -> WasmProviderBuilder {
+    WasmProviderBuilder::default()
+  }
 
-    // Register WASI preview2 host functions (stdio, clocks, etc.)
-    wasmtime_wasi::p2::add_to_linker_sync(&mut linker).map_err(|e| {
-      error!("Failed to add WASI to linker: {}", e);
-      ProviderError::InitFailed
-    })?;
+  /// Returns the number of wasmtime stores of this provider that are alive.
+  ///
+  /// Each in-process plugin holds one, and every `invoke_isolated` call or component
+  /// compared by `compare_exports` holds one until it returns. Plugins running in
+  /// worker processes are not counted.
+  #[must_use]
+  pub fn active_store_count(&self) -> usize {
+    self.stores.active()
+  }
 
-    // Register bud host functions defined in wit/bud.wit `interface host`
-    BudPlugin::add_to_linker::<PluginState, HasSelf<PluginState>>(&mut linker, |state| state)
-      .map_err(|e| {
-        error!("Failed to add host bindings to linker: {}", e);
-        ProviderError::InitFailed
-      })?;
+  /// Returns stdout written by a plugin since the previous call.
+  ///
+  /// Only plugins whose stdout is set to `StdioMode::Capture` buffer it; for any
+  /// other mode this returns an empty string.
+  ///
+  /// # Errors
+  ///
+  /// Returns `ProviderError::LoadFailed` if the plugin is not loaded.
+  pub fn take_stdout(&self, plugin_name: &str) -> Result<String, ProviderError> {
+    if let Some(worker) = self.worker_for(plugin_name) {
+      let mut worker = worker.lock().unwrap_or_else(|p| p.into_inner());
+      return Ok(std::mem::take(&mut worker.stdout));
+    }
+    self.take_captured(plugin_name, |output| output.stdout.as_mut())
+  }
 
-    let new_instance = WasmInstance {
-      engine: Arc::new(engine),
-      linker: Arc::new(linker),
-    };
+  /// Returns stderr written by a plugin since the previous call.
+  ///
+  /// Like `take_stdout`, but for plugins whose stderr is set to `StdioMode::Capture`.
+  /// Worker processes do not relay stderr, so under process isolation this is always
+  /// empty.
+  ///
+  /// # Errors
+  ///
+  /// Returns `ProviderError::LoadFailed` if the plugin is not loaded.
+  pub fn take_stderr(&self, plugin_name: &str) -> Result<String, ProviderError> {
+    if self.worker_for(plugin_name).is_some() {
+      return Ok(String::new());
+    }
+    self.take_captured(plugin_name, |output| output.stderr.as_mut())
+  }
 
-    *self.instance.write().unwrap_or_else(|p| p.into_inner()) = Some(new_instance.clone());
+  // Drains the stream `select` picks from an in-process plugin's captured output.
+  fn take_captured(
+    &self,
+    plugin_name: &str,
+    select: impl FnOnce(&mut CapturedOutput) -> Option<&mut CapturedStream>,
+  ) -> Result<String, ProviderError> {
+    let plugin = self.plugin(plugin_name)?;
+    let mut plugin = plugin.lock().unwrap_or_else(|p| p.into_inner());
 
-    info!("WasmProvider initialized successfully");
-    Ok(new_instance)
+    Ok(
+      select(&mut plugin.store.data_mut().output)
+        .map(CapturedStream::take)
+        .unwrap_or_default(),
+    )
   }
 
-  fn load<P: AsRef<Path>>(&self, path: P, config: &PluginConfigData) -> Result<(), ProviderError> {
+  /// Loads a plugin like `Provider::load`, reporting where the time went.
+  ///
+  /// `compile` covers turning the entry file (`main.wasm` unless the config names
+  /// another) into a `Component`; `instantiate` covers linking and instantiating it
+  /// (excluding the plugin's `on-load` hook). A large compile share suggests enabling `WasmProviderBuilder::compile_cache`.
+  ///
+  /// # Errors
+  ///
+  /// Same as `Provider::load`.
+  pub fn load_timed<P: AsRef<Path>>(
+    &self,
+    path: P,
+    config: &PluginConfigData,
+  ) -> Result<LoadTiming, ProviderError> {
     let plugin_dir = path.as_ref();
-    let wasm_file = plugin_dir.join(Self::MAIN_FILE);
+    let main_file = config.entry_file(Self::MAIN_FILE);
+    let wasm_file = plugin_dir.join(main_file);
 
     if !wasm_file.is_file() {
-      let msg = format!("{} not found: {}", Self::MAIN_FILE, wasm_file.display());
+      let msg = format!("{} not found: {}", main_file, wasm_file.display());
       error!("{}", msg);
       return Err(ProviderError::LoadFailed(msg));
     }
@@ -152,91 +641,336 @@ impl Provider for WasmProvider {
       ProviderError::LoadFailed("Provider not initialized. Call init() first.".to_string())
     })?;
 
+    if let Some(program) = &self.worker {
+      return self.load_in_worker(program, instance, &plugin_name, plugin_dir, config);
+    }
+
     info!(
-      "Compiling component '{}' from {}",
+      "Compiling {:?} plugin '{}' from {}",
+      self.wasi_version,
       plugin_name,
       wasm_file.display()
     );
 
-    let component = Component::from_file(&instance.engine, &wasm_file).map_err(|e| {
-      error!("Failed to compile component '{}': {}", plugin_name, e);
-      ProviderError::LoadFailed(format!(
-        "Failed to compile component '{}': {}",
-        plugin_name, e
-      ))
-    })?;
+    let compile_start = Instant::now();
+    let (code, cached) = std::fs::read(&wasm_file)
+      .map_err(wasmtime::Error::from)
+      .and_then(|bytes| match &self.compile_cache {
+        Some(cache) => cache.compile(&instance.engine, self.wasi_version, &bytes),
+        None => {
+          Code::compile(&instance.engine, self.wasi_version, &bytes).map(|code| (code, false))
+        }
+      })
+      .map_err(|e| {
+        error!("Failed to compile plugin '{}': {}", plugin_name, e);
+        ProviderError::LoadFailed(format!("Failed to compile plugin '{}': {}", plugin_name, e))
+      })?;
+    let compile = compile_start.elapsed();
 
-    let wasi = WasiCtxBuilder::new().inherit_stdio().build();
-    let mut store = Store::new(
-      &instance.engine,
-      PluginState {
-        wasi,
-        table: ResourceTable::new(),
-      },
+    let instantiate = self.instantiate_plugin(
+      instance,
+      &plugin_name,
+      code,
+      PluginSource::Path(plugin_dir.to_path_buf()),
+      config,
+    )?;
+
+    info!(
+      "Plugin '{}' loaded successfully ({} {:?}, instantiate {:?})",
+      plugin_name,
+      if cached { "cached" } else { "compile" },
+      compile,
+      instantiate
     );
+    Ok(LoadTiming {
+      compile,
+      instantiate,
+      cached,
+    })
+  }
+
+  /// Loads an already-compiled component under `name`, without reading from disk.
+  ///
+  /// Useful for tests and embedders that build components in memory, e.g. from WAT.
+  /// The component must have been compiled with this provider's engine (the one
+  /// returned by `init`).
+  ///
+  /// # Errors
+  ///
+  /// Returns `ProviderError::LoadFailed` if the provider is not initialized, the
+  /// component belongs to another engine, or instantiation fails.
+  pub fn load_component(
+    &self,
+    name: &str,
+    component: Component,
+    config: &PluginConfigData,
+  ) -> Result<(), ProviderError> {
+    self.load_code(name, Code::Component(component), config)
+  }
 
-    // Instantiate the component and wire up host↔plugin bindings
-    let bindings =
-      BudPlugin::instantiate(&mut store, &component, &instance.linker).map_err(|e| {
-        ProviderError::LoadFailed(format!("Failed to instantiate '{}': {}", plugin_name, e))
+  /// Compiles `bytes` (a binary or its WAT text) and loads it under `name`.
+  ///
+  /// `bytes` must be a component, or a core module for a provider built for
+  /// `WasiVersion::Preview1`.
+  ///
+  /// # Errors
+  ///
+  /// Returns `ProviderError::LoadFailed` if the provider is not initialized, the bytes
+  /// do not compile, or instantiation fails.
+  pub fn load_bytes(
+    &self,
+    name: &str,
+    bytes: impl AsRef<[u8]>,
+    config: &PluginConfigData,
+  ) -> Result<(), ProviderError> {
+    let engine = {
+      let instance_guard = self.instance.read().unwrap_or_else(|p| p.into_inner());
+      let instance = instance_guard.as_ref().ok_or_else(|| {
+        ProviderError::LoadFailed("Provider not initialized. Call init() first.".to_string())
       })?;
+      Arc::clone(&instance.engine)
+    };
 
-    bindings
-      .bud_sdk_plugin()
-      .call_on_load(&mut store)
-      .map_err(|e| ProviderError::LoadFailed(format!("on-load trap: {}", e)))?
-      .map_err(|e| ProviderError::LoadFailed(format!("on-load error: {}", e)))?;
+    let code = Code::compile(&engine, self.wasi_version, bytes.as_ref()).map_err(|e| {
+      ProviderError::LoadFailed(format!("Failed to compile plugin '{}': {}", name, e))
+    })?;
+    self.load_code(name, code, config)
+  }
+
+  // Loads compiled code under `name`; shared by the in-memory loaders and `reload`.
+  fn load_code(
+    &self,
+    name: &str,
+    code: Code,
+    config: &PluginConfigData,
+  ) -> Result<(), ProviderError> {
+    let instance_guard = self.instance.read().unwrap_or_else(|p| p.into_inner());
+    let instance = instance_guard.as_ref().ok_or_else(|| {
+      ProviderError::LoadFailed("Provider not initialized. Call init() first.".to_string())
+    })?;
+
+    if !Engine::same(code.engine(), &instance.engine) {
+      return Err(ProviderError::LoadFailed(format!(
+        "Code for '{}' was compiled with a different engine",
+        name
+      )));
+    }
+
+    self.instantiate_plugin(
+      instance,
+      name,
+      code.clone(),
+      PluginSource::Memory(code),
+      config,
+    )?;
+    info!("Plugin '{}' loaded successfully from memory", name);
+    Ok(())
+  }
+
+  // Spawns a worker for the plugin in `plugin_dir` and loads it there, replacing any
+  // earlier worker or in-process plugin of that name.
+  fn load_in_worker(
+    &self,
+    program: &Path,
+    instance: &WasmInstance,
+    plugin_name: &str,
+    plugin_dir: &Path,
+    config: &PluginConfigData,
+  ) -> Result<LoadTiming, ProviderError> {
+    let mut settings = serde_json::Map::new();
+    if let Some(fuel) = instance.fuel_limit {
+      settings.insert("fuelLimit".to_string(), fuel.into());
+    }
+    let init = serde_json::json!({
+      "op": "init",
+      "wasiVersion": match self.wasi_version {
+        WasiVersion::Preview1 => "preview1",
+        WasiVersion::Preview2 => "preview2",
+      },
+      "hostModule": self.host_module,
+      "settings": settings,
+      "defaultTimeoutMs": instance.default_timeout.map(|timeout| timeout.as_millis() as u64),
+      "maxMemory": instance.max_memory,
+      "compileCache": self.compile_cache.as_ref().map(|cache| cache.dir().to_string_lossy()),
+      // Only stdout is relayed, so captured stderr is discarded like `Null`.
+      "stderr": match instance.stdio.stderr {
+        StdioMode::Inherit => "inherit",
+        StdioMode::Capture | StdioMode::Null => "null",
+      },
+    });
+
+    info!(
+      "Loading plugin '{}' in worker {}",
+      plugin_name,
+      program.display()
+    );
+    let mut worker = process::Worker::spawn(program, &init, plugin_dir, config)?;
+    let timing = worker.load().inspect_err(|e| {
+      error!("Worker failed to load plugin '{}': {}", plugin_name, e);
+    })?;
 
     self
       .plugins
+      .write()
+      .unwrap_or_else(|p| p.into_inner())
+      .remove(plugin_name);
+    self
+      .workers
       .lock()
       .unwrap_or_else(|p| p.into_inner())
-      .insert(
-        plugin_name.clone(),
-        PluginInstance {
-          store,
-          bindings,
-          module_name: plugin_name.clone(),
-        },
-      );
+      .insert(plugin_name.to_string(), Arc::new(Mutex::new(worker)));
 
-    info!("Plugin '{}' loaded successfully", plugin_name);
-    Ok(())
+    info!(
+      "Plugin '{}' loaded in worker process {} (compile {:?}, instantiate {:?})",
+      plugin_name,
+      self.worker_pid(plugin_name).unwrap_or_default(),
+      timing.compile,
+      timing.instantiate
+    );
+    Ok(timing)
   }
 
-  fn inject(
+  /// Shares `data` with every plugin under `name`, replacing any dataset of that name,
+  /// and returns the replaced one.
+  ///
+  /// Components read it through the `read-shared` and `shared-size` functions of the
+  /// WIT `host` interface, which copy out only the requested range, so a plugin never
+  /// holds more of a large dataset in its linear memory than it asked for. Guest memory
+  /// belongs to the guest and cannot alias host memory, which is why the data is read
+  /// on demand rather than mapped in. The host keeps its own `Arc`: plugins cannot
+  /// modify the data, and it is freed once the host and this provider have dropped it.
+  /// Reads are bounds-checked and return fewer bytes near the end of the dataset.
+  ///
+  /// Already loaded plugins see the dataset immediately. Replacing a dataset between
+  /// two reads of one call gives the plugin the new contents for the second read.
+  /// Preview 1 modules and plugins running in worker processes have no access.
+  pub fn share_data(
     &self,
-    _instance: &mut Self::Instance,
-    _functions: &[(
-      &str,
-      &dyn Fn(Vec<ProviderValue>) -> Result<ProviderValue, ProviderError>,
-    )],
-  ) -> Result<(), ProviderError> {
-    Ok(())
+    name: impl Into<String>,
+    data: impl Into<Arc<[u8]>>,
+  ) -> Option<Arc<[u8]>> {
+    self.shared.insert(name.into(), data.into())
   }
 
-  fn invoke(
+  /// Stops sharing the dataset `name` and returns it, if it was shared.
+  pub fn unshare_data(&self, name: &str) -> Option<Arc<[u8]>> {
+    self.shared.remove(name)
+  }
+
+  /// Returns the process id of the worker running `plugin_name`, or `None` when the
+  /// plugin is not loaded under process isolation.
+  pub fn worker_pid(&self, plugin_name: &str) -> Option<u32> {
+    self
+      .worker_for(plugin_name)
+      .map(|worker| worker.lock().unwrap_or_else(|p| p.into_inner()).pid())
+  }
+
+  fn worker_for(&self, plugin_name: &str) -> Option<Arc<Mutex<process::Worker>>> {
+    self
+      .workers
+      .lock()
+      .unwrap_or_else(|p| p.into_inner())
+      .get(plugin_name)
+      .cloned()
+  }
+
+  // Forwards a call to the plugin's worker, then hands on what the plugin printed
+  // according to the stdout mode.
+  fn invoke_in_worker(
     &self,
+    worker: &Mutex<process::Worker>,
     plugin_name: &str,
     function: &str,
-    args: Vec<ProviderValue>,
+    args: &[ProviderValue],
+    readonly: bool,
+    deadline: Option<Instant>,
   ) -> Result<ProviderValue, ProviderError> {
-    let mut plugins = self.plugins.lock().unwrap_or_else(|p| p.into_inner());
+    let _permit = self.invocations.acquire()?;
+    let stdout_mode = self
+      .instance
+      .read()
+      .unwrap_or_else(|p| p.into_inner())
+      .as_ref()
+      .map_or(StdioMode::Inherit, |instance| instance.stdio.stdout);
 
-    let plugin = plugins
-      .get_mut(plugin_name)
-      .ok_or_else(|| ProviderError::LoadFailed(format!("Plugin '{}' not found", plugin_name)))?;
+    let mut worker = worker.lock().unwrap_or_else(|p| p.into_inner());
+    let result = worker.invoke(plugin_name, function, args, readonly, deadline);
+    match stdout_mode {
+      StdioMode::Inherit => {
+        print!("{}", std::mem::take(&mut worker.stdout));
+        let _ = std::io::Write::flush(&mut std::io::stdout());
+      }
+      StdioMode::Capture => {}
+      StdioMode::Null => worker.stdout.clear(),
+    }
+    if worker.failed {
+      warn!("Plugin '{}' failed in its worker process", plugin_name);
+    }
+    result
+  }
 
-    // WIT only supports string args; serialize ProviderValue array to JSON
-    let args_json = serde_json::to_string(&args_to_json(&args))
-      .map_err(|e| ProviderError::InvocationFailed(e.to_string()))?;
+  // Instantiates the code via `new_plugin_instance` and registers it under
+  // `plugin_name`. Returns the time spent instantiating.
+  fn instantiate_plugin(
+    &self,
+    instance: &WasmInstance,
+    plugin_name: &str,
+    code: Code,
+    source: PluginSource,
+    config: &PluginConfigData,
+  ) -> Result<Duration, ProviderError> {
+    warn_host_name_collisions(instance, plugin_name, &code, config);
+    let (plugin, instantiate) =
+      new_plugin_instance(instance, plugin_name, code, source, config, None)?;
+    self
+      .plugins
+      .write()
+      .unwrap_or_else(|p| p.into_inner())
+      .insert(plugin_name.to_string(), Arc::new(Mutex::new(plugin)));
 
-    let result_json = plugin
-      .bindings
-      .bud_sdk_plugin()
-      .call_on_invoke(&mut plugin.store, function, &args_json)
-      .map_err(|e| ProviderError::InvocationFailed(e.to_string()))?
-      .map_err(ProviderError::InvocationFailed)?;
+    Ok(instantiate)
+  }
+
+  /// Invokes `function` with a numeric array passed as one typed list instead of JSON.
+  ///
+  /// `array` must be a `ProviderValue::Array` whose elements are all `Int` (sent as
+  /// `s32`) or all `Float` (sent as `f64`); the plugin receives it through its
+  /// `on-invoke-numeric` export.
+  ///
+  /// # Errors
+  ///
+  /// Returns `ProviderError::LoadFailed` if the plugin is not loaded, and
+  /// `ProviderError::InvocationFailed` if the array is mixed, non-numeric or out of
+  /// range, or if the plugin call fails.
+  pub fn invoke_numeric(
+    &self,
+    plugin_name: &str,
+    function: &str,
+    array: &ProviderValue,
+  ) -> Result<ProviderValue, ProviderError> {
+    let data = numeric::to_numeric_array(array)?;
+
+    let result_json = self.call_plugin(plugin_name, |plugin| {
+      let bindings = match &plugin.guest {
+        Guest::Component(bindings) => bindings,
+        Guest::Module(_) => {
+          return Err(ProviderError::InvocationFailed(
+            "Preview 1 modules do not support numeric-array calls".to_string(),
+          ));
+        }
+        #[cfg(feature = "async")]
+        Guest::AsyncComponent(_) => {
+          return Err(ProviderError::InvocationFailed(
+            "numeric-array calls are not available with async support".to_string(),
+          ));
+        }
+      };
+      bindings
+        .bud_sdk_plugin()
+        .call_on_invoke_numeric(&mut plugin.store, function, &data)
+        .map_err(|e| trap::call_error(e, &mut plugin.store.data_mut().output))?
+        .map_err(ProviderError::InvocationFailed)
+    })?;
 
     let value = serde_json::from_str(&result_json)
       .map_err(|e| ProviderError::InvocationFailed(e.to_string()))?;
@@ -244,7 +978,1348 @@ impl Provider for WasmProvider {
     Ok(json_to_provider_value(&value))
   }
 
-  fn unload(&self, _instance: Self::Instance) -> Result<(), ProviderError> {
-    Ok(())
+  /// Calls an export of a Preview 1 module with raw `Val`s, skipping `ProviderValue`
+  /// conversion in both directions.
+  ///
+  /// An escape hatch for types `invoke` cannot represent, such as `v128`. Arguments
+  /// must match the export's parameter types exactly; no coercion is applied.
+  ///
+  /// # Errors
+  ///
+  /// Returns `ProviderError::LoadFailed` if the plugin is not loaded, and
+  /// `ProviderError::InvocationFailed` if it is a component, has no such export, or
+  /// `args` do not match its signature. Traps are reported as by `Provider::invoke`.
+  pub fn invoke_raw(
+    &self,
+    plugin_name: &str,
+    function: &str,
+    args: Vec<Val>,
+  ) -> Result<Vec<Val>, ProviderError> {
+    self.call_plugin(plugin_name, |plugin| {
+      let Guest::Module(instance) = &plugin.guest else {
+        return Err(ProviderError::InvocationFailed(
+          "raw calls are only supported for Preview 1 modules".to_string(),
+        ));
+      };
+      preview1::call_raw(&mut plugin.store, instance, function, &args)
+    })
+  }
+
+  /// Lists the WASI capabilities the plugin in `path` imports, without instantiating it.
+  ///
+  /// Accepts a component or a WASI Preview 1 core module. Comparing the result with the
+  /// plugin's declared permissions (see `WasiUsage::undeclared`) shows ambient authority
+  /// it uses but did not ask for. Importing a capability does not prove it is called.
+  ///
+  /// # Errors
+  ///
+  /// Returns `ProviderError::LoadFailed` if the provider is not initialized or
+  /// `main.wasm` is missing or invalid.
+  pub fn analyze_wasi_usage<P: AsRef<Path>>(&self, path: P) -> Result<WasiUsage, ProviderError> {
+    let wasm_file = path.as_ref().join(Self::MAIN_FILE);
+    let bytes = std::fs::read(&wasm_file).map_err(|e| {
+      ProviderError::LoadFailed(format!("Failed to read {}: {}", wasm_file.display(), e))
+    })?;
+
+    wasi_usage::analyze(&*self.engine()?, &bytes).map_err(|e| {
+      ProviderError::LoadFailed(format!("Failed to analyze {}: {}", wasm_file.display(), e))
+    })
+  }
+
+  /// Caps how many invocations may run at once across all plugins.
+  ///
+  /// Every call into a plugin takes a slot before it starts. Once `max` calls are in
+  /// flight, further callers wait or fail with `InvocationFailed("at capacity")`,
+  /// depending on `policy`. Calls already waiting are re-evaluated immediately.
+  pub fn set_max_concurrent_invocations(&self, max: usize, policy: CapacityPolicy) {
+    self.invocations.configure(Some(max), policy);
+  }
+
+  /// Removes the cap set by `set_max_concurrent_invocations`.
+  pub fn clear_max_concurrent_invocations(&self) {
+    self.invocations.configure(None, CapacityPolicy::default());
+  }
+
+  /// Invokes `function` while forbidding the guest from mutating its resources.
+  ///
+  /// Memory and table growth are refused for the duration of the call, so a call that
+  /// succeeds is known not to have grown the plugin. File writes are not intercepted,
+  /// so a plugin granted `filesystem.write` directories can still write to them. A
+  /// violation aborts the call, which leaves the plugin in the failed state until
+  /// `reload`.
+  ///
+  /// # Errors
+  ///
+  /// Returns `ProviderError::PermissionDenied` if the guest attempts a mutation, and
+  /// otherwise the same errors as `Provider::invoke`.
+  pub fn invoke_readonly(
+    &self,
+    plugin_name: &str,
+    function: &str,
+    args: Vec<ProviderValue>,
+  ) -> Result<ProviderValue, ProviderError> {
+    self.invoke_json(plugin_name, function, &args, true, None)
+  }
+
+  /// Resolves `function` of a loaded plugin to a handle for `invoke_handle`.
+  ///
+  /// For Preview 1 modules the export is looked up here, once; components dispatch
+  /// every call by name through their `on-invoke` export, so an unknown function only
+  /// surfaces when the handle is invoked.
+  ///
+  /// # Errors
+  ///
+  /// Returns `ProviderError::LoadFailed` if the plugin is not loaded, or
+  /// `ProviderError::InvocationFailed` if a module has no such export.
+  pub fn resolve_function(
+    &self,
+    plugin_name: &str,
+    function: &str,
+  ) -> Result<FuncHandle, ProviderError> {
+    let plugin = self.plugin(plugin_name)?;
+    let mut plugin = plugin.lock().unwrap_or_else(|p| p.into_inner());
+    let plugin = &mut *plugin;
+
+    let func = match &plugin.guest {
+      Guest::Component(_) => None,
+      #[cfg(feature = "async")]
+      Guest::AsyncComponent(_) => None,
+      Guest::Module(instance) => Some(preview1::lookup(&mut plugin.store, instance, function)?),
+    };
+    Ok(FuncHandle {
+      plugin_name: plugin_name.to_string(),
+      function: function.to_string(),
+      generation: plugin.generation,
+      func,
+    })
   }
+
+  /// Returns the signature of `function` of a loaded plugin, so callers can check
+  /// arity and types before marshaling arguments.
+  ///
+  /// Looks the function up in `Provider::describe`; the kinds serialize as their
+  /// display names (`"i32"` and so on).
+  ///
+  /// # Errors
+  ///
+  /// Returns the errors of `Provider::describe`, or `ProviderError::InvocationFailed`
+  /// naming the function if the plugin does not describe it, including a Preview 1
+  /// export whose types have no `ValueKind`.
+  pub fn function_signature(
+    &self,
+    plugin_name: &str,
+    function: &str,
+  ) -> Result<FunctionSignature, ProviderError> {
+    if let Some(signature) = self
+      .describe(plugin_name)?
+      .into_iter()
+      .find(|sig| sig.name == function)
+    {
+      return Ok(signature);
+    }
+
+    let msg = if self
+      .list_exports(plugin_name)?
+      .iter()
+      .any(|name| name == function)
+    {
+      format!(
+        "function '{}' of plugin '{}' has a signature that cannot be described",
+        function, plugin_name
+      )
+    } else {
+      format!("plugin '{}' has no function '{}'", plugin_name, function)
+    };
+    Err(ProviderError::InvocationFailed(msg))
+  }
+
+  /// Invokes the function behind `handle`, as `Provider::invoke` would by name.
+  ///
+  /// # Errors
+  ///
+  /// Returns `ProviderError::InvocationFailed` if the plugin was reloaded after the
+  /// handle was resolved, and otherwise the same errors as `Provider::invoke`.
+  pub fn invoke_handle(
+    &self,
+    handle: &FuncHandle,
+    args: Vec<ProviderValue>,
+  ) -> Result<ProviderValue, ProviderError> {
+    self.call_plugin(&handle.plugin_name, |plugin| {
+      if plugin.generation != handle.generation {
+        return Err(ProviderError::InvocationFailed(format!(
+          "handle for '{}' is stale: plugin '{}' was reloaded",
+          handle.function, handle.plugin_name
+        )));
+      }
+      match (&plugin.guest, handle.func) {
+        (Guest::Module(instance), Some(func)) => {
+          preview1::call_func(&mut plugin.store, instance, &handle.function, func, &args)
+        }
+        _ => call_guest(plugin, &handle.function, &args, false),
+      }
+    })
+  }
+
+  // Shared body of `invoke`, `invoke_readonly` and `invoke_with_deadline`.
+  fn invoke_json(
+    &self,
+    plugin_name: &str,
+    function: &str,
+    args: &[ProviderValue],
+    readonly: bool,
+    deadline: Option<Instant>,
+  ) -> Result<ProviderValue, ProviderError> {
+    if let Some(worker) = self.worker_for(plugin_name) {
+      return self.invoke_in_worker(&worker, plugin_name, function, args, readonly, deadline);
+    }
+    // Waits its turn like any async call, rather than finding the plugin checked out.
+    #[cfg(feature = "async")]
+    if self.async_support {
+      return async_guest::block_on(self.invoke_json_async(
+        plugin_name,
+        function,
+        args.to_vec(),
+        readonly,
+        deadline,
+      ));
+    }
+    self.call_plugin_until(plugin_name, deadline, |plugin| {
+      call_guest(plugin, function, args, readonly)
+    })
+  }
+
+  // `invoke_json` without blocking: checks the plugin out of the table for the call,
+  // after waiting for any other async call into it. Providers without async support
+  // and plugins in worker processes fall back to `invoke_json`.
+  #[cfg(feature = "async")]
+  async fn invoke_json_async(
+    &self,
+    plugin_name: &str,
+    function: &str,
+    args: Vec<ProviderValue>,
+    readonly: bool,
+    deadline: Option<Instant>,
+  ) -> Result<ProviderValue, ProviderError> {
+    if !self.async_support || self.worker_for(plugin_name).is_some() {
+      return self.invoke_json(plugin_name, function, &args, readonly, deadline);
+    }
+
+    let _permit = self.invocations.acquire()?;
+    let turn = self.turns.get(plugin_name);
+    let _turn = turn.lock().await;
+    let mut checkout = async_guest::Checkout::take(&self.plugins, plugin_name)?;
+    let plugin = checkout.plugin();
+
+    let _timer = start_call(plugin, deadline)?;
+    let result = call_guest_async(plugin, function, &args, readonly).await;
+    let result = finish_call(plugin_name, plugin, result);
+    checkout.finish();
+    result
+  }
+
+  /// Invokes `function` once per argument set received on `args_rx`, in order.
+  ///
+  /// A worker thread takes one concurrency slot and the plugin's lock, then keeps both
+  /// until `args_rx` closes or the returned receiver is dropped, so calls are not
+  /// re-locked one by one. Every other call into this plugin waits meanwhile. Each
+  /// argument set yields exactly one result, including after a trap, when the
+  /// remaining sets fail with the failed-state error.
+  ///
+  /// If the plugin is not loaded, or `CapacityPolicy::Reject` finds no free slot, the
+  /// stream yields that single error and ends.
+  pub fn invoke_stream(
+    &self,
+    plugin_name: &str,
+    function: &str,
+    args_rx: Receiver<Vec<ProviderValue>>,
+  ) -> Receiver<Result<ProviderValue, ProviderError>> {
+    let (results_tx, results_rx) = channel();
+    let plugins = Arc::clone(&self.plugins);
+    let invocations = Arc::clone(&self.invocations);
+    let plugin_name = plugin_name.to_string();
+    let function = function.to_string();
+
+    thread::spawn(move || {
+      let _permit = match invocations.acquire() {
+        Ok(permit) => permit,
+        Err(e) => {
+          let _ = results_tx.send(Err(e));
+          return;
+        }
+      };
+      let plugin = plugins
+        .read()
+        .unwrap_or_else(|p| p.into_inner())
+        .get(&plugin_name)
+        .cloned();
+      let Some(plugin) = plugin else {
+        let e = ProviderError::LoadFailed(format!("Plugin '{}' not found", plugin_name));
+        let _ = results_tx.send(Err(e));
+        return;
+      };
+      let mut plugin = plugin.lock().unwrap_or_else(|p| p.into_inner());
+
+      for args in args_rx {
+        let result = run_until(&plugin_name, &mut plugin, None, |plugin| {
+          call_guest(plugin, &function, &args, false)
+        });
+        if results_tx.send(result).is_err() {
+          break;
+        }
+      }
+    });
+
+    results_rx
+  }
+
+  /// Invokes `function` in a throwaway instance of a loaded plugin.
+  ///
+  /// The plugin's compiled component is instantiated in a brand-new store, `on-load`
+  /// runs, the call is made, and the store is dropped. Nothing the call does to
+  /// memory or globals is visible to later calls, isolated or not, and the plugin's
+  /// shared instance is left untouched, including its failed state. Captured output
+  /// is discarded with the store.
+  ///
+  /// # Errors
+  ///
+  /// Returns `ProviderError::LoadFailed` if the plugin is not loaded or the fresh
+  /// instance cannot be created, and otherwise the same errors as `Provider::invoke`.
+  pub fn invoke_isolated(
+    &self,
+    plugin_name: &str,
+    function: &str,
+    args: Vec<ProviderValue>,
+  ) -> Result<ProviderValue, ProviderError> {
+    let _permit = self.invocations.acquire()?;
+
+    let mut plugin = self.throwaway_instance(plugin_name, |_| Ok(None))?;
+    run_until(plugin_name, &mut plugin, None, |plugin| {
+      call_guest(plugin, function, &args, false)
+    })
+  }
+
+  /// Invokes `function` with a read-only handle to `file` instead of its contents.
+  ///
+  /// WASI can only preopen directories, so the file is opened on the host and wired
+  /// to standard input of a throwaway instance, as made by `invoke_isolated`. The
+  /// function receives that descriptor, `FILE_FD`, as an `i32` before `args` and
+  /// reads the file with `fd_read` (or from stdin in a component) in whatever chunks
+  /// it likes, so the file is never copied into guest memory up front. The file must
+  /// lie in one of the plugin's `filesystem.read` (or `filesystem.write`)
+  /// directories.
+  ///
+  /// # Errors
+  ///
+  /// Returns `ProviderError::PermissionDenied` if the file is outside those
+  /// directories or cannot be opened, and otherwise the errors of `invoke_isolated`.
+  pub fn invoke_with_file<F: AsRef<Path>>(
+    &self,
+    plugin_name: &str,
+    function: &str,
+    file: F,
+    args: Vec<ProviderValue>,
+  ) -> Result<ProviderValue, ProviderError> {
+    let _permit = self.invocations.acquire()?;
+
+    let file = file.as_ref();
+    let mut plugin = self.throwaway_instance(plugin_name, |config| {
+      let path = permissions::check_readable_file(config.permissions.as_ref(), file)?;
+      let handle = std::fs::File::open(&path).map_err(|e| {
+        ProviderError::PermissionDenied(format!("cannot open '{}': {}", path.display(), e))
+      })?;
+      Ok(Some(handle))
+    })?;
+
+    let mut file_args = Vec::with_capacity(args.len() + 1);
+    file_args.push(ProviderValue::Int(FILE_FD.into()));
+    file_args.extend(args);
+    run_until(plugin_name, &mut plugin, None, |plugin| {
+      call_guest(plugin, function, &file_args, false)
+    })
+  }
+
+  // Instantiates a loaded plugin's code again in a fresh store. `stdin` picks the
+  // file, if any, the instance reads as standard input, given the plugin's config.
+  fn throwaway_instance(
+    &self,
+    plugin_name: &str,
+    stdin: impl FnOnce(&PluginConfigData) -> Result<Option<std::fs::File>, ProviderError>,
+  ) -> Result<PluginInstance, ProviderError> {
+    let (code, source, config) = {
+      let plugin = self.plugin(plugin_name)?;
+      let plugin = plugin.lock().unwrap_or_else(|p| p.into_inner());
+      (
+        plugin.code.clone(),
+        plugin.source.clone(),
+        plugin.config.clone(),
+      )
+    };
+    let stdin = stdin(&config)?;
+
+    let instance_guard = self.instance.read().unwrap_or_else(|p| p.into_inner());
+    let instance = instance_guard.as_ref().ok_or_else(|| {
+      ProviderError::LoadFailed("Provider not initialized. Call init() first.".to_string())
+    })?;
+    let (plugin, _) = new_plugin_instance(instance, plugin_name, code, source, &config, stdin)?;
+    Ok(plugin)
+  }
+
+  /// Compares the functions exported by the plugins in two directories, to check
+  /// before deployment that the second can replace the first.
+  ///
+  /// Both `main.wasm` files are compiled with this provider's engine and WASI version;
+  /// neither is loaded. A Preview 1 module's signatures come from its export types,
+  /// skipping functions `describe` cannot represent. A component only reveals its
+  /// functions through its `describe` export, so it is instantiated in a throwaway
+  /// store without permissions, which runs its `on-load` hook.
+  ///
+  /// # Errors
+  ///
+  /// Returns `ProviderError::LoadFailed` if the provider is not initialized or either
+  /// plugin fails to compile or instantiate, and otherwise the errors of
+  /// `Provider::describe`.
+  pub fn compare_exports<A: AsRef<Path>, B: AsRef<Path>>(
+    &self,
+    path_a: A,
+    path_b: B,
+  ) -> Result<ExportDiff, ProviderError> {
+    let a = self.exports_of(path_a.as_ref())?;
+    let b = self.exports_of(path_b.as_ref())?;
+    Ok(ExportDiff::new(a, b))
+  }
+
+  // Compiles the plugin in `plugin_dir` and describes its functions for
+  // `compare_exports`.
+  fn exports_of(&self, plugin_dir: &Path) -> Result<Vec<FunctionSignature>, ProviderError> {
+    let wasm_file = plugin_dir.join(Self::MAIN_FILE);
+    let plugin_name = plugin_dir
+      .file_name()
+      .and_then(|n| n.to_str())
+      .ok_or_else(|| {
+        ProviderError::LoadFailed(format!("Invalid plugin path: {}", plugin_dir.display()))
+      })?
+      .to_string();
+
+    let instance_guard = self.instance.read().unwrap_or_else(|p| p.into_inner());
+    let instance = instance_guard.as_ref().ok_or_else(|| {
+      ProviderError::LoadFailed("Provider not initialized. Call init() first.".to_string())
+    })?;
+
+    let code = std::fs::read(&wasm_file)
+      .map_err(wasmtime::Error::from)
+      .and_then(|bytes| Code::compile(&instance.engine, self.wasi_version, &bytes))
+      .map_err(|e| {
+        ProviderError::LoadFailed(format!("Failed to compile {}: {}", wasm_file.display(), e))
+      })?;
+
+    match &code {
+      Code::Module(module) => Ok(preview1::describe(module)),
+      Code::Component(_) => {
+        let config = PluginConfigData {
+          id: None,
+          name: plugin_name.clone(),
+          version: String::new(),
+          description: String::new(),
+          author: String::new(),
+          main: None,
+          permissions: None,
+          functions: None,
+          timeout_ms: None,
+          categories: Vec::new(),
+          keywords: Vec::new(),
+          dependencies: Vec::new(),
+          targets: Vec::new(),
+          extra: Default::default(),
+        };
+        let (mut plugin, _) = new_plugin_instance(
+          instance,
+          &plugin_name,
+          code.clone(),
+          PluginSource::Memory(code),
+          &config,
+          None,
+        )?;
+        run_until(&plugin_name, &mut plugin, None, describe_plugin)
+      }
+    }
+  }
+
+  /// Reloads a plugin from the directory it was originally loaded from.
+  ///
+  /// The plugin gets a fresh store and instance, which also clears the failed state
+  /// set after a trap.
+  ///
+  /// # Errors
+  ///
+  /// Returns `ProviderError::LoadFailed` if the plugin is not loaded or loading it
+  /// again fails.
+  pub fn reload(&self, plugin_name: &str) -> Result<(), ProviderError> {
+    if let Some(worker) = self.worker_for(plugin_name) {
+      let (path, config) = {
+        let worker = worker.lock().unwrap_or_else(|p| p.into_inner());
+        (worker.path.clone(), worker.config.clone())
+      };
+      info!("Reloading plugin '{}' in a new worker", plugin_name);
+      return self.load_timed(path, &config).map(|_| ());
+    }
+
+    let (source, config) = {
+      let plugin = self.plugin(plugin_name)?;
+      let plugin = plugin.lock().unwrap_or_else(|p| p.into_inner());
+      (plugin.source.clone(), plugin.config.clone())
+    };
+
+    info!("Reloading plugin '{}'", plugin_name);
+    match source {
+      PluginSource::Path(path) => self.load_timed(path, &config).map(|_| ()),
+      PluginSource::Memory(code) => self.load_code(plugin_name, code, &config),
+    }
+  }
+
+  // Engine created by `init`.
+  fn engine(&self) -> Result<Arc<Engine>, ProviderError> {
+    let instance = self.instance.read().unwrap_or_else(|p| p.into_inner());
+    instance
+      .as_ref()
+      .map(|instance| Arc::clone(&instance.engine))
+      .ok_or_else(|| {
+        ProviderError::LoadFailed("Provider not initialized. Call init() first.".to_string())
+      })
+  }
+
+  // Runs `f` against a loaded plugin that has not failed. A trap leaves the component
+  // instance in an undefined state, so it marks the plugin failed until `reload`.
+  fn call_plugin<R>(
+    &self,
+    plugin_name: &str,
+    f: impl FnOnce(&mut PluginInstance) -> Result<R, ProviderError>,
+  ) -> Result<R, ProviderError> {
+    self.call_plugin_until(plugin_name, None, f)
+  }
+
+  // `call_plugin` with an optional deadline, enforced through epoch interruption.
+  // `None` falls back to the plugin's declared timeout.
+  fn call_plugin_until<R>(
+    &self,
+    plugin_name: &str,
+    deadline: Option<Instant>,
+    f: impl FnOnce(&mut PluginInstance) -> Result<R, ProviderError>,
+  ) -> Result<R, ProviderError> {
+    // Taken before the plugin lock so blocked callers do not hold it.
+    let _permit = self.invocations.acquire()?;
+
+    let plugin = self.plugin(plugin_name)?;
+    let mut plugin = plugin.lock().unwrap_or_else(|p| p.into_inner());
+
+    run_until(plugin_name, &mut plugin, deadline, f)
+  }
+
+  // Looks up the lock of an in-process plugin, holding the table lock only for that.
+  fn plugin(&self, plugin_name: &str) -> Result<Arc<Mutex<PluginInstance>>, ProviderError> {
+    self
+      .plugins
+      .read()
+      .unwrap_or_else(|p| p.into_inner())
+      .get(plugin_name)
+      .cloned()
+      .ok_or_else(|| ProviderError::LoadFailed(format!("Plugin '{}' not found", plugin_name)))
+  }
+}
+
+impl Provider for WasmProvider {
+  type Instance = WasmInstance;
+  type PluginInstance = Arc<Mutex<PluginInstance>>;
+  const MAIN_FILE: &'static str = "main.wasm";
+  const NAME: &'static str = "wasm";
+
+  fn with_plugins<F, R>(&self, f: F) -> Result<R, ProviderError>
+  where
+    F: FnOnce(&HashMap<String, Self::PluginInstance>) -> R,
+  {
+    let plugins = self.plugins.read().unwrap_or_else(|p| p.into_inner());
+    Ok(f(&plugins))
+  }
+
+  /// Applies `providers.wasm` from `bud.json`.
+  ///
+  /// `fuelLimit` (a positive integer) enables fuel metering: every call, and each
+  /// plugin's instantiation, may execute only that much fuel (roughly one unit per
+  /// instruction) before it traps. Unknown keys are ignored. Settings take effect at
+  /// the next `init`.
+  fn configure(&self, settings: &serde_json::Value) -> Result<(), ProviderError> {
+    let fuel_limit = match settings.get("fuelLimit") {
+      None => None,
+      Some(value) => match value.as_u64().filter(|fuel| *fuel > 0) {
+        Some(fuel) => Some(fuel),
+        None => {
+          error!("Invalid fuelLimit {}: expected a positive integer", value);
+          return Err(ProviderError::InitFailed);
+        }
+      },
+    };
+
+    *self.fuel_limit.write().unwrap_or_else(|p| p.into_inner()) = fuel_limit;
+    Ok(())
+  }
+
+  fn init(&self) -> Result<Self::Instance, ProviderError> {
+    info!("Initializing WasmProvider (WASI {:?})", self.wasi_version);
+
+    let fuel_limit = *self.fuel_limit.read().unwrap_or_else(|p| p.into_inner());
+
+    let mut config = Config::default();
+    config.wasm_component_model(true);
+    // Lets `invoke_with_deadline` interrupt guest code that runs past its deadline.
+    config.epoch_interruption(true);
+    config.consume_fuel(fuel_limit.is_some());
+    #[cfg(feature = "async")]
+    if self.async_support {
+      if self.wasi_version == WasiVersion::Preview1 {
+        error!("Async support is only available for WASI Preview 2 components");
+        return Err(ProviderError::InitFailed);
+      }
+      config.async_support(true);
+    }
+
+    let engine = Engine::new(&config).map_err(|e| {
+      error!("Failed to create Engine: {}", e);
+      ProviderError::InitFailed
+    })?;
+
+    let linker = base_linker(&engine).map_err(|e| {
+      error!("Failed to set up linker: {}", e);
+      ProviderError::InitFailed
+    })?;
+
+    let preview1_linker = match self.wasi_version {
+      WasiVersion::Preview1 => Some(Arc::new(preview1::linker(&engine).map_err(|e| {
+        error!("Failed to set up Preview 1 linker: {}", e);
+        ProviderError::InitFailed
+      })?)),
+      WasiVersion::Preview2 => None,
+    };
+
+    let stdio = self
+      .stdio
+      .or_else(|| StdioMode::from_env().map(StdioConfig::from))
+      .unwrap_or_default();
+
+    #[cfg(feature = "async")]
+    let ticker = self
+      .async_support
+      .then(|| Arc::new(deadline::EpochTicker::start(&engine)));
+
+    let new_instance = WasmInstance {
+      engine: Arc::new(engine),
+      linker: Arc::new(linker),
+      stdio,
+      fuel_limit,
+      default_timeout: self.default_timeout,
+      max_memory: self.max_memory,
+      host_imports: Arc::new(imports::default_host_imports()),
+      injected: Arc::default(),
+      host_module: self.host_module.clone(),
+      preview1_linker,
+      shared: self.shared.clone(),
+      stores: self.stores.clone(),
+      #[cfg(feature = "async")]
+      _ticker: ticker,
+    };
+
+    *self.instance.write().unwrap_or_else(|p| p.into_inner()) = Some(new_instance.clone());
+
+    info!("WasmProvider initialized successfully");
+    Ok(new_instance)
+  }
+
+  fn is_initialized(&self) -> bool {
+    self
+      .instance
+      .read()
+      .unwrap_or_else(|p| p.into_inner())
+      .is_some()
+  }
+
+  /// Plugins that trapped since they were last loaded, sorted by name.
+  fn failed_plugins(&self) -> Vec<String> {
+    let plugins = self.plugins.read().unwrap_or_else(|p| p.into_inner());
+    let mut failed: Vec<String> = plugins
+      .iter()
+      .filter(|(_, plugin)| plugin.lock().unwrap_or_else(|p| p.into_inner()).failed)
+      .map(|(name, _)| name.clone())
+      .collect();
+    let workers = self.workers.lock().unwrap_or_else(|p| p.into_inner());
+    failed.extend(
+      workers
+        .iter()
+        .filter(|(_, worker)| worker.lock().unwrap_or_else(|p| p.into_inner()).failed)
+        .map(|(name, _)| name.clone()),
+    );
+    failed.sort();
+    failed
+  }
+
+  fn load<P: AsRef<Path>>(&self, path: P, config: &PluginConfigData) -> Result<(), ProviderError> {
+    self.load_timed(path, config).map(|_| ())
+  }
+
+  /// Registers `functions` under the provider's host module (see
+  /// `WasmProviderBuilder::host_module`).
+  ///
+  /// Functions accumulate across calls, and a later one replaces an earlier function of
+  /// the same name. Only plugins loaded afterwards see them.
+  ///
+  /// Host functions are only reachable through a plugin's imports; `invoke` always
+  /// calls the named plugin's own export. Loading a plugin that exports a function
+  /// named like an injected one logs a warning.
+  fn inject(
+    &self,
+    instance: &mut Self::Instance,
+    functions: &[(&str, Arc<HostFunction>)],
+  ) -> Result<(), ProviderError> {
+    let mut injected = (*instance.injected).clone();
+    for (name, function) in functions {
+      injected.insert(name.to_string(), Arc::clone(function));
+    }
+
+    // Linker instances cannot be extended once defined, so the linker is rebuilt.
+    let injection_failed = |e: wasmtime::Error| {
+      error!("Failed to inject host functions: {}", e);
+      ProviderError::InjectionFailed(e.to_string())
+    };
+    let mut linker = base_linker(&instance.engine).map_err(injection_failed)?;
+    let mut module = linker
+      .instance(&self.host_module)
+      .map_err(injection_failed)?;
+    for (name, function) in &injected {
+      module
+        .func_new(name, host_funcs::adapt(Arc::clone(function)))
+        .map_err(injection_failed)?;
+    }
+
+    let mut host_imports = imports::default_host_imports();
+    host_imports
+      .entry(self.host_module.clone())
+      .or_default()
+      .extend(injected.keys().cloned());
+
+    instance.linker = Arc::new(linker);
+    instance.host_imports = Arc::new(host_imports);
+    instance.injected = Arc::new(injected);
+    *self.instance.write().unwrap_or_else(|p| p.into_inner()) = Some(instance.clone());
+
+    info!(
+      "Injected {} host function(s) under '{}'",
+      functions.len(),
+      self.host_module
+    );
+    Ok(())
+  }
+
+  fn invoke(
+    &self,
+    plugin_name: &str,
+    function: &str,
+    args: Vec<ProviderValue>,
+  ) -> Result<ProviderValue, ProviderError> {
+    self.invoke_json(plugin_name, function, &args, false, None)
+  }
+
+  /// Interrupts the call through epoch interruption once `deadline` passes; this
+  /// replaces the plugin's declared `timeoutMs` for the call.
+  ///
+  /// The remaining time is measured when the call starts, after any wait for a
+  /// concurrency slot. An interrupted call leaves the plugin in the failed state until
+  /// `reload`, like a trap.
+  fn invoke_with_deadline(
+    &self,
+    plugin_name: &str,
+    function: &str,
+    args: Vec<ProviderValue>,
+    deadline: Instant,
+  ) -> Result<ProviderValue, ProviderError> {
+    self.invoke_json(plugin_name, function, &args, false, Some(deadline))
+  }
+
+  /// With `WasmProviderBuilder::async_support`, runs the call through `call_async`,
+  /// yielding to the executor while the guest runs; the plugin's declared or default
+  /// timeout still applies. Otherwise runs `invoke` when first polled.
+  #[cfg(feature = "async")]
+  fn invoke_async(
+    &self,
+    plugin_name: &str,
+    function: &str,
+    args: Vec<ProviderValue>,
+  ) -> impl std::future::Future<Output = Result<ProviderValue, ProviderError>> + Send {
+    self.invoke_json_async(plugin_name, function, args, false, None)
+  }
+
+  fn describe(&self, plugin_name: &str) -> Result<Vec<FunctionSignature>, ProviderError> {
+    self.call_plugin(plugin_name, describe_plugin)
+  }
+
+  /// Lists every exported function of a Preview 1 module, including the allocator and
+  /// `_initialize`, in export order.
+  ///
+  /// A component's exports are the fixed `plugin` interface it dispatches through, so
+  /// for components this lists the functions named by its `describe`.
+  fn list_exports(&self, plugin_name: &str) -> Result<Vec<String>, ProviderError> {
+    let module_exports = self.call_plugin(plugin_name, |plugin| {
+      Ok(match &plugin.code {
+        Code::Module(module) => Some(
+          module
+            .exports()
+            .filter(|export| matches!(export.ty(), wasmtime::ExternType::Func(_)))
+            .map(|export| export.name().to_string())
+            .collect(),
+        ),
+        Code::Component(_) => None,
+      })
+    })?;
+
+    match module_exports {
+      Some(names) => Ok(names),
+      None => Ok(
+        self
+          .describe(plugin_name)?
+          .into_iter()
+          .map(|sig| sig.name)
+          .collect(),
+      ),
+    }
+  }
+
+  fn precompile<P: AsRef<Path>>(
+    &self,
+    path: P,
+    config: &PluginConfigData,
+    output: &Path,
+  ) -> Result<(), ProviderError> {
+    let wasm_file = path.as_ref().join(config.entry_file(Self::MAIN_FILE));
+    let bytes = std::fs::read(&wasm_file).map_err(|e| {
+      ProviderError::LoadFailed(format!("Failed to read {}: {}", wasm_file.display(), e))
+    })?;
+
+    let artifact = self.engine()?.precompile_component(&bytes).map_err(|e| {
+      ProviderError::LoadFailed(format!(
+        "Failed to precompile {}: {}",
+        wasm_file.display(),
+        e
+      ))
+    })?;
+
+    std::fs::write(output, artifact).map_err(|e| {
+      ProviderError::LoadFailed(format!("Failed to write {}: {}", output.display(), e))
+    })
+  }
+
+  fn load_precompiled(
+    &self,
+    name: &str,
+    artifact: &Path,
+    config: &PluginConfigData,
+  ) -> Result<(), ProviderError> {
+    let engine = self.engine()?;
+    // SAFETY: the trait contract limits artifacts to ones written by `precompile`;
+    // wasmtime itself rejects artifacts from other versions or engine configurations.
+    let component = unsafe { Component::deserialize_file(&engine, artifact) }.map_err(|e| {
+      ProviderError::LoadFailed(format!(
+        "Failed to deserialize '{}' from {}: {}",
+        name,
+        artifact.display(),
+        e
+      ))
+    })?;
+
+    info!("Loading '{}' from precompiled {}", name, artifact.display());
+    self.load_component(name, component, config)
+  }
+
+  /// Removes the plugin, dropping its store and with it the guest's memory, or stops
+  /// its worker process under process isolation.
+  fn unload_plugin(&self, plugin_name: &str) -> Result<(), ProviderError> {
+    let worker = self
+      .workers
+      .lock()
+      .unwrap_or_else(|p| p.into_inner())
+      .remove(plugin_name);
+    if let Some(worker) = worker {
+      // Dropping the last handle stops the worker process.
+      drop(worker);
+      info!("Unloaded plugin '{}' and stopped its worker", plugin_name);
+      return Ok(());
+    }
+
+    let plugin = self
+      .plugins
+      .write()
+      .unwrap_or_else(|p| p.into_inner())
+      .remove(plugin_name)
+      .ok_or_else(|| ProviderError::UnloadFailed(format!("Plugin '{}' not found", plugin_name)))?;
+    drop(plugin);
+    #[cfg(feature = "async")]
+    self.turns.remove(plugin_name);
+
+    info!("Unloaded plugin '{}'", plugin_name);
+    Ok(())
+  }
+
+  /// Unloads every plugin and discards the runtime; `init` must run again before the
+  /// next load.
+  fn unload(&self, instance: Self::Instance) -> Result<(), ProviderError> {
+    drop(instance);
+    self
+      .plugins
+      .write()
+      .unwrap_or_else(|p| p.into_inner())
+      .clear();
+    self
+      .workers
+      .lock()
+      .unwrap_or_else(|p| p.into_inner())
+      .clear();
+    #[cfg(feature = "async")]
+    self.turns.clear();
+    *self.instance.write().unwrap_or_else(|p| p.into_inner()) = None;
+
+    info!("WasmProvider unloaded");
+    Ok(())
+  }
+}
+
+// Linker with WASI preview2 and the bud `interface host` from wit/bud.wit registered.
+fn base_linker(engine: &Engine) -> wasmtime::Result<Linker<PluginState>> {
+  let mut linker = Linker::new(engine);
+  // Async stores need the async WASI bindings; the bud host interface is the same.
+  #[cfg(feature = "async")]
+  if engine.is_async() {
+    wasmtime_wasi::p2::add_to_linker_async(&mut linker)?;
+  } else {
+    wasmtime_wasi::p2::add_to_linker_sync(&mut linker)?;
+  }
+  #[cfg(not(feature = "async"))]
+  wasmtime_wasi::p2::add_to_linker_sync(&mut linker)?;
+  BudPlugin::add_to_linker::<PluginState, HasSelf<PluginState>>(&mut linker, |state| state)?;
+  Ok(linker)
+}
+
+// Instantiates `code` in a fresh store. Components have their imports vetted and run
+// `on-load`; Preview 1 modules run `_initialize`. Either then runs its warmup export,
+// if any. `stdin` replaces the configured standard input. Returns the plugin and the
+// time spent instantiating.
+fn new_plugin_instance(
+  instance: &WasmInstance,
+  plugin_name: &str,
+  code: Code,
+  source: PluginSource,
+  config: &PluginConfigData,
+  stdin: Option<std::fs::File>,
+) -> Result<(PluginInstance, Duration), ProviderError> {
+  if let Code::Component(component) = &code {
+    // Fail before instantiation with the exact host functions the plugin expects but
+    // the embedder never provided; WASI imports are left to the linker.
+    let missing =
+      imports::missing_host_imports(&instance.engine, component, &instance.host_imports);
+    if !missing.is_empty() {
+      let msg = format!(
+        "Plugin '{}' imports host functions that were not injected: {}",
+        plugin_name,
+        missing.join(", ")
+      );
+      error!("{}", msg);
+      return Err(ProviderError::LoadFailed(msg));
+    }
+  }
+
+  let mut wasi = WasiCtxBuilder::new();
+  let output = stdio::configure_stdio(&mut wasi, instance.stdio);
+  if let Some(file) = stdin {
+    wasi.stdin(wasmtime_wasi::cli::InputFile::new(file));
+  }
+  permissions::configure_permissions(&mut wasi, config.permissions.as_ref()).inspect_err(|e| {
+    error!(
+      "Plugin '{}' cannot be granted its permissions: {}",
+      plugin_name, e
+    );
+  })?;
+  let (wasi, preview1) = match code {
+    Code::Component(_) => (wasi.build(), None),
+    Code::Module(_) => (WasiCtx::builder().build(), Some(wasi.build_p1())),
+  };
+  let mut store = Store::new(
+    &instance.engine,
+    PluginState {
+      wasi,
+      table: ResourceTable::new(),
+      preview1,
+      output,
+      config: serde_json::to_value(config)
+        .map_err(|e| ProviderError::LoadFailed(format!("Invalid config: {}", e)))?,
+      limiter: store_limiter::StoreLimiter {
+        max_memory: instance.max_memory,
+        ..Default::default()
+      },
+      shared: instance.shared.clone(),
+      _counted: instance.stores.track(),
+    },
+  );
+  store.limiter(|state| &mut state.limiter);
+  store.set_epoch_deadline(deadline::UNBOUNDED);
+  // Instantiation, on-load and warmup share one budget.
+  if let Some(fuel) = instance.fuel_limit {
+    store
+      .set_fuel(fuel)
+      .map_err(|e| ProviderError::LoadFailed(format!("Failed to set fuel: {}", e)))?;
+  }
+
+  let instantiate_failed =
+    |e| ProviderError::LoadFailed(format!("Failed to instantiate '{}': {}", plugin_name, e));
+  let instantiate_start = Instant::now();
+  let mut component_warmup = None;
+  let guest = match &code {
+    #[cfg(feature = "async")]
+    Code::Component(component) if instance.engine.is_async() => {
+      let component_instance =
+        async_guest::block_on(instance.linker.instantiate_async(&mut store, component))
+          .map_err(instantiate_failed)?;
+      let bindings = async_guest::BudPlugin::new(&mut store, &component_instance)
+        .map_err(instantiate_failed)?;
+      component_warmup = component_instance
+        .get_typed_func::<(), ()>(&mut store, COMPONENT_WARMUP_EXPORT)
+        .ok();
+      Guest::AsyncComponent(bindings)
+    }
+    Code::Component(component) => {
+      // Instantiate the component and wire up host↔plugin bindings
+      let component_instance = instance
+        .linker
+        .instantiate(&mut store, component)
+        .map_err(instantiate_failed)?;
+      let bindings = BudPlugin::new(&mut store, &component_instance).map_err(instantiate_failed)?;
+      component_warmup = component_instance
+        .get_typed_func::<(), ()>(&mut store, COMPONENT_WARMUP_EXPORT)
+        .ok();
+      Guest::Component(bindings)
+    }
+    Code::Module(module) => {
+      let linker = instance.preview1_linker.as_ref().ok_or_else(|| {
+        ProviderError::LoadFailed(format!(
+          "Plugin '{}' is a core module, but the provider was not built for WASI Preview 1",
+          plugin_name
+        ))
+      })?;
+      let linker =
+        preview1::link_host_functions(linker, module, &instance.host_module, &instance.injected)
+          .map_err(|e| {
+            let msg = format!("Plugin '{}' {}", plugin_name, e);
+            error!("{}", msg);
+            ProviderError::LoadFailed(msg)
+          })?;
+      Guest::Module(
+        linker
+          .instantiate(&mut store, module)
+          .map_err(instantiate_failed)?,
+      )
+    }
+  };
+  let instantiate = instantiate_start.elapsed();
+
+  match &guest {
+    Guest::Component(bindings) => bindings
+      .bud_sdk_plugin()
+      .call_on_load(&mut store)
+      .map_err(|e| ProviderError::LoadFailed(format!("on-load trap: {}", e)))?
+      .map_err(|e| ProviderError::LoadFailed(format!("on-load error: {}", e)))?,
+    Guest::Module(module) => preview1::initialize(&mut store, module)
+      .map_err(|e| ProviderError::LoadFailed(format!("_initialize trap: {}", e)))?,
+    #[cfg(feature = "async")]
+    Guest::AsyncComponent(bindings) => {
+      async_guest::block_on(bindings.bud_sdk_plugin().call_on_load(&mut store))
+        .map_err(|e| ProviderError::LoadFailed(format!("on-load trap: {}", e)))?
+        .map_err(|e| ProviderError::LoadFailed(format!("on-load error: {}", e)))?
+    }
+  }
+
+  // The optional warmup export does lazy setup now, so the first invoke is not
+  // penalized for it.
+  let warmup = match &guest {
+    Guest::Component(_) => component_warmup.map(|func| {
+      let result = func
+        .call(&mut store, ())
+        .and_then(|()| func.post_return(&mut store));
+      (COMPONENT_WARMUP_EXPORT, result)
+    }),
+    Guest::Module(module) => module
+      .get_typed_func::<(), ()>(&mut store, WARMUP_EXPORT)
+      .ok()
+      .map(|func| (WARMUP_EXPORT, func.call(&mut store, ()))),
+    #[cfg(feature = "async")]
+    Guest::AsyncComponent(_) => component_warmup.map(|func| {
+      let result = async_guest::block_on(async {
+        func.call_async(&mut store, ()).await?;
+        func.post_return_async(&mut store).await
+      });
+      (COMPONENT_WARMUP_EXPORT, result)
+    }),
+  };
+  if let Some((export, Err(e))) = warmup {
+    let msg = format!(
+      "Plugin '{}' failed in its '{}' warmup export: {}",
+      plugin_name,
+      export,
+      trap::call_error(e, &mut store.data_mut().output)
+    );
+    error!("{}", msg);
+    return Err(ProviderError::LoadFailed(msg));
+  }
+
+  let plugin = PluginInstance {
+    store,
+    guest,
+    module_name: plugin_name.to_string(),
+    code,
+    source,
+    config: config.clone(),
+    failed: false,
+    fuel_limit: instance.fuel_limit,
+    default_timeout: instance.default_timeout,
+    generation: NEXT_GENERATION.fetch_add(1, Ordering::Relaxed),
+  };
+  Ok((plugin, instantiate))
+}
+
+// Runs `f` against `plugin` under `deadline`, or the plugin's declared or default
+// timeout when `None`, and marks the plugin failed if the call ends in a fatal error.
+// Warns about plugin functions named like an injected host function. Calls always go
+// to the plugin's own export, so dispatch stays unambiguous, but readers of a call
+// site may expect the host function. Components are checked by the functions their
+// plugin.json declares, since their names are only known by calling `describe`.
+fn warn_host_name_collisions(
+  instance: &WasmInstance,
+  plugin_name: &str,
+  code: &Code,
+  config: &PluginConfigData,
+) {
+  if instance.injected.is_empty() {
+    return;
+  }
+  let mut collisions: Vec<String> = match code {
+    Code::Module(module) => module
+      .exports()
+      .filter(|export| matches!(export.ty(), wasmtime::ExternType::Func(_)))
+      .map(|export| export.name().to_string())
+      .collect(),
+    Code::Component(_) => config
+      .functions
+      .iter()
+      .flat_map(|functions| functions.keys().cloned())
+      .collect(),
+  };
+  collisions.retain(|name| instance.injected.contains_key(name));
+  if !collisions.is_empty() {
+    collisions.sort();
+    warn!(
+      "Plugin '{}' exports function(s) named like injected host functions: {}; calls \
+       by these names reach the plugin's own functions",
+      plugin_name,
+      collisions.join(", ")
+    );
+  }
+}
+
+// Calls the plugin's `describe` export, or reads a module's export types.
+fn describe_plugin(plugin: &mut PluginInstance) -> Result<Vec<FunctionSignature>, ProviderError> {
+  let bindings = match (&plugin.guest, &plugin.code) {
+    (Guest::Component(bindings), _) => bindings,
+    (Guest::Module(_), Code::Module(module)) => return Ok(preview1::describe(module)),
+    (Guest::Module(_), Code::Component(_)) => unreachable!("module guest from a component"),
+    #[cfg(feature = "async")]
+    (Guest::AsyncComponent(bindings), _) => {
+      let signatures =
+        async_guest::block_on(bindings.bud_sdk_plugin().call_describe(&mut plugin.store))
+          .map_err(|e| trap::call_error(e, &mut plugin.store.data_mut().output))?;
+      return Ok(async_guest::signatures(signatures));
+    }
+  };
+  let signatures = bindings
+    .bud_sdk_plugin()
+    .call_describe(&mut plugin.store)
+    .map_err(|e| trap::call_error(e, &mut plugin.store.data_mut().output))?;
+
+  Ok(
+    signatures
+      .into_iter()
+      .map(|sig| FunctionSignature {
+        name: sig.name,
+        params: sig.params.into_iter().map(value_kind).collect(),
+        results: sig.results.into_iter().map(value_kind).collect(),
+      })
+      .collect(),
+  )
+}
+
+fn run_until<R>(
+  plugin_name: &str,
+  plugin: &mut PluginInstance,
+  deadline: Option<Instant>,
+  f: impl FnOnce(&mut PluginInstance) -> Result<R, ProviderError>,
+) -> Result<R, ProviderError> {
+  let _timer = start_call(plugin, deadline)?;
+  let result = f(plugin);
+  finish_call(plugin_name, plugin, result)
+}
+
+// First half of `run_until`: refuses a failed plugin, refills its fuel and arms the
+// deadline. Returns the timer to keep alive for the duration of the call.
+fn start_call(
+  plugin: &mut PluginInstance,
+  deadline: Option<Instant>,
+) -> Result<Option<deadline::DeadlineTimer>, ProviderError> {
+  if plugin.failed {
+    return Err(ProviderError::InvocationFailed(
+      FAILED_STATE_MESSAGE.to_string(),
+    ));
+  }
+
+  // Without an explicit deadline, the plugin's declared `timeoutMs` applies, and
+  // failing that the provider's default timeout.
+  let deadline = deadline.or_else(|| {
+    plugin
+      .config
+      .timeout_ms
+      .map(Duration::from_millis)
+      .or(plugin.default_timeout)
+      .map(|timeout| Instant::now() + timeout)
+  });
+  if deadline.is_some_and(|deadline| deadline <= Instant::now()) {
+    return Err(ProviderError::Timeout(
+      "deadline passed before the call started".to_string(),
+    ));
+  }
+  if let Some(fuel) = plugin.fuel_limit {
+    plugin
+      .store
+      .set_fuel(fuel)
+      .map_err(|e| ProviderError::InvocationFailed(format!("Failed to set fuel: {}", e)))?;
+  }
+
+  // An async engine is ticked continuously, and the deadline checked on each tick.
+  #[cfg(feature = "async")]
+  if plugin.store.engine().is_async() {
+    deadline::yield_until(&mut plugin.store, deadline);
+    return Ok(None);
+  }
+  Ok(match deadline {
+    Some(deadline) => {
+      plugin.store.set_epoch_deadline(1);
+      Some(deadline::DeadlineTimer::start(
+        plugin.store.engine(),
+        deadline.saturating_duration_since(Instant::now()),
+      ))
+    }
+    None => {
+      plugin.store.set_epoch_deadline(deadline::UNBOUNDED);
+      None
+    }
+  })
+}
+
+// Second half of `run_until`: marks the plugin failed if the call ended in a fatal
+// error.
+fn finish_call<R>(
+  plugin_name: &str,
+  plugin: &mut PluginInstance,
+  result: Result<R, ProviderError>,
+) -> Result<R, ProviderError> {
+  // Hitting the memory limit unwinds the guest mid-call, like a trap.
+  let memory_exceeded = std::mem::take(&mut plugin.store.data_mut().limiter.memory_exceeded);
+  if let Err(e) = &result
+    && (e.is_fatal() || memory_exceeded)
+  {
+    warn!(
+      "Plugin '{}' trapped and is now marked failed: {}",
+      plugin_name, e
+    );
+    plugin.failed = true;
+  }
+  result
+}
+
+// Components exchange strings through the canonical ABI, never through raw memory.
+fn strings_unsupported() -> ProviderError {
+  ProviderError::InvocationFailed(
+    "raw string access is only available for Preview 1 modules".to_string(),
+  )
+}
+
+// WIT only supports string args; serialize ProviderValue array to JSON
+fn encode_args(args: &[ProviderValue]) -> Result<String, ProviderError> {
+  try_args_to_json(args)
+    .and_then(|args| serde_json::to_string(&args).map_err(|e| e.to_string()))
+    .map_err(ProviderError::InvocationFailed)
+}
+
+// Calls `function` through the guest's entry point: `on-invoke` for components, the
+// export of that name for Preview 1 modules.
+fn call_guest(
+  plugin: &mut PluginInstance,
+  function: &str,
+  args: &[ProviderValue],
+  readonly: bool,
+) -> Result<ProviderValue, ProviderError> {
+  #[cfg(feature = "async")]
+  if matches!(plugin.guest, Guest::AsyncComponent(_)) {
+    return async_guest::block_on(call_guest_async(plugin, function, args, readonly));
+  }
+
+  plugin.store.data_mut().limiter.readonly = readonly;
+  let result = match &plugin.guest {
+    Guest::Component(bindings) => encode_args(args).and_then(|args_json| {
+      bindings
+        .bud_sdk_plugin()
+        .call_on_invoke(&mut plugin.store, function, &args_json)
+        .map_err(|e| trap::call_error(e, &mut plugin.store.data_mut().output))?
+        .map_err(ProviderError::InvocationFailed)
+        .and_then(|result_json| decode_result(&result_json))
+    }),
+    Guest::Module(instance) => preview1::call(&mut plugin.store, instance, function, args),
+    #[cfg(feature = "async")]
+    Guest::AsyncComponent(_) => unreachable!("async guest called synchronously"),
+  };
+  plugin.store.data_mut().limiter.readonly = false;
+
+  result
+}
+
+// `call_guest` for a component in an async store.
+#[cfg(feature = "async")]
+async fn call_guest_async(
+  plugin: &mut PluginInstance,
+  function: &str,
+  args: &[ProviderValue],
+  readonly: bool,
+) -> Result<ProviderValue, ProviderError> {
+  let Guest::AsyncComponent(bindings) = &plugin.guest else {
+    unreachable!("synchronous guest in an async store");
+  };
+  let args_json = encode_args(args)?;
+
+  plugin.store.data_mut().limiter.readonly = readonly;
+  let result = bindings
+    .bud_sdk_plugin()
+    .call_on_invoke(&mut plugin.store, function, &args_json)
+    .await;
+  plugin.store.data_mut().limiter.readonly = false;
+
+  result
+    .map_err(|e| trap::call_error(e, &mut plugin.store.data_mut().output))?
+    .map_err(ProviderError::InvocationFailed)
+    .and_then(|result_json| decode_result(&result_json))
+}
+
+fn decode_result(result_json: &str) -> Result<ProviderValue, ProviderError> {
+  let value = serde_json::from_str(result_json)
+    .map_err(|e| ProviderError::InvocationFailed(e.to_string()))?;
+
+  Ok(json_to_provider_value(&value))
 }
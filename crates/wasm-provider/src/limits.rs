@@ -0,0 +1,61 @@
+use shared_types::ProviderError;
+use std::sync::{Condvar, Mutex};
+
+/// What to do when an invocation would exceed the concurrency cap.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum CapacityPolicy {
+  /// Wait until a running invocation finishes.
+  #[default]
+  Block,
+  /// Fail immediately with `ProviderError::InvocationFailed("at capacity")`.
+  Reject,
+}
+
+#[derive(Default)]
+struct LimiterState {
+  active: usize,
+  max: Option<usize>,
+  policy: CapacityPolicy,
+}
+
+/// Counting semaphore shared by every invocation on one provider.
+#[derive(Default)]
+pub(crate) struct InvocationLimiter {
+  state: Mutex<LimiterState>,
+  freed: Condvar,
+}
+
+/// Held for the duration of one invocation; releases its slot on drop.
+pub(crate) struct Permit<'a> {
+  limiter: &'a InvocationLimiter,
+}
+
+impl InvocationLimiter {
+  pub(crate) fn configure(&self, max: Option<usize>, policy: CapacityPolicy) {
+    let mut state = self.state.lock().unwrap_or_else(|p| p.into_inner());
+    state.max = max;
+    state.policy = policy;
+    // A raised cap may admit callers that are already waiting.
+    self.freed.notify_all();
+  }
+
+  pub(crate) fn acquire(&self) -> Result<Permit<'_>, ProviderError> {
+    let mut state = self.state.lock().unwrap_or_else(|p| p.into_inner());
+    while state.max.is_some_and(|max| state.active >= max) {
+      if state.policy == CapacityPolicy::Reject {
+        return Err(ProviderError::InvocationFailed("at capacity".to_string()));
+      }
+      state = self.freed.wait(state).unwrap_or_else(|p| p.into_inner());
+    }
+    state.active += 1;
+    Ok(Permit { limiter: self })
+  }
+}
+
+impl Drop for Permit<'_> {
+  fn drop(&mut self) {
+    let mut state = self.limiter.state.lock().unwrap_or_else(|p| p.into_inner());
+    state.active -= 1;
+    self.limiter.freed.notify_one();
+  }
+}
@@ -0,0 +1,73 @@
+use crate::exports::bud::sdk::plugin::NumericArray;
+use shared_types::{ProviderError, ProviderValue};
+
+/// Packs a homogeneous numeric `ProviderValue::Array` into a single typed list.
+///
+/// All-integer arrays become `int32` (each element must fit in an `i32`) and all-float
+/// arrays become `float64`. Mixed, nested or non-numeric elements are rejected rather
+/// than silently coerced. An empty array is sent as an empty `int32` list.
+pub(crate) fn to_numeric_array(value: &ProviderValue) -> Result<NumericArray, ProviderError> {
+  let ProviderValue::Array(items) = value else {
+    return Err(ProviderError::InvocationFailed(
+      "numeric invoke expects an array argument".to_string(),
+    ));
+  };
+
+  match items.first() {
+    None => Ok(NumericArray::Int32(Vec::new())),
+    Some(ProviderValue::Int(_) | ProviderValue::UInt(_)) => items
+      .iter()
+      .enumerate()
+      .map(|(i, item)| match item {
+        ProviderValue::Int(n) => i32::try_from(*n).map_err(|_| too_large(i, n)),
+        ProviderValue::UInt(n) => i32::try_from(*n).map_err(|_| too_large(i, n)),
+        other => Err(mixed_element(i, other, "int")),
+      })
+      .collect::<Result<_, _>>()
+      .map(NumericArray::Int32),
+    Some(ProviderValue::Float(_)) => items
+      .iter()
+      .enumerate()
+      .map(|(i, item)| match item {
+        ProviderValue::Float(f) => Ok(*f),
+        other => Err(mixed_element(i, other, "float")),
+      })
+      .collect::<Result<_, _>>()
+      .map(NumericArray::Float64),
+    Some(other) => Err(ProviderError::InvocationFailed(format!(
+      "numeric array must contain ints or floats, found {}",
+      kind_name(other)
+    ))),
+  }
+}
+
+fn too_large(index: usize, n: impl std::fmt::Display) -> ProviderError {
+  ProviderError::InvocationFailed(format!(
+    "numeric array element {} ({}) does not fit in i32",
+    index, n
+  ))
+}
+
+fn mixed_element(index: usize, found: &ProviderValue, expected: &str) -> ProviderError {
+  ProviderError::InvocationFailed(format!(
+    "numeric array must be homogeneous: element {} is {}, expected {}",
+    index,
+    kind_name(found),
+    expected
+  ))
+}
+
+pub(crate) fn kind_name(value: &ProviderValue) -> &'static str {
+  match value {
+    ProviderValue::Null => "null",
+    ProviderValue::Bool(_) => "bool",
+    ProviderValue::Int(_) => "int",
+    ProviderValue::UInt(_) => "uint",
+    ProviderValue::Float(_) => "float",
+    ProviderValue::String(_) => "string",
+    ProviderValue::Bytes(_) => "bytes",
+    ProviderValue::Array(_) => "array",
+    ProviderValue::Tuple(_) => "tuple",
+    ProviderValue::Object(_) => "object",
+  }
+}
@@ -0,0 +1,123 @@
+use shared_types::ProviderError;
+use shared_types::config::{EnvPermission, FilesystemPermission, Permission, Permissions};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use wasmtime_wasi::{DirPerms, FilePerms, WasiCtxBuilder};
+
+/// Grants the plugin what its `filesystem` and `env` permissions declare.
+///
+/// Every `filesystem.read` directory is preopened read-only and every
+/// `filesystem.write` directory read-write, each under its own host path, so the guest
+/// sees the same absolute paths as the manifest. Without a `filesystem` permission no
+/// directory is preopened. `env: true` or `{"inherit": true}` passes the host
+/// environment through; `keys` passes only the listed variables that are set.
+/// Fails with `ProviderError::PermissionDenied` for permissions that cannot be
+/// granted as written: `filesystem: true` (which names no directory), relative paths,
+/// and directories that cannot be opened.
+pub(crate) fn configure_permissions(
+  builder: &mut WasiCtxBuilder,
+  permissions: Option<&Permissions>,
+) -> Result<(), ProviderError> {
+  let Some(permissions) = permissions else {
+    return Ok(());
+  };
+
+  match &permissions.filesystem {
+    None | Some(Permission::Bool(false)) => {}
+    Some(Permission::Bool(true)) => {
+      return Err(ProviderError::PermissionDenied(
+        "filesystem: true names no directories; list them under \"read\" and \"write\"".to_string(),
+      ));
+    }
+    Some(Permission::Config(filesystem)) => preopen_dirs(builder, filesystem)?,
+  }
+
+  match &permissions.env {
+    None | Some(Permission::Bool(false)) => {}
+    Some(Permission::Bool(true)) => {
+      builder.inherit_env();
+    }
+    Some(Permission::Config(env)) => pass_env(builder, env),
+  }
+
+  Ok(())
+}
+
+fn preopen_dirs(
+  builder: &mut WasiCtxBuilder,
+  filesystem: &FilesystemPermission,
+) -> Result<(), ProviderError> {
+  // A directory listed under both keeps the wider write access.
+  let mut dirs = BTreeMap::new();
+  for path in filesystem.read.iter().flatten() {
+    dirs.insert(path.as_str(), false);
+  }
+  for path in filesystem.write.iter().flatten() {
+    dirs.insert(path.as_str(), true);
+  }
+
+  for (path, writable) in dirs {
+    if !Path::new(path).is_absolute() {
+      return Err(ProviderError::PermissionDenied(format!(
+        "filesystem path '{}' must be absolute",
+        path
+      )));
+    }
+    let (dir_perms, file_perms) = if writable {
+      (DirPerms::all(), FilePerms::all())
+    } else {
+      (DirPerms::READ, FilePerms::READ)
+    };
+    builder
+      .preopened_dir(path, path, dir_perms, file_perms)
+      .map_err(|e| {
+        ProviderError::PermissionDenied(format!("cannot open directory '{}': {}", path, e))
+      })?;
+  }
+
+  Ok(())
+}
+
+/// Checks that `file` lies in a directory the plugin may read and resolves it.
+///
+/// Both `filesystem.read` and `filesystem.write` directories grant read access. Paths
+/// are compared after resolving symlinks and `..`, so a link cannot reach outside
+/// them. Fails with `ProviderError::PermissionDenied` otherwise, including for files
+/// that do not exist.
+pub(crate) fn check_readable_file(
+  permissions: Option<&Permissions>,
+  file: &Path,
+) -> Result<PathBuf, ProviderError> {
+  let denied = || {
+    ProviderError::PermissionDenied(format!(
+      "file '{}' is not under a filesystem.read directory",
+      file.display()
+    ))
+  };
+
+  let Some(Permission::Config(filesystem)) = permissions.and_then(|p| p.filesystem.as_ref()) else {
+    return Err(denied());
+  };
+  let file = file.canonicalize().map_err(|_| denied())?;
+  let allowed = filesystem
+    .read
+    .iter()
+    .chain(filesystem.write.iter())
+    .flatten()
+    .filter_map(|dir| Path::new(dir).canonicalize().ok())
+    .any(|dir| file.starts_with(dir));
+
+  if allowed { Ok(file) } else { Err(denied()) }
+}
+
+fn pass_env(builder: &mut WasiCtxBuilder, env: &EnvPermission) {
+  if env.inherit == Some(true) {
+    builder.inherit_env();
+    return;
+  }
+  for key in env.keys.iter().flatten() {
+    if let Ok(value) = std::env::var(key) {
+      builder.env(key, value);
+    }
+  }
+}
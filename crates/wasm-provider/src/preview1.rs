@@ -0,0 +1,450 @@
+use crate::PluginState;
+use crate::numeric::kind_name;
+use crate::trap;
+use shared_types::provider::HostFunction;
+use shared_types::{FunctionSignature, ProviderError, ProviderValue, ValueKind};
+use std::collections::HashMap;
+use std::sync::Arc;
+use wasmtime::{
+  Caller, Engine, ExternType, Func, FuncType, Instance, Linker, Module, Store, Val, ValType,
+};
+
+/// Export a Preview 1 module provides to receive string arguments:
+/// `alloc(len: i32) -> i32` returns a pointer to `len` writable bytes of its memory.
+pub const ALLOC_EXPORT: &str = "alloc";
+
+/// Optional export the host calls to hand back memory from `ALLOC_EXPORT`, and to
+/// release returned strings once it has read them: `dealloc(ptr: i32, len: i32)`.
+pub const DEALLOC_EXPORT: &str = "dealloc";
+
+/// WASI version plugins are built against.
+///
+/// Selected with `WasmProviderBuilder::wasi_version`; it decides what `load` and
+/// `load_bytes` compile `main.wasm` as.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum WasiVersion {
+  /// Core modules importing `wasi_snapshot_preview1`.
+  ///
+  /// Exported functions are called directly: arguments and results must be numbers
+  /// (`Int` for `i32`/`i64`, `Int` or `Float` for `f32`/`f64`), and a function with
+  /// several results returns them as a `Tuple`. A reactor's `_initialize` export runs
+  /// once after instantiation. Injected host functions are imported as core functions
+  /// from the host module (see `WasmProviderBuilder::host_module`) and are limited to
+  /// numeric parameters and results in the same way.
+  ///
+  /// Modules exporting `memory` and `ALLOC_EXPORT` also exchange strings and bytes:
+  /// each `String` or `Bytes` argument is copied into guest memory and passed as two
+  /// `i32`s, pointer then length, and a function returning exactly two `i32`s returns
+  /// a value the same way, released through `DEALLOC_EXPORT` if exported. That value
+  /// is `Bytes` when any argument was `Bytes`, and otherwise a `String` read as UTF-8.
+  Preview1,
+  /// Components implementing the `bud-plugin` world from `wit/bud.wit`.
+  #[default]
+  Preview2,
+}
+
+// Linker with the WASI Preview 1 functions registered.
+pub(crate) fn linker(engine: &Engine) -> wasmtime::Result<Linker<PluginState>> {
+  let mut linker = Linker::new(engine);
+  wasmtime_wasi::p1::add_to_linker_sync(&mut linker, |state: &mut PluginState| {
+    state
+      .preview1
+      .as_mut()
+      .expect("Preview 1 module instantiated in a store without a Preview 1 context")
+  })?;
+  Ok(linker)
+}
+
+// Clone of `linker` that also defines every function `module` imports from
+// `host_module`, typed after the import and backed by the injected host function of
+// that name.
+pub(crate) fn link_host_functions(
+  linker: &Linker<PluginState>,
+  module: &Module,
+  host_module: &str,
+  injected: &HashMap<String, Arc<HostFunction>>,
+) -> Result<Linker<PluginState>, String> {
+  let mut linker = linker.clone();
+  let mut missing = Vec::new();
+
+  for import in module.imports().filter(|i| i.module() == host_module) {
+    let name = import.name();
+    let ExternType::Func(ty) = import.ty() else {
+      return Err(format!("'{}::{}' is not a function", host_module, name));
+    };
+    let Some(function) = injected.get(name) else {
+      missing.push(format!("{}::{}", host_module, name));
+      continue;
+    };
+    if let Some(unsupported) = ty
+      .params()
+      .chain(ty.results())
+      .find(|t| value_kind(t).is_none())
+    {
+      return Err(format!(
+        "host function '{}' uses {}, only numeric types are supported",
+        name, unsupported
+      ));
+    }
+
+    linker
+      .func_new(
+        host_module,
+        name,
+        ty.clone(),
+        adapt(Arc::clone(function), ty),
+      )
+      .map_err(|e| e.to_string())?;
+  }
+
+  if !missing.is_empty() {
+    return Err(format!(
+      "imports host functions that were not injected: {}",
+      missing.join(", ")
+    ));
+  }
+  Ok(linker)
+}
+
+// Calls `function` with the guest's arguments and converts its value back to `ty`'s
+// results, a `Tuple` (or `Array`) for several. An `Err` from the host function traps the guest.
+fn adapt(
+  function: Arc<HostFunction>,
+  ty: FuncType,
+) -> impl Fn(Caller<'_, PluginState>, &[Val], &mut [Val]) -> wasmtime::Result<()> + Send + Sync + 'static
+{
+  move |_caller, params, results| {
+    let args = params
+      .iter()
+      .map(to_provider_value)
+      .collect::<Result<Vec<_>, _>>()
+      .map_err(wasmtime::Error::msg)?;
+    let value = function(args).map_err(|e| wasmtime::Error::msg(e.to_string()))?;
+
+    let values = match (results.len(), value) {
+      (0, _) => Vec::new(),
+      (1, value) => vec![value],
+      (_, ProviderValue::Tuple(values) | ProviderValue::Array(values)) => values,
+      (n, value) => {
+        return Err(wasmtime::Error::msg(format!(
+          "result: expected a tuple of {} values, found {}",
+          n,
+          kind_name(&value)
+        )));
+      }
+    };
+    if values.len() != results.len() {
+      return Err(wasmtime::Error::msg(format!(
+        "result: expected {} values, found {}",
+        results.len(),
+        values.len()
+      )));
+    }
+
+    for (slot, (value, ty)) in results.iter_mut().zip(values.iter().zip(ty.results())) {
+      *slot = to_val(value, &ty).map_err(|e| wasmtime::Error::msg(format!("result: {}", e)))?;
+    }
+    Ok(())
+  }
+}
+
+// Runs the reactor initializer, if the module exports one.
+pub(crate) fn initialize(
+  store: &mut Store<PluginState>,
+  instance: &Instance,
+) -> wasmtime::Result<()> {
+  match instance.get_typed_func::<(), ()>(&mut *store, "_initialize") {
+    Ok(init) => init.call(&mut *store, ()),
+    Err(_) => Ok(()),
+  }
+}
+
+// Calls the exported `function` with `args` converted to its parameter types.
+pub(crate) fn call(
+  store: &mut Store<PluginState>,
+  instance: &Instance,
+  function: &str,
+  args: &[ProviderValue],
+) -> Result<ProviderValue, ProviderError> {
+  let func = lookup(store, instance, function)?;
+  call_func(store, instance, function, func, args)
+}
+
+// Like `call`, for an export `func` of `instance` looked up earlier under the name
+// `function`.
+pub(crate) fn call_func(
+  store: &mut Store<PluginState>,
+  instance: &Instance,
+  function: &str,
+  func: Func,
+  args: &[ProviderValue],
+) -> Result<ProviderValue, ProviderError> {
+  let strings = supports_strings(store, instance);
+  let returns_bytes = args.iter().any(|arg| matches!(arg, ProviderValue::Bytes(_)));
+
+  // Strings and bytes are lowered to a pointer and a length before the arity check.
+  let mut lowered = Vec::with_capacity(args.len());
+  let mut written = Vec::new();
+  for arg in args {
+    let bytes = match arg {
+      ProviderValue::String(s) => Some(s.as_bytes()),
+      ProviderValue::Bytes(bytes) => Some(bytes.as_slice()),
+      _ => None,
+    };
+    match bytes {
+      Some(bytes) if strings => {
+        let (ptr, len) = write_bytes(store, instance, bytes)?;
+        lowered.extend([
+          ProviderValue::Int(ptr.into()),
+          ProviderValue::Int(len.into()),
+        ]);
+        written.push((ptr, len));
+      }
+      _ => lowered.push(arg.clone()),
+    }
+  }
+
+  check_arity(store, function, func, lowered.len())?;
+  let ty = func.ty(&*store);
+  let params = ty
+    .params()
+    .zip(&lowered)
+    .enumerate()
+    .map(|(i, (ty, arg))| to_val(arg, &ty).map_err(|e| format!("arg {}: {}", i, e)))
+    .collect::<Result<Vec<_>, _>>()
+    .map_err(ProviderError::InvocationFailed)?;
+  let results = call_vals(store, func, &params)?;
+
+  for (ptr, len) in written {
+    dealloc(store, instance, ptr, len)?;
+  }
+  if let (true, [Val::I32(ptr), Val::I32(len)]) = (strings, results.as_slice()) {
+    let bytes = read_bytes(store, instance, *ptr, *len)?;
+    dealloc(store, instance, *ptr, *len)?;
+    if returns_bytes {
+      return Ok(ProviderValue::Bytes(bytes));
+    }
+    let s = String::from_utf8(bytes).map_err(|e| ProviderError::InvocationFailed(e.to_string()))?;
+    return Ok(ProviderValue::String(s));
+  }
+
+  let mut values = results
+    .iter()
+    .map(to_provider_value)
+    .collect::<Result<Vec<_>, _>>()
+    .map_err(ProviderError::InvocationFailed)?;
+  Ok(match values.len() {
+    0 => ProviderValue::Null,
+    1 => values.remove(0),
+    _ => ProviderValue::Tuple(values),
+  })
+}
+
+// Whether the module exports what the string convention needs.
+fn supports_strings(store: &mut Store<PluginState>, instance: &Instance) -> bool {
+  instance.get_memory(&mut *store, "memory").is_some()
+    && instance.get_func(&mut *store, ALLOC_EXPORT).is_some()
+}
+
+// Copies `s` into memory obtained from the guest's `alloc`, returning pointer and length.
+pub(crate) fn write_string(
+  store: &mut Store<PluginState>,
+  instance: &Instance,
+  s: &str,
+) -> Result<(i32, i32), ProviderError> {
+  write_bytes(store, instance, s.as_bytes())
+}
+
+// `write_string` for bytes of any content.
+fn write_bytes(
+  store: &mut Store<PluginState>,
+  instance: &Instance,
+  bytes: &[u8],
+) -> Result<(i32, i32), ProviderError> {
+  let len = i32::try_from(bytes.len()).map_err(|_| {
+    ProviderError::InvocationFailed(format!("value of {} bytes is too long", bytes.len()))
+  })?;
+  let memory = guest_memory(store, instance)?;
+  let alloc = instance
+    .get_typed_func::<i32, i32>(&mut *store, ALLOC_EXPORT)
+    .map_err(|e| ProviderError::InvocationFailed(format!("{}: {}", ALLOC_EXPORT, e)))?;
+
+  let ptr = alloc
+    .call(&mut *store, len)
+    .map_err(|e| trap::call_error(e, &mut store.data_mut().output))?;
+  memory
+    .write(&mut *store, ptr as u32 as usize, bytes)
+    .map_err(|e| {
+      ProviderError::InvocationFailed(format!(
+        "{} returned an invalid pointer: {}",
+        ALLOC_EXPORT, e
+      ))
+    })?;
+  Ok((ptr, len))
+}
+
+// Reads `len` bytes of UTF-8 at `ptr` from the guest's memory.
+pub(crate) fn read_string(
+  store: &mut Store<PluginState>,
+  instance: &Instance,
+  ptr: i32,
+  len: i32,
+) -> Result<String, ProviderError> {
+  let bytes = read_bytes(store, instance, ptr, len)?;
+  String::from_utf8(bytes).map_err(|e| ProviderError::InvocationFailed(e.to_string()))
+}
+
+// Reads `len` bytes at `ptr` from the guest's memory.
+fn read_bytes(
+  store: &mut Store<PluginState>,
+  instance: &Instance,
+  ptr: i32,
+  len: i32,
+) -> Result<Vec<u8>, ProviderError> {
+  let memory = guest_memory(store, instance)?;
+  let mut bytes = vec![0; len as u32 as usize];
+  memory
+    .read(&*store, ptr as u32 as usize, &mut bytes)
+    .map_err(|e| ProviderError::InvocationFailed(format!("value out of bounds: {}", e)))?;
+  Ok(bytes)
+}
+
+fn guest_memory(
+  store: &mut Store<PluginState>,
+  instance: &Instance,
+) -> Result<wasmtime::Memory, ProviderError> {
+  instance
+    .get_memory(&mut *store, "memory")
+    .ok_or_else(|| ProviderError::InvocationFailed("module exports no memory".to_string()))
+}
+
+// Hands a string's memory back to the guest, if it exports `dealloc`.
+fn dealloc(
+  store: &mut Store<PluginState>,
+  instance: &Instance,
+  ptr: i32,
+  len: i32,
+) -> Result<(), ProviderError> {
+  let Ok(dealloc) = instance.get_typed_func::<(i32, i32), ()>(&mut *store, DEALLOC_EXPORT) else {
+    return Ok(());
+  };
+  dealloc
+    .call(&mut *store, (ptr, len))
+    .map_err(|e| trap::call_error(e, &mut store.data_mut().output))
+}
+
+// Calls the exported `function` with `args` as given, returning its results unconverted.
+pub(crate) fn call_raw(
+  store: &mut Store<PluginState>,
+  instance: &Instance,
+  function: &str,
+  args: &[Val],
+) -> Result<Vec<Val>, ProviderError> {
+  let func = lookup(store, instance, function)?;
+  check_arity(store, function, func, args.len())?;
+  call_vals(store, func, args)
+}
+
+fn call_vals(
+  store: &mut Store<PluginState>,
+  func: Func,
+  args: &[Val],
+) -> Result<Vec<Val>, ProviderError> {
+  let mut results = vec![Val::I32(0); func.ty(&*store).results().len()];
+
+  func
+    .call(&mut *store, args, &mut results)
+    .map_err(|e| trap::call_error(e, &mut store.data_mut().output))?;
+  Ok(results)
+}
+
+// Looks up the exported `function`.
+pub(crate) fn lookup(
+  store: &mut Store<PluginState>,
+  instance: &Instance,
+  function: &str,
+) -> Result<Func, ProviderError> {
+  instance
+    .get_func(&mut *store, function)
+    .ok_or_else(|| ProviderError::InvocationFailed(format!("unknown function '{}'", function)))
+}
+
+// Checks that `func`, exported as `function`, takes `arity` arguments.
+fn check_arity(
+  store: &Store<PluginState>,
+  function: &str,
+  func: Func,
+  arity: usize,
+) -> Result<(), ProviderError> {
+  let params = func.ty(store).params().len();
+  if params != arity {
+    return Err(ProviderError::InvocationFailed(format!(
+      "'{}' takes {} argument(s), got {}",
+      function, params, arity
+    )));
+  }
+  Ok(())
+}
+
+// Signatures of the module's exported functions. Functions with non-numeric
+// parameters or results cannot be called through `call` and are left out.
+pub(crate) fn describe(module: &Module) -> Vec<FunctionSignature> {
+  module
+    .exports()
+    .filter_map(|export| {
+      let ExternType::Func(ty) = export.ty() else {
+        return None;
+      };
+      Some(FunctionSignature {
+        name: export.name().to_string(),
+        params: ty.params().map(|t| value_kind(&t)).collect::<Option<_>>()?,
+        results: ty
+          .results()
+          .map(|t| value_kind(&t))
+          .collect::<Option<_>>()?,
+      })
+    })
+    .filter(|sig| sig.name != "_initialize")
+    .collect()
+}
+
+fn value_kind(ty: &ValType) -> Option<ValueKind> {
+  match ty {
+    ValType::I32 => Some(ValueKind::I32),
+    ValType::I64 => Some(ValueKind::I64),
+    ValType::F32 => Some(ValueKind::F32),
+    ValType::F64 => Some(ValueKind::F64),
+    _ => None,
+  }
+}
+
+fn to_val(arg: &ProviderValue, ty: &ValType) -> Result<Val, String> {
+  Ok(match (ty, arg) {
+    (ValType::I32, ProviderValue::Int(n)) => {
+      Val::I32(i32::try_from(*n).map_err(|_| format!("{} does not fit in i32", n))?)
+    }
+    (ValType::I64, ProviderValue::Int(n)) => Val::I64(*n),
+    // Core wasm integers have no sign; the guest reads the same bits as unsigned.
+    (ValType::I32, ProviderValue::UInt(n)) => {
+      Val::I32(u32::try_from(*n).map_err(|_| format!("{} does not fit in u32", n))? as i32)
+    }
+    (ValType::I64, ProviderValue::UInt(n)) => Val::I64(*n as i64),
+    (ValType::F32, ProviderValue::UInt(n)) => Val::F32((*n as f32).to_bits()),
+    (ValType::F64, ProviderValue::UInt(n)) => Val::F64((*n as f64).to_bits()),
+    (ValType::F32, ProviderValue::Int(n)) => Val::F32((*n as f32).to_bits()),
+    (ValType::F32, ProviderValue::Float(f)) => Val::F32((*f as f32).to_bits()),
+    (ValType::F64, ProviderValue::Int(n)) => Val::F64((*n as f64).to_bits()),
+    (ValType::F64, ProviderValue::Float(f)) => Val::F64(f.to_bits()),
+    (ty, arg) => return Err(format!("expected {}, found {}", ty, kind_name(arg))),
+  })
+}
+
+fn to_provider_value(val: &Val) -> Result<ProviderValue, String> {
+  match val {
+    Val::I32(n) => Ok(ProviderValue::Int((*n).into())),
+    Val::I64(n) => Ok(ProviderValue::Int(*n)),
+    Val::F32(bits) => Ok(ProviderValue::Float(f32::from_bits(*bits).into())),
+    Val::F64(bits) => Ok(ProviderValue::Float(f64::from_bits(*bits))),
+    _ => Err("result: only numeric results are supported".to_string()),
+  }
+}
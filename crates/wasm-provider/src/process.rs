@@ -0,0 +1,339 @@
+use crate::{LoadTiming, StdioConfig, StdioMode, WasiVersion, WasmProvider};
+use serde_json::{Value, json};
+use shared_types::{PluginConfigData, Provider, ProviderError, ProviderValue};
+use std::io::{self, BufRead, BufReader, Write};
+use std::path::{Path, PathBuf};
+use std::process::{Child, ChildStdin, ChildStdout, Command, Stdio};
+use std::time::{Duration, Instant};
+use utils::provider_json::{json_to_provider_value, provider_value_to_json, try_args_to_json};
+
+/// Serves plugin requests read line by line from `input`, writing one response line
+/// per request to `output`, until `input` closes.
+///
+/// This is the loop behind the `bud-wasm-worker` binary that
+/// `WasmProviderBuilder::process_isolation` spawns; a custom worker executable only
+/// needs to call it with its own stdin and stdout. Requests and responses are JSON
+/// objects, one per line. The protocol is private to this crate and may change between
+/// versions, so the worker must be built from the same version as the host.
+///
+/// # Errors
+///
+/// Returns the I/O error if reading a request or writing a response fails.
+pub fn run_worker(input: impl BufRead, mut output: impl Write) -> io::Result<()> {
+  let mut provider = None;
+  for line in input.lines() {
+    let line = line?;
+    if line.trim().is_empty() {
+      continue;
+    }
+
+    let response = match serde_json::from_str::<Value>(&line) {
+      Ok(request) => handle_request(&mut provider, &request),
+      Err(e) => error_response(&ProviderError::InvocationFailed(format!(
+        "malformed worker request: {}",
+        e
+      ))),
+    };
+    writeln!(output, "{}", response)?;
+    output.flush()?;
+  }
+  Ok(())
+}
+
+// Runs one request against the worker's provider, created by the `init` request.
+fn handle_request(provider: &mut Option<WasmProvider>, request: &Value) -> Value {
+  let op = request["op"].as_str().unwrap_or_default();
+  if op == "init" {
+    return match init_provider(request) {
+      Ok(ready) => {
+        *provider = Some(ready);
+        json!({ "ok": null })
+      }
+      Err(e) => error_response(&e),
+    };
+  }
+
+  let Some(provider) = provider.as_ref() else {
+    return error_response(&ProviderError::LoadFailed(
+      "worker not initialized".to_string(),
+    ));
+  };
+  match op {
+    "load" => match load_plugin(provider, request) {
+      Ok(ok) => json!({ "ok": ok }),
+      Err(e) => error_response(&e),
+    },
+    "invoke" => invoke_plugin(provider, request),
+    other => error_response(&ProviderError::InvocationFailed(format!(
+      "unknown worker request '{}'",
+      other
+    ))),
+  }
+}
+
+fn init_provider(request: &Value) -> Result<WasmProvider, ProviderError> {
+  let wasi_version = match request["wasiVersion"].as_str() {
+    Some("preview1") => WasiVersion::Preview1,
+    _ => WasiVersion::Preview2,
+  };
+  // The worker's stdout carries responses, so plugin stdout is captured and relayed.
+  let stderr = request["stderr"]
+    .as_str()
+    .and_then(|mode| mode.parse().ok())
+    .unwrap_or_default();
+
+  let default_timeout = request["defaultTimeoutMs"]
+    .as_u64()
+    .map(Duration::from_millis);
+
+  let provider = WasmProvider::builder()
+    .wasi_version(wasi_version)
+    .default_timeout(default_timeout)
+    .max_memory(
+      request["maxMemory"]
+        .as_u64()
+        .and_then(|max| usize::try_from(max).ok()),
+    )
+    .compile_cache(request["compileCache"].as_str().map(PathBuf::from))
+    .host_module(request["hostModule"].as_str().unwrap_or_default())
+    .stdio_streams(StdioConfig {
+      stdin: StdioMode::Null,
+      stdout: StdioMode::Capture,
+      stderr,
+    })
+    .build();
+  provider.configure(&request["settings"])?;
+  provider.init()?;
+  Ok(provider)
+}
+
+fn load_plugin(provider: &WasmProvider, request: &Value) -> Result<Value, ProviderError> {
+  let path = request["path"]
+    .as_str()
+    .ok_or_else(|| ProviderError::LoadFailed("worker load request without a path".to_string()))?;
+  let config: PluginConfigData = serde_json::from_value(request["config"].clone())
+    .map_err(|e| ProviderError::LoadFailed(format!("invalid plugin config: {}", e)))?;
+
+  let timing = provider.load_timed(path, &config)?;
+  Ok(json!({
+    "compileUs": timing.compile.as_micros() as u64,
+    "instantiateUs": timing.instantiate.as_micros() as u64,
+    "cached": timing.cached,
+  }))
+}
+
+fn invoke_plugin(provider: &WasmProvider, request: &Value) -> Value {
+  let plugin = request["plugin"].as_str().unwrap_or_default();
+  let function = request["function"].as_str().unwrap_or_default();
+  let args = request["args"]
+    .as_array()
+    .map(|args| args.iter().map(json_to_provider_value).collect())
+    .unwrap_or_default();
+
+  let result = match request["timeoutMs"].as_u64() {
+    Some(ms) => provider.invoke_with_deadline(
+      plugin,
+      function,
+      args,
+      Instant::now() + Duration::from_millis(ms),
+    ),
+    None if request["readonly"] == json!(true) => provider.invoke_readonly(plugin, function, args),
+    None => provider.invoke(plugin, function, args),
+  };
+
+  let mut response = match result {
+    Ok(value) => json!({ "ok": provider_value_to_json(&value) }),
+    Err(e) => error_response(&e),
+  };
+  response["stdout"] = provider.take_stdout(plugin).unwrap_or_default().into();
+  response
+}
+
+fn error_response(error: &ProviderError) -> Value {
+  let (kind, message) = match error {
+    ProviderError::InitFailed => ("InitFailed", ""),
+    ProviderError::LoadFailed(msg) => ("LoadFailed", msg.as_str()),
+    ProviderError::InjectionFailed(msg) => ("InjectionFailed", msg.as_str()),
+    ProviderError::InvocationFailed(msg) => ("InvocationFailed", msg.as_str()),
+    ProviderError::Trap(msg) => ("Trap", msg.as_str()),
+    ProviderError::Timeout(msg) => ("Timeout", msg.as_str()),
+    ProviderError::UnloadFailed(msg) => ("UnloadFailed", msg.as_str()),
+    ProviderError::PermissionDenied(msg) => ("PermissionDenied", msg.as_str()),
+  };
+  json!({ "error": { "kind": kind, "message": message } })
+}
+
+fn error_from_json(error: &Value) -> ProviderError {
+  let message = error["message"].as_str().unwrap_or_default().to_string();
+  match error["kind"].as_str().unwrap_or_default() {
+    "InitFailed" => ProviderError::InitFailed,
+    "LoadFailed" => ProviderError::LoadFailed(message),
+    "InjectionFailed" => ProviderError::InjectionFailed(message),
+    "Trap" => ProviderError::Trap(message),
+    "Timeout" => ProviderError::Timeout(message),
+    "UnloadFailed" => ProviderError::UnloadFailed(message),
+    "PermissionDenied" => ProviderError::PermissionDenied(message),
+    _ => ProviderError::InvocationFailed(message),
+  }
+}
+
+/// Host side of one worker process, which runs a single plugin.
+pub(crate) struct Worker {
+  child: Child,
+  stdin: ChildStdin,
+  responses: BufReader<ChildStdout>,
+  // Directory and config the plugin was loaded with, for `reload`.
+  pub(crate) path: PathBuf,
+  pub(crate) config: PluginConfigData,
+  // Set when the plugin trapped or the process died, until the plugin is reloaded.
+  pub(crate) failed: bool,
+  // Plugin stdout relayed by the worker and not yet handed on by the host.
+  pub(crate) stdout: String,
+}
+
+impl Worker {
+  // Starts `program` and initializes its provider with `init` (an `init` request).
+  pub(crate) fn spawn(
+    program: &Path,
+    init: &Value,
+    path: &Path,
+    config: &PluginConfigData,
+  ) -> Result<Self, ProviderError> {
+    let mut child = Command::new(program)
+      .stdin(Stdio::piped())
+      .stdout(Stdio::piped())
+      .stderr(Stdio::inherit())
+      .spawn()
+      .map_err(|e| {
+        ProviderError::LoadFailed(format!(
+          "Failed to start worker {}: {}",
+          program.display(),
+          e
+        ))
+      })?;
+    let (Some(stdin), Some(stdout)) = (child.stdin.take(), child.stdout.take()) else {
+      let _ = child.kill();
+      return Err(ProviderError::LoadFailed(
+        "worker has no stdio pipes".to_string(),
+      ));
+    };
+
+    let mut worker = Self {
+      child,
+      stdin,
+      responses: BufReader::new(stdout),
+      path: path.to_path_buf(),
+      config: config.clone(),
+      failed: false,
+      stdout: String::new(),
+    };
+    worker.request(init)?;
+    Ok(worker)
+  }
+
+  pub(crate) fn pid(&self) -> u32 {
+    self.child.id()
+  }
+
+  // Loads the plugin directory the worker was spawned for.
+  pub(crate) fn load(&mut self) -> Result<LoadTiming, ProviderError> {
+    let config = serde_json::to_value(&self.config)
+      .map_err(|e| ProviderError::LoadFailed(format!("invalid plugin config: {}", e)))?;
+    let request = json!({
+      "op": "load",
+      "path": self.path.to_string_lossy(),
+      "config": config,
+    });
+    let timing = self.request(&request)?;
+    let micros = |key: &str| Duration::from_micros(timing[key].as_u64().unwrap_or_default());
+    Ok(LoadTiming {
+      compile: micros("compileUs"),
+      instantiate: micros("instantiateUs"),
+      cached: timing["cached"] == json!(true),
+    })
+  }
+
+  // Invokes `function` in the worker, appending what the plugin printed to `stdout`.
+  // Fatal errors, including the worker exiting, leave the worker failed.
+  pub(crate) fn invoke(
+    &mut self,
+    plugin_name: &str,
+    function: &str,
+    args: &[ProviderValue],
+    readonly: bool,
+    deadline: Option<Instant>,
+  ) -> Result<ProviderValue, ProviderError> {
+    if self.failed {
+      return Err(ProviderError::InvocationFailed(
+        crate::FAILED_STATE_MESSAGE.to_string(),
+      ));
+    }
+
+    let args = try_args_to_json(args).map_err(ProviderError::InvocationFailed)?;
+    let mut request = json!({
+      "op": "invoke",
+      "plugin": plugin_name,
+      "function": function,
+      "args": args,
+      "readonly": readonly,
+    });
+    if let Some(deadline) = deadline {
+      let remaining = deadline.saturating_duration_since(Instant::now());
+      request["timeoutMs"] = (remaining.as_millis() as u64).into();
+    }
+
+    let response = self.send(&request)?;
+    self
+      .stdout
+      .push_str(response["stdout"].as_str().unwrap_or_default());
+    let result = into_result(response).map(|value| json_to_provider_value(&value));
+    if let Err(e) = &result {
+      self.failed |= e.is_fatal();
+    }
+    result
+  }
+
+  // Sends a request and unwraps its `ok` payload.
+  fn request(&mut self, request: &Value) -> Result<Value, ProviderError> {
+    self.send(request).and_then(into_result)
+  }
+
+  // Writes one request line and reads the response line. A closed pipe means the
+  // worker died, which marks it failed.
+  fn send(&mut self, request: &Value) -> Result<Value, ProviderError> {
+    let mut line = String::new();
+    let exchanged = writeln!(self.stdin, "{}", request)
+      .and_then(|()| self.stdin.flush())
+      .and_then(|()| self.responses.read_line(&mut line));
+
+    match exchanged {
+      Ok(n) if n > 0 => serde_json::from_str(&line)
+        .map_err(|e| ProviderError::InvocationFailed(format!("malformed worker response: {}", e))),
+      _ => {
+        self.failed = true;
+        let status = self
+          .child
+          .wait()
+          .map_or_else(|e| e.to_string(), |status| status.to_string());
+        Err(ProviderError::Trap(format!(
+          "worker process exited ({})",
+          status
+        )))
+      }
+    }
+  }
+}
+
+impl Drop for Worker {
+  fn drop(&mut self) {
+    let _ = self.child.kill();
+    let _ = self.child.wait();
+  }
+}
+
+fn into_result(mut response: Value) -> Result<Value, ProviderError> {
+  match response.get("error") {
+    Some(error) => Err(error_from_json(error)),
+    None => Ok(response["ok"].take()),
+  }
+}
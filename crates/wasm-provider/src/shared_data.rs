@@ -0,0 +1,50 @@
+use std::collections::HashMap;
+use std::sync::{Arc, RwLock};
+
+/// Read-only datasets the host shares with every plugin of a provider, by name.
+///
+/// Cloning is cheap and every clone sees the same datasets, so plugins loaded before a
+/// dataset was shared can read it too.
+#[derive(Clone, Default)]
+pub(crate) struct SharedData {
+  datasets: Arc<RwLock<HashMap<String, Arc<[u8]>>>>,
+}
+
+impl SharedData {
+  pub(crate) fn insert(&self, name: String, data: Arc<[u8]>) -> Option<Arc<[u8]>> {
+    self
+      .datasets
+      .write()
+      .unwrap_or_else(|p| p.into_inner())
+      .insert(name, data)
+  }
+
+  pub(crate) fn remove(&self, name: &str) -> Option<Arc<[u8]>> {
+    self
+      .datasets
+      .write()
+      .unwrap_or_else(|p| p.into_inner())
+      .remove(name)
+  }
+
+  fn get(&self, name: &str) -> Option<Arc<[u8]>> {
+    self
+      .datasets
+      .read()
+      .unwrap_or_else(|p| p.into_inner())
+      .get(name)
+      .cloned()
+  }
+
+  pub(crate) fn size(&self, name: &str) -> Option<u64> {
+    self.get(name).map(|data| data.len() as u64)
+  }
+
+  // Copies out the requested range only, clamped to the end of the dataset.
+  pub(crate) fn read(&self, name: &str, offset: u64, len: u32) -> Option<Vec<u8>> {
+    let data = self.get(name)?;
+    let start = usize::try_from(offset).map_or(data.len(), |offset| offset.min(data.len()));
+    let end = start.saturating_add(len as usize).min(data.len());
+    Some(data[start..end].to_vec())
+  }
+}
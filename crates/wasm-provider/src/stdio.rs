@@ -0,0 +1,148 @@
+use log::warn;
+use std::str::FromStr;
+use wasmtime_wasi::WasiCtxBuilder;
+use wasmtime_wasi::p2::pipe::MemoryOutputPipe;
+
+/// Environment variable selecting the default stdio behavior at provider init.
+pub const STDIO_ENV_VAR: &str = "BUD_PLUGIN_STDIO";
+
+/// Maximum number of bytes buffered per captured stream.
+const CAPTURE_CAPACITY: usize = 1024 * 1024;
+
+/// How a plugin's standard streams are wired to the host.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum StdioMode {
+  /// Share the host process's stdin/stdout/stderr.
+  #[default]
+  Inherit,
+  /// Buffer plugin output in memory so the host can read it back.
+  Capture,
+  /// Discard plugin output and provide an empty stdin.
+  Null,
+}
+
+impl StdioMode {
+  /// Reads the default mode from `BUD_PLUGIN_STDIO`.
+  ///
+  /// Returns `None` when the variable is unset or holds an unknown value
+  /// (the latter is logged as a warning).
+  pub fn from_env() -> Option<Self> {
+    let value = std::env::var(STDIO_ENV_VAR).ok()?;
+    match value.parse() {
+      Ok(mode) => Some(mode),
+      Err(e) => {
+        warn!("Ignoring {}: {}", STDIO_ENV_VAR, e);
+        None
+      }
+    }
+  }
+}
+
+impl FromStr for StdioMode {
+  type Err = String;
+
+  fn from_str(s: &str) -> Result<Self, Self::Err> {
+    match s.trim().to_ascii_lowercase().as_str() {
+      "inherit" => Ok(StdioMode::Inherit),
+      "capture" => Ok(StdioMode::Capture),
+      "null" => Ok(StdioMode::Null),
+      other => Err(format!(
+        "unknown stdio mode '{}', expected inherit|capture|null",
+        other
+      )),
+    }
+  }
+}
+
+/// In-memory buffers for a plugin's captured output streams.
+///
+/// Reads are incremental: each `take` returns only what was written since the previous one.
+#[derive(Clone)]
+pub struct CapturedStream {
+  pipe: MemoryOutputPipe,
+  consumed: usize,
+}
+
+impl CapturedStream {
+  fn new() -> Self {
+    Self {
+      pipe: MemoryOutputPipe::new(CAPTURE_CAPACITY),
+      consumed: 0,
+    }
+  }
+
+  /// Returns output written since the last call, lossily decoded as UTF-8.
+  pub fn take(&mut self) -> String {
+    let contents = self.pipe.contents();
+    let fresh = String::from_utf8_lossy(&contents[self.consumed..]).into_owned();
+    self.consumed = contents.len();
+    fresh
+  }
+}
+
+/// Captured stdout/stderr for one plugin; streams are `None` unless captured.
+#[derive(Default)]
+pub struct CapturedOutput {
+  pub stdout: Option<CapturedStream>,
+  pub stderr: Option<CapturedStream>,
+}
+
+/// Per-stream stdio modes for a plugin.
+///
+/// `StdioMode` converts into a config applying the same mode to all three streams.
+/// Capturing stdin has nothing to buffer, so `Capture` there gives an empty stdin,
+/// the same as `Null`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct StdioConfig {
+  pub stdin: StdioMode,
+  pub stdout: StdioMode,
+  pub stderr: StdioMode,
+}
+
+impl From<StdioMode> for StdioConfig {
+  fn from(mode: StdioMode) -> Self {
+    Self {
+      stdin: mode,
+      stdout: mode,
+      stderr: mode,
+    }
+  }
+}
+
+/// Applies `config` to the WASI builder stream by stream and returns any capture
+/// buffers it created.
+///
+/// Streams left at `Null` keep the WasiCtxBuilder default, a closed/empty stream.
+pub(crate) fn configure_stdio(builder: &mut WasiCtxBuilder, config: StdioConfig) -> CapturedOutput {
+  if config.stdin == StdioMode::Inherit {
+    builder.inherit_stdin();
+  }
+
+  let stdout = match config.stdout {
+    StdioMode::Inherit => {
+      builder.inherit_stdout();
+      None
+    }
+    StdioMode::Capture => {
+      let stream = CapturedStream::new();
+      builder.stdout(stream.pipe.clone());
+      Some(stream)
+    }
+    StdioMode::Null => None,
+  };
+
+  let stderr = match config.stderr {
+    StdioMode::Inherit => {
+      builder.inherit_stderr();
+      None
+    }
+    StdioMode::Capture => {
+      let stream = CapturedStream::new();
+      builder.stderr(stream.pipe.clone());
+      Some(stream)
+    }
+    StdioMode::Null => None,
+  };
+
+  CapturedOutput { stdout, stderr }
+}
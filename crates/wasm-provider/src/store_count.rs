@@ -0,0 +1,53 @@
+use log::warn;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+/// Counts the live stores of one provider, warning when the count crosses a threshold.
+///
+/// Cloning is cheap and every clone shares the count.
+#[derive(Clone, Default)]
+pub(crate) struct StoreCounter {
+  active: Arc<AtomicUsize>,
+  warn_above: Option<usize>,
+}
+
+impl StoreCounter {
+  pub(crate) fn new(warn_above: Option<usize>) -> Self {
+    Self {
+      active: Arc::default(),
+      warn_above,
+    }
+  }
+
+  pub(crate) fn active(&self) -> usize {
+    self.active.load(Ordering::SeqCst)
+  }
+
+  /// Counts a new store until the returned guard, kept in its state, is dropped.
+  pub(crate) fn track(&self) -> StoreGuard {
+    let active = self.active.fetch_add(1, Ordering::SeqCst) + 1;
+    // Warning only on the way up keeps a count hovering above the threshold quiet.
+    if let Some(threshold) = self.warn_above
+      && active == threshold + 1
+    {
+      warn!(
+        "{} WASM stores are alive, more than the threshold of {}; stores may be leaking",
+        active, threshold
+      );
+    }
+    StoreGuard {
+      active: Arc::clone(&self.active),
+    }
+  }
+}
+
+/// Keeps one store counted by its `StoreCounter` while alive.
+pub(crate) struct StoreGuard {
+  active: Arc<AtomicUsize>,
+}
+
+impl Drop for StoreGuard {
+  fn drop(&mut self) {
+    self.active.fetch_sub(1, Ordering::SeqCst);
+  }
+}
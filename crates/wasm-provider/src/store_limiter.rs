@@ -0,0 +1,80 @@
+use std::fmt;
+use wasmtime::ResourceLimiter;
+
+/// Raised by the store limiter when a read-only call tries to mutate guest resources.
+#[derive(Debug)]
+pub(crate) struct ReadOnlyViolation(&'static str);
+
+impl fmt::Display for ReadOnlyViolation {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    write!(f, "read-only call attempted to grow {}", self.0)
+  }
+}
+
+impl std::error::Error for ReadOnlyViolation {}
+
+/// Raised by the store limiter when a guest tries to grow a memory past the limit.
+#[derive(Debug)]
+pub(crate) struct MemoryLimitExceeded {
+  limit: usize,
+  desired: usize,
+}
+
+impl fmt::Display for MemoryLimitExceeded {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    write!(
+      f,
+      "memory limit of {} bytes exceeded: guest tried to grow a memory to {} bytes",
+      self.limit, self.desired
+    )
+  }
+}
+
+impl std::error::Error for MemoryLimitExceeded {}
+
+/// Store limiter enforcing the per-plugin memory limit, and refusing all memory and
+/// table growth while `readonly` is set.
+///
+/// Otherwise it only enforces the module's declared maximums.
+#[derive(Default)]
+pub(crate) struct StoreLimiter {
+  pub(crate) readonly: bool,
+  /// Maximum size in bytes of each linear memory; `None` for no limit.
+  pub(crate) max_memory: Option<usize>,
+  /// Set when growth hit `max_memory`, so the interrupted call marks the plugin failed.
+  pub(crate) memory_exceeded: bool,
+}
+
+impl ResourceLimiter for StoreLimiter {
+  fn memory_growing(
+    &mut self,
+    _current: usize,
+    desired: usize,
+    maximum: Option<usize>,
+  ) -> wasmtime::Result<bool> {
+    if self.readonly {
+      return Err(ReadOnlyViolation("memory").into());
+    }
+    if let Some(limit) = self.max_memory
+      && desired > limit
+    {
+      // Failing with an error rather than `Ok(false)` traps at the `memory.grow`, so
+      // the caller sees the limit instead of the guest's reaction to a failed grow.
+      self.memory_exceeded = true;
+      return Err(MemoryLimitExceeded { limit, desired }.into());
+    }
+    Ok(maximum.is_none_or(|max| desired <= max))
+  }
+
+  fn table_growing(
+    &mut self,
+    _current: usize,
+    desired: usize,
+    maximum: Option<usize>,
+  ) -> wasmtime::Result<bool> {
+    if self.readonly {
+      return Err(ReadOnlyViolation("a table").into());
+    }
+    Ok(maximum.is_none_or(|max| desired <= max))
+  }
+}
@@ -0,0 +1,103 @@
+use crate::stdio::CapturedOutput;
+use crate::store_limiter::{MemoryLimitExceeded, ReadOnlyViolation};
+use shared_types::ProviderError;
+use wasmtime::{Trap, WasmBacktrace};
+
+/// Converts an error from a guest call into a `ProviderError`.
+///
+/// Traps become `ProviderError::Trap`, described by the trap code and followed by the
+/// guest's backtrace, one frame per line with function names from the module's name
+/// section where present. A Rust panic aborts through an `unreachable` trap which
+/// carries no message of its own, so when stderr is captured the panic report written
+/// just before the abort is appended to the trap description.
+/// Mutations refused during a read-only call become `ProviderError::PermissionDenied`,
+/// growth past the memory limit becomes `ProviderError::InvocationFailed` naming the
+/// limit, and epoch interruptions raised by a deadline become `ProviderError::Timeout`.
+pub(crate) fn call_error(error: wasmtime::Error, output: &mut CapturedOutput) -> ProviderError {
+  if let Some(violation) = error.downcast_ref::<ReadOnlyViolation>() {
+    return ProviderError::PermissionDenied(violation.to_string());
+  }
+  if let Some(exceeded) = error.downcast_ref::<MemoryLimitExceeded>() {
+    return ProviderError::InvocationFailed(exceeded.to_string());
+  }
+
+  let Some(trap) = error.downcast_ref::<Trap>() else {
+    // Host function errors are wrapped in a wasm backtrace; report the original message.
+    return ProviderError::InvocationFailed(error.root_cause().to_string());
+  };
+
+  if let Trap::Interrupt = trap {
+    return ProviderError::Timeout("deadline exceeded".to_string());
+  }
+
+  let panic = match trap {
+    Trap::UnreachableCodeReached => output
+      .stderr
+      .as_mut()
+      .and_then(|stderr| panic_message(&stderr.take())),
+    _ => None,
+  };
+
+  let message = match panic {
+    Some(panic) => format!("{}: {}", trap, panic),
+    None => trap.to_string(),
+  };
+  match error
+    .downcast_ref::<WasmBacktrace>()
+    .filter(|backtrace| !backtrace.frames().is_empty())
+  {
+    Some(backtrace) => ProviderError::Trap(format!(
+      "{}\nwasm backtrace:\n{}",
+      message,
+      frames(backtrace)
+    )),
+    None => ProviderError::Trap(message),
+  }
+}
+
+// Lists the frames of a guest backtrace, innermost first, by name where the module
+// has a name section and by function index otherwise.
+fn frames(backtrace: &WasmBacktrace) -> String {
+  let lines: Vec<String> = backtrace
+    .frames()
+    .iter()
+    .enumerate()
+    .map(|(i, frame)| {
+      let name = frame
+        .func_name()
+        .map_or_else(|| format!("<func {}>", frame.func_index()), str::to_string);
+      match frame.module_offset() {
+        Some(offset) => format!("  {}: {} at {:#x}", i, name, offset),
+        None => format!("  {}: {}", i, name),
+      }
+    })
+    .collect();
+  lines.join("\n")
+}
+
+// Extracts the last panic report from captured stderr.
+//
+// Since Rust 1.73 the report spans two lines ("thread '..' panicked at file:line:col:"
+// followed by the payload) and is usually followed by a "note: run with RUST_BACKTRACE"
+// hint; both lines are joined and the hint dropped. Without a recognizable report the
+// last non-empty line is returned.
+fn panic_message(stderr: &str) -> Option<String> {
+  let lines: Vec<&str> = stderr.lines().filter(|l| !l.trim().is_empty()).collect();
+
+  let Some(start) = lines.iter().rposition(|l| l.contains("panicked at")) else {
+    return lines.last().map(|l| l.trim().to_string());
+  };
+
+  let report: Vec<&str> = lines[start..]
+    .iter()
+    .take_while(|l| !l.starts_with("note:"))
+    .map(|l| l.trim())
+    .collect();
+
+  let report = report.join(" ");
+  let report = match report.find("panicked at") {
+    Some(at) => &report[at..],
+    None => &report,
+  };
+  Some(report.to_string())
+}
@@ -0,0 +1,105 @@
+use shared_types::PermissionKind;
+use shared_types::config::Permissions;
+use std::collections::{BTreeMap, BTreeSet};
+use wasmtime::component::Component;
+use wasmtime::{Engine, Module};
+
+/// Import modules used by WASI Preview 1 core modules.
+const PREVIEW1_MODULES: &[&str] = &["wasi_snapshot_preview1", "wasi_unstable"];
+
+/// WASI capabilities a plugin references through its imports, found without running it.
+///
+/// Components are reported per interface (`wasi:filesystem/types@0.2.6`), Preview 1
+/// core modules per function (`path_open`).
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct WasiUsage {
+  /// WASI imports grouped by the permission dimension they need.
+  pub permissions: BTreeMap<PermissionKind, BTreeSet<String>>,
+  /// WASI imports that need no permission, such as clocks, randomness and polling.
+  pub ambient: BTreeSet<String>,
+}
+
+impl WasiUsage {
+  /// Returns `true` if any import needs the given permission dimension.
+  pub fn uses(&self, kind: PermissionKind) -> bool {
+    self.permissions.contains_key(&kind)
+  }
+
+  /// Returns the dimensions the plugin imports but does not request in `declared`.
+  pub fn undeclared(&self, declared: Option<&Permissions>) -> Vec<PermissionKind> {
+    self
+      .permissions
+      .keys()
+      .copied()
+      .filter(|kind| !declared.is_some_and(|p| p.is_requested(*kind)))
+      .collect()
+  }
+
+  fn record(&mut self, import: String, kind: Option<PermissionKind>) {
+    match kind {
+      Some(kind) => {
+        self.permissions.entry(kind).or_default().insert(import);
+      }
+      None => {
+        self.ambient.insert(import);
+      }
+    }
+  }
+}
+
+/// Collects the WASI imports of a component or Preview 1 core module (binary or WAT).
+pub(crate) fn analyze(engine: &Engine, bytes: &[u8]) -> wasmtime::Result<WasiUsage> {
+  let mut usage = WasiUsage::default();
+
+  match Component::new(engine, bytes) {
+    Ok(component) => {
+      for (name, _) in component.component_type().imports(engine) {
+        if name.starts_with("wasi:") {
+          usage.record(name.to_string(), interface_kind(name));
+        }
+      }
+    }
+    Err(_) => {
+      let module = Module::new(engine, bytes)?;
+      for import in module.imports() {
+        if PREVIEW1_MODULES.contains(&import.module()) {
+          usage.record(import.name().to_string(), preview1_kind(import.name()));
+        }
+      }
+    }
+  }
+
+  Ok(usage)
+}
+
+// Maps a WASI Preview 2 interface name to the permission it needs.
+fn interface_kind(name: &str) -> Option<PermissionKind> {
+  let interface = name.split('@').next().unwrap_or(name);
+  match interface {
+    i if i.starts_with("wasi:filesystem/") => Some(PermissionKind::Filesystem),
+    i if i.starts_with("wasi:sockets/") => Some(PermissionKind::Network),
+    "wasi:cli/environment" => Some(PermissionKind::Env),
+    "wasi:cli/exit" => Some(PermissionKind::Process),
+    i if i.starts_with("wasi:cli/std") || i.starts_with("wasi:cli/terminal-") => {
+      Some(PermissionKind::Stdio)
+    }
+    _ => None,
+  }
+}
+
+// Maps a WASI Preview 1 function to the permission it needs.
+//
+// Descriptor calls other than the preopen and directory ones count as stdio: without
+// filesystem or network access, the only descriptors a plugin holds are its streams.
+fn preview1_kind(name: &str) -> Option<PermissionKind> {
+  match name {
+    n if n.starts_with("path_") || n.starts_with("fd_prestat_") || n == "fd_readdir" => {
+      Some(PermissionKind::Filesystem)
+    }
+    n if n.starts_with("fd_") => Some(PermissionKind::Stdio),
+    n if n.starts_with("sock_") => Some(PermissionKind::Network),
+    n if n.starts_with("environ_") || n.starts_with("args_") => Some(PermissionKind::Env),
+    n if n.starts_with("proc_") => Some(PermissionKind::Process),
+    _ => None,
+  }
+}
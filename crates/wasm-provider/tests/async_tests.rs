@@ -0,0 +1,111 @@
+#![cfg(feature = "async")]
+
+mod common;
+
+use common::{example_plugin_dir, plugin_config, write_plugin};
+use shared_types::{Provider, ProviderError, ProviderValue};
+use std::fs;
+use std::time::{Duration, Instant};
+use tempfile::TempDir;
+use wasm_provider::{FAILED_STATE_MESSAGE, WasiVersion, WasmProvider};
+
+// Async provider with a copy of test-plugin loaded under each of `names`, declaring
+// `timeout_ms`.
+fn async_provider(names: &[&str], timeout_ms: Option<u64>) -> WasmProvider {
+  let provider = WasmProvider::builder().async_support(true).build();
+  provider.init().expect("init failed");
+
+  let main_wasm = fs::read(example_plugin_dir("test-plugin").join("main.wasm")).unwrap();
+  let root = TempDir::new().unwrap();
+  for name in names {
+    let mut config = plugin_config(name);
+    config.timeout_ms = timeout_ms;
+    provider
+      .load(write_plugin(root.path(), name, &main_wasm), &config)
+      .expect("load failed");
+  }
+  provider
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn test_two_plugins_run_concurrently_on_one_thread() {
+  let provider = async_provider(&["first", "second"], None);
+  let sleep = || vec![ProviderValue::Int(400)];
+
+  let started = Instant::now();
+  let (first, second) = tokio::join!(
+    provider.invoke_async("first", "Sleep", sleep()),
+    provider.invoke_async("second", "Sleep", sleep()),
+  );
+
+  assert_eq!(first.unwrap(), ProviderValue::Null);
+  assert_eq!(second.unwrap(), ProviderValue::Null);
+  // Back to back they would take 800ms; overlapping they take about 400ms.
+  let elapsed = started.elapsed();
+  assert!(elapsed < Duration::from_millis(700), "took {:?}", elapsed);
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn test_spinning_guest_yields_and_times_out() {
+  let provider = async_provider(&["spinner", "echo"], Some(1000));
+
+  let started = Instant::now();
+  let echo = async {
+    let result = provider
+      .invoke_async("echo", "Echo", vec![ProviderValue::Int(1)])
+      .await;
+    (result, started.elapsed())
+  };
+  let (spun, (echoed, echoed_after)) =
+    tokio::join!(provider.invoke_async("spinner", "Spin", vec![]), echo);
+
+  assert!(matches!(spun, Err(ProviderError::Timeout(_))), "{:?}", spun);
+  assert_eq!(echoed.unwrap(), ProviderValue::Int(1));
+  assert!(
+    echoed_after < Duration::from_millis(500),
+    "echo waited {:?} for the spinning call",
+    echoed_after
+  );
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn test_cancelled_call_leaves_plugin_failed() {
+  let provider = async_provider(&["spinner"], None);
+
+  let call = provider.invoke_async("spinner", "Spin", vec![]);
+  assert!(
+    tokio::time::timeout(Duration::from_millis(50), call)
+      .await
+      .is_err()
+  );
+
+  // The plugin is back in the table, but was stopped part way through a call.
+  assert!(
+    provider
+      .with_plugins(|plugins| plugins.contains_key("spinner"))
+      .unwrap()
+  );
+  match provider.invoke("spinner", "Echo", vec![]) {
+    Err(ProviderError::InvocationFailed(msg)) => assert_eq!(msg, FAILED_STATE_MESSAGE),
+    other => panic!("expected the failed state, got {:?}", other),
+  }
+}
+
+#[test]
+fn test_sync_invoke_blocks_on_async_call() {
+  let provider = async_provider(&["test-plugin"], None);
+
+  let result = provider.invoke("test-plugin", "Double", vec![ProviderValue::Int(21)]);
+
+  assert_eq!(result.unwrap(), ProviderValue::Int(42));
+}
+
+#[test]
+fn test_preview1_rejects_async_support() {
+  let provider = WasmProvider::builder()
+    .wasi_version(WasiVersion::Preview1)
+    .async_support(true)
+    .build();
+
+  assert!(matches!(provider.init(), Err(ProviderError::InitFailed)));
+}
@@ -0,0 +1,41 @@
+//! Shared fixtures for the wasm-provider integration tests.
+#![allow(dead_code)]
+
+use shared_types::PluginConfigData;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Path to an example plugin directory in the workspace (e.g. `test-plugin`).
+pub fn example_plugin_dir(name: &str) -> PathBuf {
+  PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+    .join("../../example")
+    .join(name)
+}
+
+/// Minimal plugin config with the given name and no permissions.
+pub fn plugin_config(name: &str) -> PluginConfigData {
+  PluginConfigData {
+    id: None,
+    name: name.to_string(),
+    version: "1.0.0".to_string(),
+    description: "Fixture plugin".to_string(),
+    author: "tester".to_string(),
+    main: None,
+    permissions: None,
+    functions: None,
+    timeout_ms: None,
+    categories: Vec::new(),
+    keywords: Vec::new(),
+    dependencies: Vec::new(),
+    targets: Vec::new(),
+    extra: Default::default(),
+  }
+}
+
+/// Writes `main_wasm` (binary or WAT text) into `<root>/<name>/main.wasm` and returns the plugin dir.
+pub fn write_plugin(root: &Path, name: &str, main_wasm: impl AsRef<[u8]>) -> PathBuf {
+  let dir = root.join(name);
+  fs::create_dir_all(&dir).expect("failed to create plugin dir");
+  fs::write(dir.join("main.wasm"), main_wasm).expect("failed to write main.wasm");
+  dir
+}
@@ -0,0 +1,103 @@
+mod common;
+
+use common::{example_plugin_dir, plugin_config, write_plugin};
+use shared_types::{Provider, ProviderValue};
+use std::fs;
+use std::path::Path;
+use tempfile::TempDir;
+use wasm_provider::{LoadTiming, WasmProvider};
+
+fn load_with_cache(cache: Option<&Path>, plugin_dir: &Path) -> (WasmProvider, LoadTiming) {
+  let provider = WasmProvider::builder()
+    .compile_cache(cache.map(Path::to_path_buf))
+    .build();
+  provider.init().expect("init failed");
+  let timing = provider
+    .load_timed(plugin_dir, &plugin_config("test-plugin"))
+    .expect("load failed");
+  (provider, timing)
+}
+
+fn cache_entries(cache: &Path) -> usize {
+  fs::read_dir(cache).map_or(0, |entries| entries.count())
+}
+
+#[test]
+fn test_second_load_uses_cached_code() {
+  let root = TempDir::new().unwrap();
+  let cache = root.path().join("cache");
+  let wasm = fs::read(example_plugin_dir("test-plugin").join("main.wasm")).unwrap();
+  let plugin_dir = write_plugin(root.path(), "test-plugin", &wasm);
+
+  let (_, first) = load_with_cache(Some(&cache), &plugin_dir);
+  assert!(!first.cached);
+  assert_eq!(cache_entries(&cache), 1);
+
+  let (provider, second) = load_with_cache(Some(&cache), &plugin_dir);
+  assert!(second.cached);
+  assert!(
+    second.compile < first.compile,
+    "cached load took {:?}, compiling took {:?}",
+    second.compile,
+    first.compile
+  );
+  assert_eq!(
+    provider
+      .invoke("test-plugin", "Increment", vec![ProviderValue::Int(1)])
+      .unwrap(),
+    ProviderValue::Int(2)
+  );
+}
+
+#[test]
+fn test_changed_wasm_misses_cache() {
+  let root = TempDir::new().unwrap();
+  let cache = root.path().join("cache");
+  let plugin_dir = write_plugin(
+    root.path(),
+    "test-plugin",
+    fs::read(example_plugin_dir("test-plugin").join("main.wasm")).unwrap(),
+  );
+  load_with_cache(Some(&cache), &plugin_dir);
+
+  fs::copy(
+    example_plugin_dir("sum-plugin").join("main.wasm"),
+    plugin_dir.join("main.wasm"),
+  )
+  .unwrap();
+  let (_, timing) = load_with_cache(Some(&cache), &plugin_dir);
+  assert!(!timing.cached);
+  assert_eq!(cache_entries(&cache), 2);
+}
+
+#[test]
+fn test_unusable_entry_is_recompiled() {
+  let root = TempDir::new().unwrap();
+  let cache = root.path().join("cache");
+  let plugin_dir = write_plugin(
+    root.path(),
+    "test-plugin",
+    fs::read(example_plugin_dir("test-plugin").join("main.wasm")).unwrap(),
+  );
+  load_with_cache(Some(&cache), &plugin_dir);
+
+  let entry = fs::read_dir(&cache)
+    .unwrap()
+    .next()
+    .unwrap()
+    .unwrap()
+    .path();
+  fs::write(&entry, b"not compiled code").unwrap();
+  let (_, timing) = load_with_cache(Some(&cache), &plugin_dir);
+  assert!(!timing.cached);
+
+  // The entry was rewritten with usable code.
+  let (_, timing) = load_with_cache(Some(&cache), &plugin_dir);
+  assert!(timing.cached);
+}
+
+#[test]
+fn test_cache_disabled_by_default() {
+  let (_, timing) = load_with_cache(None, &example_plugin_dir("test-plugin"));
+  assert!(!timing.cached);
+}
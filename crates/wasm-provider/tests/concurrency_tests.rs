@@ -0,0 +1,133 @@
+mod common;
+
+use common::{example_plugin_dir, plugin_config, write_plugin};
+use shared_types::{Provider, ProviderError, ProviderValue};
+use std::fs;
+use std::sync::Arc;
+use std::thread;
+use std::time::{Duration, Instant};
+use tempfile::TempDir;
+use wasm_provider::{CapacityPolicy, WasmProvider};
+
+fn loaded_provider() -> Arc<WasmProvider> {
+  let provider = WasmProvider::new();
+  provider.init().expect("init failed");
+  provider
+    .load(
+      example_plugin_dir("test-plugin"),
+      &plugin_config("test-plugin"),
+    )
+    .expect("load failed");
+  Arc::new(provider)
+}
+
+// Provider with a copy of test-plugin loaded under each of `names`.
+fn provider_with_copies(root: &TempDir, names: &[&str]) -> Arc<WasmProvider> {
+  let provider = WasmProvider::new();
+  provider.init().expect("init failed");
+  let main_wasm = fs::read(example_plugin_dir("test-plugin").join("main.wasm")).unwrap();
+  for name in names {
+    provider
+      .load(
+        write_plugin(root.path(), name, &main_wasm),
+        &plugin_config(name),
+      )
+      .expect("load failed");
+  }
+  Arc::new(provider)
+}
+
+fn sleep_in_background(provider: &Arc<WasmProvider>, millis: i64) -> thread::JoinHandle<()> {
+  sleep_plugin_in_background(provider, "test-plugin", millis)
+}
+
+fn sleep_plugin_in_background(
+  provider: &Arc<WasmProvider>,
+  plugin_name: &'static str,
+  millis: i64,
+) -> thread::JoinHandle<()> {
+  let provider = Arc::clone(provider);
+  thread::spawn(move || {
+    provider
+      .invoke(plugin_name, "Sleep", vec![ProviderValue::Int(millis)])
+      .expect("sleep invoke failed");
+  })
+}
+
+#[test]
+fn test_reject_policy_fails_fast_at_capacity() {
+  let provider = loaded_provider();
+  provider.set_max_concurrent_invocations(1, CapacityPolicy::Reject);
+
+  let running = sleep_in_background(&provider, 500);
+  thread::sleep(Duration::from_millis(100));
+
+  match provider.invoke("test-plugin", "Sleep", vec![ProviderValue::Int(0)]) {
+    Err(ProviderError::InvocationFailed(msg)) => assert_eq!(msg, "at capacity"),
+    other => panic!("expected at capacity, got {:?}", other),
+  }
+  running.join().unwrap();
+
+  // The slot is released once the running call returns.
+  provider
+    .invoke("test-plugin", "Sleep", vec![ProviderValue::Int(0)])
+    .expect("invoke after release failed");
+}
+
+#[test]
+fn test_block_policy_waits_for_a_slot() {
+  let provider = loaded_provider();
+  provider.set_max_concurrent_invocations(1, CapacityPolicy::Block);
+
+  let started = Instant::now();
+  let running = sleep_in_background(&provider, 300);
+  thread::sleep(Duration::from_millis(50));
+
+  provider
+    .invoke("test-plugin", "Sleep", vec![ProviderValue::Int(0)])
+    .expect("blocked invoke failed");
+  assert!(started.elapsed() >= Duration::from_millis(300));
+  running.join().unwrap();
+}
+
+#[test]
+fn test_clearing_the_cap_admits_rejected_calls() {
+  let provider = loaded_provider();
+  provider.set_max_concurrent_invocations(0, CapacityPolicy::Reject);
+  assert!(provider.invoke("test-plugin", "Sleep", vec![]).is_err());
+
+  provider.clear_max_concurrent_invocations();
+  provider
+    .invoke("test-plugin", "Sleep", vec![])
+    .expect("uncapped invoke failed");
+}
+
+#[test]
+fn test_calls_into_different_plugins_overlap() {
+  let root = TempDir::new().unwrap();
+  let provider = provider_with_copies(&root, &["first", "second"]);
+
+  let started = Instant::now();
+  let first = sleep_plugin_in_background(&provider, "first", 400);
+  let second = sleep_plugin_in_background(&provider, "second", 400);
+  first.join().unwrap();
+  second.join().unwrap();
+
+  // Back to back they would take 800ms; overlapping they take about 400ms.
+  let elapsed = started.elapsed();
+  assert!(elapsed < Duration::from_millis(700), "took {:?}", elapsed);
+}
+
+#[test]
+fn test_calls_into_one_plugin_take_turns() {
+  let root = TempDir::new().unwrap();
+  let provider = provider_with_copies(&root, &["only"]);
+
+  let started = Instant::now();
+  let first = sleep_plugin_in_background(&provider, "only", 300);
+  let second = sleep_plugin_in_background(&provider, "only", 300);
+  first.join().unwrap();
+  second.join().unwrap();
+
+  assert!(started.elapsed() >= Duration::from_millis(600));
+}
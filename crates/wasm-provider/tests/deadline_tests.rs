@@ -0,0 +1,136 @@
+mod common;
+
+use common::{example_plugin_dir, plugin_config};
+use shared_types::{Provider, ProviderError, ProviderValue};
+use std::time::{Duration, Instant};
+use wasm_provider::WasmProvider;
+
+fn loaded_provider() -> WasmProvider {
+  let provider = WasmProvider::new();
+  provider.init().expect("init failed");
+  provider
+    .load(
+      example_plugin_dir("test-plugin"),
+      &plugin_config("test-plugin"),
+    )
+    .expect("load failed");
+  provider
+}
+
+#[test]
+fn test_expired_deadline_times_out_without_entering_plugin() {
+  let provider = loaded_provider();
+  let expired = Instant::now() - Duration::from_millis(1);
+
+  match provider.invoke_with_deadline("test-plugin", "Config", vec![], expired) {
+    Err(ProviderError::Timeout(_)) => {}
+    other => panic!("expected Timeout, got {:?}", other),
+  }
+
+  // Rejecting the call up front leaves the plugin usable.
+  provider
+    .invoke("test-plugin", "Config", vec![])
+    .expect("plugin should not be failed");
+}
+
+#[test]
+fn test_future_deadline_allows_call_to_finish() {
+  let provider = loaded_provider();
+  let deadline = Instant::now() + Duration::from_secs(10);
+
+  let name = vec![ProviderValue::String("name".to_string())];
+  assert_eq!(
+    provider
+      .invoke_with_deadline("test-plugin", "Config", name, deadline)
+      .unwrap(),
+    ProviderValue::String("test-plugin".to_string())
+  );
+}
+
+#[test]
+fn test_deadline_interrupts_running_call() {
+  let provider = loaded_provider();
+  let deadline = Instant::now() + Duration::from_millis(100);
+
+  match provider.invoke_with_deadline("test-plugin", "Spin", vec![], deadline) {
+    Err(ProviderError::Timeout(msg)) => assert_eq!(msg, "deadline exceeded"),
+    other => panic!("expected Timeout, got {:?}", other),
+  }
+  assert!(Instant::now() >= deadline);
+  assert!(
+    provider
+      .with_plugins(|p| p["test-plugin"].lock().unwrap().is_failed())
+      .unwrap()
+  );
+}
+
+#[test]
+fn test_declared_timeout_interrupts_plain_invoke() {
+  let provider = WasmProvider::new();
+  provider.init().expect("init failed");
+  let mut config = plugin_config("test-plugin");
+  config.timeout_ms = Some(50);
+  provider
+    .load(example_plugin_dir("test-plugin"), &config)
+    .expect("load failed");
+
+  match provider.invoke("test-plugin", "Spin", vec![]) {
+    Err(ProviderError::Timeout(msg)) => assert_eq!(msg, "deadline exceeded"),
+    other => panic!("expected Timeout, got {:?}", other),
+  }
+}
+
+#[test]
+fn test_default_timeout_interrupts_plain_invoke() {
+  let provider = WasmProvider::builder()
+    .default_timeout(Some(Duration::from_millis(200)))
+    .build();
+  let instance = provider.init().expect("init failed");
+  assert_eq!(instance.default_timeout, Some(Duration::from_millis(200)));
+  provider
+    .load(
+      example_plugin_dir("test-plugin"),
+      &plugin_config("test-plugin"),
+    )
+    .expect("load failed");
+
+  let start = Instant::now();
+  match provider.invoke("test-plugin", "Spin", vec![]) {
+    Err(ProviderError::Timeout(msg)) => assert_eq!(msg, "deadline exceeded"),
+    other => panic!("expected Timeout, got {:?}", other),
+  }
+  let elapsed = start.elapsed();
+  assert!(elapsed >= Duration::from_millis(200), "{:?}", elapsed);
+  assert!(elapsed < Duration::from_secs(3), "{:?}", elapsed);
+}
+
+#[test]
+fn test_default_timeout_yields_to_declared_timeout() {
+  assert_eq!(
+    WasmProvider::new().init().unwrap().default_timeout,
+    Some(wasm_provider::DEFAULT_TIMEOUT)
+  );
+  assert_eq!(
+    WasmProvider::builder()
+      .default_timeout(None)
+      .build()
+      .init()
+      .unwrap()
+      .default_timeout,
+    None
+  );
+
+  // The plugin's own `timeoutMs` wins over a shorter provider default.
+  let provider = WasmProvider::builder()
+    .default_timeout(Some(Duration::from_millis(1)))
+    .build();
+  provider.init().expect("init failed");
+  let mut config = plugin_config("test-plugin");
+  config.timeout_ms = Some(10_000);
+  provider
+    .load(example_plugin_dir("test-plugin"), &config)
+    .expect("load failed");
+  provider
+    .invoke("test-plugin", "Sleep", vec![ProviderValue::Int(20)])
+    .expect("declared timeout should apply");
+}
@@ -0,0 +1,111 @@
+mod common;
+
+use common::{example_plugin_dir, write_plugin};
+use shared_types::{FunctionSignature, Provider, ProviderError, ValueKind};
+use tempfile::TempDir;
+use wasm_provider::{WasiVersion, WasmProvider};
+
+const CALCULATOR_WAT: &str = r#"
+(module
+  (memory (export "memory") 1)
+  (func (export "Sum") (param i32 i32) (result i32)
+    (i32.add (local.get 0) (local.get 1)))
+  (func (export "Mul") (param i32 i32) (result i32)
+    (i32.mul (local.get 0) (local.get 1)))
+)
+"#;
+
+const ADDER_WAT: &str = r#"
+(module
+  (func (export "Sum") (param i32 i32) (result i32)
+    (i32.add (local.get 0) (local.get 1)))
+)
+"#;
+
+const WIDE_ADDER_WAT: &str = r#"
+(module
+  (func (export "Sum") (param i64 i64) (result i64)
+    (i64.add (local.get 0) (local.get 1)))
+)
+"#;
+
+fn preview1_provider() -> WasmProvider {
+  let provider = WasmProvider::builder()
+    .wasi_version(WasiVersion::Preview1)
+    .build();
+  provider.init().expect("init failed");
+  provider
+}
+
+fn signature(name: &str, kind: ValueKind) -> FunctionSignature {
+  FunctionSignature {
+    name: name.to_string(),
+    params: vec![kind, kind],
+    results: vec![kind],
+  }
+}
+
+#[test]
+fn test_missing_function_is_reported() {
+  let root = TempDir::new().unwrap();
+  let calculator = write_plugin(root.path(), "calculator", CALCULATOR_WAT);
+  let adder = write_plugin(root.path(), "adder", ADDER_WAT);
+  let provider = preview1_provider();
+
+  let diff = provider.compare_exports(&calculator, &adder).unwrap();
+  assert_eq!(diff.only_in_a, vec![signature("Mul", ValueKind::I32)]);
+  assert!(diff.only_in_b.is_empty());
+  assert!(diff.changed.is_empty());
+  assert!(!diff.is_drop_in());
+
+  // Exporting more than the original is still a drop-in replacement.
+  let diff = provider.compare_exports(&adder, &calculator).unwrap();
+  assert_eq!(diff.only_in_b, vec![signature("Mul", ValueKind::I32)]);
+  assert!(diff.is_drop_in());
+  assert!(!diff.is_empty());
+
+  // Neither plugin was loaded.
+  assert!(provider.list_exports("adder").is_err());
+}
+
+#[test]
+fn test_signature_change_is_reported() {
+  let root = TempDir::new().unwrap();
+  let adder = write_plugin(root.path(), "adder", ADDER_WAT);
+  let wide = write_plugin(root.path(), "wide-adder", WIDE_ADDER_WAT);
+
+  let diff = preview1_provider().compare_exports(&adder, &wide).unwrap();
+  assert_eq!(
+    diff.changed,
+    vec![(
+      signature("Sum", ValueKind::I32),
+      signature("Sum", ValueKind::I64)
+    )]
+  );
+  assert!(!diff.is_drop_in());
+}
+
+#[test]
+fn test_component_compared_through_describe() {
+  let provider = WasmProvider::new();
+  provider.init().expect("init failed");
+
+  let sum_plugin = example_plugin_dir("sum-plugin");
+  let diff = provider.compare_exports(&sum_plugin, &sum_plugin).unwrap();
+  assert!(diff.is_empty(), "{:?}", diff);
+
+  let diff = provider
+    .compare_exports(&sum_plugin, example_plugin_dir("test-plugin"))
+    .unwrap();
+  assert!(
+    diff.only_in_a.iter().any(|sig| sig.name == "Sum"),
+    "{:?}",
+    diff
+  );
+
+  let root = TempDir::new().unwrap();
+  assert!(matches!(
+    provider.compare_exports(&sum_plugin, root.path().join("missing")),
+    Err(ProviderError::LoadFailed(_))
+  ));
+}
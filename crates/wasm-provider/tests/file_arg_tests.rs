@@ -0,0 +1,104 @@
+mod common;
+
+use common::plugin_config;
+use shared_types::config::{FilesystemPermission, Permission, Permissions};
+use shared_types::{Provider, ProviderError, ProviderValue};
+use std::fs;
+use std::path::Path;
+use wasm_provider::{WasiVersion, WasmProvider};
+
+// Preview 1 module whose `ByteCount` reads its descriptor argument to the end with
+// `fd_read` through a 4 KiB buffer and returns the number of bytes read, or the
+// negated errno.
+const BYTE_COUNT_WAT: &str = r#"
+(module
+  (import "wasi_snapshot_preview1" "fd_read"
+    (func $fd_read (param i32 i32 i32 i32) (result i32)))
+  (memory (export "memory") 1)
+  (func (export "ByteCount") (param $fd i32) (result i32)
+    (local $total i32)
+    (local $errno i32)
+    (i32.store (i32.const 0) (i32.const 64))
+    (i32.store (i32.const 4) (i32.const 4096))
+    (block $done
+      (loop $read
+        (local.set $errno
+          (call $fd_read (local.get $fd) (i32.const 0) (i32.const 1) (i32.const 8)))
+        (if (local.get $errno)
+          (then (return (i32.sub (i32.const 0) (local.get $errno)))))
+        (br_if $done (i32.eqz (i32.load (i32.const 8))))
+        (local.set $total (i32.add (local.get $total) (i32.load (i32.const 8))))
+        (br $read)))
+    (local.get $total))
+)
+"#;
+
+fn load_reader(readable: &Path) -> WasmProvider {
+  let provider = WasmProvider::builder()
+    .wasi_version(WasiVersion::Preview1)
+    .build();
+  provider.init().expect("init failed");
+
+  let mut config = plugin_config("reader");
+  config.permissions = Some(Permissions {
+    filesystem: Some(Permission::Config(FilesystemPermission {
+      read: Some(vec![readable.display().to_string()]),
+      write: None,
+    })),
+    ..Permissions::default()
+  });
+  provider
+    .load_bytes("reader", BYTE_COUNT_WAT, &config)
+    .expect("load failed");
+  provider
+}
+
+#[test]
+fn test_plugin_reads_file_through_descriptor() {
+  let dir = tempfile::tempdir().unwrap();
+  let file = dir.path().join("big.bin");
+  fs::write(&file, vec![7u8; 100_000]).unwrap();
+  let provider = load_reader(dir.path());
+
+  assert_eq!(
+    provider
+      .invoke_with_file("reader", "ByteCount", &file, vec![])
+      .unwrap(),
+    ProviderValue::Int(100_000)
+  );
+}
+
+#[test]
+fn test_file_outside_read_permission_is_refused() {
+  let readable = tempfile::tempdir().unwrap();
+  let other = tempfile::tempdir().unwrap();
+  let secret = other.path().join("secret.txt");
+  fs::write(&secret, "hidden").unwrap();
+  let provider = load_reader(readable.path());
+
+  // Neither a direct path nor one escaping the permitted directory gets through.
+  let escaping = readable
+    .path()
+    .join("..")
+    .join(other.path().file_name().unwrap())
+    .join("secret.txt");
+  for path in [&secret, &escaping] {
+    assert!(matches!(
+      provider.invoke_with_file("reader", "ByteCount", path, vec![]),
+      Err(ProviderError::PermissionDenied(_))
+    ));
+  }
+
+  // Without a filesystem permission no file can be passed at all.
+  provider
+    .load_bytes(
+      "unprivileged",
+      BYTE_COUNT_WAT,
+      &plugin_config("unprivileged"),
+    )
+    .expect("load failed");
+  assert!(matches!(
+    provider.invoke_with_file("unprivileged", "ByteCount", &secret, vec![]),
+    Err(ProviderError::PermissionDenied(_))
+  ));
+}
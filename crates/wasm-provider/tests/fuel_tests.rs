@@ -0,0 +1,59 @@
+mod common;
+
+use common::{example_plugin_dir, plugin_config};
+use serde_json::json;
+use shared_types::{Provider, ProviderError};
+use wasm_provider::WasmProvider;
+
+#[test]
+fn test_fuel_limit_interrupts_runaway_call() {
+  let provider = WasmProvider::new();
+  provider
+    .configure(&json!({"fuelLimit": 50_000_000}))
+    .expect("configure failed");
+  let instance = provider.init().expect("init failed");
+  assert_eq!(instance.fuel_limit, Some(50_000_000));
+
+  provider
+    .load(
+      example_plugin_dir("test-plugin"),
+      &plugin_config("test-plugin"),
+    )
+    .expect("load failed");
+  match provider.invoke("test-plugin", "Spin", vec![]) {
+    Err(ProviderError::Trap(msg)) => assert!(msg.contains("fuel"), "{}", msg),
+    other => panic!("expected Trap, got {:?}", other),
+  }
+}
+
+#[test]
+fn test_no_fuel_limit_by_default() {
+  let provider = WasmProvider::new();
+  provider.configure(&json!({})).expect("configure failed");
+  assert_eq!(provider.init().unwrap().fuel_limit, None);
+}
+
+#[test]
+fn test_invalid_fuel_limit_rejected() {
+  let provider = WasmProvider::new();
+  for value in [json!(0), json!(-5), json!("lots")] {
+    assert!(matches!(
+      provider.configure(&json!({ "fuelLimit": value })),
+      Err(ProviderError::InitFailed)
+    ));
+  }
+
+  // A too-small budget fails the load instead of a later call.
+  provider
+    .configure(&json!({"fuelLimit": 1}))
+    .expect("configure failed");
+  provider.init().expect("init failed");
+  assert!(
+    provider
+      .load(
+        example_plugin_dir("test-plugin"),
+        &plugin_config("test-plugin"),
+      )
+      .is_err()
+  );
+}
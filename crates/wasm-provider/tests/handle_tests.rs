@@ -0,0 +1,101 @@
+mod common;
+
+use common::{example_plugin_dir, plugin_config};
+use shared_types::{Provider, ProviderError, ProviderValue};
+use wasm_provider::{WasiVersion, WasmProvider};
+
+const ADD_WAT: &str = r#"
+(module
+  (func (export "Add") (param i64 i64) (result i64)
+    (i64.add (local.get 0) (local.get 1)))
+)
+"#;
+
+const ITERATIONS: i64 = 10_000;
+
+#[test]
+fn test_handle_loop_over_preview1_export() {
+  let provider = WasmProvider::builder()
+    .wasi_version(WasiVersion::Preview1)
+    .build();
+  provider.init().expect("init failed");
+  provider
+    .load_bytes("adder", ADD_WAT, &plugin_config("adder"))
+    .expect("load failed");
+
+  let handle = provider
+    .resolve_function("adder", "Add")
+    .expect("resolve failed");
+  assert_eq!((handle.plugin_name(), handle.function()), ("adder", "Add"));
+
+  let mut total = ProviderValue::Int(0);
+  for i in 1..=ITERATIONS {
+    total = provider
+      .invoke_handle(&handle, vec![total, ProviderValue::Int(i)])
+      .expect("invoke_handle failed");
+  }
+  assert_eq!(total, ProviderValue::Int(ITERATIONS * (ITERATIONS + 1) / 2));
+
+  // The handle reports the same errors a call by name would.
+  assert!(matches!(
+    provider.invoke_handle(&handle, vec![ProviderValue::Int(1)]),
+    Err(ProviderError::InvocationFailed(_))
+  ));
+  assert!(matches!(
+    provider.resolve_function("adder", "Missing"),
+    Err(ProviderError::InvocationFailed(_))
+  ));
+  assert!(matches!(
+    provider.resolve_function("nobody", "Add"),
+    Err(ProviderError::LoadFailed(_))
+  ));
+}
+
+#[test]
+fn test_handle_loop_over_component() {
+  let provider = WasmProvider::new();
+  provider.init().expect("init failed");
+  provider
+    .load(
+      example_plugin_dir("test-plugin"),
+      &plugin_config("test-plugin"),
+    )
+    .expect("load failed");
+
+  let handle = provider
+    .resolve_function("test-plugin", "Increment")
+    .expect("resolve failed");
+  let mut value = ProviderValue::Int(0);
+  for _ in 0..100 {
+    value = provider
+      .invoke_handle(&handle, vec![value])
+      .expect("invoke_handle failed");
+  }
+  assert_eq!(value, ProviderValue::Int(100));
+}
+
+#[test]
+fn test_reload_invalidates_handle() {
+  let provider = WasmProvider::builder()
+    .wasi_version(WasiVersion::Preview1)
+    .build();
+  provider.init().expect("init failed");
+  provider
+    .load_bytes("adder", ADD_WAT, &plugin_config("adder"))
+    .expect("load failed");
+  let handle = provider.resolve_function("adder", "Add").unwrap();
+
+  provider.reload("adder").expect("reload failed");
+  match provider.invoke_handle(&handle, vec![ProviderValue::Int(1), ProviderValue::Int(2)]) {
+    Err(ProviderError::InvocationFailed(msg)) => assert!(msg.contains("stale"), "{}", msg),
+    other => panic!("expected a stale handle error, got {:?}", other),
+  }
+
+  let handle = provider.resolve_function("adder", "Add").unwrap();
+  assert_eq!(
+    provider
+      .invoke_handle(&handle, vec![ProviderValue::Int(1), ProviderValue::Int(2)])
+      .unwrap(),
+    ProviderValue::Int(3)
+  );
+}
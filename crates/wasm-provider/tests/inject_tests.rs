@@ -0,0 +1,137 @@
+mod common;
+
+use common::plugin_config;
+use shared_types::provider::HostFunction;
+use shared_types::{Provider, ProviderError, ProviderValue};
+use std::sync::{Arc, Mutex};
+use wasm_provider::WasmProvider;
+
+// Plugin importing `record` from an `env` instance. `on-invoke` calls `record(7)`, then
+// echoes its arguments as `ok` if the host answered 1 and as `err` otherwise.
+const ENV_IMPORT_WAT: &str = r#"
+(component
+  (import "env" (instance $env (export "record" (func (param "n" s32) (result s32)))))
+  (core func $record (canon lower (func $env "record")))
+
+  (core module $m
+    (import "env" "record" (func $record (param i32) (result i32)))
+    (memory (export "memory") 1)
+    (global $heap (mut i32) (i32.const 1024))
+    (func (export "realloc") (param i32 i32 i32 i32) (result i32)
+      (local $ptr i32)
+      (local.set $ptr
+        (i32.and (i32.add (global.get $heap) (i32.const 7)) (i32.const -8)))
+      (global.set $heap (i32.add (local.get $ptr) (local.get 3)))
+      (local.get $ptr))
+    (func (export "on-load") (result i32) (i32.const 0))
+    (func (export "on-invoke") (param i32 i32 i32 i32) (result i32)
+      (i32.store (i32.const 16)
+        (i32.ne (call $record (i32.const 7)) (i32.const 1)))
+      (i32.store (i32.const 20) (local.get 2))
+      (i32.store (i32.const 24) (local.get 3))
+      (i32.const 16))
+    (func (export "on-invoke-numeric") (param i32 i32 i32 i32 i32) (result i32) (i32.const 0))
+    (func (export "describe") (result i32) (i32.const 0))
+  )
+  (core instance $i (instantiate $m
+    (with "env" (instance (export "record" (func $record))))))
+
+  (type $numeric-array (variant (case "int32" (list s32)) (case "float64" (list float64))))
+  (type $value-kind (enum "boolean" "int32" "int64" "float32" "float64" "text" "array" "object"))
+  (type $function-signature (record
+    (field "name" string)
+    (field "params" (list $value-kind))
+    (field "results" (list $value-kind))))
+
+  (func $on-load (result (result (error string)))
+    (canon lift (core func $i "on-load") (memory $i "memory") (realloc (func $i "realloc"))))
+  (func $on-invoke (param "function" string) (param "args-json" string)
+    (result (result string (error string)))
+    (canon lift (core func $i "on-invoke") (memory $i "memory") (realloc (func $i "realloc"))))
+  (func $on-invoke-numeric (param "function" string) (param "data" $numeric-array)
+    (result (result string (error string)))
+    (canon lift (core func $i "on-invoke-numeric") (memory $i "memory") (realloc (func $i "realloc"))))
+  (func $describe (result (list $function-signature))
+    (canon lift (core func $i "describe") (memory $i "memory") (realloc (func $i "realloc"))))
+
+  (instance $plugin
+    (export "numeric-array" (type $numeric-array))
+    (export "value-kind" (type $value-kind))
+    (export "function-signature" (type $function-signature))
+    (export "on-load" (func $on-load))
+    (export "on-invoke" (func $on-invoke))
+    (export "on-invoke-numeric" (func $on-invoke-numeric))
+    (export "describe" (func $describe)))
+  (export "bud:sdk/plugin@0.1.0" (instance $plugin))
+)
+"#;
+
+#[test]
+fn test_inject_under_custom_module_name() {
+  let provider = WasmProvider::builder().host_module("env").build();
+  let mut instance = provider.init().expect("init failed");
+
+  let seen = Arc::new(Mutex::new(Vec::new()));
+  let record: Arc<HostFunction> = {
+    let seen = Arc::clone(&seen);
+    Arc::new(move |args| {
+      seen.lock().unwrap().extend(args);
+      Ok(ProviderValue::Int(1))
+    })
+  };
+  provider
+    .inject(&mut instance, &[("record", record)])
+    .expect("inject failed");
+
+  provider
+    .load_bytes("env-user", ENV_IMPORT_WAT, &plugin_config("env-user"))
+    .expect("load failed");
+  let result = provider
+    .invoke("env-user", "Echo", vec![ProviderValue::Bool(true)])
+    .expect("invoke failed");
+
+  assert_eq!(
+    result,
+    ProviderValue::Array(vec![ProviderValue::Bool(true)])
+  );
+  assert_eq!(*seen.lock().unwrap(), vec![ProviderValue::Int(7)]);
+}
+
+#[test]
+fn test_default_module_name_does_not_satisfy_env_imports() {
+  let provider = WasmProvider::new();
+  let mut instance = provider.init().expect("init failed");
+  let record: Arc<HostFunction> = Arc::new(|_| Ok(ProviderValue::Int(1)));
+  provider
+    .inject(&mut instance, &[("record", record)])
+    .expect("inject failed");
+
+  match provider.load_bytes("env-user", ENV_IMPORT_WAT, &plugin_config("env-user")) {
+    Err(ProviderError::LoadFailed(msg)) => assert!(msg.contains("env#record"), "{}", msg),
+    other => panic!("expected LoadFailed, got {:?}", other),
+  }
+}
+
+#[test]
+fn test_mismatched_host_result_reports_its_path() {
+  let provider = WasmProvider::builder().host_module("env").build();
+  let mut instance = provider.init().expect("init failed");
+  let record: Arc<HostFunction> = Arc::new(|_| Ok(ProviderValue::Object(vec![])));
+  provider
+    .inject(&mut instance, &[("record", record)])
+    .expect("inject failed");
+  provider
+    .load_bytes("env-user", ENV_IMPORT_WAT, &plugin_config("env-user"))
+    .expect("load failed");
+
+  match provider.invoke("env-user", "Echo", vec![]) {
+    Err(ProviderError::InvocationFailed(msg)) => {
+      assert!(
+        msg.contains("result: expected s32, found object"),
+        "{}",
+        msg
+      )
+    }
+    other => panic!("expected InvocationFailed, got {:?}", other),
+  }
+}
@@ -0,0 +1,120 @@
+mod common;
+
+use common::plugin_config;
+use shared_types::{Provider, ProviderValue};
+use wasm_provider::WasmProvider;
+
+// Plugin whose `on-invoke` increments a counter kept in linear memory and returns it
+// as a one-digit JSON number.
+const COUNTER_WAT: &str = r#"
+(component
+  (core module $m
+    (memory (export "memory") 1)
+    (data (i32.const 100) "0")
+    (global $heap (mut i32) (i32.const 1024))
+    (func (export "realloc") (param i32 i32 i32 i32) (result i32)
+      (local $ptr i32)
+      (local.set $ptr
+        (i32.and (i32.add (global.get $heap) (i32.const 7)) (i32.const -8)))
+      (global.set $heap (i32.add (local.get $ptr) (local.get 3)))
+      (local.get $ptr))
+    (func (export "on-load") (result i32) (i32.const 0))
+    (func (export "on-invoke") (param i32 i32 i32 i32) (result i32)
+      (i32.store8 (i32.const 100) (i32.add (i32.load8_u (i32.const 100)) (i32.const 1)))
+      (i32.store (i32.const 16) (i32.const 0))
+      (i32.store (i32.const 20) (i32.const 100))
+      (i32.store (i32.const 24) (i32.const 1))
+      (i32.const 16))
+    (func (export "on-invoke-numeric") (param i32 i32 i32 i32 i32) (result i32) (i32.const 0))
+    (func (export "describe") (result i32) (i32.const 0))
+  )
+  (core instance $i (instantiate $m))
+
+  (type $numeric-array (variant (case "int32" (list s32)) (case "float64" (list float64))))
+  (type $value-kind (enum "boolean" "int32" "int64" "float32" "float64" "text" "array" "object"))
+  (type $function-signature (record
+    (field "name" string)
+    (field "params" (list $value-kind))
+    (field "results" (list $value-kind))))
+
+  (func $on-load (result (result (error string)))
+    (canon lift (core func $i "on-load") (memory $i "memory") (realloc (func $i "realloc"))))
+  (func $on-invoke (param "function" string) (param "args-json" string)
+    (result (result string (error string)))
+    (canon lift (core func $i "on-invoke") (memory $i "memory") (realloc (func $i "realloc"))))
+  (func $on-invoke-numeric (param "function" string) (param "data" $numeric-array)
+    (result (result string (error string)))
+    (canon lift (core func $i "on-invoke-numeric") (memory $i "memory") (realloc (func $i "realloc"))))
+  (func $describe (result (list $function-signature))
+    (canon lift (core func $i "describe") (memory $i "memory") (realloc (func $i "realloc"))))
+
+  (instance $plugin
+    (export "numeric-array" (type $numeric-array))
+    (export "value-kind" (type $value-kind))
+    (export "function-signature" (type $function-signature))
+    (export "on-load" (func $on-load))
+    (export "on-invoke" (func $on-invoke))
+    (export "on-invoke-numeric" (func $on-invoke-numeric))
+    (export "describe" (func $describe)))
+  (export "bud:sdk/plugin@0.1.0" (instance $plugin))
+)
+"#;
+
+fn loaded_provider() -> WasmProvider {
+  let provider = WasmProvider::new();
+  provider.init().expect("init failed");
+  provider
+    .load_bytes("counter", COUNTER_WAT, &plugin_config("counter"))
+    .expect("load failed");
+  provider
+}
+
+#[test]
+fn test_isolated_calls_do_not_share_state() {
+  let provider = loaded_provider();
+
+  for _ in 0..2 {
+    assert_eq!(
+      provider.invoke_isolated("counter", "Next", vec![]).unwrap(),
+      ProviderValue::Int(1)
+    );
+  }
+}
+
+#[test]
+fn test_isolated_calls_leave_shared_instance_untouched() {
+  let provider = loaded_provider();
+
+  assert_eq!(
+    provider.invoke("counter", "Next", vec![]).unwrap(),
+    ProviderValue::Int(1)
+  );
+  assert_eq!(
+    provider.invoke_isolated("counter", "Next", vec![]).unwrap(),
+    ProviderValue::Int(1)
+  );
+  assert_eq!(
+    provider.invoke("counter", "Next", vec![]).unwrap(),
+    ProviderValue::Int(2)
+  );
+}
+
+#[test]
+fn test_isolated_calls_keep_store_count_bounded() {
+  let provider = WasmProvider::builder().store_warning_threshold(4).build();
+  provider.init().expect("init failed");
+  assert_eq!(provider.active_store_count(), 0);
+  provider
+    .load_bytes("counter", COUNTER_WAT, &plugin_config("counter"))
+    .expect("load failed");
+  assert_eq!(provider.active_store_count(), 1);
+
+  // Each throwaway store is dropped when its call returns.
+  for _ in 0..20 {
+    provider.invoke_isolated("counter", "Next", vec![]).unwrap();
+    assert_eq!(provider.active_store_count(), 1);
+  }
+
+  provider.unload_plugin("counter").unwrap();
+  assert_eq!(provider.active_store_count(), 0);
+}
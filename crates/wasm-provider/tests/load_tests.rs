@@ -0,0 +1,98 @@
+mod common;
+
+use common::{example_plugin_dir, plugin_config, write_plugin};
+use shared_types::{Provider, ProviderError, ProviderValue};
+use tempfile::TempDir;
+use wasm_provider::WasmProvider;
+
+// Imports one function the host implements, one it does not, a WASI interface, and an
+// interface the host has never heard of.
+const UNINJECTED_IMPORTS_WAT: &str = r#"
+(component
+  (import "wasi:cli/environment@0.2.0" (instance
+    (export "get-arguments" (func (result (list string))))
+  ))
+  (import "bud:sdk/host@0.1.0" (instance
+    (export "get-config" (func (param "key" string) (result (option string))))
+    (export "compute" (func (param "x" s32) (result s32)))
+  ))
+  (import "acme:math/ops" (instance
+    (export "add" (func (param "a" s32) (param "b" s32) (result s32)))
+  ))
+)
+"#;
+
+#[test]
+fn test_load_rejects_uninjected_host_imports() {
+  let root = TempDir::new().unwrap();
+  let dir = write_plugin(root.path(), "needs-compute", UNINJECTED_IMPORTS_WAT);
+
+  let provider = WasmProvider::new();
+  provider.init().unwrap();
+
+  let err = provider
+    .load(&dir, &plugin_config("needs-compute"))
+    .expect_err("load should fail on missing host imports");
+  let ProviderError::LoadFailed(msg) = err else {
+    panic!("expected LoadFailed, got {:?}", err);
+  };
+  assert!(msg.contains("bud:sdk/host@0.1.0#compute"), "{}", msg);
+  assert!(msg.contains("acme:math/ops#add"), "{}", msg);
+  assert!(!msg.contains("get-config"), "{}", msg);
+  assert!(!msg.contains("wasi:"), "{}", msg);
+}
+
+#[test]
+fn test_load_accepts_plugin_using_only_provided_imports() {
+  let provider = WasmProvider::new();
+  provider.init().unwrap();
+  provider
+    .load(
+      example_plugin_dir("sum-plugin"),
+      &plugin_config("sum-plugin"),
+    )
+    .expect("sum-plugin only imports host functions the provider implements");
+}
+
+#[test]
+fn test_plugin_reads_its_own_config() {
+  let provider = WasmProvider::new();
+  provider.init().unwrap();
+
+  let mut config = plugin_config("test-plugin");
+  config.version = "2.3.4".to_string();
+  config
+    .extra
+    .insert("greeting".to_string(), serde_json::json!("hello"));
+  provider
+    .load(example_plugin_dir("test-plugin"), &config)
+    .expect("load failed");
+
+  let config_field = |key: &str| {
+    provider
+      .invoke(
+        "test-plugin",
+        "Config",
+        vec![ProviderValue::String(key.to_string())],
+      )
+      .expect("invoke failed")
+  };
+
+  assert_eq!(
+    config_field("version"),
+    ProviderValue::String("2.3.4".to_string())
+  );
+  assert_eq!(
+    config_field("greeting"),
+    ProviderValue::String("hello".to_string())
+  );
+  assert_eq!(config_field("missing"), ProviderValue::Null);
+
+  let ProviderValue::Object(whole) = config_field("") else {
+    panic!("expected the whole config as an object");
+  };
+  assert!(whole.contains(&(
+    "name".to_string(),
+    ProviderValue::String("test-plugin".to_string())
+  )));
+}
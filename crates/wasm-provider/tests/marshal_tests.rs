@@ -0,0 +1,58 @@
+mod common;
+
+use common::{example_plugin_dir, plugin_config};
+use shared_types::{Provider, ProviderError, ProviderValue};
+use wasm_provider::WasmProvider;
+
+#[test]
+fn test_unrepresentable_nested_value_reports_its_path() {
+  let provider = WasmProvider::new();
+  provider.init().expect("init failed");
+  provider
+    .load(
+      example_plugin_dir("test-plugin"),
+      &plugin_config("test-plugin"),
+    )
+    .expect("load failed");
+
+  let nested = ProviderValue::Array(vec![
+    ProviderValue::Int(1),
+    ProviderValue::Int(2),
+    ProviderValue::Object(vec![("x".to_string(), ProviderValue::Float(f64::NAN))]),
+  ]);
+
+  match provider.invoke(
+    "test-plugin",
+    "Config",
+    vec![ProviderValue::String("name".to_string()), nested],
+  ) {
+    Err(ProviderError::InvocationFailed(msg)) => assert_eq!(
+      msg,
+      "arg 1 -> [2] -> field 'x': NaN is not representable in JSON"
+    ),
+    other => panic!("expected InvocationFailed, got {:?}", other),
+  }
+}
+
+#[test]
+fn test_unsigned_values_round_trip_through_component() {
+  let provider = WasmProvider::new();
+  provider.init().expect("init failed");
+  provider
+    .load(
+      example_plugin_dir("test-plugin"),
+      &plugin_config("test-plugin"),
+    )
+    .expect("load failed");
+
+  let echo = |value| provider.invoke("test-plugin", "Echo", vec![value]).unwrap();
+  assert_eq!(
+    echo(ProviderValue::UInt(u64::MAX)),
+    ProviderValue::UInt(u64::MAX)
+  );
+  // Values in the signed range come back as `Int`.
+  assert_eq!(
+    echo(ProviderValue::UInt(0xFFFF_FFFF)),
+    ProviderValue::Int(0xFFFF_FFFF)
+  );
+}
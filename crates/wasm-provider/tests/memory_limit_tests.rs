@@ -0,0 +1,100 @@
+mod common;
+
+use common::{example_plugin_dir, plugin_config};
+use shared_types::{Provider, ProviderError, ProviderValue};
+use wasm_provider::{DEFAULT_MAX_MEMORY, WasiVersion, WasmProvider};
+
+const PAGE: usize = 64 * 1024;
+
+// Grows its memory by the given number of pages, returning the old size or -1.
+const GROW_WAT: &str = r#"
+(module
+  (memory (export "memory") 1)
+  (func (export "Grow") (param i32) (result i32)
+    (memory.grow (local.get 0)))
+)
+"#;
+
+fn grow_provider(max_memory: Option<usize>) -> WasmProvider {
+  let provider = WasmProvider::builder()
+    .wasi_version(WasiVersion::Preview1)
+    .max_memory(max_memory)
+    .build();
+  provider.init().expect("init failed");
+  provider
+    .load_bytes("grow", GROW_WAT, &plugin_config("grow"))
+    .expect("load failed");
+  provider
+}
+
+#[test]
+fn test_memory_grow_past_default_limit_fails() {
+  let provider = grow_provider(Some(DEFAULT_MAX_MEMORY));
+  assert_eq!(
+    provider.init().unwrap().max_memory,
+    Some(DEFAULT_MAX_MEMORY)
+  );
+
+  // Up to the limit is fine.
+  let pages_left = (DEFAULT_MAX_MEMORY / PAGE - 1) as i64;
+  assert_eq!(
+    provider
+      .invoke("grow", "Grow", vec![ProviderValue::Int(pages_left)])
+      .unwrap(),
+    ProviderValue::Int(1)
+  );
+
+  match provider.invoke("grow", "Grow", vec![ProviderValue::Int(1)]) {
+    Err(ProviderError::InvocationFailed(msg)) => assert!(
+      msg.contains(&format!(
+        "memory limit of {} bytes exceeded",
+        DEFAULT_MAX_MEMORY
+      )),
+      "{}",
+      msg
+    ),
+    other => panic!("expected InvocationFailed, got {:?}", other),
+  }
+  // The guest was interrupted mid-call, so it must be reloaded.
+  assert_eq!(provider.failed_plugins(), vec!["grow".to_string()]);
+}
+
+#[test]
+fn test_memory_limit_is_configurable() {
+  let provider = grow_provider(None);
+  assert_eq!(
+    provider
+      .invoke("grow", "Grow", vec![ProviderValue::Int(2048)])
+      .unwrap(),
+    ProviderValue::Int(1)
+  );
+
+  // A component allocating past a tighter limit fails the same way.
+  let provider = WasmProvider::builder()
+    .max_memory(Some(4 * 1024 * 1024))
+    .build();
+  provider.init().expect("init failed");
+  provider
+    .load(
+      example_plugin_dir("test-plugin"),
+      &plugin_config("test-plugin"),
+    )
+    .expect("load failed");
+  match provider.invoke("test-plugin", "Grow", vec![]) {
+    Err(ProviderError::InvocationFailed(msg)) => {
+      assert!(msg.contains("memory limit of 4194304 bytes"), "{}", msg)
+    }
+    other => panic!("expected InvocationFailed, got {:?}", other),
+  }
+
+  // An initial memory above the limit fails the load.
+  let provider = WasmProvider::builder()
+    .wasi_version(WasiVersion::Preview1)
+    .max_memory(Some(PAGE / 2))
+    .build();
+  provider.init().expect("init failed");
+  assert!(matches!(
+    provider.load_bytes("grow", GROW_WAT, &plugin_config("grow")),
+    Err(ProviderError::LoadFailed(_))
+  ));
+}
@@ -0,0 +1,109 @@
+mod common;
+
+use common::plugin_config;
+use shared_types::{Provider, ProviderError, ProviderValue};
+use wasm_provider::WasmProvider;
+use wasmtime::component::Component;
+
+// Minimal hand-written plugin: `on-invoke` echoes its JSON arguments back as the result,
+// every other export reports success with empty data.
+const ECHO_PLUGIN_WAT: &str = r#"
+(component
+  (core module $m
+    (memory (export "memory") 1)
+    (global $heap (mut i32) (i32.const 1024))
+    (func (export "realloc") (param i32 i32 i32 i32) (result i32)
+      (local $ptr i32)
+      (local.set $ptr
+        (i32.and (i32.add (global.get $heap) (i32.const 7)) (i32.const -8)))
+      (global.set $heap (i32.add (local.get $ptr) (local.get 3)))
+      (local.get $ptr))
+    ;; Return areas: offset 0 stays zeroed (`ok` / empty list), offset 16 holds the echo.
+    (func (export "on-load") (result i32) (i32.const 0))
+    (func (export "on-invoke") (param i32 i32 i32 i32) (result i32)
+      (i32.store (i32.const 16) (i32.const 0))
+      (i32.store (i32.const 20) (local.get 2))
+      (i32.store (i32.const 24) (local.get 3))
+      (i32.const 16))
+    (func (export "on-invoke-numeric") (param i32 i32 i32 i32 i32) (result i32) (i32.const 0))
+    (func (export "describe") (result i32) (i32.const 0))
+  )
+  (core instance $i (instantiate $m))
+
+  (type $numeric-array (variant (case "int32" (list s32)) (case "float64" (list float64))))
+  (type $value-kind (enum "boolean" "int32" "int64" "float32" "float64" "text" "array" "object"))
+  (type $function-signature (record
+    (field "name" string)
+    (field "params" (list $value-kind))
+    (field "results" (list $value-kind))))
+
+  (func $on-load (result (result (error string)))
+    (canon lift (core func $i "on-load") (memory $i "memory") (realloc (func $i "realloc"))))
+  (func $on-invoke (param "function" string) (param "args-json" string)
+    (result (result string (error string)))
+    (canon lift (core func $i "on-invoke") (memory $i "memory") (realloc (func $i "realloc"))))
+  (func $on-invoke-numeric (param "function" string) (param "data" $numeric-array)
+    (result (result string (error string)))
+    (canon lift (core func $i "on-invoke-numeric") (memory $i "memory") (realloc (func $i "realloc"))))
+  (func $describe (result (list $function-signature))
+    (canon lift (core func $i "describe") (memory $i "memory") (realloc (func $i "realloc"))))
+
+  (instance $plugin
+    (export "numeric-array" (type $numeric-array))
+    (export "value-kind" (type $value-kind))
+    (export "function-signature" (type $function-signature))
+    (export "on-load" (func $on-load))
+    (export "on-invoke" (func $on-invoke))
+    (export "on-invoke-numeric" (func $on-invoke-numeric))
+    (export "describe" (func $describe)))
+  (export "bud:sdk/plugin@0.1.0" (instance $plugin))
+)
+"#;
+
+fn compile_echo_plugin(provider: &WasmProvider) -> Component {
+  let instance = provider.init().expect("init failed");
+  Component::new(&instance.engine, ECHO_PLUGIN_WAT).expect("WAT failed to compile")
+}
+
+#[test]
+fn test_load_component_from_wat() {
+  let provider = WasmProvider::new();
+  let component = compile_echo_plugin(&provider);
+
+  provider
+    .load_component("echo", component, &plugin_config("echo"))
+    .expect("load_component failed");
+
+  let result = provider
+    .invoke(
+      "echo",
+      "Echo",
+      vec![ProviderValue::Int(7), ProviderValue::Bool(true)],
+    )
+    .expect("invoke failed");
+  assert_eq!(
+    result,
+    ProviderValue::Array(vec![ProviderValue::Int(7), ProviderValue::Bool(true)])
+  );
+}
+
+#[test]
+fn test_load_bytes_and_engine_mismatch() {
+  let provider = WasmProvider::new();
+  provider.init().expect("init failed");
+
+  provider
+    .load_bytes("echo", ECHO_PLUGIN_WAT, &plugin_config("echo"))
+    .expect("load_bytes failed");
+  provider.reload("echo").expect("in-memory reload failed");
+  assert_eq!(
+    provider.invoke("echo", "Echo", vec![]).unwrap(),
+    ProviderValue::Array(vec![])
+  );
+
+  let foreign = compile_echo_plugin(&WasmProvider::new());
+  match provider.load_component("foreign", foreign, &plugin_config("foreign")) {
+    Err(ProviderError::LoadFailed(msg)) => assert!(msg.contains("different engine"), "{}", msg),
+    other => panic!("expected LoadFailed, got {:?}", other),
+  }
+}
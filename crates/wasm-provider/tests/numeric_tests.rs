@@ -0,0 +1,70 @@
+mod common;
+
+use common::{example_plugin_dir, plugin_config};
+use shared_types::{Provider, ProviderError, ProviderValue};
+use wasm_provider::WasmProvider;
+
+fn loaded_provider() -> WasmProvider {
+  let provider = WasmProvider::new();
+  provider.init().expect("init failed");
+  provider
+    .load(
+      example_plugin_dir("test-plugin"),
+      &plugin_config("test-plugin"),
+    )
+    .expect("load failed");
+  provider
+}
+
+#[test]
+fn test_invoke_numeric_arrays() {
+  let provider = loaded_provider();
+
+  let ints = ProviderValue::Array((1..=100).map(ProviderValue::Int).collect());
+  let sum = provider
+    .invoke_numeric("test-plugin", "SumArray", &ints)
+    .expect("int invoke failed");
+  assert_eq!(sum, ProviderValue::Int(5050));
+
+  let floats = ProviderValue::Array(vec![ProviderValue::Float(0.5), ProviderValue::Float(1.25)]);
+  let sum = provider
+    .invoke_numeric("test-plugin", "SumArray", &floats)
+    .expect("float invoke failed");
+  assert_eq!(sum, ProviderValue::Float(1.75));
+
+  let empty = ProviderValue::Array(vec![]);
+  let sum = provider
+    .invoke_numeric("test-plugin", "SumArray", &empty)
+    .expect("empty invoke failed");
+  assert_eq!(sum, ProviderValue::Int(0));
+}
+
+#[test]
+fn test_invoke_numeric_rejects_invalid_arrays() {
+  let provider = loaded_provider();
+
+  let cases = [
+    (
+      ProviderValue::Array(vec![ProviderValue::Int(1), ProviderValue::Float(2.0)]),
+      "element 1 is float, expected int",
+    ),
+    (
+      ProviderValue::Array(vec![ProviderValue::String("1".to_string())]),
+      "must contain ints or floats, found string",
+    ),
+    (
+      ProviderValue::Array(vec![ProviderValue::Int(i64::from(i32::MAX) + 1)]),
+      "does not fit in i32",
+    ),
+    (ProviderValue::Int(3), "expects an array argument"),
+  ];
+
+  for (value, expected) in cases {
+    match provider.invoke_numeric("test-plugin", "SumArray", &value) {
+      Err(ProviderError::InvocationFailed(msg)) => {
+        assert!(msg.contains(expected), "unexpected message: {}", msg)
+      }
+      other => panic!("expected InvocationFailed for {:?}, got {:?}", value, other),
+    }
+  }
+}
@@ -0,0 +1,117 @@
+mod common;
+
+use common::{example_plugin_dir, plugin_config};
+use serde_json::json;
+use shared_types::config::Permissions;
+use shared_types::{Provider, ProviderError, ProviderValue};
+use std::fs;
+use wasm_provider::WasmProvider;
+
+// Loads the test plugin with `permissions` given as plugin.json JSON.
+fn load_with(permissions: serde_json::Value) -> Result<WasmProvider, ProviderError> {
+  let mut config = plugin_config("test-plugin");
+  config.permissions =
+    Some(serde_json::from_value::<Permissions>(permissions).expect("invalid permissions"));
+  let provider = WasmProvider::new();
+  provider.init().expect("init failed");
+  provider.load(example_plugin_dir("test-plugin"), &config)?;
+  Ok(provider)
+}
+
+fn call(
+  provider: &WasmProvider,
+  function: &str,
+  args: &[&str],
+) -> Result<ProviderValue, ProviderError> {
+  let args = args
+    .iter()
+    .map(|arg| ProviderValue::String(arg.to_string()))
+    .collect();
+  provider.invoke("test-plugin", function, args)
+}
+
+#[test]
+fn test_granted_directories_are_preopened() {
+  let readable = tempfile::tempdir().unwrap();
+  let writable = tempfile::tempdir().unwrap();
+  let data = readable.path().join("data.txt");
+  fs::write(&data, "lookup table").unwrap();
+  let out = writable.path().join("out.txt");
+
+  let provider = load_with(json!({
+    "filesystem": {
+      "read": [readable.path()],
+      "write": [writable.path()],
+    }
+  }))
+  .expect("load failed");
+
+  assert_eq!(
+    call(&provider, "ReadFile", &[data.to_str().unwrap()]).unwrap(),
+    ProviderValue::String("lookup table".to_string())
+  );
+  call(&provider, "WriteFile", &[out.to_str().unwrap(), "result"]).expect("write failed");
+  assert_eq!(fs::read_to_string(&out).unwrap(), "result");
+
+  // Read-only grants refuse writes.
+  let denied = readable.path().join("new.txt");
+  assert!(matches!(
+    call(&provider, "WriteFile", &[denied.to_str().unwrap(), "x"]),
+    Err(ProviderError::InvocationFailed(_))
+  ));
+  assert!(!denied.exists());
+}
+
+#[test]
+fn test_filesystem_denied_without_permission() {
+  let dir = tempfile::tempdir().unwrap();
+  let data = dir.path().join("data.txt");
+  fs::write(&data, "secret").unwrap();
+
+  for permissions in [json!({}), json!({"filesystem": false})] {
+    let provider = load_with(permissions).expect("load failed");
+    assert!(matches!(
+      call(&provider, "ReadFile", &[data.to_str().unwrap()]),
+      Err(ProviderError::InvocationFailed(_))
+    ));
+  }
+}
+
+#[test]
+fn test_ungrantable_filesystem_permissions_fail_load() {
+  let missing = tempfile::tempdir().unwrap().path().join("missing");
+  for permissions in [
+    json!({"filesystem": true}),
+    json!({"filesystem": {"read": ["relative/dir"]}}),
+    json!({"filesystem": {"write": [missing]}}),
+  ] {
+    assert!(
+      matches!(
+        load_with(permissions.clone()),
+        Err(ProviderError::PermissionDenied(_))
+      ),
+      "{}",
+      permissions
+    );
+  }
+}
+
+#[test]
+fn test_env_permission() {
+  // Cargo sets this for every test process.
+  let key = "CARGO_PKG_NAME";
+  let visible = ProviderValue::String("wasm-provider".to_string());
+
+  let provider = load_with(json!({})).unwrap();
+  assert_eq!(call(&provider, "Env", &[key]).unwrap(), ProviderValue::Null);
+
+  let provider = load_with(json!({"env": true})).unwrap();
+  assert_eq!(call(&provider, "Env", &[key]).unwrap(), visible);
+
+  let provider = load_with(json!({"env": {"keys": [key]}})).unwrap();
+  assert_eq!(call(&provider, "Env", &[key]).unwrap(), visible);
+  assert_eq!(
+    call(&provider, "Env", &["CARGO_PKG_VERSION"]).unwrap(),
+    ProviderValue::Null
+  );
+}
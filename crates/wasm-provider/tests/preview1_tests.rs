@@ -0,0 +1,389 @@
+mod common;
+
+use common::plugin_config;
+use shared_types::provider::HostFunction;
+use shared_types::{FunctionSignature, Provider, ProviderError, ProviderValue, ValueKind};
+use std::fs;
+use std::sync::Arc;
+use wasm_provider::{WasiVersion, WasmProvider};
+use wasmtime::Val;
+
+// Preview 1 module with plain numeric exports; `Entropy` returns the errno of a WASI
+// call, so it only succeeds if the Preview 1 functions are linked.
+const PREVIEW1_WAT: &str = r#"
+(module
+  (import "wasi_snapshot_preview1" "random_get" (func $random_get (param i32 i32) (result i32)))
+  (memory (export "memory") 1)
+  (global $calls (mut i32) (i32.const 0))
+  (func (export "_initialize") (global.set $calls (i32.const 100)))
+  (func (export "Sum") (param i32 i32) (result i32)
+    (i32.add (local.get 0) (local.get 1)))
+  (func (export "Calls") (result i32) (global.get $calls))
+  (func (export "Entropy") (result i32)
+    (call $random_get (i32.const 0) (i32.const 8)))
+  (func (export "Fail") unreachable)
+  (func (export "Splat") (param i32) (result v128) (i32x4.splat (local.get 0)))
+)
+"#;
+
+fn preview1_provider() -> WasmProvider {
+  let provider = WasmProvider::builder()
+    .wasi_version(WasiVersion::Preview1)
+    .build();
+  provider.init().expect("init failed");
+  provider
+}
+
+#[test]
+fn test_preview1_module_loads_and_invokes_from_disk() {
+  let dir = tempfile::tempdir().unwrap();
+  let plugin_dir = dir.path().join("p1-plugin");
+  fs::create_dir(&plugin_dir).unwrap();
+  fs::write(plugin_dir.join("main.wasm"), PREVIEW1_WAT).unwrap();
+
+  let provider = preview1_provider();
+  provider
+    .load(&plugin_dir, &plugin_config("p1-plugin"))
+    .expect("load failed");
+
+  assert_eq!(
+    provider
+      .invoke(
+        "p1-plugin",
+        "Sum",
+        vec![ProviderValue::Int(2), ProviderValue::Int(3)]
+      )
+      .unwrap(),
+    ProviderValue::Int(5)
+  );
+  assert_eq!(
+    provider.invoke("p1-plugin", "Entropy", vec![]).unwrap(),
+    ProviderValue::Int(0)
+  );
+  // `_initialize` ran once at load.
+  assert_eq!(
+    provider.invoke("p1-plugin", "Calls", vec![]).unwrap(),
+    ProviderValue::Int(100)
+  );
+}
+
+#[test]
+fn test_preview1_describe_lists_numeric_exports() {
+  let provider = preview1_provider();
+  provider
+    .load_bytes("p1-plugin", PREVIEW1_WAT, &plugin_config("p1-plugin"))
+    .expect("load failed");
+
+  let signatures = provider.describe("p1-plugin").unwrap();
+  assert!(signatures.contains(&FunctionSignature {
+    name: "Sum".to_string(),
+    params: vec![ValueKind::I32, ValueKind::I32],
+    results: vec![ValueKind::I32],
+  }));
+  assert!(!signatures.iter().any(|sig| sig.name == "_initialize"));
+  // v128 has no `ValueKind`, so only `invoke_raw` can call `Splat`.
+  assert!(!signatures.iter().any(|sig| sig.name == "Splat"));
+}
+
+#[test]
+fn test_preview1_list_exports_includes_every_function() {
+  let provider = preview1_provider();
+  provider
+    .load_bytes("p1-plugin", PREVIEW1_WAT, &plugin_config("p1-plugin"))
+    .expect("load failed");
+
+  // Unlike `describe`, undescribable functions are listed; the memory is not.
+  assert_eq!(
+    provider.list_exports("p1-plugin").unwrap(),
+    ["_initialize", "Sum", "Calls", "Entropy", "Fail", "Splat"]
+  );
+  assert!(matches!(
+    provider.list_exports("missing"),
+    Err(ProviderError::LoadFailed(_))
+  ));
+}
+
+#[test]
+fn test_preview1_argument_and_trap_errors() {
+  let provider = preview1_provider();
+  provider
+    .load_bytes("p1-plugin", PREVIEW1_WAT, &plugin_config("p1-plugin"))
+    .expect("load failed");
+
+  match provider.invoke("p1-plugin", "Sum", vec![ProviderValue::Int(1)]) {
+    Err(ProviderError::InvocationFailed(msg)) => {
+      assert_eq!(msg, "'Sum' takes 2 argument(s), got 1")
+    }
+    other => panic!("expected InvocationFailed, got {:?}", other),
+  }
+  match provider.invoke(
+    "p1-plugin",
+    "Sum",
+    vec![
+      ProviderValue::String("1".to_string()),
+      ProviderValue::Int(2),
+    ],
+  ) {
+    Err(ProviderError::InvocationFailed(msg)) => {
+      assert_eq!(msg, "arg 0: expected i32, found string")
+    }
+    other => panic!("expected InvocationFailed, got {:?}", other),
+  }
+  assert!(matches!(
+    provider.invoke("p1-plugin", "Fail", vec![]),
+    Err(ProviderError::Trap(_))
+  ));
+}
+
+// Zero-extends its i32 argument, so the result shows the bits the guest received.
+const WIDEN_WAT: &str = r#"
+(module
+  (func (export "Widen") (param i32) (result i64) (i64.extend_i32_u (local.get 0)))
+  (func (export "Same") (param i64) (result i64) (local.get 0))
+)
+"#;
+
+#[test]
+fn test_preview1_unsigned_arguments_are_reinterpreted() {
+  let provider = preview1_provider();
+  provider
+    .load_bytes("widen", WIDEN_WAT, &plugin_config("widen"))
+    .expect("load failed");
+
+  assert_eq!(
+    provider
+      .invoke("widen", "Widen", vec![ProviderValue::UInt(0xFFFF_FFFF)])
+      .unwrap(),
+    ProviderValue::Int(0xFFFF_FFFF)
+  );
+  // An i64 result has no sign either; `Int` carries the same bits.
+  assert_eq!(
+    provider
+      .invoke("widen", "Same", vec![ProviderValue::UInt(u64::MAX)])
+      .unwrap(),
+    ProviderValue::Int(-1)
+  );
+  match provider.invoke("widen", "Widen", vec![ProviderValue::UInt(1 << 32)]) {
+    Err(ProviderError::InvocationFailed(msg)) => assert!(msg.contains("u32"), "{}", msg),
+    other => panic!("expected InvocationFailed, got {:?}", other),
+  }
+}
+
+#[test]
+fn test_preview2_provider_rejects_core_modules() {
+  let provider = WasmProvider::new();
+  provider.init().expect("init failed");
+
+  assert!(matches!(
+    provider.load_bytes("p1-plugin", PREVIEW1_WAT, &plugin_config("p1-plugin")),
+    Err(ProviderError::LoadFailed(_))
+  ));
+}
+
+#[test]
+fn test_invoke_raw_returns_unconverted_vals() {
+  let provider = preview1_provider();
+  provider
+    .load_bytes("p1-plugin", PREVIEW1_WAT, &plugin_config("p1-plugin"))
+    .expect("load failed");
+
+  let results = provider
+    .invoke_raw("p1-plugin", "Splat", vec![Val::I32(7)])
+    .unwrap();
+  match results.as_slice() {
+    [Val::V128(lanes)] => assert_eq!(lanes.as_u128(), 0x7_0000_0007_0000_0007_0000_0007),
+    other => panic!("expected one v128, got {:?}", other),
+  }
+
+  // No coercion: an i64 does not satisfy an i32 parameter.
+  assert!(matches!(
+    provider.invoke_raw("p1-plugin", "Splat", vec![Val::I64(7)]),
+    Err(ProviderError::InvocationFailed(_))
+  ));
+}
+
+#[test]
+fn test_invoke_raw_rejects_components() {
+  let provider = WasmProvider::new();
+  provider.init().expect("init failed");
+  provider
+    .load(
+      common::example_plugin_dir("test-plugin"),
+      &plugin_config("test-plugin"),
+    )
+    .expect("load failed");
+
+  match provider.invoke_raw("test-plugin", "Config", vec![]) {
+    Err(ProviderError::InvocationFailed(msg)) => assert!(msg.contains("Preview 1"), "{}", msg),
+    other => panic!("expected InvocationFailed, got {:?}", other),
+  }
+}
+
+// Core module calling an injected `env.host_add`.
+const HOST_ADD_WAT: &str = r#"
+(module
+  (import "env" "host_add" (func $host_add (param i32 i32) (result i32)))
+  (func (export "AddTwice") (param i32 i32) (result i32)
+    (call $host_add (call $host_add (local.get 0) (local.get 1)) (local.get 1)))
+)
+"#;
+
+fn host_add_provider(host_add: Arc<HostFunction>) -> WasmProvider {
+  let provider = WasmProvider::builder()
+    .wasi_version(WasiVersion::Preview1)
+    .host_module("env")
+    .build();
+  let mut instance = provider.init().expect("init failed");
+  provider
+    .inject(&mut instance, &[("host_add", host_add)])
+    .expect("inject failed");
+  provider
+}
+
+#[test]
+fn test_preview1_module_calls_injected_host_function() {
+  let host_add: Arc<HostFunction> = Arc::new(|args| match args.as_slice() {
+    [ProviderValue::Int(a), ProviderValue::Int(b)] => Ok(ProviderValue::Int(a + b)),
+    _ => Err(ProviderError::InvocationFailed(
+      "expected two ints".to_string(),
+    )),
+  });
+  let provider = host_add_provider(host_add);
+  provider
+    .load_bytes("adder", HOST_ADD_WAT, &plugin_config("adder"))
+    .expect("load failed");
+
+  assert_eq!(
+    provider
+      .invoke(
+        "adder",
+        "AddTwice",
+        vec![ProviderValue::Int(2), ProviderValue::Int(3)]
+      )
+      .unwrap(),
+    ProviderValue::Int(8)
+  );
+}
+
+// Exports a `host_add` of its own that calls the injected one and adds 1000, so the
+// result shows which of the two was invoked.
+const SHADOWING_WAT: &str = r#"
+(module
+  (import "env" "host_add" (func $host_add (param i32 i32) (result i32)))
+  (func (export "host_add") (param i32 i32) (result i32)
+    (i32.add (call $host_add (local.get 0) (local.get 1)) (i32.const 1000)))
+)
+"#;
+
+#[test]
+fn test_plugin_function_named_like_host_function_is_dispatched_to_plugin() {
+  let host_add: Arc<HostFunction> = Arc::new(|args| match args.as_slice() {
+    [ProviderValue::Int(a), ProviderValue::Int(b)] => Ok(ProviderValue::Int(a + b)),
+    _ => Err(ProviderError::InvocationFailed(
+      "expected two ints".to_string(),
+    )),
+  });
+  let provider = host_add_provider(host_add);
+  provider
+    .load_bytes("shadow", SHADOWING_WAT, &plugin_config("shadow"))
+    .expect("a colliding name only warns");
+
+  assert_eq!(
+    provider
+      .invoke(
+        "shadow",
+        "host_add",
+        vec![ProviderValue::Int(2), ProviderValue::Int(3)]
+      )
+      .unwrap(),
+    ProviderValue::Int(1005)
+  );
+}
+
+#[test]
+fn test_preview1_host_function_errors() {
+  // A result that does not fit the import's type fails the call.
+  let provider = host_add_provider(Arc::new(|_| Ok(ProviderValue::String("x".to_string()))));
+  provider
+    .load_bytes("adder", HOST_ADD_WAT, &plugin_config("adder"))
+    .expect("load failed");
+  match provider.invoke(
+    "adder",
+    "AddTwice",
+    vec![ProviderValue::Int(1), ProviderValue::Int(1)],
+  ) {
+    Err(ProviderError::InvocationFailed(msg)) => {
+      assert!(msg.contains("expected i32, found string"), "{}", msg)
+    }
+    other => panic!("expected InvocationFailed, got {:?}", other),
+  }
+
+  // Importing a function that was never injected fails the load.
+  let provider = WasmProvider::builder()
+    .wasi_version(WasiVersion::Preview1)
+    .host_module("env")
+    .build();
+  provider.init().expect("init failed");
+  match provider.load_bytes("adder", HOST_ADD_WAT, &plugin_config("adder")) {
+    Err(ProviderError::LoadFailed(msg)) => {
+      assert!(msg.contains("not injected: env::host_add"), "{}", msg)
+    }
+    other => panic!("expected LoadFailed, got {:?}", other),
+  }
+}
+
+// Exports returning zero, one and several results.
+const RESULTS_WAT: &str = r#"
+(module
+  (func (export "Nothing"))
+  (func (export "One") (result i32) (i32.const 7))
+  (func (export "Pair") (result i32 f64) (i32.const 1) (f64.const 2.5))
+)
+"#;
+
+#[test]
+fn test_preview1_result_arity() {
+  let provider = preview1_provider();
+  provider
+    .load_bytes("results", RESULTS_WAT, &plugin_config("results"))
+    .expect("load failed");
+  let call = |function| provider.invoke("results", function, vec![]).unwrap();
+
+  assert_eq!(call("Nothing"), ProviderValue::Null);
+  assert_eq!(call("One"), ProviderValue::Int(7));
+  assert_eq!(
+    call("Pair"),
+    ProviderValue::Tuple(vec![ProviderValue::Int(1), ProviderValue::Float(2.5)])
+  );
+}
+
+// Traps two calls deep, in functions named through the name section.
+const CRASH_WAT: &str = r#"
+(module
+  (func $explode unreachable)
+  (func $crash (export "Crash") (call $explode))
+)
+"#;
+
+#[test]
+fn test_trap_reports_code_and_backtrace() {
+  let provider = preview1_provider();
+  provider
+    .load_bytes("crash", CRASH_WAT, &plugin_config("crash"))
+    .expect("load failed");
+
+  match provider.invoke("crash", "Crash", vec![]) {
+    Err(ProviderError::Trap(msg)) => {
+      assert!(msg.contains("unreachable"), "missing trap kind: {}", msg);
+      let backtrace = msg
+        .split_once("wasm backtrace:\n")
+        .expect("missing backtrace")
+        .1;
+      let frames: Vec<&str> = backtrace.lines().collect();
+      assert_eq!(frames.len(), 2, "{}", backtrace);
+      assert!(frames[0].starts_with("  0: explode at 0x"), "{}", frames[0]);
+      assert!(frames[1].starts_with("  1: crash at 0x"), "{}", frames[1]);
+    }
+    other => panic!("expected Trap, got {:?}", other),
+  }
+}
@@ -0,0 +1,158 @@
+mod common;
+
+use common::{example_plugin_dir, plugin_config};
+use serde_json::json;
+use shared_types::{Provider, ProviderError, ProviderValue};
+use wasm_provider::{StdioMode, WasmProvider};
+
+fn isolated_provider(stdout: StdioMode) -> WasmProvider {
+  let provider = WasmProvider::builder()
+    .process_isolation(env!("CARGO_BIN_EXE_bud-wasm-worker"))
+    .stdio(stdout)
+    .build();
+  provider.init().expect("init failed");
+  provider
+    .load(
+      example_plugin_dir("test-plugin"),
+      &plugin_config("test-plugin"),
+    )
+    .expect("load failed");
+  provider
+}
+
+#[test]
+fn test_invoke_in_worker_process() {
+  let provider = isolated_provider(StdioMode::Null);
+
+  let pid = provider.worker_pid("test-plugin").expect("no worker");
+  assert_ne!(pid, std::process::id());
+  // The plugin lives in the worker, not in this process's plugin table.
+  assert!(provider.plugins.read().unwrap().is_empty());
+
+  assert_eq!(
+    provider
+      .invoke("test-plugin", "Increment", vec![ProviderValue::Int(41)])
+      .unwrap(),
+    ProviderValue::Int(42)
+  );
+  // The plugin's config crosses the process boundary too.
+  assert_eq!(
+    provider
+      .invoke(
+        "test-plugin",
+        "Config",
+        vec![ProviderValue::String("author".to_string())]
+      )
+      .unwrap(),
+    ProviderValue::String("tester".to_string())
+  );
+
+  provider
+    .unload_plugin("test-plugin")
+    .expect("unload failed");
+  assert_eq!(provider.worker_pid("test-plugin"), None);
+}
+
+#[test]
+fn test_worker_stdout_is_relayed() {
+  let provider = isolated_provider(StdioMode::Capture);
+  provider
+    .invoke(
+      "test-plugin",
+      "Print",
+      vec![ProviderValue::String("from the worker".to_string())],
+    )
+    .unwrap();
+  assert_eq!(
+    provider.take_stdout("test-plugin").unwrap(),
+    "stdout: from the worker\n"
+  );
+  assert_eq!(provider.take_stdout("test-plugin").unwrap(), "");
+}
+
+#[test]
+fn test_trap_in_worker_marks_plugin_failed() {
+  let provider = isolated_provider(StdioMode::Null);
+  assert!(matches!(
+    provider.invoke("test-plugin", "Panic", vec![]),
+    Err(ProviderError::Trap(_))
+  ));
+  assert_eq!(provider.failed_plugins(), vec!["test-plugin".to_string()]);
+
+  provider.reload("test-plugin").expect("reload failed");
+  assert!(provider.failed_plugins().is_empty());
+  assert_eq!(
+    provider
+      .invoke("test-plugin", "Double", vec![ProviderValue::Int(4)])
+      .unwrap(),
+    ProviderValue::Int(8)
+  );
+}
+
+#[cfg(unix)]
+#[test]
+fn test_worker_crash_leaves_host_running() {
+  let provider = isolated_provider(StdioMode::Null);
+  let pid = provider.worker_pid("test-plugin").unwrap();
+  let status = std::process::Command::new("kill")
+    .args(["-9", &pid.to_string()])
+    .status()
+    .expect("failed to run kill");
+  assert!(status.success());
+
+  match provider.invoke("test-plugin", "Increment", vec![ProviderValue::Int(1)]) {
+    Err(ProviderError::Trap(msg)) => assert!(msg.contains("worker process exited"), "{}", msg),
+    other => panic!("expected Trap, got {:?}", other),
+  }
+  assert_eq!(provider.failed_plugins(), vec!["test-plugin".to_string()]);
+
+  // A reload starts a fresh worker.
+  provider.reload("test-plugin").expect("reload failed");
+  assert_ne!(provider.worker_pid("test-plugin"), Some(pid));
+  assert_eq!(
+    provider
+      .invoke("test-plugin", "Increment", vec![ProviderValue::Int(1)])
+      .unwrap(),
+    ProviderValue::Int(2)
+  );
+}
+
+#[test]
+fn test_worker_applies_fuel_limit() {
+  let provider = WasmProvider::builder()
+    .process_isolation(env!("CARGO_BIN_EXE_bud-wasm-worker"))
+    .stdio(StdioMode::Null)
+    .build();
+  provider
+    .configure(&json!({"fuelLimit": 50_000_000}))
+    .expect("configure failed");
+  provider.init().expect("init failed");
+  provider
+    .load(
+      example_plugin_dir("test-plugin"),
+      &plugin_config("test-plugin"),
+    )
+    .expect("load failed");
+
+  match provider.invoke("test-plugin", "Spin", vec![]) {
+    Err(ProviderError::Trap(msg)) => assert!(msg.contains("fuel"), "{}", msg),
+    other => panic!("expected Trap, got {:?}", other),
+  }
+}
+
+#[test]
+fn test_missing_worker_executable() {
+  let provider = WasmProvider::builder()
+    .process_isolation("/nonexistent/bud-wasm-worker")
+    .build();
+  provider.init().expect("init failed");
+  match provider.load(
+    example_plugin_dir("test-plugin"),
+    &plugin_config("test-plugin"),
+  ) {
+    Err(ProviderError::LoadFailed(msg)) => {
+      assert!(msg.contains("Failed to start worker"), "{}", msg)
+    }
+    other => panic!("expected LoadFailed, got {:?}", other),
+  }
+}
@@ -0,0 +1,38 @@
+mod common;
+
+use common::{example_plugin_dir, plugin_config};
+use shared_types::{Provider, ProviderError, ProviderValue};
+use wasm_provider::WasmProvider;
+
+#[test]
+fn test_readonly_invoke_rejects_memory_growth() {
+  let provider = WasmProvider::new();
+  provider.init().expect("init failed");
+  provider
+    .load(
+      example_plugin_dir("test-plugin"),
+      &plugin_config("test-plugin"),
+    )
+    .expect("load failed");
+
+  // A call that stays within existing memory is allowed.
+  let key = vec![ProviderValue::String("name".to_string())];
+  assert_eq!(
+    provider
+      .invoke_readonly("test-plugin", "Config", key)
+      .unwrap(),
+    ProviderValue::String("test-plugin".to_string())
+  );
+
+  match provider.invoke_readonly("test-plugin", "Grow", vec![]) {
+    Err(ProviderError::PermissionDenied(msg)) => assert!(msg.contains("memory"), "{}", msg),
+    other => panic!("expected PermissionDenied, got {:?}", other),
+  }
+
+  // The same call succeeds once the plugin is rebuilt and not restricted.
+  provider.reload("test-plugin").expect("reload failed");
+  assert_eq!(
+    provider.invoke("test-plugin", "Grow", vec![]).unwrap(),
+    ProviderValue::Int(8 * 1024 * 1024)
+  );
+}
@@ -0,0 +1,94 @@
+mod common;
+
+use common::{example_plugin_dir, plugin_config};
+use shared_types::{Provider, ProviderValue};
+use std::sync::Arc;
+use wasm_provider::WasmProvider;
+
+fn loaded_provider() -> WasmProvider {
+  let provider = WasmProvider::new();
+  provider.init().expect("init failed");
+  provider
+    .load(
+      example_plugin_dir("test-plugin"),
+      &plugin_config("test-plugin"),
+    )
+    .expect("load failed");
+  provider
+}
+
+fn read(provider: &WasmProvider, name: &str, offset: i64, len: i64) -> ProviderValue {
+  provider
+    .invoke(
+      "test-plugin",
+      "Shared",
+      vec![
+        ProviderValue::String(name.to_string()),
+        ProviderValue::Int(offset),
+        ProviderValue::Int(len),
+      ],
+    )
+    .expect("Shared failed")
+}
+
+fn bytes(values: &[u8]) -> ProviderValue {
+  ProviderValue::Array(
+    values
+      .iter()
+      .map(|b| ProviderValue::Int((*b).into()))
+      .collect(),
+  )
+}
+
+#[test]
+fn test_plugin_reads_shared_dataset() {
+  let provider = loaded_provider();
+  // A lookup table far larger than anything a single call reads.
+  let table: Arc<[u8]> = (0..4 * 1024 * 1024).map(|i| (i % 251) as u8).collect();
+  assert!(provider.share_data("table", Arc::clone(&table)).is_none());
+
+  assert_eq!(
+    provider
+      .invoke(
+        "test-plugin",
+        "SharedSize",
+        vec![ProviderValue::String("table".to_string())]
+      )
+      .unwrap(),
+    ProviderValue::Int(table.len() as i64)
+  );
+  let offset = 3 * 1024 * 1024 + 7;
+  assert_eq!(
+    read(&provider, "table", offset as i64, 4),
+    bytes(&table[offset..offset + 4])
+  );
+
+  // Reads are clamped to the end of the dataset.
+  assert_eq!(
+    read(&provider, "table", table.len() as i64 - 2, 10),
+    bytes(&table[table.len() - 2..])
+  );
+  assert_eq!(
+    read(&provider, "table", table.len() as i64 + 5, 10),
+    bytes(&[])
+  );
+}
+
+#[test]
+fn test_shared_dataset_replace_and_unshare() {
+  let provider = loaded_provider();
+  assert_eq!(read(&provider, "words", 0, 4), ProviderValue::Null);
+
+  provider.share_data("words", b"alpha".to_vec());
+  assert_eq!(read(&provider, "words", 0, 16), bytes(b"alpha"));
+
+  let previous = provider.share_data("words", b"beta".to_vec());
+  assert_eq!(previous.as_deref(), Some(&b"alpha"[..]));
+  assert_eq!(read(&provider, "words", 1, 2), bytes(b"et"));
+
+  assert_eq!(
+    provider.unshare_data("words").as_deref(),
+    Some(&b"beta"[..])
+  );
+  assert_eq!(read(&provider, "words", 0, 4), ProviderValue::Null);
+}
@@ -0,0 +1,57 @@
+mod common;
+
+use common::{example_plugin_dir, plugin_config};
+use serde_json::json;
+use shared_types::{Provider, ProviderError, ValueKind};
+use wasm_provider::{WasiVersion, WasmProvider};
+
+#[test]
+fn test_function_signature_of_component() {
+  let provider = WasmProvider::new();
+  provider.init().expect("init failed");
+  provider
+    .load(
+      example_plugin_dir("sum-plugin"),
+      &plugin_config("sum-plugin"),
+    )
+    .expect("load failed");
+
+  let sum = provider.function_signature("sum-plugin", "Sum").unwrap();
+  assert_eq!(sum.params, vec![ValueKind::I32, ValueKind::I32]);
+  assert_eq!(sum.results, vec![ValueKind::I32]);
+  assert_eq!(
+    serde_json::to_value(&sum).unwrap(),
+    json!({"name": "Sum", "params": ["i32", "i32"], "results": ["i32"]})
+  );
+
+  match provider.function_signature("sum-plugin", "Product") {
+    Err(ProviderError::InvocationFailed(msg)) => assert!(msg.contains("'Product'"), "{}", msg),
+    other => panic!("expected InvocationFailed, got {:?}", other),
+  }
+  assert!(matches!(
+    provider.function_signature("missing", "Sum"),
+    Err(ProviderError::LoadFailed(_))
+  ));
+}
+
+#[test]
+fn test_function_signature_of_undescribable_export() {
+  let provider = WasmProvider::builder()
+    .wasi_version(WasiVersion::Preview1)
+    .build();
+  provider.init().expect("init failed");
+  provider
+    .load_bytes(
+      "simd",
+      r#"(module (func (export "Splat") (param i32) (result v128) (i32x4.splat (local.get 0))))"#,
+      &plugin_config("simd"),
+    )
+    .expect("load failed");
+
+  match provider.function_signature("simd", "Splat") {
+    Err(ProviderError::InvocationFailed(msg)) => {
+      assert!(msg.contains("cannot be described"), "{}", msg)
+    }
+    other => panic!("expected InvocationFailed, got {:?}", other),
+  }
+}
@@ -0,0 +1,107 @@
+mod common;
+
+use common::{example_plugin_dir, plugin_config};
+use shared_types::{Provider, ProviderValue};
+use wasm_provider::{STDIO_ENV_VAR, StdioConfig, StdioMode, WasmProvider};
+
+// Loads the fixture plugin, has it print `text`, and returns what was captured on stdout.
+fn print_and_capture(provider: &WasmProvider, text: &str) -> String {
+  provider.init().expect("init failed");
+  provider
+    .load(
+      example_plugin_dir("test-plugin"),
+      &plugin_config("test-plugin"),
+    )
+    .expect("load failed");
+  provider
+    .invoke(
+      "test-plugin",
+      "Print",
+      vec![ProviderValue::String(text.to_string())],
+    )
+    .expect("invoke failed");
+  provider
+    .take_stdout("test-plugin")
+    .expect("take_stdout failed")
+}
+
+#[test]
+fn test_stdio_mode_parse() {
+  assert_eq!("inherit".parse(), Ok(StdioMode::Inherit));
+  assert_eq!("Capture".parse(), Ok(StdioMode::Capture));
+  assert_eq!(" null ".parse(), Ok(StdioMode::Null));
+  assert!("pipe".parse::<StdioMode>().is_err());
+}
+
+#[test]
+fn test_capture_mode_buffers_output() {
+  let provider = WasmProvider::builder().stdio(StdioMode::Capture).build();
+  let stdout = print_and_capture(&provider, "hello");
+  assert_eq!(stdout, "stdout: hello\n");
+
+  // Captured output is consumed by each read.
+  assert_eq!(provider.take_stdout("test-plugin").unwrap(), "");
+}
+
+#[test]
+fn test_null_mode_discards_output() {
+  let provider = WasmProvider::builder().stdio(StdioMode::Null).build();
+  assert_eq!(print_and_capture(&provider, "dropped"), "");
+}
+
+#[test]
+fn test_inherit_mode_does_not_buffer() {
+  let provider = WasmProvider::builder().stdio(StdioMode::Inherit).build();
+  assert_eq!(print_and_capture(&provider, "passthrough"), "");
+}
+
+#[test]
+fn test_capture_stdout_with_inherited_stderr() {
+  let provider = WasmProvider::builder()
+    .stdio_streams(StdioConfig {
+      stdin: StdioMode::Null,
+      stdout: StdioMode::Capture,
+      stderr: StdioMode::Inherit,
+    })
+    .build();
+  // Print writes to both streams; stderr goes to the test's own stderr and only
+  // stdout lands in the buffer.
+  assert_eq!(print_and_capture(&provider, "split"), "stdout: split\n");
+}
+
+#[test]
+fn test_capture_stderr_separately() {
+  let provider = WasmProvider::builder().stdio(StdioMode::Capture).build();
+  assert_eq!(print_and_capture(&provider, "both"), "stdout: both\n");
+  assert_eq!(
+    provider.take_stderr("test-plugin").unwrap(),
+    "stderr: both\n"
+  );
+  assert_eq!(provider.take_stderr("test-plugin").unwrap(), "");
+  assert!(provider.take_stderr("missing").is_err());
+}
+
+#[test]
+fn test_uniform_mode_applies_to_every_stream() {
+  let config = StdioConfig::from(StdioMode::Null);
+  assert_eq!(config.stdin, StdioMode::Null);
+  assert_eq!(config.stdout, StdioMode::Null);
+  assert_eq!(config.stderr, StdioMode::Null);
+  assert_eq!(StdioConfig::default(), StdioMode::Inherit.into());
+}
+
+// The env default and the builder override are asserted in one test because the
+// variable is process-global.
+#[test]
+fn test_env_default_and_builder_override() {
+  // SAFETY: no other test in this binary reads or writes BUD_PLUGIN_STDIO.
+  unsafe { std::env::set_var(STDIO_ENV_VAR, "capture") };
+
+  let from_env = WasmProvider::new();
+  assert_eq!(print_and_capture(&from_env, "env"), "stdout: env\n");
+
+  let overridden = WasmProvider::builder().stdio(StdioMode::Null).build();
+  assert_eq!(print_and_capture(&overridden, "override"), "");
+
+  unsafe { std::env::remove_var(STDIO_ENV_VAR) };
+}
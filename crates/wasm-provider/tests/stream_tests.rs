@@ -0,0 +1,80 @@
+mod common;
+
+use common::{example_plugin_dir, plugin_config};
+use shared_types::{Provider, ProviderError, ProviderValue};
+use std::sync::mpsc::channel;
+use std::thread;
+use wasm_provider::WasmProvider;
+
+fn loaded_provider(name: &str) -> WasmProvider {
+  let provider = WasmProvider::new();
+  provider.init().expect("init failed");
+  provider
+    .load(example_plugin_dir(name), &plugin_config(name))
+    .expect("load failed");
+  provider
+}
+
+#[test]
+fn test_stream_yields_results_in_order() {
+  let provider = loaded_provider("sum-plugin");
+  let (args_tx, args_rx) = channel();
+  let results = provider.invoke_stream("sum-plugin", "Sum", args_rx);
+
+  let feeder = thread::spawn(move || {
+    for n in 0..5 {
+      args_tx
+        .send(vec![ProviderValue::Int(n), ProviderValue::Int(10)])
+        .unwrap();
+    }
+  });
+
+  let sums: Vec<_> = results.iter().map(Result::unwrap).collect();
+  feeder.join().unwrap();
+  assert_eq!(sums, (10..15).map(ProviderValue::Int).collect::<Vec<_>>());
+
+  // The stream released the plugin when the input closed.
+  assert!(
+    provider
+      .invoke(
+        "sum-plugin",
+        "Sum",
+        vec![ProviderValue::Int(1), ProviderValue::Int(1)]
+      )
+      .is_ok()
+  );
+}
+
+#[test]
+fn test_stream_reports_every_set_after_trap() {
+  let provider = loaded_provider("test-plugin");
+  let (args_tx, args_rx) = channel();
+  let results = provider.invoke_stream("test-plugin", "Panic", args_rx);
+
+  args_tx.send(vec![]).unwrap();
+  args_tx.send(vec![]).unwrap();
+  drop(args_tx);
+
+  let results: Vec<_> = results.iter().collect();
+  assert_eq!(results.len(), 2);
+  assert!(matches!(results[0], Err(ProviderError::Trap(_))));
+  assert!(matches!(
+    results[1],
+    Err(ProviderError::InvocationFailed(_))
+  ));
+}
+
+#[test]
+fn test_stream_for_unknown_plugin_yields_single_error() {
+  let provider = WasmProvider::new();
+  provider.init().expect("init failed");
+  let (args_tx, args_rx) = channel::<Vec<ProviderValue>>();
+
+  let results: Vec<_> = provider
+    .invoke_stream("missing", "Sum", args_rx)
+    .iter()
+    .collect();
+  drop(args_tx);
+  assert_eq!(results.len(), 1);
+  assert!(matches!(results[0], Err(ProviderError::LoadFailed(_))));
+}
@@ -0,0 +1,159 @@
+mod common;
+
+use common::plugin_config;
+use shared_types::{Provider, ProviderError, ProviderValue};
+use wasm_provider::{WasiVersion, WasmProvider};
+
+// Preview 1 module following the string convention: a bump allocator, a `dealloc`
+// that counts releases, `ToUpper` returning an uppercased copy of its argument and
+// `Reverse` returning a reversed copy.
+const TO_UPPER_WAT: &str = r#"
+(module
+  (memory (export "memory") 1)
+  (global $heap (mut i32) (i32.const 1024))
+  (global $freed (mut i32) (i32.const 0))
+  (func $alloc (export "alloc") (param $len i32) (result i32)
+    (local $ptr i32)
+    (local.set $ptr (global.get $heap))
+    (global.set $heap (i32.add (global.get $heap) (local.get $len)))
+    (local.get $ptr))
+  (func (export "dealloc") (param i32 i32)
+    (global.set $freed (i32.add (global.get $freed) (i32.const 1))))
+  (func (export "Freed") (result i32) (global.get $freed))
+  (func (export "Len") (param i32 i32) (result i32) (local.get 1))
+  (func (export "ToUpper") (param $ptr i32) (param $len i32) (result i32 i32)
+    (local $out i32) (local $i i32) (local $c i32)
+    (local.set $out (call $alloc (local.get $len)))
+    (block $done
+      (loop $next
+        (br_if $done (i32.ge_u (local.get $i) (local.get $len)))
+        (local.set $c (i32.load8_u (i32.add (local.get $ptr) (local.get $i))))
+        (if (i32.and
+              (i32.ge_u (local.get $c) (i32.const 97))
+              (i32.le_u (local.get $c) (i32.const 122)))
+          (then (local.set $c (i32.sub (local.get $c) (i32.const 32)))))
+        (i32.store8 (i32.add (local.get $out) (local.get $i)) (local.get $c))
+        (local.set $i (i32.add (local.get $i) (i32.const 1)))
+        (br $next)))
+    (local.get $out)
+    (local.get $len))
+  (func (export "Reverse") (param $ptr i32) (param $len i32) (result i32 i32)
+    (local $out i32) (local $i i32)
+    (local.set $out (call $alloc (local.get $len)))
+    (block $done
+      (loop $next
+        (br_if $done (i32.ge_u (local.get $i) (local.get $len)))
+        (i32.store8
+          (i32.sub
+            (i32.add (local.get $out) (local.get $len))
+            (i32.add (local.get $i) (i32.const 1)))
+          (i32.load8_u (i32.add (local.get $ptr) (local.get $i))))
+        (local.set $i (i32.add (local.get $i) (i32.const 1)))
+        (br $next)))
+    (local.get $out)
+    (local.get $len))
+)
+"#;
+
+fn to_upper_provider() -> WasmProvider {
+  let provider = WasmProvider::builder()
+    .wasi_version(WasiVersion::Preview1)
+    .build();
+  provider.init().expect("init failed");
+  provider
+    .load_bytes("upper", TO_UPPER_WAT, &plugin_config("upper"))
+    .expect("load failed");
+  provider
+}
+
+#[test]
+fn test_string_round_trip() {
+  let provider = to_upper_provider();
+
+  assert_eq!(
+    provider
+      .invoke(
+        "upper",
+        "ToUpper",
+        vec![ProviderValue::String("hello, wasm".to_string())]
+      )
+      .unwrap(),
+    ProviderValue::String("HELLO, WASM".to_string())
+  );
+  // Both the argument and the returned string were released.
+  assert_eq!(
+    provider.invoke("upper", "Freed", vec![]).unwrap(),
+    ProviderValue::Int(2)
+  );
+
+  // Strings can be mixed with numbers; a string counts as a pointer and a length.
+  assert_eq!(
+    provider
+      .invoke(
+        "upper",
+        "Len",
+        vec![ProviderValue::String("héllo".to_string())]
+      )
+      .unwrap(),
+    ProviderValue::Int(6)
+  );
+}
+
+#[test]
+fn test_bytes_round_trip() {
+  let provider = to_upper_provider();
+  // Not valid UTF-8 either way round.
+  let payload = vec![0x00, 0xff, 0xfe, b'a', 0x80];
+
+  let reversed = provider
+    .invoke("upper", "Reverse", vec![ProviderValue::Bytes(payload.clone())])
+    .unwrap();
+
+  let mut expected = payload;
+  expected.reverse();
+  assert_eq!(reversed, ProviderValue::Bytes(expected));
+  assert_eq!(
+    provider.invoke("upper", "Freed", vec![]).unwrap(),
+    ProviderValue::Int(2)
+  );
+}
+
+#[test]
+fn test_plugin_instance_string_helpers() {
+  let provider = to_upper_provider();
+  let plugin = provider.plugins.read().unwrap()["upper"].clone();
+  let mut plugin = plugin.lock().unwrap();
+
+  let (ptr, len) = plugin.write_string("round trip").expect("write failed");
+  assert_eq!(len, 10);
+  assert_eq!(plugin.read_string(ptr, len).unwrap(), "round trip");
+
+  assert!(matches!(
+    plugin.read_string(i32::MAX, 16),
+    Err(ProviderError::InvocationFailed(_))
+  ));
+}
+
+#[test]
+fn test_strings_need_alloc_export() {
+  let provider = WasmProvider::builder()
+    .wasi_version(WasiVersion::Preview1)
+    .build();
+  provider.init().expect("init failed");
+  provider
+    .load_bytes(
+      "no-alloc",
+      r#"(module (memory (export "memory") 1) (func (export "Len") (param i32 i32) (result i32) (local.get 1)))"#,
+      &plugin_config("no-alloc"),
+    )
+    .expect("load failed");
+
+  match provider.invoke(
+    "no-alloc",
+    "Len",
+    vec![ProviderValue::String("text".to_string())],
+  ) {
+    Err(ProviderError::InvocationFailed(msg)) => assert!(msg.contains("argument"), "{}", msg),
+    other => panic!("expected InvocationFailed, got {:?}", other),
+  }
+}
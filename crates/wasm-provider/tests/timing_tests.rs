@@ -0,0 +1,28 @@
+mod common;
+
+use common::{example_plugin_dir, plugin_config};
+use shared_types::Provider;
+use std::time::Duration;
+use wasm_provider::WasmProvider;
+
+#[test]
+fn test_load_timed_reports_both_phases() {
+  let provider = WasmProvider::new();
+  provider.init().expect("init failed");
+
+  let timing = provider
+    .load_timed(
+      example_plugin_dir("sum-plugin"),
+      &plugin_config("sum-plugin"),
+    )
+    .expect("load failed");
+
+  assert!(timing.compile > Duration::ZERO, "compile not measured");
+  assert!(
+    timing.instantiate > Duration::ZERO,
+    "instantiate not measured"
+  );
+  provider
+    .with_plugins(|plugins| assert!(plugins.contains_key("sum-plugin")))
+    .unwrap();
+}
@@ -0,0 +1,82 @@
+mod common;
+
+use common::{example_plugin_dir, plugin_config};
+use shared_types::{Provider, ProviderError};
+use wasm_provider::{FAILED_STATE_MESSAGE, StdioMode, WasmProvider};
+
+fn invoke_panic(stdio: StdioMode) -> ProviderError {
+  let provider = WasmProvider::builder().stdio(stdio).build();
+  provider.init().expect("init failed");
+  provider
+    .load(
+      example_plugin_dir("test-plugin"),
+      &plugin_config("test-plugin"),
+    )
+    .expect("load failed");
+  provider
+    .invoke("test-plugin", "Panic", vec![])
+    .expect_err("panic should fail the call")
+}
+
+#[test]
+fn test_panic_message_surfaces_when_capturing() {
+  match invoke_panic(StdioMode::Capture) {
+    ProviderError::Trap(msg) => {
+      assert!(msg.contains("unreachable"), "unexpected message: {}", msg);
+      assert!(
+        msg.contains("panicked at src/lib.rs"),
+        "missing panic location: {}",
+        msg
+      );
+      assert!(
+        msg.contains("index out of bounds: the len is 0 but the index is 1"),
+        "missing panic payload: {}",
+        msg
+      );
+      assert!(
+        !msg.contains("RUST_BACKTRACE"),
+        "hint not stripped: {}",
+        msg
+      );
+    }
+    other => panic!("expected Trap, got {:?}", other),
+  }
+}
+
+#[test]
+fn test_panic_without_capture_is_bare_trap() {
+  match invoke_panic(StdioMode::Null) {
+    ProviderError::Trap(msg) => assert!(!msg.contains("panicked"), "unexpected message: {}", msg),
+    other => panic!("expected Trap, got {:?}", other),
+  }
+}
+
+#[test]
+fn test_trap_marks_plugin_failed_until_reload() {
+  let provider = WasmProvider::builder().stdio(StdioMode::Null).build();
+  provider.init().expect("init failed");
+  provider
+    .load(
+      example_plugin_dir("test-plugin"),
+      &plugin_config("test-plugin"),
+    )
+    .expect("load failed");
+
+  let print = || provider.invoke("test-plugin", "Print", vec![]);
+
+  assert!(matches!(
+    provider.invoke("test-plugin", "Panic", vec![]),
+    Err(ProviderError::Trap(_))
+  ));
+  provider
+    .with_plugins(|plugins| assert!(plugins["test-plugin"].lock().unwrap().is_failed()))
+    .unwrap();
+
+  match print() {
+    Err(ProviderError::InvocationFailed(msg)) => assert_eq!(msg, FAILED_STATE_MESSAGE),
+    other => panic!("expected failed-state error, got {:?}", other),
+  }
+
+  provider.reload("test-plugin").expect("reload failed");
+  print().expect("invoke after reload failed");
+}
@@ -0,0 +1,49 @@
+mod common;
+
+use common::{example_plugin_dir, plugin_config};
+use shared_types::{Provider, ProviderError, ProviderValue};
+use wasm_provider::WasmProvider;
+
+fn provider_with_test_plugin() -> WasmProvider {
+  let provider = WasmProvider::new();
+  provider.init().expect("init failed");
+  provider
+    .load(
+      example_plugin_dir("test-plugin"),
+      &plugin_config("test-plugin"),
+    )
+    .expect("load failed");
+  provider
+}
+
+#[test]
+fn test_unloaded_plugin_is_not_found() {
+  let provider = provider_with_test_plugin();
+  provider
+    .invoke("test-plugin", "Increment", vec![ProviderValue::Int(1)])
+    .expect("invoke before unload failed");
+
+  provider
+    .unload_plugin("test-plugin")
+    .expect("unload_plugin failed");
+  assert!(provider.plugins.read().unwrap().is_empty());
+  match provider.invoke("test-plugin", "Increment", vec![ProviderValue::Int(1)]) {
+    Err(ProviderError::LoadFailed(msg)) => assert!(msg.contains("not found"), "{}", msg),
+    other => panic!("expected a not found error, got {:?}", other),
+  }
+
+  match provider.unload_plugin("test-plugin") {
+    Err(ProviderError::UnloadFailed(msg)) => assert!(msg.contains("not found"), "{}", msg),
+    other => panic!("expected UnloadFailed, got {:?}", other),
+  }
+}
+
+#[test]
+fn test_unload_discards_runtime_and_plugins() {
+  let provider = provider_with_test_plugin();
+  let instance = provider.init().expect("init failed");
+
+  provider.unload(instance).expect("unload failed");
+  assert!(!provider.is_initialized());
+  assert!(provider.plugins.read().unwrap().is_empty());
+}
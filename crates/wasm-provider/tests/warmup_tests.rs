@@ -0,0 +1,153 @@
+mod common;
+
+use common::plugin_config;
+use shared_types::{Provider, ProviderError, ProviderValue};
+use wasm_provider::{WasiVersion, WasmProvider};
+
+// Component whose `on-invoke` increments a counter kept in linear memory and returns
+// it; its `bud-warmup` export increments the same counter once.
+const WARMED_COUNTER_WAT: &str = r#"
+(component
+  (core module $m
+    (memory (export "memory") 1)
+    (data (i32.const 100) "0")
+    (global $heap (mut i32) (i32.const 1024))
+    (func (export "realloc") (param i32 i32 i32 i32) (result i32)
+      (local $ptr i32)
+      (local.set $ptr
+        (i32.and (i32.add (global.get $heap) (i32.const 7)) (i32.const -8)))
+      (global.set $heap (i32.add (local.get $ptr) (local.get 3)))
+      (local.get $ptr))
+    (func (export "on-load") (result i32) (i32.const 0))
+    (func (export "warmup")
+      (i32.store8 (i32.const 100) (i32.add (i32.load8_u (i32.const 100)) (i32.const 1))))
+    (func (export "on-invoke") (param i32 i32 i32 i32) (result i32)
+      (i32.store8 (i32.const 100) (i32.add (i32.load8_u (i32.const 100)) (i32.const 1)))
+      (i32.store (i32.const 16) (i32.const 0))
+      (i32.store (i32.const 20) (i32.const 100))
+      (i32.store (i32.const 24) (i32.const 1))
+      (i32.const 16))
+    (func (export "on-invoke-numeric") (param i32 i32 i32 i32 i32) (result i32) (i32.const 0))
+    (func (export "describe") (result i32) (i32.const 0))
+  )
+  (core instance $i (instantiate $m))
+
+  (type $numeric-array (variant (case "int32" (list s32)) (case "float64" (list float64))))
+  (type $value-kind (enum "boolean" "int32" "int64" "float32" "float64" "text" "array" "object"))
+  (type $function-signature (record
+    (field "name" string)
+    (field "params" (list $value-kind))
+    (field "results" (list $value-kind))))
+
+  (func $on-load (result (result (error string)))
+    (canon lift (core func $i "on-load") (memory $i "memory") (realloc (func $i "realloc"))))
+  (func $on-invoke (param "function" string) (param "args-json" string)
+    (result (result string (error string)))
+    (canon lift (core func $i "on-invoke") (memory $i "memory") (realloc (func $i "realloc"))))
+  (func $on-invoke-numeric (param "function" string) (param "data" $numeric-array)
+    (result (result string (error string)))
+    (canon lift (core func $i "on-invoke-numeric") (memory $i "memory") (realloc (func $i "realloc"))))
+  (func $describe (result (list $function-signature))
+    (canon lift (core func $i "describe") (memory $i "memory") (realloc (func $i "realloc"))))
+
+  (instance $plugin
+    (export "numeric-array" (type $numeric-array))
+    (export "value-kind" (type $value-kind))
+    (export "function-signature" (type $function-signature))
+    (export "on-load" (func $on-load))
+    (export "on-invoke" (func $on-invoke))
+    (export "on-invoke-numeric" (func $on-invoke-numeric))
+    (export "describe" (func $describe)))
+  (export "bud:sdk/plugin@0.1.0" (instance $plugin))
+  (func $warmup (canon lift (core func $i "warmup")))
+  (export "bud-warmup" (func $warmup))
+)
+"#;
+
+// Preview 1 module recording whether `__bud_warmup` ran before the first call.
+const WARMED_MODULE_WAT: &str = r#"
+(module
+  (global $warmed (mut i32) (i32.const 0))
+  (func (export "__bud_warmup") (global.set $warmed (i32.const 1)))
+  (func (export "Warmed") (result i32) (global.get $warmed))
+)
+"#;
+
+const COLD_MODULE_WAT: &str = r#"
+(module
+  (func (export "Answer") (result i32) (i32.const 42))
+)
+"#;
+
+const FAILING_WARMUP_WAT: &str = r#"
+(module
+  (func (export "__bud_warmup") unreachable)
+)
+"#;
+
+fn preview1_provider() -> WasmProvider {
+  let provider = WasmProvider::builder()
+    .wasi_version(WasiVersion::Preview1)
+    .build();
+  provider.init().expect("init failed");
+  provider
+}
+
+#[test]
+fn test_component_warmup_runs_during_load() {
+  let provider = WasmProvider::new();
+  provider.init().expect("init failed");
+  provider
+    .load_bytes("counter", WARMED_COUNTER_WAT, &plugin_config("counter"))
+    .expect("load failed");
+
+  assert_eq!(
+    provider.invoke("counter", "Next", vec![]).unwrap(),
+    ProviderValue::Int(2)
+  );
+}
+
+#[test]
+fn test_module_warmup_runs_during_load() {
+  let provider = preview1_provider();
+  provider
+    .load_bytes("warmed", WARMED_MODULE_WAT, &plugin_config("warmed"))
+    .expect("load failed");
+
+  assert_eq!(
+    provider.invoke("warmed", "Warmed", vec![]).unwrap(),
+    ProviderValue::Int(1)
+  );
+}
+
+#[test]
+fn test_missing_warmup_is_skipped() {
+  let provider = preview1_provider();
+  provider
+    .load_bytes("cold", COLD_MODULE_WAT, &plugin_config("cold"))
+    .expect("load failed");
+
+  assert_eq!(
+    provider.invoke("cold", "Answer", vec![]).unwrap(),
+    ProviderValue::Int(42)
+  );
+}
+
+#[test]
+fn test_trapping_warmup_fails_load() {
+  let provider = preview1_provider();
+
+  match provider.load_bytes("failing", FAILING_WARMUP_WAT, &plugin_config("failing")) {
+    Err(ProviderError::LoadFailed(msg)) => assert!(
+      msg.contains("failed in its '__bud_warmup' warmup export"),
+      "{}",
+      msg
+    ),
+    other => panic!("expected LoadFailed, got {:?}", other),
+  }
+  assert!(
+    !provider
+      .with_plugins(|plugins| plugins.contains_key("failing"))
+      .unwrap()
+  );
+}
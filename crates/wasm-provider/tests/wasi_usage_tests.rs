@@ -0,0 +1,56 @@
+mod common;
+
+use common::{example_plugin_dir, write_plugin};
+use shared_types::{PermissionKind, Provider};
+use wasm_provider::WasmProvider;
+
+// Preview 1 core module that can open files and read the clock.
+const PATH_OPEN_MODULE_WAT: &str = r#"
+(module
+  (import "wasi_snapshot_preview1" "path_open"
+    (func (param i32 i32 i32 i32 i32 i64 i64 i32 i32) (result i32)))
+  (import "wasi_snapshot_preview1" "clock_time_get"
+    (func (param i32 i64 i32) (result i32)))
+  (memory (export "memory") 1))
+"#;
+
+#[test]
+fn test_path_open_reports_filesystem_usage() {
+  let root = tempfile::tempdir().unwrap();
+  let plugin_dir = write_plugin(root.path(), "opener", PATH_OPEN_MODULE_WAT);
+
+  let provider = WasmProvider::new();
+  provider.init().expect("init failed");
+  let usage = provider
+    .analyze_wasi_usage(&plugin_dir)
+    .expect("analysis failed");
+
+  assert!(usage.uses(PermissionKind::Filesystem));
+  assert!(usage.permissions[&PermissionKind::Filesystem].contains("path_open"));
+  assert!(usage.ambient.contains("clock_time_get"));
+  assert_eq!(usage.undeclared(None), vec![PermissionKind::Filesystem]);
+}
+
+#[test]
+fn test_component_reports_wasi_interfaces() {
+  let provider = WasmProvider::new();
+  provider.init().expect("init failed");
+  let usage = provider
+    .analyze_wasi_usage(example_plugin_dir("test-plugin"))
+    .expect("analysis failed");
+
+  // The test plugin prints, so it imports the stdio interfaces.
+  assert!(usage.uses(PermissionKind::Stdio));
+  assert!(
+    usage.permissions[&PermissionKind::Stdio]
+      .iter()
+      .any(|i| i.starts_with("wasi:cli/stdout"))
+  );
+  assert!(
+    !usage
+      .permissions
+      .values()
+      .flatten()
+      .any(|i| i.starts_with("bud:"))
+  );
+}
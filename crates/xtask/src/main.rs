@@ -5,6 +5,8 @@ use std::process::Command;
 
 type TaskResult<T> = Result<T, Box<dyn std::error::Error>>;
 
+const EXAMPLE_PLUGINS: &[&str] = &["sum-plugin", "test-plugin"];
+
 fn main() -> TaskResult<()> {
   let command = env::args().nth(1).unwrap_or_default();
   let root = workspace_root()?;
@@ -17,18 +19,23 @@ fn main() -> TaskResult<()> {
     }
     "sum-plugin" => {
       sync_wit(&root)?;
-      build_sum_plugin(&root)?;
-      copy_sum_plugin_wasm(&root)
+      build_example_plugin(&root, "sum-plugin")
+    }
+    "test-plugin" => {
+      sync_wit(&root)?;
+      build_example_plugin(&root, "test-plugin")
     }
     "plugin-dev" => {
       sync_wit(&root)?;
       run_cargo(&root, &["build", "-p", "bud-plugin-sdk"])?;
-      build_sum_plugin(&root)?;
-      copy_sum_plugin_wasm(&root)
+      for name in EXAMPLE_PLUGINS {
+        build_example_plugin(&root, name)?;
+      }
+      Ok(())
     }
     _ => Err(
       format!(
-        "Unknown command '{}'. Available: sync-wit | sdk-rust | sum-plugin | plugin-dev",
+        "Unknown command '{}'. Available: sync-wit | sdk-rust | sum-plugin | test-plugin | plugin-dev",
         command
       )
       .into(),
@@ -65,33 +72,38 @@ fn sync_wit(root: &Path) -> TaskResult<()> {
   Ok(())
 }
 
-fn copy_sum_plugin_wasm(root: &Path) -> TaskResult<()> {
-  let src = root.join("target/wasm32-wasip2/release/sum_plugin.wasm");
-  let dst = root.join("example/sum-plugin/main.wasm");
-
-  if !src.is_file() {
-    return Err(format!("Built wasm artifact does not exist: {}", src.display()).into());
-  }
-
-  fs::copy(&src, &dst)?;
-  println!("Synced wasm: {} -> {}", src.display(), dst.display());
-  Ok(())
-}
-
-fn build_sum_plugin(root: &Path) -> TaskResult<()> {
+fn build_example_plugin(root: &Path, name: &str) -> TaskResult<()> {
+  let manifest = format!("example/{}/Cargo.toml", name);
   run_cargo(
     root,
     &[
       "build",
       "--manifest-path",
-      "example/sum-plugin/Cargo.toml",
+      &manifest,
       "--target",
       "wasm32-wasip2",
       "--target-dir",
       "target",
       "--release",
     ],
-  )
+  )?;
+  copy_example_wasm(root, name)
+}
+
+fn copy_example_wasm(root: &Path, name: &str) -> TaskResult<()> {
+  let src = root.join(format!(
+    "target/wasm32-wasip2/release/{}.wasm",
+    name.replace('-', "_")
+  ));
+  let dst = root.join(format!("example/{}/main.wasm", name));
+
+  if !src.is_file() {
+    return Err(format!("Built wasm artifact does not exist: {}", src.display()).into());
+  }
+
+  fs::copy(&src, &dst)?;
+  println!("Synced wasm: {} -> {}", src.display(), dst.display());
+  Ok(())
 }
 
 fn run_cargo(root: &Path, args: &[&str]) -> TaskResult<()> {
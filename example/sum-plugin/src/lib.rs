@@ -1,5 +1,6 @@
 use bud_plugin_sdk::bud::sdk::host::{emit, log, LogLevel};
-use bud_plugin_sdk::{register, Plugin};
+use bud_plugin_sdk::{register, FunctionSignature, Plugin, ValueKind};
+use serde_json::Value;
 
 struct SumPlugin;
 
@@ -9,6 +10,33 @@ impl Plugin for SumPlugin {
     emit("test", "test payload");
     Ok(())
   }
+
+  fn on_invoke(function: &str, args_json: &str) -> Result<String, String> {
+    let args: Vec<Value> =
+      serde_json::from_str(args_json).map_err(|e| format!("invalid args: {}", e))?;
+    match function {
+      "Sum" => {
+        let [a, b] = args.as_slice() else {
+          return Err(format!("Sum expects 2 arguments, got {}", args.len()));
+        };
+        let operand = |v: &Value| {
+          v.as_i64()
+            .and_then(|n| i32::try_from(n).ok())
+            .ok_or_else(|| format!("Sum expects i32 arguments, got {}", v))
+        };
+        Ok(operand(a)?.wrapping_add(operand(b)?).to_string())
+      }
+      _ => Err(format!("unknown function '{}'", function)),
+    }
+  }
+
+  fn describe() -> Vec<FunctionSignature> {
+    vec![FunctionSignature {
+      name: "Sum".to_string(),
+      params: vec![ValueKind::Int32, ValueKind::Int32],
+      results: vec![ValueKind::Int32],
+    }]
+  }
 }
 
 register!(SumPlugin);
@@ -0,0 +1,96 @@
+use bud_plugin_sdk::bud::sdk::host::{get_config, read_shared, shared_size};
+use bud_plugin_sdk::{register, NumericArray, Plugin};
+use serde_json::Value;
+
+struct TestPlugin;
+
+fn parse_args(args_json: &str) -> Result<Vec<Value>, String> {
+  serde_json::from_str(args_json).map_err(|e| format!("invalid args: {}", e))
+}
+
+impl Plugin for TestPlugin {
+  fn on_invoke(function: &str, args_json: &str) -> Result<String, String> {
+    let args = parse_args(args_json)?;
+    match function {
+      // Writes the first argument to stdout and stderr, used to observe stdio wiring.
+      "Print" => {
+        let text = args.first().and_then(Value::as_str).unwrap_or_default();
+        println!("stdout: {}", text);
+        eprintln!("stderr: {}", text);
+        Ok("null".to_string())
+      }
+      // Returns a field of the plugin's own config (the whole config for "").
+      "Config" => {
+        let key = args.first().and_then(Value::as_str).unwrap_or_default();
+        Ok(get_config(key).unwrap_or_else(|| "null".to_string()))
+      }
+      // Reads `[name, offset, len]` from a host-shared dataset as an array of bytes.
+      "Shared" => {
+        let name = args.first().and_then(Value::as_str).unwrap_or_default();
+        let offset = args.get(1).and_then(Value::as_u64).unwrap_or_default();
+        let len = args.get(2).and_then(Value::as_u64).unwrap_or_default();
+        let bytes = read_shared(name, offset, len as u32);
+        serde_json::to_string(&bytes).map_err(|e| e.to_string())
+      }
+      "SharedSize" => {
+        let name = args.first().and_then(Value::as_str).unwrap_or_default();
+        serde_json::to_string(&shared_size(name)).map_err(|e| e.to_string())
+      }
+      // Reads a file, or writes the second argument to it, used to check preopens.
+      "ReadFile" => {
+        let path = args.first().and_then(Value::as_str).unwrap_or_default();
+        let contents = std::fs::read_to_string(path).map_err(|e| e.to_string())?;
+        serde_json::to_string(&contents).map_err(|e| e.to_string())
+      }
+      "WriteFile" => {
+        let path = args.first().and_then(Value::as_str).unwrap_or_default();
+        let contents = args.get(1).and_then(Value::as_str).unwrap_or_default();
+        std::fs::write(path, contents).map_err(|e| e.to_string())?;
+        Ok("null".to_string())
+      }
+      // Returns an environment variable, or null when it is not visible to the plugin.
+      "Env" => {
+        let key = args.first().and_then(Value::as_str).unwrap_or_default();
+        serde_json::to_string(&std::env::var(key).ok()).map_err(|e| e.to_string())
+      }
+      // Blocks for the given number of milliseconds, used to hold an invocation open.
+      "Sleep" => {
+        let millis = args.first().and_then(Value::as_u64).unwrap_or_default();
+        std::thread::sleep(std::time::Duration::from_millis(millis));
+        Ok("null".to_string())
+      }
+      // Arithmetic on the first argument, used to chain calls across plugins.
+      "Increment" => Ok((args.first().and_then(Value::as_i64).unwrap_or_default() + 1).to_string()),
+      "Double" => Ok((args.first().and_then(Value::as_i64).unwrap_or_default() * 2).to_string()),
+      // Returns the first argument unchanged, used to round-trip values through JSON.
+      "Echo" => Ok(args.first().unwrap_or(&Value::Null).to_string()),
+      // Loops forever, used to check that deadlines interrupt running guest code.
+      "Spin" => loop {
+        std::hint::spin_loop();
+      },
+      // Allocates well past the initial heap, forcing the guest to grow its memory.
+      "Grow" => {
+        let buffer = vec![1u8; 8 * 1024 * 1024];
+        Ok(buffer.iter().map(|b| *b as u64).sum::<u64>().to_string())
+      }
+      // Indexes past the end of the argument list to trigger a real bounds-check panic.
+      "Panic" => {
+        let index = args.len() + 1;
+        Ok(args[index].to_string())
+      }
+      _ => Err(format!("unknown function '{}'", function)),
+    }
+  }
+
+  fn on_invoke_numeric(function: &str, data: NumericArray) -> Result<String, String> {
+    match (function, data) {
+      ("SumArray", NumericArray::Int32(values)) => {
+        Ok(values.iter().map(|v| *v as i64).sum::<i64>().to_string())
+      }
+      ("SumArray", NumericArray::Float64(values)) => Ok(values.iter().sum::<f64>().to_string()),
+      _ => Err(format!("unknown function '{}'", function)),
+    }
+  }
+}
+
+register!(TestPlugin);
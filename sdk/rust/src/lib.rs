@@ -7,13 +7,24 @@ wit_bindgen::generate!({
 #[doc(hidden)]
 pub use exports as __exports;
 
+pub use exports::bud::sdk::plugin::{FunctionSignature, NumericArray, ValueKind};
+
 pub trait Plugin {
   fn on_load() -> Result<(), String> {
     Ok(())
   }
-  fn on_invoke(function: &str, _args_json: &str) -> Result<String, String> {
+  fn on_invoke(_function: &str, _args_json: &str) -> Result<String, String> {
     Ok("".to_string())
   }
+  fn on_invoke_numeric(function: &str, _data: NumericArray) -> Result<String, String> {
+    Err(format!(
+      "function '{}' does not accept numeric arrays",
+      function
+    ))
+  }
+  fn describe() -> Vec<FunctionSignature> {
+    Vec::new()
+  }
 }
 
 #[macro_export]
@@ -27,6 +38,15 @@ macro_rules! register {
             fn on_invoke(function: String, args_json: String) -> Result<String, String> {
                 <$t as bud_plugin_sdk::Plugin>::on_invoke(&function, &args_json)
             }
+            fn on_invoke_numeric(
+                function: String,
+                data: bud_plugin_sdk::NumericArray,
+            ) -> Result<String, String> {
+                <$t as bud_plugin_sdk::Plugin>::on_invoke_numeric(&function, data)
+            }
+            fn describe() -> Vec<bud_plugin_sdk::FunctionSignature> {
+                <$t as bud_plugin_sdk::Plugin>::describe()
+            }
         }
         bud_plugin_sdk::export!(__BudGuestImpl with_types_in bud_plugin_sdk);
     };